@@ -2,10 +2,8 @@
 #![allow(dead_code)]
 #![allow(deprecated)]
 use core::ffi::c_void;
-#[cfg(all(feature = "shared-memory", feature = "unstable"))]
-use std::sync::Arc;
 use std::{
-    sync::{Condvar, Mutex, MutexGuard},
+    sync::{Arc, Condvar, Mutex, MutexGuard},
     thread::JoinHandle,
 };
 
@@ -33,6 +31,8 @@ use zenoh::{
 use zenoh::{
     cancellation::CancellationToken,
     internal::builders::close::NolocalJoinHandle,
+    key_expr::{format::KeFormat, keyexpr_tree::KeBoxTree, OwnedKeyExpr},
+    query::Parameters,
     sample::SourceInfo,
     session::{
         EntityGlobalId, Link, LinkEvent, LinkEventsListener, Transport, TransportEvent,
@@ -237,6 +237,10 @@ get_opaque_type_data!(Option<KeyExpr<'static>>, z_view_keyexpr_t);
 /// Using `z_declare_keyexpr` allows Zenoh to optimize a key expression,
 /// both for local processing and network-wise.
 get_opaque_type_data!(KeyExpr<'static>, z_loaned_keyexpr_t);
+#[cfg(feature = "unstable")]
+/// @warning This API has been marked as unstable: it works as advertised, but it may be changed in a future release.
+/// @brief An iterator over the chunks of a key expression.
+get_opaque_type_data!(std::str::Split<'static, char>, z_keyexpr_chunk_iterator_t);
 
 /// An owned Zenoh session.
 get_opaque_type_data!(Option<Session>, z_owned_session_t);
@@ -373,6 +377,35 @@ get_opaque_type_data!(
 /// @brief A loaned Zenoh publication cache.
 get_opaque_type_data!(zenoh_ext::PublicationCache, ze_loaned_publication_cache_t);
 
+#[cfg(feature = "unstable")]
+struct MemoryStorage {
+    _key_expr: KeyExpr<'static>,
+    _subscriber: Subscriber<()>,
+    _queryable: Queryable<()>,
+}
+#[cfg(feature = "unstable")]
+/// @warning This API has been marked as unstable: it works as advertised, but it may be changed in a future release.
+/// @brief An owned Zenoh in-memory storage, storing samples of a key expression and answering queries about them.
+get_opaque_type_data!(Option<MemoryStorage>, ze_owned_memory_storage_t);
+#[cfg(feature = "unstable")]
+/// @warning This API has been marked as unstable: it works as advertised, but it may be changed in a future release.
+/// @brief A loaned Zenoh in-memory storage.
+get_opaque_type_data!(MemoryStorage, ze_loaned_memory_storage_t);
+
+#[cfg(feature = "unstable")]
+struct FileStorage {
+    _storage: MemoryStorage,
+    _state: Arc<Mutex<()>>,
+}
+#[cfg(feature = "unstable")]
+/// @warning This API has been marked as unstable: it works as advertised, but it may be changed in a future release.
+/// @brief An owned Zenoh file storage, persisting samples of a key expression to a file and answering queries about them.
+get_opaque_type_data!(Option<FileStorage>, ze_owned_file_storage_t);
+#[cfg(feature = "unstable")]
+/// @warning This API has been marked as unstable: it works as advertised, but it may be changed in a future release.
+/// @brief A loaned Zenoh file storage.
+get_opaque_type_data!(FileStorage, ze_loaned_file_storage_t);
+
 /// An owned mutex.
 get_opaque_type_data!(
     Option<(Mutex<()>, Option<MutexGuard<'static, ()>>)>,
@@ -585,53 +618,299 @@ get_opaque_type_data!(Option<PtrInSegment>, z_owned_ptr_in_segment_t);
 /// @brief A loaned pointer in SHM Segment.
 get_opaque_type_data!(PtrInSegment, z_loaned_ptr_in_segment_t);
 
+struct ChannelNotifier;
+
 /// An owned Zenoh fifo sample handler.
 get_opaque_type_data!(
-    Option<FifoChannelHandler<Sample>>,
+    Option<(FifoChannelHandler<Sample>, Arc<ChannelNotifier>)>,
     z_owned_fifo_handler_sample_t
 );
 /// An loaned Zenoh fifo sample handler.
-get_opaque_type_data!(FifoChannelHandler<Sample>, z_loaned_fifo_handler_sample_t);
+get_opaque_type_data!(
+    (FifoChannelHandler<Sample>, Arc<ChannelNotifier>),
+    z_loaned_fifo_handler_sample_t
+);
 
 /// An owned Zenoh ring sample handler.
 get_opaque_type_data!(
-    Option<RingChannelHandler<Sample>>,
+    Option<(RingChannelHandler<Sample>, Arc<ChannelNotifier>)>,
     z_owned_ring_handler_sample_t
 );
 /// An loaned Zenoh ring sample handler.
-get_opaque_type_data!(RingChannelHandler<Sample>, z_loaned_ring_handler_sample_t);
+get_opaque_type_data!(
+    (RingChannelHandler<Sample>, Arc<ChannelNotifier>),
+    z_loaned_ring_handler_sample_t
+);
 
 /// An owned Zenoh fifo query handler.
 get_opaque_type_data!(
-    Option<FifoChannelHandler<Query>>,
+    Option<(FifoChannelHandler<Query>, Arc<ChannelNotifier>)>,
     z_owned_fifo_handler_query_t
 );
 /// An loaned Zenoh fifo query handler.
-get_opaque_type_data!(FifoChannelHandler<Query>, z_loaned_fifo_handler_query_t);
+get_opaque_type_data!(
+    (FifoChannelHandler<Query>, Arc<ChannelNotifier>),
+    z_loaned_fifo_handler_query_t
+);
 
 /// An owned Zenoh ring query handler.
 get_opaque_type_data!(
-    Option<RingChannelHandler<Query>>,
+    Option<(RingChannelHandler<Query>, Arc<ChannelNotifier>)>,
     z_owned_ring_handler_query_t
 );
 /// An loaned Zenoh ring query handler.
-get_opaque_type_data!(RingChannelHandler<Query>, z_loaned_ring_handler_query_t);
+get_opaque_type_data!(
+    (RingChannelHandler<Query>, Arc<ChannelNotifier>),
+    z_loaned_ring_handler_query_t
+);
 
 /// An owned Zenoh fifo reply handler.
 get_opaque_type_data!(
-    Option<FifoChannelHandler<Reply>>,
+    Option<(FifoChannelHandler<Reply>, Arc<ChannelNotifier>)>,
     z_owned_fifo_handler_reply_t
 );
 /// An loaned Zenoh fifo reply handler.
-get_opaque_type_data!(FifoChannelHandler<Reply>, z_loaned_fifo_handler_reply_t);
+get_opaque_type_data!(
+    (FifoChannelHandler<Reply>, Arc<ChannelNotifier>),
+    z_loaned_fifo_handler_reply_t
+);
 
 /// An owned Zenoh ring reply handler.
 get_opaque_type_data!(
-    Option<RingChannelHandler<Reply>>,
+    Option<(RingChannelHandler<Reply>, Arc<ChannelNotifier>)>,
     z_owned_ring_handler_reply_t
 );
 /// An loaned Zenoh ring reply handler.
-get_opaque_type_data!(RingChannelHandler<Reply>, z_loaned_ring_handler_reply_t);
+get_opaque_type_data!(
+    (RingChannelHandler<Reply>, Arc<ChannelNotifier>),
+    z_loaned_ring_handler_reply_t
+);
+
+#[cfg(feature = "unstable")]
+/// Mirrors `z_matching_status_t`.
+#[allow(non_camel_case_types)]
+#[repr(C)]
+struct z_matching_status_t {
+    matching: bool,
+}
+
+#[cfg(feature = "unstable")]
+/// @warning This API has been marked as unstable: it works as advertised, but it may be changed in a future release.
+/// @brief An owned Zenoh fifo hello handler.
+get_opaque_type_data!(
+    Option<(FifoChannelHandler<Hello>, Arc<ChannelNotifier>)>,
+    z_owned_fifo_handler_hello_t
+);
+#[cfg(feature = "unstable")]
+/// @warning This API has been marked as unstable: it works as advertised, but it may be changed in a future release.
+/// @brief A loaned Zenoh fifo hello handler.
+get_opaque_type_data!(
+    (FifoChannelHandler<Hello>, Arc<ChannelNotifier>),
+    z_loaned_fifo_handler_hello_t
+);
+#[cfg(feature = "unstable")]
+/// @warning This API has been marked as unstable: it works as advertised, but it may be changed in a future release.
+/// @brief An owned Zenoh ring hello handler.
+get_opaque_type_data!(
+    Option<(RingChannelHandler<Hello>, Arc<ChannelNotifier>)>,
+    z_owned_ring_handler_hello_t
+);
+#[cfg(feature = "unstable")]
+/// @warning This API has been marked as unstable: it works as advertised, but it may be changed in a future release.
+/// @brief A loaned Zenoh ring hello handler.
+get_opaque_type_data!(
+    (RingChannelHandler<Hello>, Arc<ChannelNotifier>),
+    z_loaned_ring_handler_hello_t
+);
+
+#[cfg(feature = "unstable")]
+/// @warning This API has been marked as unstable: it works as advertised, but it may be changed in a future release.
+/// @brief An owned Zenoh fifo matching status handler.
+get_opaque_type_data!(
+    Option<(FifoChannelHandler<z_matching_status_t>, Arc<ChannelNotifier>)>,
+    z_owned_fifo_handler_matching_status_t
+);
+#[cfg(feature = "unstable")]
+/// @warning This API has been marked as unstable: it works as advertised, but it may be changed in a future release.
+/// @brief A loaned Zenoh fifo matching status handler.
+get_opaque_type_data!(
+    (FifoChannelHandler<z_matching_status_t>, Arc<ChannelNotifier>),
+    z_loaned_fifo_handler_matching_status_t
+);
+#[cfg(feature = "unstable")]
+/// @warning This API has been marked as unstable: it works as advertised, but it may be changed in a future release.
+/// @brief An owned Zenoh ring matching status handler.
+get_opaque_type_data!(
+    Option<(RingChannelHandler<z_matching_status_t>, Arc<ChannelNotifier>)>,
+    z_owned_ring_handler_matching_status_t
+);
+#[cfg(feature = "unstable")]
+/// @warning This API has been marked as unstable: it works as advertised, but it may be changed in a future release.
+/// @brief A loaned Zenoh ring matching status handler.
+get_opaque_type_data!(
+    (RingChannelHandler<z_matching_status_t>, Arc<ChannelNotifier>),
+    z_loaned_ring_handler_matching_status_t
+);
+
+#[cfg(feature = "unstable")]
+/// @warning This API has been marked as unstable: it works as advertised, but it may be changed in a future release.
+/// @brief An owned Zenoh fifo transport event handler.
+get_opaque_type_data!(
+    Option<(FifoChannelHandler<TransportEvent>, Arc<ChannelNotifier>)>,
+    z_owned_fifo_handler_transport_event_t
+);
+#[cfg(feature = "unstable")]
+/// @warning This API has been marked as unstable: it works as advertised, but it may be changed in a future release.
+/// @brief A loaned Zenoh fifo transport event handler.
+get_opaque_type_data!(
+    (FifoChannelHandler<TransportEvent>, Arc<ChannelNotifier>),
+    z_loaned_fifo_handler_transport_event_t
+);
+#[cfg(feature = "unstable")]
+/// @warning This API has been marked as unstable: it works as advertised, but it may be changed in a future release.
+/// @brief An owned Zenoh ring transport event handler.
+get_opaque_type_data!(
+    Option<(RingChannelHandler<TransportEvent>, Arc<ChannelNotifier>)>,
+    z_owned_ring_handler_transport_event_t
+);
+#[cfg(feature = "unstable")]
+/// @warning This API has been marked as unstable: it works as advertised, but it may be changed in a future release.
+/// @brief A loaned Zenoh ring transport event handler.
+get_opaque_type_data!(
+    (RingChannelHandler<TransportEvent>, Arc<ChannelNotifier>),
+    z_loaned_ring_handler_transport_event_t
+);
+
+#[cfg(feature = "unstable")]
+/// @warning This API has been marked as unstable: it works as advertised, but it may be changed in a future release.
+/// @brief An owned Zenoh fifo link event handler.
+get_opaque_type_data!(
+    Option<(FifoChannelHandler<LinkEvent>, Arc<ChannelNotifier>)>,
+    z_owned_fifo_handler_link_event_t
+);
+#[cfg(feature = "unstable")]
+/// @warning This API has been marked as unstable: it works as advertised, but it may be changed in a future release.
+/// @brief A loaned Zenoh fifo link event handler.
+get_opaque_type_data!(
+    (FifoChannelHandler<LinkEvent>, Arc<ChannelNotifier>),
+    z_loaned_fifo_handler_link_event_t
+);
+#[cfg(feature = "unstable")]
+/// @warning This API has been marked as unstable: it works as advertised, but it may be changed in a future release.
+/// @brief An owned Zenoh ring link event handler.
+get_opaque_type_data!(
+    Option<(RingChannelHandler<LinkEvent>, Arc<ChannelNotifier>)>,
+    z_owned_ring_handler_link_event_t
+);
+#[cfg(feature = "unstable")]
+/// @warning This API has been marked as unstable: it works as advertised, but it may be changed in a future release.
+/// @brief A loaned Zenoh ring link event handler.
+get_opaque_type_data!(
+    (RingChannelHandler<LinkEvent>, Arc<ChannelNotifier>),
+    z_loaned_ring_handler_link_event_t
+);
+
+#[cfg(feature = "unstable")]
+struct HandlerSet {
+    _signal: Arc<ChannelNotifier>,
+    _entries: Vec<Option<Arc<ChannelNotifier>>>,
+    _next: usize,
+}
+#[cfg(feature = "unstable")]
+/// @warning This API has been marked as unstable: it works as advertised, but it may be changed in a future release.
+/// @brief An owned set of channel handlers, allowing to wait on several handlers at once.
+get_opaque_type_data!(Option<HandlerSet>, z_owned_handler_set_t);
+#[cfg(feature = "unstable")]
+/// @warning This API has been marked as unstable: it works as advertised, but it may be changed in a future release.
+/// @brief A loaned set of channel handlers, allowing to wait on several handlers at once.
+get_opaque_type_data!(HandlerSet, z_loaned_handler_set_t);
+
+#[cfg(feature = "unstable")]
+struct ConfigDiagnostic {
+    _severity: u32,
+    _key: CSlice,
+    _message: CSlice,
+}
+#[cfg(feature = "unstable")]
+/// @warning This API has been marked as unstable: it works as advertised, but it may be changed in a future release.
+/// @brief An owned list of configuration diagnostics.
+get_opaque_type_data!(Option<Vec<ConfigDiagnostic>>, zc_owned_config_diagnostics_t);
+#[cfg(feature = "unstable")]
+/// @warning This API has been marked as unstable: it works as advertised, but it may be changed in a future release.
+/// @brief A loaned list of configuration diagnostics.
+get_opaque_type_data!(Vec<ConfigDiagnostic>, zc_loaned_config_diagnostics_t);
+#[cfg(feature = "unstable")]
+/// @warning This API has been marked as unstable: it works as advertised, but it may be changed in a future release.
+/// @brief A loaned configuration diagnostic: its severity, the key it refers to and a message.
+get_opaque_type_data!(ConfigDiagnostic, zc_loaned_config_diagnostic_t);
+
+#[cfg(feature = "unstable")]
+struct KeyExprTree {
+    _tree: KeBoxTree<*mut c_void>,
+    _drop: Option<extern "C" fn(value: *mut c_void)>,
+}
+#[cfg(feature = "unstable")]
+/// @warning This API has been marked as unstable: it works as advertised, but it may be changed in a future release.
+/// @brief An owned key expression tree, associating key expressions to user values.
+get_opaque_type_data!(Option<KeyExprTree>, z_owned_keyexpr_tree_t);
+#[cfg(feature = "unstable")]
+/// @warning This API has been marked as unstable: it works as advertised, but it may be changed in a future release.
+/// @brief A loaned key expression tree, associating key expressions to user values.
+get_opaque_type_data!(KeyExprTree, z_loaned_keyexpr_tree_t);
+
+#[cfg(feature = "unstable")]
+struct KeyExprTreeIterator {
+    _entries: Vec<(OwnedKeyExpr, *mut c_void)>,
+    _next: usize,
+}
+#[cfg(feature = "unstable")]
+/// @warning This API has been marked as unstable: it works as advertised, but it may be changed in a future release.
+/// @brief An owned iterator over the entries of a key expression tree.
+get_opaque_type_data!(Option<KeyExprTreeIterator>, z_owned_keyexpr_tree_iterator_t);
+#[cfg(feature = "unstable")]
+/// @warning This API has been marked as unstable: it works as advertised, but it may be changed in a future release.
+/// @brief A loaned iterator over the entries of a key expression tree.
+get_opaque_type_data!(KeyExprTreeIterator, z_loaned_keyexpr_tree_iterator_t);
+
+#[cfg(feature = "unstable")]
+struct KeyExprFormat {
+    _format: KeFormat<'static>,
+    _source: Box<str>,
+}
+#[cfg(feature = "unstable")]
+/// @warning This API has been marked as unstable: it works as advertised, but it may be changed in a future release.
+/// @brief An owned key expression format, used to build and parse key expressions with named chunks.
+get_opaque_type_data!(Option<KeyExprFormat>, z_owned_keyexpr_format_t);
+#[cfg(feature = "unstable")]
+/// @warning This API has been marked as unstable: it works as advertised, but it may be changed in a future release.
+/// @brief A loaned key expression format, used to build and parse key expressions with named chunks.
+get_opaque_type_data!(KeyExprFormat, z_loaned_keyexpr_format_t);
+
+#[cfg(feature = "unstable")]
+/// @warning This API has been marked as unstable: it works as advertised, but it may be changed in a future release.
+/// @brief Owned selector parameters: a list of `;`-separated `key=value` pairs.
+get_opaque_type_data!(Option<Parameters<'static>>, z_owned_parameters_t);
+#[cfg(feature = "unstable")]
+/// @warning This API has been marked as unstable: it works as advertised, but it may be changed in a future release.
+/// @brief Loaned selector parameters: a list of `;`-separated `key=value` pairs.
+get_opaque_type_data!(Parameters<'static>, z_loaned_parameters_t);
+#[cfg(feature = "unstable")]
+struct ParametersIterator(std::str::Split<'static, char>);
+#[cfg(feature = "unstable")]
+/// @warning This API has been marked as unstable: it works as advertised, but it may be changed in a future release.
+/// @brief An iterator over the `key=value` pairs of selector parameters.
+get_opaque_type_data!(ParametersIterator, z_parameters_iterator_t);
+
+#[cfg(feature = "unstable")]
+struct WorkItem(Option<Arc<ChannelNotifier>>);
+#[cfg(feature = "unstable")]
+/// @warning This API has been marked as unstable: it works as advertised, but it may be changed in a future release.
+/// @brief An owned work item, holding pending callbacks of an entity to be run by a user-provided executor.
+get_opaque_type_data!(Option<WorkItem>, zc_owned_work_item_t);
+#[cfg(feature = "unstable")]
+/// @warning This API has been marked as unstable: it works as advertised, but it may be changed in a future release.
+/// @brief A loaned work item, holding pending callbacks of an entity to be run by a user-provided executor.
+get_opaque_type_data!(WorkItem, zc_loaned_work_item_t);
 
 #[cfg(feature = "unstable")]
 /// @warning This API has been marked as unstable: it works as advertised, but it may be changed in a future release.
@@ -642,12 +921,35 @@ get_opaque_type_data!(SourceInfo, z_source_info_t);
 /// @brief An entity gloabal id.
 get_opaque_type_data!(EntityGlobalId, z_entity_global_id_t);
 
+struct Serializer {
+    _inner: zenoh_ext::ZSerializer,
+    _containers: Vec<(u8, Option<usize>, usize)>,
+}
 /// @brief An owned Zenoh serializer.
-get_opaque_type_data!(Option<zenoh_ext::ZSerializer>, ze_owned_serializer_t);
+get_opaque_type_data!(Option<Serializer>, ze_owned_serializer_t);
 /// @brief A loaned Zenoh serializer.
-get_opaque_type_data!(zenoh_ext::ZSerializer, ze_loaned_serializer_t);
-/// @brief A Zenoh serializer.
-get_opaque_type_data!(zenoh_ext::ZDeserializer<'static>, ze_deserializer_t);
+get_opaque_type_data!(Serializer, ze_loaned_serializer_t);
+struct Deserializer {
+    _inner: zenoh_ext::ZDeserializer<'static>,
+    _bytes: &'static ZBytes,
+    _position: usize,
+}
+/// @brief A Zenoh deserializer.
+get_opaque_type_data!(Deserializer, ze_deserializer_t);
+
+#[cfg(feature = "unstable")]
+struct Schema {
+    _size: usize,
+    _fields: Vec<()>,
+}
+#[cfg(feature = "unstable")]
+/// @warning This API has been marked as unstable: it works as advertised, but it may be changed in a future release.
+/// @brief An owned schema, describing the layout of a C structure to serialize it.
+get_opaque_type_data!(Option<Schema>, ze_owned_schema_t);
+#[cfg(feature = "unstable")]
+/// @warning This API has been marked as unstable: it works as advertised, but it may be changed in a future release.
+/// @brief A loaned schema.
+get_opaque_type_data!(Schema, ze_loaned_schema_t);
 
 #[cfg(feature = "unstable")]
 /// @warning This API has been marked as unstable: it works as advertised, but it may be changed in a future release.
@@ -188,6 +188,7 @@ fn create_generics_header(path_in: &str, path_out: &str) {
     let call_funcs = find_call_functions(path_in);
     let closure_constructors = find_closure_constructors(path_in);
     let recv_funcs = find_recv_functions(path_in);
+    let recv_timeout_funcs = find_recv_timeout_functions(path_in);
     let clone_funcs = find_clone_functions(path_in);
 
     let drops = drop_funcs
@@ -299,7 +300,7 @@ fn create_generics_header(path_in: &str, path_out: &str) {
     file_out.write_all(out.as_bytes()).unwrap();
     file_out.write_all("\n\n".as_bytes()).unwrap();
 
-    let out = generate_generic_recv_c(&recv_funcs);
+    let out = generate_generic_recv_c(&recv_funcs, &recv_timeout_funcs);
     file_out.write_all(out.as_bytes()).unwrap();
     file_out.write_all("\n\n".as_bytes()).unwrap();
 
@@ -362,7 +363,7 @@ fn create_generics_header(path_in: &str, path_out: &str) {
     file_out.write_all(out.as_bytes()).unwrap();
     file_out.write_all("\n\n".as_bytes()).unwrap();
 
-    let out = generate_generic_recv_cpp(&recv_funcs);
+    let out = generate_generic_recv_cpp(&recv_funcs, &recv_timeout_funcs);
     file_out.write_all(out.as_bytes()).unwrap();
     file_out.write_all("\n\n".as_bytes()).unwrap();
 
@@ -786,6 +787,32 @@ fn find_recv_functions(path_in: &str) -> Vec<FunctionSignature> {
     res
 }
 
+fn find_recv_timeout_functions(path_in: &str) -> Vec<FunctionSignature> {
+    let bindings = std::fs::read_to_string(path_in).unwrap();
+    let re = Regex::new(r"(\w+)\s+z_(\w+)_handler_(\w+)_recv_timeout\(const\s+struct\s+(\w+)\s+\*(\w+),\s+struct\s+(\w+)\s+\*(\w+),\s+uint64_t\s+(\w+)\);").unwrap();
+    let mut res = Vec::<FunctionSignature>::new();
+
+    for (
+        _,
+        [return_type, handler_type, value_type, arg1_type, arg1_name, arg2_type, arg2_name, arg3_name],
+    ) in re.captures_iter(&bindings).map(|c| c.extract())
+    {
+        let (_, _, semantic, _) = split_type_name(arg1_type);
+        let f = FunctionSignature::new(
+            semantic,
+            return_type,
+            "z_".to_string() + handler_type + "_handler_" + value_type + "_recv_timeout",
+            vec![
+                FuncArg::new(&("const ".to_string() + arg1_type + "*"), arg1_name),
+                FuncArg::new(&(arg2_type.to_string() + "*"), arg2_name),
+                FuncArg::new("uint64_t", arg3_name),
+            ],
+        );
+        res.push(f);
+    }
+    res
+}
+
 fn find_clone_functions(path_in: &str) -> Vec<FunctionSignature> {
     let bindings = std::fs::read_to_string(path_in).unwrap();
     let re = Regex::new(
@@ -967,7 +994,10 @@ fn generate_generic_closure_c(macro_func: &[FunctionSignature]) -> String {
     out
 }
 
-fn generate_generic_recv_c(macro_func: &[FunctionSignature]) -> String {
+fn generate_generic_recv_c(
+    macro_func: &[FunctionSignature],
+    recv_timeout_funcs: &[FunctionSignature],
+) -> String {
     let try_recv_funcs: Vec<FunctionSignature> = macro_func
         .iter()
         .filter(|f| f.func_name.contains("try_recv"))
@@ -978,9 +1008,15 @@ fn generate_generic_recv_c(macro_func: &[FunctionSignature]) -> String {
         .filter(|f| !f.func_name.contains("try_recv"))
         .cloned()
         .collect();
-    generate_generic_c(&try_recv_funcs, "z_try_recv", false)
+    let mut out = generate_generic_c(&try_recv_funcs, "z_try_recv", false)
         + "\n\n"
-        + generate_generic_c(&recv_funcs, "z_recv", false).as_str()
+        + generate_generic_c(&recv_funcs, "z_recv", false).as_str();
+    // recv_timeout functions are unstable, so they may be absent
+    if !recv_timeout_funcs.is_empty() {
+        out += "\n\n";
+        out += &generate_generic_c(recv_timeout_funcs, "z_recv_timeout", false);
+    }
+    out
 }
 
 fn generate_generic_cpp(
@@ -1104,7 +1140,10 @@ fn generate_generic_clone_cpp(macro_func: &[FunctionSignature]) -> String {
     generate_generic_cpp(macro_func, "z_clone", false)
 }
 
-fn generate_generic_recv_cpp(macro_func: &[FunctionSignature]) -> String {
+fn generate_generic_recv_cpp(
+    macro_func: &[FunctionSignature],
+    recv_timeout_funcs: &[FunctionSignature],
+) -> String {
     let try_recv_funcs: Vec<FunctionSignature> = macro_func
         .iter()
         .filter(|f| f.func_name.contains("try_recv"))
@@ -1115,9 +1154,14 @@ fn generate_generic_recv_cpp(macro_func: &[FunctionSignature]) -> String {
         .filter(|f| !f.func_name.contains("try_recv"))
         .cloned()
         .collect();
-    generate_generic_cpp(&try_recv_funcs, "z_try_recv", false)
+    let mut out = generate_generic_cpp(&try_recv_funcs, "z_try_recv", false)
         + "\n\n"
-        + generate_generic_cpp(&recv_funcs, "z_recv", false).as_str()
+        + generate_generic_cpp(&recv_funcs, "z_recv", false).as_str();
+    if !recv_timeout_funcs.is_empty() {
+        out += "\n\n";
+        out += &generate_generic_cpp(recv_timeout_funcs, "z_recv_timeout", false);
+    }
+    out
 }
 
 fn generate_generic_closure_cpp(macro_func: &[FunctionSignature]) -> String {
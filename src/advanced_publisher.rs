@@ -28,6 +28,7 @@ use zenoh_ext::{
 
 use crate::{
     _apply_publisher_delete_options, _declare_publisher_inner,
+    executor::dispatch_callback,
     result::{self},
     transmute::{IntoCType, LoanedCTypeRef, RustTypeRef, RustTypeRefUninit, TakeRustType},
     z_closure_matching_status_call, z_closure_matching_status_loan, z_congestion_control_t,
@@ -243,11 +244,12 @@ pub extern "C" fn ze_declare_advanced_publisher(
     }
     match p.wait() {
         Err(e) => {
-            crate::report_error!("{}", e);
+            crate::report_error!(zc_error_kind_t::NETWORK, "{}", e);
             this.write(None);
             result::Z_EGENERIC
         }
         Ok(publisher) => {
+            crate::executor::inherit_executor(session.as_rust_type_ref(), publisher.id());
             this.write(Some(publisher));
             result::Z_OK
         }
@@ -365,7 +367,7 @@ pub unsafe extern "C" fn ze_advanced_publisher_put(
         Ok(_) => result::Z_OK,
         Err(e) if e.downcast_ref::<SessionClosedError>().is_some() => result::Z_ESESSION_CLOSED,
         Err(e) => {
-            crate::report_error!("{}", e);
+            crate::report_error!(zc_error_kind_t::NETWORK, "{}", e);
             result::Z_EGENERIC
         }
     }
@@ -408,7 +410,7 @@ pub extern "C" fn ze_advanced_publisher_delete(
         del = _apply_publisher_delete_options(del, &mut options.delete_options)
     }
     if let Err(e) = del.wait() {
-        crate::report_error!("{}", e);
+        crate::report_error!(zc_error_kind_t::NETWORK, "{}", e);
         result::Z_EGENERIC
     } else {
         result::Z_OK
@@ -443,14 +445,15 @@ fn _advanced_publisher_matching_listener_declare_inner<'a>(
 ) -> zenoh::matching::MatchingListenerBuilder<'a, Callback<MatchingStatus>> {
     let publisher = publisher.as_rust_type_ref();
     let callback = callback.take_rust_type();
-    let listener = publisher
-        .matching_listener()
-        .callback(move |matching_status| {
+    let listener = publisher.matching_listener().callback(dispatch_callback(
+        publisher,
+        move |matching_status: MatchingStatus| {
             let status = z_matching_status_t {
                 matching: matching_status.matching(),
             };
             z_closure_matching_status_call(z_closure_matching_status_loan(&callback), &status);
-        });
+        },
+    ));
     listener
 }
 
@@ -478,7 +481,7 @@ pub extern "C" fn ze_advanced_publisher_declare_matching_listener(
         }
         Err(e) => {
             this.write(None);
-            crate::report_error!("{}", e);
+            crate::report_error!(zc_error_kind_t::NETWORK, "{}", e);
             result::Z_EGENERIC
         }
     }
@@ -502,7 +505,7 @@ pub extern "C" fn ze_advanced_publisher_declare_background_matching_listener(
     match listener.background().wait() {
         Ok(_) => result::Z_OK,
         Err(e) => {
-            crate::report_error!("{}", e);
+            crate::report_error!(zc_error_kind_t::NETWORK, "{}", e);
             result::Z_EGENERIC
         }
     }
@@ -527,7 +530,7 @@ pub extern "C" fn ze_advanced_publisher_get_matching_status(
             result::Z_OK
         }
         Err(e) => {
-            crate::report_error!("{}", e);
+            crate::report_error!(zc_error_kind_t::NETWORK, "{}", e);
             result::Z_ENETWORK
         }
     }
@@ -552,7 +555,7 @@ pub extern "C" fn ze_undeclare_advanced_publisher(
 ) -> result::z_result_t {
     if let Some(p) = this_.take_rust_type() {
         if let Err(e) = p.undeclare().wait_callbacks().wait() {
-            crate::report_error!("{}", e);
+            crate::report_error!(zc_error_kind_t::NETWORK, "{}", e);
             return result::Z_ENETWORK;
         }
     }
@@ -18,7 +18,9 @@ use zenoh::{handlers::Callback, liveliness::LivelinessSubscriberBuilder, sample:
 use zenoh_ext::{AdvancedSubscriberBuilderExt, HistoryConfig, RecoveryConfig, SampleMissListener};
 
 use crate::{
-    _declare_subscriber_inner, result,
+    _declare_subscriber_inner,
+    executor::dispatch_callback,
+    result,
     transmute::{IntoCType, LoanedCTypeRef, RustTypeRef, RustTypeRefUninit, TakeRustType},
     z_closure_sample_call, z_closure_sample_loan, z_entity_global_id_t,
     z_liveliness_subscriber_options_t, z_loaned_keyexpr_t, z_loaned_session_t,
@@ -300,11 +302,12 @@ pub extern "C" fn ze_declare_advanced_subscriber(
     let s = _declare_advanced_subscriber_inner(session, key_expr, callback, options);
     match s.wait() {
         Ok(sub) => {
+            crate::executor::inherit_executor(session.as_rust_type_ref(), sub.id());
             this.write(Some(sub));
             result::Z_OK
         }
         Err(e) => {
-            crate::report_error!("{}", e);
+            crate::report_error!(zc_error_kind_t::NETWORK, "{}", e);
             this.write(None);
             result::Z_EGENERIC
         }
@@ -331,7 +334,7 @@ pub extern "C" fn ze_declare_background_advanced_subscriber(
     match subscriber.background().wait() {
         Ok(_) => result::Z_OK,
         Err(e) => {
-            crate::report_error!("{}", e);
+            crate::report_error!(zc_error_kind_t::NETWORK, "{}", e);
             result::Z_EGENERIC
         }
     }
@@ -347,7 +350,7 @@ pub extern "C" fn ze_undeclare_advanced_subscriber(
 ) -> result::z_result_t {
     if let Some(s) = this_.take_rust_type() {
         if let Err(e) = s.undeclare().wait_callbacks().wait() {
-            crate::report_error!("{}", e);
+            crate::report_error!(zc_error_kind_t::NETWORK, "{}", e);
             return result::Z_EGENERIC;
         }
     }
@@ -404,7 +407,7 @@ pub extern "C" fn ze_undeclare_sample_miss_listener(
 ) -> result::z_result_t {
     if let Some(m) = this.take_rust_type() {
         if let Err(e) = m.undeclare().wait_callbacks().wait() {
-            crate::report_error!("{}", e);
+            crate::report_error!(zc_error_kind_t::NETWORK, "{}", e);
             return result::Z_ENETWORK;
         }
     }
@@ -417,13 +420,18 @@ fn _advanced_subscriber_sample_miss_listener_declare_inner<'a>(
 ) -> zenoh_ext::SampleMissListenerBuilder<'a, Callback<zenoh_ext::Miss>> {
     let subscriber = subscriber.as_rust_type_ref();
     let callback = callback.take_rust_type();
-    let listener = subscriber.sample_miss_listener().callback(move |miss| {
-        let miss = ze_miss_t {
-            source: miss.source().into_c_type(),
-            nb: miss.nb(),
-        };
-        ze_closure_miss_call(ze_closure_miss_loan(&callback), &miss);
-    });
+    let listener = subscriber
+        .sample_miss_listener()
+        .callback(dispatch_callback(
+            subscriber,
+            move |miss: zenoh_ext::Miss| {
+                let miss = ze_miss_t {
+                    source: miss.source().into_c_type(),
+                    nb: miss.nb(),
+                };
+                ze_closure_miss_call(ze_closure_miss_loan(&callback), &miss);
+            },
+        ));
     listener
 }
 
@@ -450,7 +458,7 @@ pub extern "C" fn ze_advanced_subscriber_declare_sample_miss_listener(
         }
         Err(e) => {
             this.write(None);
-            crate::report_error!("{}", e);
+            crate::report_error!(zc_error_kind_t::NETWORK, "{}", e);
             result::Z_EGENERIC
         }
     }
@@ -473,7 +481,7 @@ pub extern "C" fn ze_advanced_subscriber_declare_background_sample_miss_listener
     match listener.background().wait() {
         Ok(_) => result::Z_OK,
         Err(e) => {
-            crate::report_error!("{}", e);
+            crate::report_error!(zc_error_kind_t::NETWORK, "{}", e);
             result::Z_EGENERIC
         }
     }
@@ -489,7 +497,7 @@ fn _advanced_subscriber_detect_publishers_inner(
     let sub = subscriber
         .detect_publishers()
         .history(options.is_some_and(|o| o.history))
-        .callback(move |sample| {
+        .callback(dispatch_callback(subscriber, move |sample: Sample| {
             let mut owned_sample = Some(sample);
             z_closure_sample_call(z_closure_sample_loan(&callback), unsafe {
                 owned_sample
@@ -497,7 +505,7 @@ fn _advanced_subscriber_detect_publishers_inner(
                     .unwrap_unchecked()
                     .as_loaned_c_type_mut()
             })
-        });
+        }));
     sub
 }
 
@@ -525,7 +533,10 @@ pub extern "C" fn ze_advanced_subscriber_detect_publishers(
             result::Z_OK
         }
         Err(e) => {
-            crate::report_error!("Failed to subscribe to liveliness: {e}");
+            crate::report_error!(
+                zc_error_kind_t::NETWORK,
+                "Failed to subscribe to liveliness: {e}"
+            );
             liveliness_subscriber.write(None);
             result::Z_EGENERIC
         }
@@ -550,7 +561,10 @@ pub extern "C" fn ze_advanced_subscriber_detect_publishers_background(
     match builder.background().wait() {
         Ok(_) => result::Z_OK,
         Err(e) => {
-            crate::report_error!("Failed to subscribe to liveliness: {e}");
+            crate::report_error!(
+                zc_error_kind_t::NETWORK,
+                "Failed to subscribe to liveliness: {e}"
+            );
             result::Z_EGENERIC
         }
     }
@@ -117,7 +117,7 @@ pub extern "C" fn z_cancellation_token_cancel(
     match this_.as_rust_type_ref().cancel().wait() {
         Ok(_) => result::Z_OK,
         Err(e) => {
-            crate::report_error!("{}", e);
+            crate::report_error!(zc_error_kind_t::OTHER, "{}", e);
             result::Z_EGENERIC
         }
     }
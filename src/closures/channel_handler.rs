@@ -0,0 +1,188 @@
+//
+// Copyright (c) 2017, 2024 ZettaScale Technology.
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ZettaScale Zenoh team, <zenoh@zettascale.tech>
+//
+
+//! Implementation shared by the fifo and ring channels of all item types.
+//!
+//! The `#[no_mangle]` functions of each channel are thin wrappers around the functions of this module:
+//! `write` arguments store a received item (or `None` when there is none) into the output parameter
+//! of the wrapper, in whatever form its C type requires.
+
+#[cfg(feature = "unstable")]
+use std::time::{Duration, Instant};
+
+use libc::c_void;
+use zenoh::handlers::{
+    Callback, FifoChannel, FifoChannelHandler, IntoHandler, RingChannel, RingChannelHandler,
+};
+
+use crate::{
+    closures::channel_notifier::{new_notifying_callback, NotifyingHandler},
+    result::{self, z_result_t},
+};
+
+/// Receiving end of a fifo or ring channel.
+pub(crate) trait ChannelHandler<T> {
+    fn recv(&self) -> zenoh::Result<T>;
+    fn try_recv(&self) -> zenoh::Result<Option<T>>;
+    #[cfg(feature = "unstable")]
+    fn recv_deadline(&self, deadline: Instant) -> zenoh::Result<Option<T>>;
+    /// Takes up to `max` pending items without blocking.
+    #[cfg(feature = "unstable")]
+    fn drain(&self, max: usize) -> impl Iterator<Item = T> + '_;
+}
+
+impl<T> ChannelHandler<T> for FifoChannelHandler<T> {
+    fn recv(&self) -> zenoh::Result<T> {
+        FifoChannelHandler::recv(self)
+    }
+
+    fn try_recv(&self) -> zenoh::Result<Option<T>> {
+        FifoChannelHandler::try_recv(self)
+    }
+
+    #[cfg(feature = "unstable")]
+    fn recv_deadline(&self, deadline: Instant) -> zenoh::Result<Option<T>> {
+        FifoChannelHandler::recv_deadline(self, deadline)
+    }
+
+    #[cfg(feature = "unstable")]
+    fn drain(&self, max: usize) -> impl Iterator<Item = T> + '_ {
+        self.try_iter().take(max)
+    }
+}
+
+impl<T> ChannelHandler<T> for RingChannelHandler<T> {
+    fn recv(&self) -> zenoh::Result<T> {
+        RingChannelHandler::recv(self)
+    }
+
+    fn try_recv(&self) -> zenoh::Result<Option<T>> {
+        RingChannelHandler::try_recv(self)
+    }
+
+    #[cfg(feature = "unstable")]
+    fn recv_deadline(&self, deadline: Instant) -> zenoh::Result<Option<T>> {
+        RingChannelHandler::recv_deadline(self, deadline)
+    }
+
+    #[cfg(feature = "unstable")]
+    fn drain(&self, max: usize) -> impl Iterator<Item = T> + '_ {
+        // The ring buffer can only be popped one item at a time.
+        std::iter::from_fn(|| RingChannelHandler::try_recv(self).ok().flatten()).take(max)
+    }
+}
+
+/// Constructs a fifo channel. Returns the context of the sending end, to be used with `notifying_callback_call()`
+/// and `notifying_callback_drop()`, and the receiving end.
+pub(crate) fn new_fifo_channel<T: Send + 'static>(
+    capacity: usize,
+) -> (*mut c_void, NotifyingHandler<FifoChannelHandler<T>>) {
+    let (cb, handler): (Callback<T>, _) = FifoChannel::new(capacity).into_handler();
+    let (context, notifier) = new_notifying_callback(cb);
+    (context, (handler, notifier))
+}
+
+/// Constructs a ring channel. Returns the context of the sending end, to be used with `notifying_callback_call()`
+/// and `notifying_callback_drop()`, and the receiving end.
+pub(crate) fn new_ring_channel<T: Send + 'static>(
+    capacity: usize,
+) -> (*mut c_void, NotifyingHandler<RingChannelHandler<T>>) {
+    let (cb, handler): (Callback<T>, _) = RingChannel::new(capacity).into_handler();
+    let (context, notifier) = new_notifying_callback(cb);
+    (context, (handler, notifier))
+}
+
+fn write_received<T>(
+    received: zenoh::Result<Option<T>>,
+    write: impl FnOnce(Option<T>),
+) -> z_result_t {
+    match received {
+        Ok(Some(value)) => {
+            write(Some(value));
+            result::Z_OK
+        }
+        Ok(None) => {
+            write(None);
+            result::Z_CHANNEL_NODATA
+        }
+        Err(_) => {
+            write(None);
+            result::Z_CHANNEL_DISCONNECTED
+        }
+    }
+}
+
+/// Blocks until an item is received or until the channel is dropped.
+pub(crate) fn recv<T, H: ChannelHandler<T>>(
+    this: &NotifyingHandler<H>,
+    write: impl FnOnce(Option<T>),
+) -> z_result_t {
+    write_received(this.0.recv().map(Some), write)
+}
+
+/// Receives a pending item without blocking.
+pub(crate) fn try_recv<T, H: ChannelHandler<T>>(
+    this: &NotifyingHandler<H>,
+    write: impl FnOnce(Option<T>),
+) -> z_result_t {
+    let (handler, notifier) = this;
+    write_received(notifier.try_recv(|| handler.try_recv()), write)
+}
+
+/// Blocks until an item is received, until the channel is dropped or until the timeout expires.
+///
+/// Timeouts too large to be represented as a deadline block without timeout, as `recv()` does.
+#[cfg(feature = "unstable")]
+pub(crate) fn recv_timeout<T, H: ChannelHandler<T>>(
+    this: &NotifyingHandler<H>,
+    timeout_ms: u64,
+    write: impl FnOnce(Option<T>),
+) -> z_result_t {
+    match Instant::now().checked_add(Duration::from_millis(timeout_ms)) {
+        Some(deadline) => write_received(this.0.recv_deadline(deadline), write),
+        None => recv(this, write),
+    }
+}
+
+/// Receives up to `max` pending items without blocking, storing them into the first elements of `out`.
+///
+/// # Safety
+/// `out` should either be NULL or point to an array of at least `max` elements.
+#[cfg(feature = "unstable")]
+pub(crate) unsafe fn recv_batch<T, O, H: ChannelHandler<T>>(
+    this: &NotifyingHandler<H>,
+    out: *mut O,
+    max: usize,
+    out_count: &mut usize,
+    write: impl Fn(&mut O, T),
+) -> z_result_t {
+    *out_count = 0;
+    if max == 0 {
+        return result::Z_OK;
+    }
+    if out.is_null() {
+        crate::report_error!(zc_error_kind_t::INVALID_ARGUMENT, "Output array is NULL");
+        return result::Z_EINVAL;
+    }
+    let out = std::slice::from_raw_parts_mut(out, max);
+    let (handler, notifier) = this;
+    let (count, res) = notifier.try_recv_batch(
+        |max| handler.drain(max),
+        || handler.try_recv(),
+        max,
+        |i, value| write(&mut out[i], value),
+    );
+    *out_count = count;
+    res
+}
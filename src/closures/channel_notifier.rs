@@ -0,0 +1,314 @@
+//
+// Copyright (c) 2017, 2024 ZettaScale Technology.
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ZettaScale Zenoh team, <zenoh@zettascale.tech>
+//
+
+#[cfg(feature = "unstable")]
+use std::sync::{atomic::AtomicUsize, Condvar, Mutex, Weak};
+use std::sync::{
+    atomic::{AtomicBool, Ordering},
+    Arc, OnceLock,
+};
+
+#[cfg(feature = "unstable")]
+use libc::c_int;
+use libc::c_void;
+use zenoh::handlers::Callback;
+
+#[cfg(feature = "unstable")]
+use crate::result::{self, z_result_t};
+
+/// Receiving end of a channel, as stored in `z_owned_*_handler_*_t`.
+pub(crate) type NotifyingHandler<H> = (H, Arc<ChannelNotifier>);
+
+/// Sending end of a channel, as stored in the context of the closure returned by `z_*_channel_*_new()`.
+pub(crate) type NotifyingCallback<T> = (Callback<T>, Arc<ChannelNotifier>);
+
+/// Wakes up threads waiting on several channels at once.
+#[cfg(feature = "unstable")]
+#[derive(Default)]
+pub(crate) struct WaitSignal {
+    pub(crate) mutex: Mutex<()>,
+    pub(crate) condvar: Condvar,
+}
+
+/// Signals the receiving end of a channel that new items were queued or that the sending end was dropped.
+///
+/// The readiness state is set by the sender after each item is pushed and is cleared by the receiver
+/// once `try_recv` finds the channel buffer empty. Clearing is followed by another `try_recv`, so an item
+/// pushed concurrently is never missed, at the cost of possible spurious wakeups.
+#[derive(Default)]
+pub(crate) struct ChannelNotifier {
+    ready: AtomicBool,
+    fd: OnceLock<Option<ReadinessFd>>,
+    #[cfg(feature = "unstable")]
+    waiters: Mutex<Vec<Weak<WaitSignal>>>,
+    /// Number of entries of `waiters`, checked before locking it so that channels which are not part of
+    /// any handler set do not pay for it.
+    #[cfg(feature = "unstable")]
+    waiter_count: AtomicUsize,
+}
+
+impl ChannelNotifier {
+    pub(crate) fn notify(&self) {
+        self.ready.store(true, Ordering::SeqCst);
+        if let Some(Some(fd)) = self.fd.get() {
+            fd.set();
+        }
+        #[cfg(feature = "unstable")]
+        {
+            // Handler sets check the readiness state before waiting, so a waiter added concurrently is not missed.
+            if self.waiter_count.load(Ordering::SeqCst) == 0 {
+                return;
+            }
+            let mut waiters = self.waiters.lock().unwrap();
+            waiters.retain(|w| match w.upgrade() {
+                Some(signal) => {
+                    // Taking the lock guarantees that the waiter either already checked the readiness
+                    // state or is waiting on the condition variable.
+                    let _guard = signal.mutex.lock().unwrap();
+                    signal.condvar.notify_all();
+                    true
+                }
+                None => false,
+            });
+            self.waiter_count.store(waiters.len(), Ordering::SeqCst);
+        }
+    }
+
+    /// Clears the readiness state. Returns `false` if the channel was not signaled since the last call.
+    fn rearm(&self) -> bool {
+        if let Some(Some(fd)) = self.fd.get() {
+            fd.clear();
+        }
+        self.ready.swap(false, Ordering::SeqCst)
+    }
+
+    #[cfg(feature = "unstable")]
+    pub(crate) fn is_ready(&self) -> bool {
+        self.ready.load(Ordering::SeqCst)
+    }
+
+    #[cfg(feature = "unstable")]
+    pub(crate) fn add_waiter(&self, signal: &Arc<WaitSignal>) {
+        let mut waiters = self.waiters.lock().unwrap();
+        waiters.push(Arc::downgrade(signal));
+        self.waiter_count.store(waiters.len(), Ordering::SeqCst);
+    }
+
+    #[cfg(feature = "unstable")]
+    pub(crate) fn remove_waiter(&self, signal: &Arc<WaitSignal>) {
+        let mut waiters = self.waiters.lock().unwrap();
+        waiters.retain(|w| !std::ptr::eq(w.as_ptr(), Arc::as_ptr(signal)));
+        self.waiter_count.store(waiters.len(), Ordering::SeqCst);
+    }
+
+    /// Calls `try_recv`, clearing the readiness state if the channel buffer is empty.
+    pub(crate) fn try_recv<T, E>(
+        &self,
+        try_recv: impl Fn() -> Result<Option<T>, E>,
+    ) -> Result<Option<T>, E> {
+        match try_recv() {
+            Ok(None) if self.rearm() => try_recv(),
+            r => r,
+        }
+    }
+
+    /// Receives up to `max` items without blocking, passing each of them to `write` along with its index.
+    ///
+    /// Pending items are taken in one pass with `drain`, which should yield at most the given number of items.
+    /// If the buffer was emptied, the readiness state is then cleared once and `try_recv` is called to pick up items
+    /// pushed concurrently and to get the status of the channel.
+    /// Returns the number of received items and `Z_OK` if at least one item was received, otherwise the status
+    /// of the channel: `Z_CHANNEL_NODATA` or `Z_CHANNEL_DISCONNECTED`.
+    #[cfg(feature = "unstable")]
+    pub(crate) fn try_recv_batch<T, E, I: Iterator<Item = T>>(
+        &self,
+        drain: impl FnOnce(usize) -> I,
+        try_recv: impl Fn() -> Result<Option<T>, E>,
+        max: usize,
+        mut write: impl FnMut(usize, T),
+    ) -> (usize, z_result_t) {
+        let mut count = 0;
+        for value in drain(max) {
+            write(count, value);
+            count += 1;
+        }
+        if count == max {
+            return (count, result::Z_OK);
+        }
+        self.rearm();
+        let status = loop {
+            if count == max {
+                break result::Z_OK;
+            }
+            match try_recv() {
+                Ok(Some(value)) => {
+                    write(count, value);
+                    count += 1;
+                }
+                Ok(None) => break result::Z_CHANNEL_NODATA,
+                Err(_) => break result::Z_CHANNEL_DISCONNECTED,
+            }
+        };
+        (count, if count > 0 { result::Z_OK } else { status })
+    }
+
+    #[cfg(feature = "unstable")]
+    pub(crate) fn get_fd(&self, out_fd: &mut c_int) -> z_result_t {
+        let fd = self.fd.get_or_init(|| {
+            let fd = ReadinessFd::new()?;
+            // Items may have been queued before the descriptor was created.
+            fd.set();
+            Some(fd)
+        });
+        match fd {
+            Some(fd) => {
+                *out_fd = fd.read_end();
+                result::Z_OK
+            }
+            None if cfg!(unix) => {
+                crate::report_error!(
+                    zc_error_kind_t::IO,
+                    "Failed to create readiness file descriptor: {}",
+                    std::io::Error::last_os_error()
+                );
+                result::Z_EIO
+            }
+            None => {
+                crate::report_error!(
+                    zc_error_kind_t::UNAVAILABLE,
+                    "Readiness file descriptors are not supported on this platform"
+                );
+                result::Z_EUNAVAILABLE
+            }
+        }
+    }
+}
+
+pub(crate) fn new_notifying_callback<T>(
+    callback: Callback<T>,
+) -> (*mut c_void, Arc<ChannelNotifier>) {
+    let notifier = Arc::new(ChannelNotifier::default());
+    let context = Box::into_raw(Box::new((callback, notifier.clone())));
+    (context as *mut c_void, notifier)
+}
+
+/// # Safety
+/// `context` should be obtained from `new_notifying_callback::<T>()`.
+pub(crate) unsafe fn notifying_callback_call<T>(context: *mut c_void, value: T) {
+    let (callback, notifier) = &*(context as *const NotifyingCallback<T>);
+    callback.call(value);
+    notifier.notify();
+}
+
+/// # Safety
+/// `context` should be obtained from `new_notifying_callback::<T>()`.
+pub(crate) unsafe fn notifying_callback_drop<T>(context: *mut c_void) {
+    let (callback, notifier) = *Box::from_raw(context as *mut NotifyingCallback<T>);
+    // Dropping the callback disconnects the channel, the receiver should be woken up to observe it.
+    std::mem::drop(callback);
+    notifier.notify();
+}
+
+/// A file descriptor which is readable while the channel is signaled: eventfd on Linux, pipe on other unix systems.
+struct ReadinessFd {
+    #[cfg(unix)]
+    read: libc::c_int,
+    #[cfg(all(unix, not(target_os = "linux")))]
+    write: libc::c_int,
+}
+
+impl ReadinessFd {
+    #[cfg(target_os = "linux")]
+    fn new() -> Option<Self> {
+        let fd = unsafe { libc::eventfd(0, libc::EFD_NONBLOCK | libc::EFD_CLOEXEC) };
+        (fd >= 0).then_some(Self { read: fd })
+    }
+
+    #[cfg(all(unix, not(target_os = "linux")))]
+    fn new() -> Option<Self> {
+        let mut fds = [0; 2];
+        if unsafe { libc::pipe(fds.as_mut_ptr()) } != 0 {
+            return None;
+        }
+        for fd in fds {
+            unsafe {
+                libc::fcntl(fd, libc::F_SETFL, libc::O_NONBLOCK);
+                libc::fcntl(fd, libc::F_SETFD, libc::FD_CLOEXEC);
+            }
+        }
+        Some(Self {
+            read: fds[0],
+            write: fds[1],
+        })
+    }
+
+    #[cfg(not(unix))]
+    fn new() -> Option<Self> {
+        None
+    }
+
+    #[cfg(all(unix, feature = "unstable"))]
+    fn read_end(&self) -> libc::c_int {
+        self.read
+    }
+
+    #[cfg(all(not(unix), feature = "unstable"))]
+    fn read_end(&self) -> libc::c_int {
+        -1
+    }
+
+    #[cfg(target_os = "linux")]
+    fn set(&self) {
+        let v = 1u64;
+        unsafe { libc::write(self.read, &v as *const u64 as *const c_void, 8) };
+    }
+
+    #[cfg(all(unix, not(target_os = "linux")))]
+    fn set(&self) {
+        // If the pipe is full, it is readable anyway.
+        let v = 1u8;
+        unsafe { libc::write(self.write, &v as *const u8 as *const c_void, 1) };
+    }
+
+    #[cfg(not(unix))]
+    fn set(&self) {}
+
+    #[cfg(target_os = "linux")]
+    fn clear(&self) {
+        let mut v = 0u64;
+        unsafe { libc::read(self.read, &mut v as *mut u64 as *mut c_void, 8) };
+    }
+
+    #[cfg(all(unix, not(target_os = "linux")))]
+    fn clear(&self) {
+        let mut buf = [0u8; 64];
+        while unsafe { libc::read(self.read, buf.as_mut_ptr() as *mut c_void, buf.len()) } > 0 {}
+    }
+
+    #[cfg(not(unix))]
+    fn clear(&self) {}
+}
+
+impl Drop for ReadinessFd {
+    fn drop(&mut self) {
+        #[cfg(unix)]
+        unsafe {
+            libc::close(self.read);
+        }
+        #[cfg(all(unix, not(target_os = "linux")))]
+        unsafe {
+            libc::close(self.write);
+        }
+    }
+}
@@ -0,0 +1,435 @@
+//
+// Copyright (c) 2017, 2024 ZettaScale Technology.
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ZettaScale Zenoh team, <zenoh@zettascale.tech>
+//
+
+use std::{
+    mem::MaybeUninit,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    },
+    time::{Duration, Instant},
+};
+
+pub use crate::opaque_types::{
+    z_loaned_handler_set_t, z_moved_handler_set_t, z_owned_handler_set_t,
+};
+use crate::{
+    closures::channel_notifier::{ChannelNotifier, WaitSignal},
+    result::{self, z_result_t},
+    transmute::{LoanedCTypeRef, RustTypeRef, RustTypeRefUninit, TakeRustType},
+    z_loaned_fifo_handler_hello_t, z_loaned_fifo_handler_link_event_t,
+    z_loaned_fifo_handler_matching_status_t, z_loaned_fifo_handler_query_t,
+    z_loaned_fifo_handler_reply_t, z_loaned_fifo_handler_sample_t,
+    z_loaned_fifo_handler_transport_event_t, z_loaned_ring_handler_hello_t,
+    z_loaned_ring_handler_link_event_t, z_loaned_ring_handler_matching_status_t,
+    z_loaned_ring_handler_query_t, z_loaned_ring_handler_reply_t, z_loaned_ring_handler_sample_t,
+    z_loaned_ring_handler_transport_event_t,
+};
+
+pub struct HandlerSet {
+    signal: Arc<WaitSignal>,
+    entries: Vec<Option<Arc<ChannelNotifier>>>,
+    next: AtomicUsize,
+}
+
+impl HandlerSet {
+    fn new() -> Self {
+        Self {
+            signal: Arc::new(WaitSignal::default()),
+            entries: Vec::new(),
+            next: AtomicUsize::new(0),
+        }
+    }
+
+    fn add(&mut self, notifier: &Arc<ChannelNotifier>) -> usize {
+        notifier.add_waiter(&self.signal);
+        self.entries.push(Some(notifier.clone()));
+        self.entries.len() - 1
+    }
+
+    fn remove(&mut self, index: usize) -> bool {
+        match self.entries.get_mut(index).and_then(Option::take) {
+            Some(notifier) => {
+                notifier.remove_waiter(&self.signal);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Returns the index of a signaled handler, scanning the handlers in round-robin order,
+    /// so that a busy handler can not starve the others.
+    fn find_ready(&self) -> Option<usize> {
+        let len = self.entries.len();
+        let start = self.next.load(Ordering::Relaxed);
+        let index = (0..len)
+            .map(|i| (start + i) % len)
+            .find(|&i| self.entries[i].as_ref().is_some_and(|n| n.is_ready()))?;
+        self.next.store(index + 1, Ordering::Relaxed);
+        Some(index)
+    }
+
+    fn is_empty(&self) -> bool {
+        self.entries.iter().all(Option::is_none)
+    }
+
+    fn wait(&self) -> usize {
+        let mut guard = self.signal.mutex.lock().unwrap();
+        loop {
+            if let Some(index) = self.find_ready() {
+                return index;
+            }
+            guard = self.signal.condvar.wait(guard).unwrap();
+        }
+    }
+
+    fn wait_until(&self, deadline: Instant) -> Option<usize> {
+        let mut guard = self.signal.mutex.lock().unwrap();
+        loop {
+            if let Some(index) = self.find_ready() {
+                return Some(index);
+            }
+            let now = Instant::now();
+            if now >= deadline {
+                return None;
+            }
+            guard = self
+                .signal
+                .condvar
+                .wait_timeout(guard, deadline - now)
+                .unwrap()
+                .0;
+        }
+    }
+}
+
+/// Reports an error if the set is empty, since waiting on it would never return.
+fn check_not_empty(this: &HandlerSet) -> z_result_t {
+    if this.is_empty() {
+        crate::report_error!(
+            zc_error_kind_t::INVALID_ARGUMENT,
+            "Can not wait on an empty handler set"
+        );
+        return result::Z_EINVAL;
+    }
+    result::Z_OK
+}
+
+impl Drop for HandlerSet {
+    fn drop(&mut self) {
+        for notifier in self.entries.iter().flatten() {
+            notifier.remove_waiter(&self.signal);
+        }
+    }
+}
+
+decl_c_type!(
+    owned(z_owned_handler_set_t, option HandlerSet),
+    loaned(z_loaned_handler_set_t),
+);
+
+/// @warning This API has been marked as unstable: it works as advertised, but it may be changed in a future release.
+/// @brief Constructs an empty handler set.
+///
+/// A handler set allows to wait on several fifo or ring channel handlers (of any type) at once.
+#[no_mangle]
+pub extern "C" fn z_handler_set_new(this_: &mut MaybeUninit<z_owned_handler_set_t>) {
+    this_
+        .as_rust_type_mut_uninit()
+        .write(Some(HandlerSet::new()));
+}
+
+/// @warning This API has been marked as unstable: it works as advertised, but it may be changed in a future release.
+/// @brief Constructs a handler set in its gravestone state.
+#[no_mangle]
+pub extern "C" fn z_internal_handler_set_null(this_: &mut MaybeUninit<z_owned_handler_set_t>) {
+    this_.as_rust_type_mut_uninit().write(None);
+}
+
+/// @warning This API has been marked as unstable: it works as advertised, but it may be changed in a future release.
+/// @brief Returns ``true`` if handler set is valid, ``false`` if it is in a gravestone state.
+#[no_mangle]
+pub extern "C" fn z_internal_handler_set_check(this_: &z_owned_handler_set_t) -> bool {
+    this_.as_rust_type_ref().is_some()
+}
+
+/// @warning This API has been marked as unstable: it works as advertised, but it may be changed in a future release.
+/// @brief Borrows handler set.
+#[no_mangle]
+#[allow(clippy::missing_safety_doc)]
+pub unsafe extern "C" fn z_handler_set_loan(
+    this_: &z_owned_handler_set_t,
+) -> &z_loaned_handler_set_t {
+    this_
+        .as_rust_type_ref()
+        .as_ref()
+        .unwrap_unchecked()
+        .as_loaned_c_type_ref()
+}
+
+/// @warning This API has been marked as unstable: it works as advertised, but it may be changed in a future release.
+/// @brief Mutably borrows handler set.
+#[no_mangle]
+#[allow(clippy::missing_safety_doc)]
+pub unsafe extern "C" fn z_handler_set_loan_mut(
+    this_: &mut z_owned_handler_set_t,
+) -> &mut z_loaned_handler_set_t {
+    this_
+        .as_rust_type_mut()
+        .as_mut()
+        .unwrap_unchecked()
+        .as_loaned_c_type_mut()
+}
+
+/// @warning This API has been marked as unstable: it works as advertised, but it may be changed in a future release.
+/// @brief Drops the handler set and resets it to its gravestone state. The registered handlers are not affected.
+#[no_mangle]
+pub extern "C" fn z_handler_set_drop(this_: &mut z_moved_handler_set_t) {
+    let _ = this_.take_rust_type();
+}
+
+/// @warning This API has been marked as unstable: it works as advertised, but it may be changed in a future release.
+/// @brief Adds a fifo sample handler to the set.
+/// @return The index identifying the handler in the set.
+#[no_mangle]
+pub extern "C" fn z_handler_set_add_fifo_sample(
+    this_: &mut z_loaned_handler_set_t,
+    handler: &z_loaned_fifo_handler_sample_t,
+) -> usize {
+    this_.as_rust_type_mut().add(&handler.as_rust_type_ref().1)
+}
+
+/// @warning This API has been marked as unstable: it works as advertised, but it may be changed in a future release.
+/// @brief Adds a ring sample handler to the set.
+/// @return The index identifying the handler in the set.
+#[no_mangle]
+pub extern "C" fn z_handler_set_add_ring_sample(
+    this_: &mut z_loaned_handler_set_t,
+    handler: &z_loaned_ring_handler_sample_t,
+) -> usize {
+    this_.as_rust_type_mut().add(&handler.as_rust_type_ref().1)
+}
+
+/// @warning This API has been marked as unstable: it works as advertised, but it may be changed in a future release.
+/// @brief Adds a fifo query handler to the set.
+/// @return The index identifying the handler in the set.
+#[no_mangle]
+pub extern "C" fn z_handler_set_add_fifo_query(
+    this_: &mut z_loaned_handler_set_t,
+    handler: &z_loaned_fifo_handler_query_t,
+) -> usize {
+    this_.as_rust_type_mut().add(&handler.as_rust_type_ref().1)
+}
+
+/// @warning This API has been marked as unstable: it works as advertised, but it may be changed in a future release.
+/// @brief Adds a ring query handler to the set.
+/// @return The index identifying the handler in the set.
+#[no_mangle]
+pub extern "C" fn z_handler_set_add_ring_query(
+    this_: &mut z_loaned_handler_set_t,
+    handler: &z_loaned_ring_handler_query_t,
+) -> usize {
+    this_.as_rust_type_mut().add(&handler.as_rust_type_ref().1)
+}
+
+/// @warning This API has been marked as unstable: it works as advertised, but it may be changed in a future release.
+/// @brief Adds a fifo reply handler to the set.
+/// @return The index identifying the handler in the set.
+#[no_mangle]
+pub extern "C" fn z_handler_set_add_fifo_reply(
+    this_: &mut z_loaned_handler_set_t,
+    handler: &z_loaned_fifo_handler_reply_t,
+) -> usize {
+    this_.as_rust_type_mut().add(&handler.as_rust_type_ref().1)
+}
+
+/// @warning This API has been marked as unstable: it works as advertised, but it may be changed in a future release.
+/// @brief Adds a ring reply handler to the set.
+/// @return The index identifying the handler in the set.
+#[no_mangle]
+pub extern "C" fn z_handler_set_add_ring_reply(
+    this_: &mut z_loaned_handler_set_t,
+    handler: &z_loaned_ring_handler_reply_t,
+) -> usize {
+    this_.as_rust_type_mut().add(&handler.as_rust_type_ref().1)
+}
+
+/// @warning This API has been marked as unstable: it works as advertised, but it may be changed in a future release.
+/// @brief Adds a fifo hello handler to the set.
+/// @return The index identifying the handler in the set.
+#[no_mangle]
+pub extern "C" fn z_handler_set_add_fifo_hello(
+    this_: &mut z_loaned_handler_set_t,
+    handler: &z_loaned_fifo_handler_hello_t,
+) -> usize {
+    this_.as_rust_type_mut().add(&handler.as_rust_type_ref().1)
+}
+
+/// @warning This API has been marked as unstable: it works as advertised, but it may be changed in a future release.
+/// @brief Adds a ring hello handler to the set.
+/// @return The index identifying the handler in the set.
+#[no_mangle]
+pub extern "C" fn z_handler_set_add_ring_hello(
+    this_: &mut z_loaned_handler_set_t,
+    handler: &z_loaned_ring_handler_hello_t,
+) -> usize {
+    this_.as_rust_type_mut().add(&handler.as_rust_type_ref().1)
+}
+
+/// @warning This API has been marked as unstable: it works as advertised, but it may be changed in a future release.
+/// @brief Adds a fifo matching status handler to the set.
+/// @return The index identifying the handler in the set.
+#[no_mangle]
+pub extern "C" fn z_handler_set_add_fifo_matching_status(
+    this_: &mut z_loaned_handler_set_t,
+    handler: &z_loaned_fifo_handler_matching_status_t,
+) -> usize {
+    this_.as_rust_type_mut().add(&handler.as_rust_type_ref().1)
+}
+
+/// @warning This API has been marked as unstable: it works as advertised, but it may be changed in a future release.
+/// @brief Adds a ring matching status handler to the set.
+/// @return The index identifying the handler in the set.
+#[no_mangle]
+pub extern "C" fn z_handler_set_add_ring_matching_status(
+    this_: &mut z_loaned_handler_set_t,
+    handler: &z_loaned_ring_handler_matching_status_t,
+) -> usize {
+    this_.as_rust_type_mut().add(&handler.as_rust_type_ref().1)
+}
+
+/// @warning This API has been marked as unstable: it works as advertised, but it may be changed in a future release.
+/// @brief Adds a fifo transport event handler to the set.
+/// @return The index identifying the handler in the set.
+#[no_mangle]
+pub extern "C" fn z_handler_set_add_fifo_transport_event(
+    this_: &mut z_loaned_handler_set_t,
+    handler: &z_loaned_fifo_handler_transport_event_t,
+) -> usize {
+    this_.as_rust_type_mut().add(&handler.as_rust_type_ref().1)
+}
+
+/// @warning This API has been marked as unstable: it works as advertised, but it may be changed in a future release.
+/// @brief Adds a ring transport event handler to the set.
+/// @return The index identifying the handler in the set.
+#[no_mangle]
+pub extern "C" fn z_handler_set_add_ring_transport_event(
+    this_: &mut z_loaned_handler_set_t,
+    handler: &z_loaned_ring_handler_transport_event_t,
+) -> usize {
+    this_.as_rust_type_mut().add(&handler.as_rust_type_ref().1)
+}
+
+/// @warning This API has been marked as unstable: it works as advertised, but it may be changed in a future release.
+/// @brief Adds a fifo link event handler to the set.
+/// @return The index identifying the handler in the set.
+#[no_mangle]
+pub extern "C" fn z_handler_set_add_fifo_link_event(
+    this_: &mut z_loaned_handler_set_t,
+    handler: &z_loaned_fifo_handler_link_event_t,
+) -> usize {
+    this_.as_rust_type_mut().add(&handler.as_rust_type_ref().1)
+}
+
+/// @warning This API has been marked as unstable: it works as advertised, but it may be changed in a future release.
+/// @brief Adds a ring link event handler to the set.
+/// @return The index identifying the handler in the set.
+#[no_mangle]
+pub extern "C" fn z_handler_set_add_ring_link_event(
+    this_: &mut z_loaned_handler_set_t,
+    handler: &z_loaned_ring_handler_link_event_t,
+) -> usize {
+    this_.as_rust_type_mut().add(&handler.as_rust_type_ref().1)
+}
+
+/// @warning This API has been marked as unstable: it works as advertised, but it may be changed in a future release.
+/// @brief Removes the handler with specified index from the set. Indices of other handlers are not changed.
+///
+/// Handlers whose channel was disconnected stay signaled forever, so they should be removed from the set.
+/// @return 0 in case of success, `Z_EINVAL` if there is no handler with such index in the set.
+#[no_mangle]
+pub extern "C" fn z_handler_set_remove(
+    this_: &mut z_loaned_handler_set_t,
+    index: usize,
+) -> z_result_t {
+    if this_.as_rust_type_mut().remove(index) {
+        result::Z_OK
+    } else {
+        crate::report_error!(
+            zc_error_kind_t::INVALID_ARGUMENT,
+            "No handler with index {} in the set",
+            index
+        );
+        result::Z_EINVAL
+    }
+}
+
+/// @warning This API has been marked as unstable: it works as advertised, but it may be changed in a future release.
+/// @brief Blocks until one of the handlers in the set is signaled, i.e. new items were pushed into its buffer or its channel was dropped.
+///
+/// The handler stays signaled until its `try_recv` function returns `Z_CHANNEL_NODATA`, so once it is returned by this function,
+/// pending items should be received with `try_recv` until the buffer is empty. Spurious wakeups are possible.
+/// If several handlers are signaled they are returned in round-robin order.
+///
+/// @param this_: The handler set.
+/// @param out_index: Pointer where the index of signaled handler will be written.
+/// @return 0 in case of success, `Z_EINVAL` if the set is empty.
+#[no_mangle]
+pub extern "C" fn z_handler_set_wait(
+    this_: &z_loaned_handler_set_t,
+    out_index: &mut usize,
+) -> z_result_t {
+    let this = this_.as_rust_type_ref();
+    let r = check_not_empty(this);
+    if r != result::Z_OK {
+        return r;
+    }
+    *out_index = this.wait();
+    result::Z_OK
+}
+
+/// @warning This API has been marked as unstable: it works as advertised, but it may be changed in a future release.
+/// @brief Blocks until one of the handlers in the set is signaled or the timeout expires.
+///
+/// See `z_handler_set_wait()` for details.
+///
+/// @param this_: The handler set.
+/// @param timeout_ms: Maximum time to wait in milliseconds, 0 to return immediately. Timeouts too large to be
+/// represented as a deadline, such as `UINT64_MAX`, wait without timeout, like `z_handler_set_wait()`.
+/// @param out_index: Pointer where the index of signaled handler will be written.
+/// @return 0 in case of success, `Z_CHANNEL_NODATA` if no handler was signaled before the timeout expired,
+/// `Z_EINVAL` if the set is empty.
+#[no_mangle]
+pub extern "C" fn z_handler_set_wait_timeout(
+    this_: &z_loaned_handler_set_t,
+    timeout_ms: u64,
+    out_index: &mut usize,
+) -> z_result_t {
+    let this = this_.as_rust_type_ref();
+    let r = check_not_empty(this);
+    if r != result::Z_OK {
+        return r;
+    }
+    let Some(deadline) = Instant::now().checked_add(Duration::from_millis(timeout_ms)) else {
+        *out_index = this.wait();
+        return result::Z_OK;
+    };
+    match this.wait_until(deadline) {
+        Some(index) => {
+            *out_index = index;
+            result::Z_OK
+        }
+        None => result::Z_CHANNEL_NODATA,
+    }
+}
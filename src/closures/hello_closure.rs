@@ -91,7 +91,10 @@ pub extern "C" fn z_closure_hello_call(
     match closure._call {
         Some(call) => call(hello, closure._context),
         None => {
-            crate::report_error!("Attempted to call an uninitialized closure!");
+            crate::report_error!(
+                zc_error_kind_t::INVALID_ARGUMENT,
+                "Attempted to call an uninitialized closure!"
+            );
         }
     }
 }
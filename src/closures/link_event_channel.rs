@@ -0,0 +1,365 @@
+//
+// Copyright (c) 2017, 2024 ZettaScale Technology.
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ZettaScale Zenoh team, <zenoh@zettascale.tech>
+//
+
+use std::mem::MaybeUninit;
+
+use libc::{c_int, c_void};
+use zenoh::{
+    handlers::{FifoChannelHandler, RingChannelHandler},
+    session::LinkEvent,
+};
+
+use crate::{
+    closures::{
+        channel_handler::{self, new_fifo_channel, new_ring_channel},
+        channel_notifier::{notifying_callback_call, notifying_callback_drop, NotifyingHandler},
+    },
+    result::z_result_t,
+    transmute::{LoanedCTypeRef, RustTypeRef, RustTypeRefUninit, TakeRustType},
+    z_closure_link_event, z_loaned_link_event_t, z_owned_closure_link_event_t,
+    z_owned_link_event_t,
+};
+
+extern "C" fn __z_handler_link_event_send(event: &mut z_loaned_link_event_t, context: *mut c_void) {
+    let event = std::mem::replace(event.as_rust_type_mut(), LinkEvent::empty());
+    unsafe { notifying_callback_call(context, event) };
+}
+
+extern "C" fn __z_handler_link_event_drop(context: *mut c_void) {
+    unsafe { notifying_callback_drop::<LinkEvent>(context) };
+}
+
+pub use crate::opaque_types::{
+    z_loaned_fifo_handler_link_event_t, z_moved_fifo_handler_link_event_t,
+    z_owned_fifo_handler_link_event_t,
+};
+decl_c_type!(
+    owned(z_owned_fifo_handler_link_event_t, option NotifyingHandler<FifoChannelHandler<LinkEvent>>),
+    loaned(z_loaned_fifo_handler_link_event_t),
+);
+
+/// @warning This API has been marked as unstable: it works as advertised, but it may be changed in a future release.
+/// @brief Drops the handler and resets it to a gravestone state.
+#[no_mangle]
+pub extern "C" fn z_fifo_handler_link_event_drop(this_: &mut z_moved_fifo_handler_link_event_t) {
+    let _ = this_.take_rust_type();
+}
+
+/// @warning This API has been marked as unstable: it works as advertised, but it may be changed in a future release.
+/// @brief Constructs a handler in gravestone state.
+#[no_mangle]
+pub extern "C" fn z_internal_fifo_handler_link_event_null(
+    this_: &mut MaybeUninit<z_owned_fifo_handler_link_event_t>,
+) {
+    this_.as_rust_type_mut_uninit().write(None);
+}
+
+/// @warning This API has been marked as unstable: it works as advertised, but it may be changed in a future release.
+/// @brief Returns ``true`` if handler is valid, ``false`` if it is in gravestone state.
+#[no_mangle]
+pub extern "C" fn z_internal_fifo_handler_link_event_check(
+    this_: &z_owned_fifo_handler_link_event_t,
+) -> bool {
+    this_.as_rust_type_ref().is_some()
+}
+
+/// @warning This API has been marked as unstable: it works as advertised, but it may be changed in a future release.
+/// @brief Constructs send and recieve ends of the fifo channel of link events.
+#[no_mangle]
+#[allow(clippy::missing_safety_doc)]
+pub unsafe extern "C" fn z_fifo_channel_link_event_new(
+    callback: &mut MaybeUninit<z_owned_closure_link_event_t>,
+    handler: &mut MaybeUninit<z_owned_fifo_handler_link_event_t>,
+    capacity: usize,
+) {
+    let (context, h) = new_fifo_channel(capacity);
+    handler.as_rust_type_mut_uninit().write(Some(h));
+    z_closure_link_event(
+        callback,
+        Some(__z_handler_link_event_send),
+        Some(__z_handler_link_event_drop),
+        context,
+    );
+}
+
+/// @warning This API has been marked as unstable: it works as advertised, but it may be changed in a future release.
+/// @brief Borrows handler.
+#[no_mangle]
+#[allow(clippy::missing_safety_doc)]
+pub unsafe extern "C" fn z_fifo_handler_link_event_loan(
+    this_: &z_owned_fifo_handler_link_event_t,
+) -> &z_loaned_fifo_handler_link_event_t {
+    this_
+        .as_rust_type_ref()
+        .as_ref()
+        .unwrap_unchecked()
+        .as_loaned_c_type_ref()
+}
+
+/// @warning This API has been marked as unstable: it works as advertised, but it may be changed in a future release.
+/// @brief Returns link event from the fifo buffer. If there are no more pending link events will block until next link event is received, or until
+/// the channel is dropped.
+/// @return 0 in case of success, `Z_CHANNEL_DISCONNECTED` if channel was dropped (the link event will be in the gravestone state).
+#[no_mangle]
+pub extern "C" fn z_fifo_handler_link_event_recv(
+    this_: &z_loaned_fifo_handler_link_event_t,
+    event: &mut MaybeUninit<z_owned_link_event_t>,
+) -> z_result_t {
+    channel_handler::recv(this_.as_rust_type_ref(), |v| {
+        event.as_rust_type_mut_uninit().write(v);
+    })
+}
+
+/// @warning This API has been marked as unstable: it works as advertised, but it may be changed in a future release.
+/// @brief Returns link event from the fifo buffer. If there are no more pending link events will return immediately.
+/// @return 0 in case of success, `Z_CHANNEL_DISCONNECTED` if channel was dropped (the link event will be in the gravestone state),
+/// `Z_CHANNEL_NODATA` if the channel is still alive, but its buffer is empty (the link event will be in the gravestone state).
+#[no_mangle]
+pub extern "C" fn z_fifo_handler_link_event_try_recv(
+    this_: &z_loaned_fifo_handler_link_event_t,
+    event: &mut MaybeUninit<z_owned_link_event_t>,
+) -> z_result_t {
+    channel_handler::try_recv(this_.as_rust_type_ref(), |v| {
+        event.as_rust_type_mut_uninit().write(v);
+    })
+}
+
+/// @warning This API has been marked as unstable: it works as advertised, but it may be changed in a future release.
+/// @brief Returns link event from the fifo buffer. If there are no more pending link events will block until next link event is received, until
+/// the channel is dropped or until the timeout expires.
+///
+/// @param this_: The handler.
+/// @param event: An uninitialized memory location where the link event will be received.
+/// @param timeout_ms: Maximum time to wait in milliseconds, 0 to return immediately. Timeouts too large to be
+/// represented as a deadline, such as `UINT64_MAX`, wait without timeout.
+/// @return 0 in case of success, `Z_CHANNEL_DISCONNECTED` if channel was dropped (the link event will be in the gravestone state),
+/// `Z_CHANNEL_NODATA` if the timeout expired before a link event was received (the link event will be in the gravestone state).
+#[no_mangle]
+pub extern "C" fn z_fifo_handler_link_event_recv_timeout(
+    this_: &z_loaned_fifo_handler_link_event_t,
+    event: &mut MaybeUninit<z_owned_link_event_t>,
+    timeout_ms: u64,
+) -> z_result_t {
+    channel_handler::recv_timeout(this_.as_rust_type_ref(), timeout_ms, |v| {
+        event.as_rust_type_mut_uninit().write(v);
+    })
+}
+
+/// @warning This API has been marked as unstable: it works as advertised, but it may be changed in a future release.
+/// @brief Receives up to `max` pending link events from the fifo buffer in a single call. Does not block if the buffer is empty.
+///
+/// @param this_: The handler.
+/// @param events: An array of at least `max` uninitialized link events. On return, its first `out_count` elements are initialized with the received link events,
+/// the others are left untouched.
+/// @param max: The maximum number of link events to receive.
+/// @param out_count: Pointer where the number of received link events will be written.
+/// @return 0 if at least one link event was received, `Z_CHANNEL_NODATA` if the channel is still alive, but its buffer is empty,
+/// `Z_CHANNEL_DISCONNECTED` if the buffer is empty and the channel was dropped, `Z_EINVAL` if `events` is NULL.
+#[no_mangle]
+#[allow(clippy::missing_safety_doc)]
+pub unsafe extern "C" fn z_fifo_handler_link_event_recv_batch(
+    this_: &z_loaned_fifo_handler_link_event_t,
+    events: *mut MaybeUninit<z_owned_link_event_t>,
+    max: usize,
+    out_count: &mut usize,
+) -> z_result_t {
+    channel_handler::recv_batch(
+        this_.as_rust_type_ref(),
+        events,
+        max,
+        out_count,
+        |out, v| {
+            out.as_rust_type_mut_uninit().write(Some(v));
+        },
+    )
+}
+
+/// @warning This API has been marked as unstable: it works as advertised, but it may be changed in a future release.
+/// @brief Returns a file descriptor which becomes readable when new link events are pushed into the fifo buffer or when the channel is dropped.
+///
+/// The descriptor stays readable until `z_fifo_handler_link_event_try_recv()` returns `Z_CHANNEL_NODATA`.
+/// See `zc_fifo_handler_sample_get_fd()` for details.
+///
+/// @param this_: The handler.
+/// @param out_fd: Pointer where the file descriptor will be written.
+/// @return 0 in case of success, `Z_EUNAVAILABLE` if the platform does not support it, `Z_EIO` if the descriptor could not be created.
+#[no_mangle]
+pub extern "C" fn zc_fifo_handler_link_event_get_fd(
+    this_: &z_loaned_fifo_handler_link_event_t,
+    out_fd: &mut c_int,
+) -> z_result_t {
+    this_.as_rust_type_ref().1.get_fd(out_fd)
+}
+
+pub use crate::opaque_types::{
+    z_loaned_ring_handler_link_event_t, z_moved_ring_handler_link_event_t,
+    z_owned_ring_handler_link_event_t,
+};
+decl_c_type!(
+    owned(z_owned_ring_handler_link_event_t, option NotifyingHandler<RingChannelHandler<LinkEvent>>),
+    loaned(z_loaned_ring_handler_link_event_t),
+);
+
+/// @warning This API has been marked as unstable: it works as advertised, but it may be changed in a future release.
+/// @brief Drops the handler and resets it to a gravestone state.
+#[no_mangle]
+pub extern "C" fn z_ring_handler_link_event_drop(this_: &mut z_moved_ring_handler_link_event_t) {
+    let _ = this_.take_rust_type();
+}
+
+/// @warning This API has been marked as unstable: it works as advertised, but it may be changed in a future release.
+/// @brief Constructs a handler in gravestone state.
+#[no_mangle]
+pub extern "C" fn z_internal_ring_handler_link_event_null(
+    this_: &mut MaybeUninit<z_owned_ring_handler_link_event_t>,
+) {
+    this_.as_rust_type_mut_uninit().write(None);
+}
+
+/// @warning This API has been marked as unstable: it works as advertised, but it may be changed in a future release.
+/// @brief Returns ``true`` if handler is valid, ``false`` if it is in gravestone state.
+#[no_mangle]
+pub extern "C" fn z_internal_ring_handler_link_event_check(
+    this_: &z_owned_ring_handler_link_event_t,
+) -> bool {
+    this_.as_rust_type_ref().is_some()
+}
+
+/// @warning This API has been marked as unstable: it works as advertised, but it may be changed in a future release.
+/// @brief Constructs send and recieve ends of the ring channel of link events.
+#[no_mangle]
+#[allow(clippy::missing_safety_doc)]
+pub unsafe extern "C" fn z_ring_channel_link_event_new(
+    callback: &mut MaybeUninit<z_owned_closure_link_event_t>,
+    handler: &mut MaybeUninit<z_owned_ring_handler_link_event_t>,
+    capacity: usize,
+) {
+    let (context, h) = new_ring_channel(capacity);
+    handler.as_rust_type_mut_uninit().write(Some(h));
+    z_closure_link_event(
+        callback,
+        Some(__z_handler_link_event_send),
+        Some(__z_handler_link_event_drop),
+        context,
+    );
+}
+
+/// @warning This API has been marked as unstable: it works as advertised, but it may be changed in a future release.
+/// @brief Borrows handler.
+#[no_mangle]
+#[allow(clippy::missing_safety_doc)]
+pub unsafe extern "C" fn z_ring_handler_link_event_loan(
+    this_: &z_owned_ring_handler_link_event_t,
+) -> &z_loaned_ring_handler_link_event_t {
+    this_
+        .as_rust_type_ref()
+        .as_ref()
+        .unwrap_unchecked()
+        .as_loaned_c_type_ref()
+}
+
+/// @warning This API has been marked as unstable: it works as advertised, but it may be changed in a future release.
+/// @brief Returns link event from the ring buffer. If there are no more pending link events will block until next link event is received, or until
+/// the channel is dropped.
+/// @return 0 in case of success, `Z_CHANNEL_DISCONNECTED` if channel was dropped (the link event will be in the gravestone state).
+#[no_mangle]
+pub extern "C" fn z_ring_handler_link_event_recv(
+    this_: &z_loaned_ring_handler_link_event_t,
+    event: &mut MaybeUninit<z_owned_link_event_t>,
+) -> z_result_t {
+    channel_handler::recv(this_.as_rust_type_ref(), |v| {
+        event.as_rust_type_mut_uninit().write(v);
+    })
+}
+
+/// @warning This API has been marked as unstable: it works as advertised, but it may be changed in a future release.
+/// @brief Returns link event from the ring buffer. If there are no more pending link events will return immediately.
+/// @return 0 in case of success, `Z_CHANNEL_DISCONNECTED` if channel was dropped (the link event will be in the gravestone state),
+/// `Z_CHANNEL_NODATA` if the channel is still alive, but its buffer is empty (the link event will be in the gravestone state).
+#[no_mangle]
+pub extern "C" fn z_ring_handler_link_event_try_recv(
+    this_: &z_loaned_ring_handler_link_event_t,
+    event: &mut MaybeUninit<z_owned_link_event_t>,
+) -> z_result_t {
+    channel_handler::try_recv(this_.as_rust_type_ref(), |v| {
+        event.as_rust_type_mut_uninit().write(v);
+    })
+}
+
+/// @warning This API has been marked as unstable: it works as advertised, but it may be changed in a future release.
+/// @brief Returns link event from the ring buffer. If there are no more pending link events will block until next link event is received, until
+/// the channel is dropped or until the timeout expires.
+///
+/// @param this_: The handler.
+/// @param event: An uninitialized memory location where the link event will be received.
+/// @param timeout_ms: Maximum time to wait in milliseconds, 0 to return immediately. Timeouts too large to be
+/// represented as a deadline, such as `UINT64_MAX`, wait without timeout.
+/// @return 0 in case of success, `Z_CHANNEL_DISCONNECTED` if channel was dropped (the link event will be in the gravestone state),
+/// `Z_CHANNEL_NODATA` if the timeout expired before a link event was received (the link event will be in the gravestone state).
+#[no_mangle]
+pub extern "C" fn z_ring_handler_link_event_recv_timeout(
+    this_: &z_loaned_ring_handler_link_event_t,
+    event: &mut MaybeUninit<z_owned_link_event_t>,
+    timeout_ms: u64,
+) -> z_result_t {
+    channel_handler::recv_timeout(this_.as_rust_type_ref(), timeout_ms, |v| {
+        event.as_rust_type_mut_uninit().write(v);
+    })
+}
+
+/// @warning This API has been marked as unstable: it works as advertised, but it may be changed in a future release.
+/// @brief Receives up to `max` pending link events from the ring buffer in a single call. Does not block if the buffer is empty.
+///
+/// @param this_: The handler.
+/// @param events: An array of at least `max` uninitialized link events. On return, its first `out_count` elements are initialized with the received link events,
+/// the others are left untouched.
+/// @param max: The maximum number of link events to receive.
+/// @param out_count: Pointer where the number of received link events will be written.
+/// @return 0 if at least one link event was received, `Z_CHANNEL_NODATA` if the channel is still alive, but its buffer is empty,
+/// `Z_CHANNEL_DISCONNECTED` if the buffer is empty and the channel was dropped, `Z_EINVAL` if `events` is NULL.
+#[no_mangle]
+#[allow(clippy::missing_safety_doc)]
+pub unsafe extern "C" fn z_ring_handler_link_event_recv_batch(
+    this_: &z_loaned_ring_handler_link_event_t,
+    events: *mut MaybeUninit<z_owned_link_event_t>,
+    max: usize,
+    out_count: &mut usize,
+) -> z_result_t {
+    channel_handler::recv_batch(
+        this_.as_rust_type_ref(),
+        events,
+        max,
+        out_count,
+        |out, v| {
+            out.as_rust_type_mut_uninit().write(Some(v));
+        },
+    )
+}
+
+/// @warning This API has been marked as unstable: it works as advertised, but it may be changed in a future release.
+/// @brief Returns a file descriptor which becomes readable when new link events are pushed into the ring buffer or when the channel is dropped.
+///
+/// The descriptor stays readable until `z_ring_handler_link_event_try_recv()` returns `Z_CHANNEL_NODATA`.
+/// See `zc_ring_handler_sample_get_fd()` for details.
+///
+/// @param this_: The handler.
+/// @param out_fd: Pointer where the file descriptor will be written.
+/// @return 0 in case of success, `Z_EUNAVAILABLE` if the platform does not support it, `Z_EIO` if the descriptor could not be created.
+#[no_mangle]
+pub extern "C" fn zc_ring_handler_link_event_get_fd(
+    this_: &z_loaned_ring_handler_link_event_t,
+    out_fd: &mut c_int,
+) -> z_result_t {
+    this_.as_rust_type_ref().1.get_fd(out_fd)
+}
@@ -157,7 +157,10 @@ pub extern "C" fn zc_closure_log_call(
     match closure._call {
         Some(call) => call(severity, msg, closure._context),
         None => {
-            crate::report_error!("Attempted to call an uninitialized closure!");
+            crate::report_error!(
+                zc_error_kind_t::INVALID_ARGUMENT,
+                "Attempted to call an uninitialized closure!"
+            );
         }
     }
 }
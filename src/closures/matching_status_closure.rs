@@ -106,7 +106,10 @@ pub extern "C" fn z_closure_matching_status_call(
     match closure._call {
         Some(call) => call(mathing_status, closure._context),
         None => {
-            crate::report_error!("Attempted to call an uninitialized closure!");
+            crate::report_error!(
+                zc_error_kind_t::INVALID_ARGUMENT,
+                "Attempted to call an uninitialized closure!"
+            );
         }
     }
 }
@@ -104,7 +104,10 @@ pub extern "C" fn ze_closure_miss_call(
     match closure._call {
         Some(call) => call(mathing_status, closure._context),
         None => {
-            crate::report_error!("Attempted to call an uninitialized closure!");
+            crate::report_error!(
+                zc_error_kind_t::INVALID_ARGUMENT,
+                "Attempted to call an uninitialized closure!"
+            );
         }
     }
 }
@@ -23,6 +23,9 @@ mod reply_closure;
 pub use zenohid_closure::*;
 mod zenohid_closure;
 
+mod channel_handler;
+mod channel_notifier;
+
 pub use response_channel::*;
 mod response_channel;
 
@@ -32,6 +35,31 @@ mod query_channel;
 pub use sample_channel::*;
 mod sample_channel;
 
+#[cfg(feature = "unstable")]
+pub use hello_channel::*;
+#[cfg(feature = "unstable")]
+mod hello_channel;
+
+#[cfg(feature = "unstable")]
+pub use matching_status_channel::*;
+#[cfg(feature = "unstable")]
+mod matching_status_channel;
+
+#[cfg(feature = "unstable")]
+pub use transport_event_channel::*;
+#[cfg(feature = "unstable")]
+mod transport_event_channel;
+
+#[cfg(feature = "unstable")]
+pub use link_event_channel::*;
+#[cfg(feature = "unstable")]
+mod link_event_channel;
+
+#[cfg(feature = "unstable")]
+pub use handler_set::*;
+#[cfg(feature = "unstable")]
+mod handler_set;
+
 pub use hello_closure::*;
 mod hello_closure;
 
@@ -65,3 +93,8 @@ mod transport_event_closure;
 pub use link_event_closure::*;
 #[cfg(feature = "unstable")]
 mod link_event_closure;
+
+#[cfg(feature = "unstable")]
+pub use work_item_closure::*;
+#[cfg(feature = "unstable")]
+mod work_item_closure;
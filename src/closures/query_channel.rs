@@ -14,9 +14,11 @@
 
 use std::mem::MaybeUninit;
 
+#[cfg(feature = "unstable")]
+use libc::c_int;
 use libc::c_void;
 use zenoh::{
-    handlers::{self, Callback, FifoChannelHandler, IntoHandler, RingChannelHandler},
+    handlers::{FifoChannelHandler, RingChannelHandler},
     query::Query,
 };
 
@@ -24,12 +26,16 @@ pub use crate::opaque_types::{
     z_loaned_fifo_handler_query_t, z_moved_fifo_handler_query_t, z_owned_fifo_handler_query_t,
 };
 use crate::{
-    result::{self, z_result_t},
+    closures::{
+        channel_handler::{self, new_fifo_channel, new_ring_channel},
+        channel_notifier::{notifying_callback_call, notifying_callback_drop, NotifyingHandler},
+    },
+    result::z_result_t,
     transmute::{LoanedCTypeRef, RustTypeRef, RustTypeRefUninit, TakeRustType},
-    z_loaned_query_t, z_owned_closure_query_t, z_owned_query_t,
+    z_closure_query, z_loaned_query_t, z_owned_closure_query_t, z_owned_query_t,
 };
 decl_c_type!(
-    owned(z_owned_fifo_handler_query_t, option FifoChannelHandler<Query> ),
+    owned(z_owned_fifo_handler_query_t, option NotifyingHandler<FifoChannelHandler<Query>>),
     loaned(z_loaned_fifo_handler_query_t),
 );
 
@@ -57,19 +63,13 @@ pub extern "C" fn z_internal_fifo_handler_query_check(
 
 extern "C" fn __z_handler_query_send(query: &mut z_loaned_query_t, context: *mut c_void) {
     unsafe {
-        let f = (context as *mut Callback<Query>)
-            .as_mut()
-            .unwrap_unchecked();
         let owned_ref: &mut Option<Query> = std::mem::transmute(query);
-        f.call(std::mem::take(owned_ref).unwrap_unchecked());
+        notifying_callback_call(context, std::mem::take(owned_ref).unwrap_unchecked());
     }
 }
 
 extern "C" fn __z_handler_query_drop(context: *mut c_void) {
-    unsafe {
-        let f = Box::from_raw(context as *mut Callback<Query>);
-        std::mem::drop(f);
-    }
+    unsafe { notifying_callback_drop::<Query>(context) };
 }
 
 /// Constructs send and recieve ends of the fifo channel
@@ -80,15 +80,14 @@ pub unsafe extern "C" fn z_fifo_channel_query_new(
     handler: &mut MaybeUninit<z_owned_fifo_handler_query_t>,
     capacity: usize,
 ) {
-    let fifo = handlers::FifoChannel::new(capacity);
-    let (cb, h) = fifo.into_handler();
-    let cb_ptr = Box::into_raw(Box::new(cb)) as *mut libc::c_void;
+    let (context, h) = new_fifo_channel(capacity);
     handler.as_rust_type_mut_uninit().write(Some(h));
-    callback.write(z_owned_closure_query_t {
-        _call: Some(__z_handler_query_send),
-        _context: cb_ptr,
-        _drop: Some(__z_handler_query_drop),
-    });
+    z_closure_query(
+        callback,
+        Some(__z_handler_query_send),
+        Some(__z_handler_query_drop),
+        context,
+    );
 }
 
 /// Borrows handler.
@@ -112,16 +111,9 @@ pub extern "C" fn z_fifo_handler_query_recv(
     this: &z_loaned_fifo_handler_query_t,
     query: &mut MaybeUninit<z_owned_query_t>,
 ) -> z_result_t {
-    match this.as_rust_type_ref().recv() {
-        Ok(q) => {
-            query.as_rust_type_mut_uninit().write(Some(q));
-            result::Z_OK
-        }
-        Err(_) => {
-            query.as_rust_type_mut_uninit().write(None);
-            result::Z_CHANNEL_DISCONNECTED
-        }
-    }
+    channel_handler::recv(this.as_rust_type_ref(), |v| {
+        query.as_rust_type_mut_uninit().write(v);
+    })
 }
 
 /// Returns query from the fifo buffer. If there are no more pending queries will return immediately (with query set to its gravestone state).
@@ -132,30 +124,92 @@ pub extern "C" fn z_fifo_handler_query_try_recv(
     this: &z_loaned_fifo_handler_query_t,
     query: &mut MaybeUninit<z_owned_query_t>,
 ) -> z_result_t {
-    match this.as_rust_type_ref().try_recv() {
-        Ok(Some(q)) => {
-            query.as_rust_type_mut_uninit().write(Some(q));
-            result::Z_OK
-        }
-        Ok(None) => {
-            query.as_rust_type_mut_uninit().write(None);
-            result::Z_CHANNEL_NODATA
-        }
-        Err(_) => {
-            query.as_rust_type_mut_uninit().write(None);
-            result::Z_CHANNEL_DISCONNECTED
-        }
-    }
+    channel_handler::try_recv(this.as_rust_type_ref(), |v| {
+        query.as_rust_type_mut_uninit().write(v);
+    })
+}
+
+#[cfg(feature = "unstable")]
+/// @warning This API has been marked as unstable: it works as advertised, but it may be changed in a future release.
+/// @brief Returns query from the fifo buffer. If there are no more pending queries will block until next query is received, until
+/// the channel is dropped or until the timeout expires.
+///
+/// @param this_: The handler.
+/// @param query: An uninitialized memory location where the query will be received.
+/// @param timeout_ms: Maximum time to wait in milliseconds, 0 to return immediately. Timeouts too large to be
+/// represented as a deadline, such as `UINT64_MAX`, wait without timeout.
+/// @return 0 in case of success, `Z_CHANNEL_DISCONNECTED` if channel was dropped (the query will be in the gravestone state),
+/// `Z_CHANNEL_NODATA` if the timeout expired before a query was received (the query will be in the gravestone state).
+#[no_mangle]
+pub extern "C" fn z_fifo_handler_query_recv_timeout(
+    this_: &z_loaned_fifo_handler_query_t,
+    query: &mut MaybeUninit<z_owned_query_t>,
+    timeout_ms: u64,
+) -> z_result_t {
+    channel_handler::recv_timeout(this_.as_rust_type_ref(), timeout_ms, |v| {
+        query.as_rust_type_mut_uninit().write(v);
+    })
+}
+
+#[cfg(feature = "unstable")]
+/// @warning This API has been marked as unstable: it works as advertised, but it may be changed in a future release.
+/// @brief Receives up to `max` pending queries from the fifo buffer in a single call. Does not block if the buffer is empty.
+///
+/// This is equivalent to calling `z_fifo_handler_query_try_recv()` in a loop, but reduces the per-query overhead. To wait for the first query, it can be combined
+/// with `z_fifo_handler_query_recv_timeout()`, `zc_fifo_handler_query_get_fd()` or a handler set.
+///
+/// @param this_: The handler.
+/// @param queries: An array of at least `max` uninitialized queries. On return, its first `out_count` elements are initialized with the received queries,
+/// the others are left untouched.
+/// @param max: The maximum number of queries to receive.
+/// @param out_count: Pointer where the number of received queries will be written.
+/// @return 0 if at least one query was received, `Z_CHANNEL_NODATA` if the channel is still alive, but its buffer is empty,
+/// `Z_CHANNEL_DISCONNECTED` if the buffer is empty and the channel was dropped, `Z_EINVAL` if `queries` is NULL.
+#[no_mangle]
+#[allow(clippy::missing_safety_doc)]
+pub unsafe extern "C" fn z_fifo_handler_query_recv_batch(
+    this_: &z_loaned_fifo_handler_query_t,
+    queries: *mut MaybeUninit<z_owned_query_t>,
+    max: usize,
+    out_count: &mut usize,
+) -> z_result_t {
+    channel_handler::recv_batch(
+        this_.as_rust_type_ref(),
+        queries,
+        max,
+        out_count,
+        |out, v| {
+            out.as_rust_type_mut_uninit().write(Some(v));
+        },
+    )
+}
+
+#[cfg(feature = "unstable")]
+/// @warning This API has been marked as unstable: it works as advertised, but it may be changed in a future release.
+/// @brief Returns a file descriptor which becomes readable when new queries are pushed into the fifo buffer or when the channel is dropped.
+///
+/// This allows to wait for the handler in an external event loop (e.g. with `poll()`, `epoll` or `libuv`), along with other descriptors.
+/// The descriptor stays readable until `z_fifo_handler_query_try_recv()` returns `Z_CHANNEL_NODATA`, so once it is signaled,
+/// pending queries should be received with `z_fifo_handler_query_try_recv()` until the buffer is empty. Spurious wakeups are possible.
+/// The descriptor is owned by the handler: it should not be read from or closed by the user and it remains valid until the handler is dropped.
+///
+/// @param this_: The handler.
+/// @param out_fd: Pointer where the file descriptor will be written.
+/// @return 0 in case of success, `Z_EUNAVAILABLE` if the platform does not support it (currently only unix platforms are supported, using eventfd on Linux),
+/// `Z_EIO` if the descriptor could not be created.
+#[no_mangle]
+pub extern "C" fn zc_fifo_handler_query_get_fd(
+    this_: &z_loaned_fifo_handler_query_t,
+    out_fd: &mut c_int,
+) -> z_result_t {
+    this_.as_rust_type_ref().1.get_fd(out_fd)
 }
 
 pub use crate::opaque_types::{
     z_loaned_ring_handler_query_t, z_moved_ring_handler_query_t, z_owned_ring_handler_query_t,
 };
 decl_c_type!(
-    owned(
-        z_owned_ring_handler_query_t,
-        option RingChannelHandler<Query>,
-    ),
+    owned(z_owned_ring_handler_query_t, option NotifyingHandler<RingChannelHandler<Query>>),
     loaned(z_loaned_ring_handler_query_t),
 );
 
@@ -189,15 +243,14 @@ pub unsafe extern "C" fn z_ring_channel_query_new(
     handler: &mut MaybeUninit<z_owned_ring_handler_query_t>,
     capacity: usize,
 ) {
-    let ring = handlers::RingChannel::new(capacity);
-    let (cb, h) = ring.into_handler();
-    let cb_ptr = Box::into_raw(Box::new(cb)) as *mut libc::c_void;
+    let (context, h) = new_ring_channel(capacity);
     handler.as_rust_type_mut_uninit().write(Some(h));
-    callback.write(z_owned_closure_query_t {
-        _call: Some(__z_handler_query_send),
-        _context: cb_ptr,
-        _drop: Some(__z_handler_query_drop),
-    });
+    z_closure_query(
+        callback,
+        Some(__z_handler_query_send),
+        Some(__z_handler_query_drop),
+        context,
+    );
 }
 
 /// Borrows handler.
@@ -220,16 +273,9 @@ pub extern "C" fn z_ring_handler_query_recv(
     this: &z_loaned_ring_handler_query_t,
     query: &mut MaybeUninit<z_owned_query_t>,
 ) -> z_result_t {
-    match this.as_rust_type_ref().recv() {
-        Ok(q) => {
-            query.as_rust_type_mut_uninit().write(Some(q));
-            result::Z_OK
-        }
-        Err(_) => {
-            query.as_rust_type_mut_uninit().write(None);
-            result::Z_CHANNEL_DISCONNECTED
-        }
-    }
+    channel_handler::recv(this.as_rust_type_ref(), |v| {
+        query.as_rust_type_mut_uninit().write(v);
+    })
 }
 
 /// Returns query from the ring buffer. If there are no more pending queries will return immediately (with query set to its gravestone state).
@@ -240,19 +286,83 @@ pub extern "C" fn z_ring_handler_query_try_recv(
     this: &z_loaned_ring_handler_query_t,
     query: &mut MaybeUninit<z_owned_query_t>,
 ) -> z_result_t {
-    match this.as_rust_type_ref().try_recv() {
-        Ok(q) => {
-            let r = if q.is_some() {
-                result::Z_OK
-            } else {
-                result::Z_CHANNEL_NODATA
-            };
-            query.as_rust_type_mut_uninit().write(q);
-            r
-        }
-        Err(_) => {
-            query.as_rust_type_mut_uninit().write(None);
-            result::Z_CHANNEL_DISCONNECTED
-        }
-    }
+    channel_handler::try_recv(this.as_rust_type_ref(), |v| {
+        query.as_rust_type_mut_uninit().write(v);
+    })
+}
+
+#[cfg(feature = "unstable")]
+/// @warning This API has been marked as unstable: it works as advertised, but it may be changed in a future release.
+/// @brief Returns query from the ring buffer. If there are no more pending queries will block until next query is received, until
+/// the channel is dropped or until the timeout expires.
+///
+/// @param this_: The handler.
+/// @param query: An uninitialized memory location where the query will be received.
+/// @param timeout_ms: Maximum time to wait in milliseconds, 0 to return immediately. Timeouts too large to be
+/// represented as a deadline, such as `UINT64_MAX`, wait without timeout.
+/// @return 0 in case of success, `Z_CHANNEL_DISCONNECTED` if channel was dropped (the query will be in the gravestone state),
+/// `Z_CHANNEL_NODATA` if the timeout expired before a query was received (the query will be in the gravestone state).
+#[no_mangle]
+pub extern "C" fn z_ring_handler_query_recv_timeout(
+    this_: &z_loaned_ring_handler_query_t,
+    query: &mut MaybeUninit<z_owned_query_t>,
+    timeout_ms: u64,
+) -> z_result_t {
+    channel_handler::recv_timeout(this_.as_rust_type_ref(), timeout_ms, |v| {
+        query.as_rust_type_mut_uninit().write(v);
+    })
+}
+
+#[cfg(feature = "unstable")]
+/// @warning This API has been marked as unstable: it works as advertised, but it may be changed in a future release.
+/// @brief Receives up to `max` pending queries from the ring buffer in a single call. Does not block if the buffer is empty.
+///
+/// This is equivalent to calling `z_ring_handler_query_try_recv()` in a loop, but reduces the per-query overhead. To wait for the first query, it can be combined
+/// with `z_ring_handler_query_recv_timeout()`, `zc_ring_handler_query_get_fd()` or a handler set.
+///
+/// @param this_: The handler.
+/// @param queries: An array of at least `max` uninitialized queries. On return, its first `out_count` elements are initialized with the received queries,
+/// the others are left untouched.
+/// @param max: The maximum number of queries to receive.
+/// @param out_count: Pointer where the number of received queries will be written.
+/// @return 0 if at least one query was received, `Z_CHANNEL_NODATA` if the channel is still alive, but its buffer is empty,
+/// `Z_CHANNEL_DISCONNECTED` if the buffer is empty and the channel was dropped, `Z_EINVAL` if `queries` is NULL.
+#[no_mangle]
+#[allow(clippy::missing_safety_doc)]
+pub unsafe extern "C" fn z_ring_handler_query_recv_batch(
+    this_: &z_loaned_ring_handler_query_t,
+    queries: *mut MaybeUninit<z_owned_query_t>,
+    max: usize,
+    out_count: &mut usize,
+) -> z_result_t {
+    channel_handler::recv_batch(
+        this_.as_rust_type_ref(),
+        queries,
+        max,
+        out_count,
+        |out, v| {
+            out.as_rust_type_mut_uninit().write(Some(v));
+        },
+    )
+}
+
+#[cfg(feature = "unstable")]
+/// @warning This API has been marked as unstable: it works as advertised, but it may be changed in a future release.
+/// @brief Returns a file descriptor which becomes readable when new queries are pushed into the ring buffer or when the channel is dropped.
+///
+/// This allows to wait for the handler in an external event loop (e.g. with `poll()`, `epoll` or `libuv`), along with other descriptors.
+/// The descriptor stays readable until `z_ring_handler_query_try_recv()` returns `Z_CHANNEL_NODATA`, so once it is signaled,
+/// pending queries should be received with `z_ring_handler_query_try_recv()` until the buffer is empty. Spurious wakeups are possible.
+/// The descriptor is owned by the handler: it should not be read from or closed by the user and it remains valid until the handler is dropped.
+///
+/// @param this_: The handler.
+/// @param out_fd: Pointer where the file descriptor will be written.
+/// @return 0 in case of success, `Z_EUNAVAILABLE` if the platform does not support it (currently only unix platforms are supported, using eventfd on Linux),
+/// `Z_EIO` if the descriptor could not be created.
+#[no_mangle]
+pub extern "C" fn zc_ring_handler_query_get_fd(
+    this_: &z_loaned_ring_handler_query_t,
+    out_fd: &mut c_int,
+) -> z_result_t {
+    this_.as_rust_type_ref().1.get_fd(out_fd)
 }
@@ -14,9 +14,11 @@
 
 use std::mem::MaybeUninit;
 
+#[cfg(feature = "unstable")]
+use libc::c_int;
 use libc::c_void;
 use zenoh::{
-    handlers::{self, Callback, FifoChannelHandler, IntoHandler, RingChannelHandler},
+    handlers::{FifoChannelHandler, RingChannelHandler},
     query::Reply,
 };
 
@@ -24,12 +26,16 @@ pub use crate::opaque_types::{
     z_loaned_fifo_handler_reply_t, z_moved_fifo_handler_reply_t, z_owned_fifo_handler_reply_t,
 };
 use crate::{
-    result::{self, z_result_t},
+    closures::{
+        channel_handler::{self, new_fifo_channel, new_ring_channel},
+        channel_notifier::{notifying_callback_call, notifying_callback_drop, NotifyingHandler},
+    },
+    result::z_result_t,
     transmute::{LoanedCTypeRef, RustTypeRef, RustTypeRefUninit, TakeRustType},
-    z_loaned_reply_t, z_owned_closure_reply_t, z_owned_reply_t,
+    z_closure_reply, z_loaned_reply_t, z_owned_closure_reply_t, z_owned_reply_t,
 };
 decl_c_type!(
-    owned(z_owned_fifo_handler_reply_t, option FifoChannelHandler<Reply>),
+    owned(z_owned_fifo_handler_reply_t, option NotifyingHandler<FifoChannelHandler<Reply>>),
     loaned(z_loaned_fifo_handler_reply_t),
 );
 
@@ -57,19 +63,13 @@ pub extern "C" fn z_internal_fifo_handler_reply_check(
 
 extern "C" fn __z_handler_reply_send(reply: &mut z_loaned_reply_t, context: *mut c_void) {
     unsafe {
-        let f = (context as *mut Callback<Reply>)
-            .as_mut()
-            .unwrap_unchecked();
         let owned_ref: &mut Option<Reply> = std::mem::transmute(reply);
-        f.call(std::mem::take(owned_ref).unwrap_unchecked());
+        notifying_callback_call(context, std::mem::take(owned_ref).unwrap_unchecked());
     }
 }
 
 extern "C" fn __z_handler_reply_drop(context: *mut c_void) {
-    unsafe {
-        let f = Box::from_raw(context as *mut Callback<Reply>);
-        std::mem::drop(f);
-    }
+    unsafe { notifying_callback_drop::<Reply>(context) };
 }
 
 /// Constructs send and recieve ends of the fifo channel
@@ -80,15 +80,14 @@ pub unsafe extern "C" fn z_fifo_channel_reply_new(
     handler: &mut MaybeUninit<z_owned_fifo_handler_reply_t>,
     capacity: usize,
 ) {
-    let fifo = handlers::FifoChannel::new(capacity);
-    let (cb, h) = fifo.into_handler();
-    let cb_ptr = Box::into_raw(Box::new(cb)) as *mut libc::c_void;
+    let (context, h) = new_fifo_channel(capacity);
     handler.as_rust_type_mut_uninit().write(Some(h));
-    callback.write(z_owned_closure_reply_t {
-        _call: Some(__z_handler_reply_send),
-        _context: cb_ptr,
-        _drop: Some(__z_handler_reply_drop),
-    });
+    z_closure_reply(
+        callback,
+        Some(__z_handler_reply_send),
+        Some(__z_handler_reply_drop),
+        context,
+    );
 }
 
 /// Borrows handler.
@@ -111,16 +110,9 @@ pub extern "C" fn z_fifo_handler_reply_recv(
     this: &z_loaned_fifo_handler_reply_t,
     reply: &mut MaybeUninit<z_owned_reply_t>,
 ) -> z_result_t {
-    match this.as_rust_type_ref().recv() {
-        Ok(q) => {
-            reply.as_rust_type_mut_uninit().write(Some(q));
-            result::Z_OK
-        }
-        Err(_) => {
-            reply.as_rust_type_mut_uninit().write(None);
-            result::Z_CHANNEL_DISCONNECTED
-        }
-    }
+    channel_handler::recv(this.as_rust_type_ref(), |v| {
+        reply.as_rust_type_mut_uninit().write(v);
+    })
 }
 
 /// Returns reply from the fifo buffer. If there are no more pending replies will return immediately (with reply set to its gravestone state).
@@ -131,27 +123,92 @@ pub extern "C" fn z_fifo_handler_reply_try_recv(
     this: &z_loaned_fifo_handler_reply_t,
     reply: &mut MaybeUninit<z_owned_reply_t>,
 ) -> z_result_t {
-    match this.as_rust_type_ref().try_recv() {
-        Ok(Some(q)) => {
-            reply.as_rust_type_mut_uninit().write(Some(q));
-            result::Z_OK
-        }
-        Ok(None) => {
-            reply.as_rust_type_mut_uninit().write(None);
-            result::Z_CHANNEL_NODATA
-        }
-        Err(_) => {
-            reply.as_rust_type_mut_uninit().write(None);
-            result::Z_CHANNEL_DISCONNECTED
-        }
-    }
+    channel_handler::try_recv(this.as_rust_type_ref(), |v| {
+        reply.as_rust_type_mut_uninit().write(v);
+    })
+}
+
+#[cfg(feature = "unstable")]
+/// @warning This API has been marked as unstable: it works as advertised, but it may be changed in a future release.
+/// @brief Returns reply from the fifo buffer. If there are no more pending replies will block until next reply is received, until
+/// the channel is dropped or until the timeout expires.
+///
+/// @param this_: The handler.
+/// @param reply: An uninitialized memory location where the reply will be received.
+/// @param timeout_ms: Maximum time to wait in milliseconds, 0 to return immediately. Timeouts too large to be
+/// represented as a deadline, such as `UINT64_MAX`, wait without timeout.
+/// @return 0 in case of success, `Z_CHANNEL_DISCONNECTED` if channel was dropped (the reply will be in the gravestone state),
+/// `Z_CHANNEL_NODATA` if the timeout expired before a reply was received (the reply will be in the gravestone state).
+#[no_mangle]
+pub extern "C" fn z_fifo_handler_reply_recv_timeout(
+    this_: &z_loaned_fifo_handler_reply_t,
+    reply: &mut MaybeUninit<z_owned_reply_t>,
+    timeout_ms: u64,
+) -> z_result_t {
+    channel_handler::recv_timeout(this_.as_rust_type_ref(), timeout_ms, |v| {
+        reply.as_rust_type_mut_uninit().write(v);
+    })
+}
+
+#[cfg(feature = "unstable")]
+/// @warning This API has been marked as unstable: it works as advertised, but it may be changed in a future release.
+/// @brief Receives up to `max` pending replies from the fifo buffer in a single call. Does not block if the buffer is empty.
+///
+/// This is equivalent to calling `z_fifo_handler_reply_try_recv()` in a loop, but reduces the per-reply overhead. To wait for the first reply, it can be combined
+/// with `z_fifo_handler_reply_recv_timeout()`, `zc_fifo_handler_reply_get_fd()` or a handler set.
+///
+/// @param this_: The handler.
+/// @param replies: An array of at least `max` uninitialized replies. On return, its first `out_count` elements are initialized with the received replies,
+/// the others are left untouched.
+/// @param max: The maximum number of replies to receive.
+/// @param out_count: Pointer where the number of received replies will be written.
+/// @return 0 if at least one reply was received, `Z_CHANNEL_NODATA` if the channel is still alive, but its buffer is empty,
+/// `Z_CHANNEL_DISCONNECTED` if the buffer is empty and the channel was dropped, `Z_EINVAL` if `replies` is NULL.
+#[no_mangle]
+#[allow(clippy::missing_safety_doc)]
+pub unsafe extern "C" fn z_fifo_handler_reply_recv_batch(
+    this_: &z_loaned_fifo_handler_reply_t,
+    replies: *mut MaybeUninit<z_owned_reply_t>,
+    max: usize,
+    out_count: &mut usize,
+) -> z_result_t {
+    channel_handler::recv_batch(
+        this_.as_rust_type_ref(),
+        replies,
+        max,
+        out_count,
+        |out, v| {
+            out.as_rust_type_mut_uninit().write(Some(v));
+        },
+    )
+}
+
+#[cfg(feature = "unstable")]
+/// @warning This API has been marked as unstable: it works as advertised, but it may be changed in a future release.
+/// @brief Returns a file descriptor which becomes readable when new replies are pushed into the fifo buffer or when the channel is dropped.
+///
+/// This allows to wait for the handler in an external event loop (e.g. with `poll()`, `epoll` or `libuv`), along with other descriptors.
+/// The descriptor stays readable until `z_fifo_handler_reply_try_recv()` returns `Z_CHANNEL_NODATA`, so once it is signaled,
+/// pending replies should be received with `z_fifo_handler_reply_try_recv()` until the buffer is empty. Spurious wakeups are possible.
+/// The descriptor is owned by the handler: it should not be read from or closed by the user and it remains valid until the handler is dropped.
+///
+/// @param this_: The handler.
+/// @param out_fd: Pointer where the file descriptor will be written.
+/// @return 0 in case of success, `Z_EUNAVAILABLE` if the platform does not support it (currently only unix platforms are supported, using eventfd on Linux),
+/// `Z_EIO` if the descriptor could not be created.
+#[no_mangle]
+pub extern "C" fn zc_fifo_handler_reply_get_fd(
+    this_: &z_loaned_fifo_handler_reply_t,
+    out_fd: &mut c_int,
+) -> z_result_t {
+    this_.as_rust_type_ref().1.get_fd(out_fd)
 }
 
 pub use crate::opaque_types::{
     z_loaned_ring_handler_reply_t, z_moved_ring_handler_reply_t, z_owned_ring_handler_reply_t,
 };
 decl_c_type!(
-    owned(z_owned_ring_handler_reply_t, option RingChannelHandler<Reply>),
+    owned(z_owned_ring_handler_reply_t, option NotifyingHandler<RingChannelHandler<Reply>>),
     loaned(z_loaned_ring_handler_reply_t),
 );
 
@@ -185,15 +242,14 @@ pub unsafe extern "C" fn z_ring_channel_reply_new(
     handler: &mut MaybeUninit<z_owned_ring_handler_reply_t>,
     capacity: usize,
 ) {
-    let ring = handlers::RingChannel::new(capacity);
-    let (cb, h) = ring.into_handler();
-    let cb_ptr = Box::into_raw(Box::new(cb)) as *mut libc::c_void;
+    let (context, h) = new_ring_channel(capacity);
     handler.as_rust_type_mut_uninit().write(Some(h));
-    callback.write(z_owned_closure_reply_t {
-        _call: Some(__z_handler_reply_send),
-        _context: cb_ptr,
-        _drop: Some(__z_handler_reply_drop),
-    });
+    z_closure_reply(
+        callback,
+        Some(__z_handler_reply_send),
+        Some(__z_handler_reply_drop),
+        context,
+    );
 }
 
 /// Borrows handler.
@@ -216,16 +272,9 @@ pub extern "C" fn z_ring_handler_reply_recv(
     this: &z_loaned_ring_handler_reply_t,
     reply: &mut MaybeUninit<z_owned_reply_t>,
 ) -> z_result_t {
-    match this.as_rust_type_ref().recv() {
-        Ok(q) => {
-            reply.as_rust_type_mut_uninit().write(Some(q));
-            result::Z_OK
-        }
-        Err(_) => {
-            reply.as_rust_type_mut_uninit().write(None);
-            result::Z_CHANNEL_DISCONNECTED
-        }
-    }
+    channel_handler::recv(this.as_rust_type_ref(), |v| {
+        reply.as_rust_type_mut_uninit().write(v);
+    })
 }
 
 /// Returns reply from the ring buffer. If there are no more pending replies will return immediately (with reply set to its gravestone state).
@@ -236,19 +285,83 @@ pub extern "C" fn z_ring_handler_reply_try_recv(
     this: &z_loaned_ring_handler_reply_t,
     reply: &mut MaybeUninit<z_owned_reply_t>,
 ) -> z_result_t {
-    match this.as_rust_type_ref().try_recv() {
-        Ok(q) => {
-            let r = if q.is_some() {
-                result::Z_OK
-            } else {
-                result::Z_CHANNEL_NODATA
-            };
-            reply.as_rust_type_mut_uninit().write(q);
-            r
-        }
-        Err(_) => {
-            reply.as_rust_type_mut_uninit().write(None);
-            result::Z_CHANNEL_DISCONNECTED
-        }
-    }
+    channel_handler::try_recv(this.as_rust_type_ref(), |v| {
+        reply.as_rust_type_mut_uninit().write(v);
+    })
+}
+
+#[cfg(feature = "unstable")]
+/// @warning This API has been marked as unstable: it works as advertised, but it may be changed in a future release.
+/// @brief Returns reply from the ring buffer. If there are no more pending replies will block until next reply is received, until
+/// the channel is dropped or until the timeout expires.
+///
+/// @param this_: The handler.
+/// @param reply: An uninitialized memory location where the reply will be received.
+/// @param timeout_ms: Maximum time to wait in milliseconds, 0 to return immediately. Timeouts too large to be
+/// represented as a deadline, such as `UINT64_MAX`, wait without timeout.
+/// @return 0 in case of success, `Z_CHANNEL_DISCONNECTED` if channel was dropped (the reply will be in the gravestone state),
+/// `Z_CHANNEL_NODATA` if the timeout expired before a reply was received (the reply will be in the gravestone state).
+#[no_mangle]
+pub extern "C" fn z_ring_handler_reply_recv_timeout(
+    this_: &z_loaned_ring_handler_reply_t,
+    reply: &mut MaybeUninit<z_owned_reply_t>,
+    timeout_ms: u64,
+) -> z_result_t {
+    channel_handler::recv_timeout(this_.as_rust_type_ref(), timeout_ms, |v| {
+        reply.as_rust_type_mut_uninit().write(v);
+    })
+}
+
+#[cfg(feature = "unstable")]
+/// @warning This API has been marked as unstable: it works as advertised, but it may be changed in a future release.
+/// @brief Receives up to `max` pending replies from the ring buffer in a single call. Does not block if the buffer is empty.
+///
+/// This is equivalent to calling `z_ring_handler_reply_try_recv()` in a loop, but reduces the per-reply overhead. To wait for the first reply, it can be combined
+/// with `z_ring_handler_reply_recv_timeout()`, `zc_ring_handler_reply_get_fd()` or a handler set.
+///
+/// @param this_: The handler.
+/// @param replies: An array of at least `max` uninitialized replies. On return, its first `out_count` elements are initialized with the received replies,
+/// the others are left untouched.
+/// @param max: The maximum number of replies to receive.
+/// @param out_count: Pointer where the number of received replies will be written.
+/// @return 0 if at least one reply was received, `Z_CHANNEL_NODATA` if the channel is still alive, but its buffer is empty,
+/// `Z_CHANNEL_DISCONNECTED` if the buffer is empty and the channel was dropped, `Z_EINVAL` if `replies` is NULL.
+#[no_mangle]
+#[allow(clippy::missing_safety_doc)]
+pub unsafe extern "C" fn z_ring_handler_reply_recv_batch(
+    this_: &z_loaned_ring_handler_reply_t,
+    replies: *mut MaybeUninit<z_owned_reply_t>,
+    max: usize,
+    out_count: &mut usize,
+) -> z_result_t {
+    channel_handler::recv_batch(
+        this_.as_rust_type_ref(),
+        replies,
+        max,
+        out_count,
+        |out, v| {
+            out.as_rust_type_mut_uninit().write(Some(v));
+        },
+    )
+}
+
+#[cfg(feature = "unstable")]
+/// @warning This API has been marked as unstable: it works as advertised, but it may be changed in a future release.
+/// @brief Returns a file descriptor which becomes readable when new replies are pushed into the ring buffer or when the channel is dropped.
+///
+/// This allows to wait for the handler in an external event loop (e.g. with `poll()`, `epoll` or `libuv`), along with other descriptors.
+/// The descriptor stays readable until `z_ring_handler_reply_try_recv()` returns `Z_CHANNEL_NODATA`, so once it is signaled,
+/// pending replies should be received with `z_ring_handler_reply_try_recv()` until the buffer is empty. Spurious wakeups are possible.
+/// The descriptor is owned by the handler: it should not be read from or closed by the user and it remains valid until the handler is dropped.
+///
+/// @param this_: The handler.
+/// @param out_fd: Pointer where the file descriptor will be written.
+/// @return 0 in case of success, `Z_EUNAVAILABLE` if the platform does not support it (currently only unix platforms are supported, using eventfd on Linux),
+/// `Z_EIO` if the descriptor could not be created.
+#[no_mangle]
+pub extern "C" fn zc_ring_handler_reply_get_fd(
+    this_: &z_loaned_ring_handler_reply_t,
+    out_fd: &mut c_int,
+) -> z_result_t {
+    this_.as_rust_type_ref().1.get_fd(out_fd)
 }
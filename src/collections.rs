@@ -154,7 +154,10 @@ impl CSlice {
         context: *mut c_void,
     ) -> Result<Self, z_result_t> {
         if data.is_null() && len > 0 {
-            crate::report_error!("Non zero-length array should not be NULL");
+            crate::report_error!(
+                zc_error_kind_t::INVALID_ARGUMENT,
+                "Non zero-length array should not be NULL"
+            );
             Err(result::Z_EINVAL)
         } else {
             Ok(Self::new_unchecked(data, len, drop, context))
@@ -163,7 +166,10 @@ impl CSlice {
 
     pub fn new_borrowed(data: *const u8, len: usize) -> Result<Self, z_result_t> {
         if data.is_null() && len > 0 {
-            crate::report_error!("Non zero-length arra should not be NULL");
+            crate::report_error!(
+                zc_error_kind_t::INVALID_ARGUMENT,
+                "Non zero-length arra should not be NULL"
+            );
             Err(result::Z_EINVAL)
         } else {
             Ok(Self::new_borrowed_unchecked(data, len))
@@ -191,7 +197,10 @@ impl CSlice {
     #[allow(clippy::missing_safety_doc)]
     pub unsafe fn new_owned(data: *const u8, len: usize) -> Result<Self, z_result_t> {
         if data.is_null() && len > 0 {
-            crate::report_error!("Non zero-length array should not be NULL");
+            crate::report_error!(
+                zc_error_kind_t::INVALID_ARGUMENT,
+                "Non zero-length array should not be NULL"
+            );
             Err(result::Z_EINVAL)
         } else {
             Ok(Self::new_owned_unchecked(data, len))
@@ -573,6 +582,12 @@ impl From<String> for CStringOwned {
     }
 }
 
+impl From<String> for CStringInner {
+    fn from(value: String) -> Self {
+        CStringOwned::from(value).0
+    }
+}
+
 impl From<CStringInner> for CSlice {
     fn from(value: CStringInner) -> Self {
         value.0
@@ -103,14 +103,18 @@ pub unsafe extern "C" fn zc_config_get_from_substr(
     let config = this.as_rust_type_ref();
     if key.is_null() {
         z_internal_string_null(out_value_string);
-        crate::report_error!("Key should not be null");
+        crate::report_error!(zc_error_kind_t::INVALID_ARGUMENT, "Key should not be null");
         return result::Z_EINVAL;
     }
 
     let key = match from_utf8(from_raw_parts(key as _, key_len)) {
         Ok(s) => s,
         Err(e) => {
-            crate::report_error!("Config key is not a valid utf-8 string: {}", e);
+            crate::report_error!(
+                zc_error_kind_t::INVALID_ARGUMENT,
+                "Config key is not a valid utf-8 string: {}",
+                e
+            );
             z_internal_string_null(out_value_string);
             return result::Z_EINVAL;
         }
@@ -126,7 +130,11 @@ pub unsafe extern "C" fn zc_config_get_from_substr(
             result::Z_OK
         }
         None => {
-            crate::report_error!("No value was found in the config for key: '{}'", key);
+            crate::report_error!(
+                zc_error_kind_t::UNAVAILABLE,
+                "No value was found in the config for key: '{}'",
+                key
+            );
             z_internal_string_null(out_value_string);
             result::Z_EUNAVAILABLE
         }
@@ -166,7 +174,11 @@ pub unsafe extern "C" fn zc_config_insert_json5_from_substr(
     let key = match (&csk).try_into() {
         Ok(s) => s,
         Err(e) => {
-            crate::report_error!("Config key is not a valid utf-8 string: {}", e);
+            crate::report_error!(
+                zc_error_kind_t::INVALID_ARGUMENT,
+                "Config key is not a valid utf-8 string: {}",
+                e
+            );
             return result::Z_EINVAL;
         }
     };
@@ -177,7 +189,11 @@ pub unsafe extern "C" fn zc_config_insert_json5_from_substr(
     let value = match (&csv).try_into() {
         Ok(s) => s,
         Err(e) => {
-            crate::report_error!("Config value is not a valid utf-8 string: {}", e);
+            crate::report_error!(
+                zc_error_kind_t::INVALID_ARGUMENT,
+                "Config value is not a valid utf-8 string: {}",
+                e
+            );
             return result::Z_EINVAL;
         }
     };
@@ -185,6 +201,7 @@ pub unsafe extern "C" fn zc_config_insert_json5_from_substr(
         Ok(_) => 0,
         Err(e) => {
             crate::report_error!(
+                zc_error_kind_t::INVALID_ARGUMENT,
                 "Failed to insert value '{}' for key '{}' into config: {}",
                 value,
                 key,
@@ -232,14 +249,21 @@ pub unsafe extern "C" fn zc_config_from_substr(
 ) -> result::z_result_t {
     z_internal_config_null(this);
     if s.is_null() {
-        crate::report_error!("String should not be NULL");
+        crate::report_error!(
+            zc_error_kind_t::INVALID_ARGUMENT,
+            "String should not be NULL"
+        );
         result::Z_EINVAL
     } else {
         let slice = std::slice::from_raw_parts(s as _, len);
         let conf_str = match std::str::from_utf8(slice) {
             Ok(cs) => cs,
             Err(e) => {
-                crate::report_error!("Config should be a valid utf-8 string {}", e);
+                crate::report_error!(
+                    zc_error_kind_t::INVALID_ARGUMENT,
+                    "Config should be a valid utf-8 string {}",
+                    e
+                );
                 return result::Z_EINVAL;
             }
         };
@@ -249,7 +273,7 @@ pub unsafe extern "C" fn zc_config_from_substr(
                 result::Z_OK
             }
             Err(e) => {
-                crate::report_error!("Invalid config string: {}", e);
+                crate::report_error!(zc_error_kind_t::PARSE, "Invalid config string: {}", e);
                 result::Z_EPARSE
             }
         }
@@ -278,7 +302,7 @@ pub unsafe extern "C" fn zc_config_to_string(
             result::Z_OK
         }
         Err(e) => {
-            crate::report_error!("Config is not a valid json5: {}", e);
+            crate::report_error!(zc_error_kind_t::PARSE, "Config is not a valid json5: {}", e);
             z_internal_string_null(out_config_string);
             result::Z_EPARSE
         }
@@ -309,14 +333,18 @@ pub unsafe extern "C" fn zc_config_from_file_substr(
 ) -> result::z_result_t {
     z_internal_config_null(this);
     if path.is_null() {
-        crate::report_error!("Path should be NULL");
+        crate::report_error!(zc_error_kind_t::INVALID_ARGUMENT, "Path should be NULL");
         return result::Z_EINVAL;
     }
     let slice = std::slice::from_raw_parts(path as _, len);
     let path_str = match std::str::from_utf8(slice) {
         Ok(cs) => cs,
         Err(e) => {
-            crate::report_error!("Path should be a valid utf-8 string {}", e);
+            crate::report_error!(
+                zc_error_kind_t::INVALID_ARGUMENT,
+                "Path should be a valid utf-8 string {}",
+                e
+            );
             return result::Z_EINVAL;
         }
     };
@@ -326,7 +354,12 @@ pub unsafe extern "C" fn zc_config_from_file_substr(
             result::Z_OK
         }
         Err(e) => {
-            crate::report_error!("Failed to read config from {}: {}", path_str, e);
+            crate::report_error!(
+                zc_error_kind_t::PARSE,
+                "Failed to read config from {}: {}",
+                path_str,
+                e
+            );
             result::Z_EPARSE
         }
     }
@@ -346,7 +379,11 @@ pub unsafe extern "C" fn zc_config_from_env(
             result::Z_OK
         }
         Err(e) => {
-            crate::report_error!("Close error: {}", e);
+            crate::report_error!(
+                zc_error_kind_t::IO,
+                "Failed to load configuration from environment: {}",
+                e
+            );
             result::Z_EIO
         }
     }
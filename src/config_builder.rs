@@ -0,0 +1,820 @@
+//
+// Copyright (c) 2017, 2024 ZettaScale Technology.
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ZettaScale Zenoh team, <zenoh@zettascale.tech>
+//
+
+//! Typed setters for the most common configuration fields, validating each value before inserting it into the config.
+
+use std::{net::SocketAddr, str::FromStr};
+
+use libc::c_char;
+use serde_json::{json, Value};
+use zenoh::{
+    config::{Config, EndPoint, WhatAmI},
+    key_expr::keyexpr,
+};
+
+use crate::{
+    result::{self, z_result_t},
+    str_arg, strlen_or_zero,
+    transmute::RustTypeRef,
+    z_loaned_config_t, z_whatami_t,
+};
+
+/// Converts an array of null-terminated C strings to a vector of `&str`.
+unsafe fn str_array_arg<'a>(
+    array: *const *const c_char,
+    len: usize,
+    what: &str,
+) -> Result<Vec<&'a str>, z_result_t> {
+    if len == 0 {
+        return Ok(Vec::new());
+    }
+    if array.is_null() {
+        crate::report_error!(
+            zc_error_kind_t::INVALID_ARGUMENT,
+            "{} should not be NULL",
+            what
+        );
+        return Err(result::Z_EINVAL);
+    }
+    std::slice::from_raw_parts(array, len)
+        .iter()
+        .enumerate()
+        .map(|(i, s)| str_arg(*s, strlen_or_zero(*s), &format!("{}[{}]", what, i)))
+        .collect()
+}
+
+fn validate_positive(value: u64, key: &str) -> Result<(), z_result_t> {
+    if value == 0 {
+        crate::report_error!(
+            zc_error_kind_t::INVALID_ARGUMENT,
+            "'{}' should be greater than 0",
+            key
+        );
+        return Err(result::Z_EINVAL);
+    }
+    Ok(())
+}
+
+/// Inserts an already validated `value` at the `key` position of the configuration.
+fn insert(config: &mut Config, key: &str, value: Value) -> z_result_t {
+    match config.insert_json5(key, &value.to_string()) {
+        Ok(()) => result::Z_OK,
+        Err(e) => {
+            crate::report_error!(
+                zc_error_kind_t::INVALID_ARGUMENT,
+                "Failed to set '{}' to {}: {}",
+                key,
+                value,
+                e
+            );
+            result::Z_EINVAL
+        }
+    }
+}
+
+/// Returns the current value of a list at the `key` position of the configuration, or an empty list if it is not set.
+fn get_list(config: &Config, key: &str) -> Vec<Value> {
+    match config
+        .get_json(key)
+        .ok()
+        .and_then(|s| serde_json::from_str(&s).ok())
+    {
+        Some(Value::Array(values)) => values,
+        _ => Vec::new(),
+    }
+}
+
+fn contains_id(list: &[Value], id: &str) -> bool {
+    list.iter()
+        .any(|v| v.get("id").and_then(Value::as_str) == Some(id))
+}
+
+macro_rules! try_arg {
+    ($e:expr) => {
+        match $e {
+            Ok(v) => v,
+            Err(r) => return r,
+        }
+    };
+}
+
+/// @warning This API has been marked as unstable: it works as advertised, but it may be changed in a future release.
+/// @brief Sets the mode of the session (router, peer or client).
+///
+/// @return 0 in case of success, negative error code otherwise.
+#[no_mangle]
+pub extern "C" fn zc_config_set_mode(
+    config: &mut z_loaned_config_t,
+    mode: z_whatami_t,
+) -> z_result_t {
+    let Ok(mode) = WhatAmI::try_from(mode as u8) else {
+        crate::report_error!(
+            zc_error_kind_t::INVALID_ARGUMENT,
+            "Invalid mode: {:?}",
+            mode
+        );
+        return result::Z_EINVAL;
+    };
+    insert(config.as_rust_type_mut(), "mode", json!(mode.to_str()))
+}
+
+unsafe fn set_endpoints(
+    config: &mut z_loaned_config_t,
+    key: &str,
+    endpoints: *const *const c_char,
+    len: usize,
+) -> z_result_t {
+    let endpoints = try_arg!(str_array_arg(endpoints, len, "Endpoints"));
+    for e in &endpoints {
+        if let Err(err) = EndPoint::from_str(e) {
+            crate::report_error!(
+                zc_error_kind_t::INVALID_ARGUMENT,
+                "Invalid endpoint '{}': {}",
+                e,
+                err
+            );
+            return result::Z_EINVAL;
+        }
+    }
+    insert(config.as_rust_type_mut(), key, json!(endpoints))
+}
+
+/// @warning This API has been marked as unstable: it works as advertised, but it may be changed in a future release.
+/// @brief Sets the endpoints the session connects to, replacing the previous ones.
+///
+/// @param config: A configuration.
+/// @param endpoints: An array of `len` null-terminated endpoint strings, such as "tcp/192.168.0.1:7447".
+/// @param len: The number of endpoints.
+/// @return 0 in case of success, negative error code otherwise (the configuration is left unchanged).
+#[no_mangle]
+#[allow(clippy::missing_safety_doc)]
+pub unsafe extern "C" fn zc_config_set_connect_endpoints(
+    config: &mut z_loaned_config_t,
+    endpoints: *const *const c_char,
+    len: usize,
+) -> z_result_t {
+    set_endpoints(config, "connect/endpoints", endpoints, len)
+}
+
+/// @warning This API has been marked as unstable: it works as advertised, but it may be changed in a future release.
+/// @brief Sets the endpoints the session listens on, replacing the previous ones.
+///
+/// @param config: A configuration.
+/// @param endpoints: An array of `len` null-terminated endpoint strings, such as "tcp/[::]:7447".
+/// @param len: The number of endpoints.
+/// @return 0 in case of success, negative error code otherwise (the configuration is left unchanged).
+#[no_mangle]
+#[allow(clippy::missing_safety_doc)]
+pub unsafe extern "C" fn zc_config_set_listen_endpoints(
+    config: &mut z_loaned_config_t,
+    endpoints: *const *const c_char,
+    len: usize,
+) -> z_result_t {
+    set_endpoints(config, "listen/endpoints", endpoints, len)
+}
+
+/// @warning This API has been marked as unstable: it works as advertised, but it may be changed in a future release.
+/// @brief Enables or disables multicast scouting.
+///
+/// @return 0 in case of success, negative error code otherwise.
+#[no_mangle]
+pub extern "C" fn zc_config_set_scouting_multicast_enabled(
+    config: &mut z_loaned_config_t,
+    enabled: bool,
+) -> z_result_t {
+    insert(
+        config.as_rust_type_mut(),
+        "scouting/multicast/enabled",
+        json!(enabled),
+    )
+}
+
+/// @warning This API has been marked as unstable: it works as advertised, but it may be changed in a future release.
+/// @brief Sets the multicast address used for scouting, such as "224.0.0.224:7446".
+///
+/// @return 0 in case of success, negative error code otherwise.
+#[no_mangle]
+#[allow(clippy::missing_safety_doc)]
+pub unsafe extern "C" fn zc_config_set_scouting_multicast_address(
+    config: &mut z_loaned_config_t,
+    address: *const c_char,
+) -> z_result_t {
+    let address = try_arg!(str_arg(
+        address,
+        strlen_or_zero(address),
+        "Multicast address"
+    ));
+    match SocketAddr::from_str(address) {
+        Ok(a) if a.ip().is_multicast() => {}
+        Ok(_) => {
+            crate::report_error!(
+                zc_error_kind_t::INVALID_ARGUMENT,
+                "'{}' is not a multicast address",
+                address
+            );
+            return result::Z_EINVAL;
+        }
+        Err(e) => {
+            crate::report_error!(
+                zc_error_kind_t::INVALID_ARGUMENT,
+                "Invalid multicast address '{}': {}",
+                address,
+                e
+            );
+            return result::Z_EINVAL;
+        }
+    }
+    insert(
+        config.as_rust_type_mut(),
+        "scouting/multicast/address",
+        json!(address),
+    )
+}
+
+/// @warning This API has been marked as unstable: it works as advertised, but it may be changed in a future release.
+/// @brief Sets the network interface used for multicast scouting, such as "eth0", or "auto".
+///
+/// @return 0 in case of success, negative error code otherwise.
+#[no_mangle]
+#[allow(clippy::missing_safety_doc)]
+pub unsafe extern "C" fn zc_config_set_scouting_multicast_interface(
+    config: &mut z_loaned_config_t,
+    interface: *const c_char,
+) -> z_result_t {
+    let interface = try_arg!(str_arg(
+        interface,
+        strlen_or_zero(interface),
+        "Multicast interface"
+    ));
+    if interface.is_empty() {
+        crate::report_error!(
+            zc_error_kind_t::INVALID_ARGUMENT,
+            "Multicast interface should not be empty"
+        );
+        return result::Z_EINVAL;
+    }
+    insert(
+        config.as_rust_type_mut(),
+        "scouting/multicast/interface",
+        json!(interface),
+    )
+}
+
+/// @warning This API has been marked as unstable: it works as advertised, but it may be changed in a future release.
+/// @brief Enables or disables gossip scouting.
+///
+/// @return 0 in case of success, negative error code otherwise.
+#[no_mangle]
+pub extern "C" fn zc_config_set_scouting_gossip_enabled(
+    config: &mut z_loaned_config_t,
+    enabled: bool,
+) -> z_result_t {
+    insert(
+        config.as_rust_type_mut(),
+        "scouting/gossip/enabled",
+        json!(enabled),
+    )
+}
+
+/// @warning This API has been marked as unstable: it works as advertised, but it may be changed in a future release.
+/// @brief Sets the time in milliseconds the session waits for scouting replies in client mode.
+///
+/// @return 0 in case of success, negative error code otherwise.
+#[no_mangle]
+pub extern "C" fn zc_config_set_scouting_timeout_ms(
+    config: &mut z_loaned_config_t,
+    timeout_ms: u64,
+) -> z_result_t {
+    try_arg!(validate_positive(timeout_ms, "scouting/timeout"));
+    insert(
+        config.as_rust_type_mut(),
+        "scouting/timeout",
+        json!(timeout_ms),
+    )
+}
+
+/// @warning This API has been marked as unstable: it works as advertised, but it may be changed in a future release.
+/// @brief Enables or disables timestamping of the published samples.
+///
+/// @return 0 in case of success, negative error code otherwise.
+#[no_mangle]
+pub extern "C" fn zc_config_set_timestamping_enabled(
+    config: &mut z_loaned_config_t,
+    enabled: bool,
+) -> z_result_t {
+    insert(
+        config.as_rust_type_mut(),
+        "timestamping/enabled",
+        json!(enabled),
+    )
+}
+
+/// @warning This API has been marked as unstable: it works as advertised, but it may be changed in a future release.
+/// @brief Sets whether samples with timestamps too far in the future are dropped.
+///
+/// @return 0 in case of success, negative error code otherwise.
+#[no_mangle]
+pub extern "C" fn zc_config_set_timestamping_drop_future_timestamp(
+    config: &mut z_loaned_config_t,
+    drop_future_timestamp: bool,
+) -> z_result_t {
+    insert(
+        config.as_rust_type_mut(),
+        "timestamping/drop_future_timestamp",
+        json!(drop_future_timestamp),
+    )
+}
+
+/// @warning This API has been marked as unstable: it works as advertised, but it may be changed in a future release.
+/// @brief Enables or disables QoS (priorities and reliability) on both unicast and multicast transports.
+///
+/// @return 0 in case of success, negative error code otherwise (the configuration is left unchanged).
+#[no_mangle]
+pub extern "C" fn zc_config_set_qos_enabled(
+    config: &mut z_loaned_config_t,
+    enabled: bool,
+) -> z_result_t {
+    let config = config.as_rust_type_mut();
+    // Both transports are updated on a copy, so that a failure leaves the configuration unchanged.
+    let mut updated = config.clone();
+    for key in [
+        "transport/unicast/qos/enabled",
+        "transport/multicast/qos/enabled",
+    ] {
+        let res = insert(&mut updated, key, json!(enabled));
+        if res != result::Z_OK {
+            return res;
+        }
+    }
+    *config = updated;
+    result::Z_OK
+}
+
+/// @warning This API has been marked as unstable: it works as advertised, but it may be changed in a future release.
+/// @brief Enables or disables shared memory transport optimization.
+///
+/// @return 0 in case of success, negative error code otherwise.
+#[no_mangle]
+pub extern "C" fn zc_config_set_shared_memory_enabled(
+    config: &mut z_loaned_config_t,
+    enabled: bool,
+) -> z_result_t {
+    insert(
+        config.as_rust_type_mut(),
+        "transport/shared_memory/enabled",
+        json!(enabled),
+    )
+}
+
+/// @warning This API has been marked as unstable: it works as advertised, but it may be changed in a future release.
+/// @brief Sets the maximum number of unicast sessions the session may establish with other nodes.
+///
+/// @return 0 in case of success, negative error code otherwise.
+#[no_mangle]
+pub extern "C" fn zc_config_set_transport_max_sessions(
+    config: &mut z_loaned_config_t,
+    max_sessions: usize,
+) -> z_result_t {
+    try_arg!(validate_positive(
+        max_sessions as u64,
+        "transport/unicast/max_sessions"
+    ));
+    insert(
+        config.as_rust_type_mut(),
+        "transport/unicast/max_sessions",
+        json!(max_sessions),
+    )
+}
+
+/// @warning This API has been marked as unstable: it works as advertised, but it may be changed in a future release.
+/// @brief Sets the maximum number of links per unicast session.
+///
+/// @return 0 in case of success, negative error code otherwise.
+#[no_mangle]
+pub extern "C" fn zc_config_set_transport_max_links(
+    config: &mut z_loaned_config_t,
+    max_links: usize,
+) -> z_result_t {
+    try_arg!(validate_positive(
+        max_links as u64,
+        "transport/unicast/max_links"
+    ));
+    insert(
+        config.as_rust_type_mut(),
+        "transport/unicast/max_links",
+        json!(max_links),
+    )
+}
+
+/// @warning This API has been marked as unstable: it works as advertised, but it may be changed in a future release.
+/// @brief Sets the lease in milliseconds after which a silent link is considered as failed.
+///
+/// @return 0 in case of success, negative error code otherwise.
+#[no_mangle]
+pub extern "C" fn zc_config_set_transport_lease_ms(
+    config: &mut z_loaned_config_t,
+    lease_ms: u64,
+) -> z_result_t {
+    try_arg!(validate_positive(lease_ms, "transport/link/tx/lease"));
+    insert(
+        config.as_rust_type_mut(),
+        "transport/link/tx/lease",
+        json!(lease_ms),
+    )
+}
+
+/// @warning This API has been marked as unstable: it works as advertised, but it may be changed in a future release.
+/// @brief Sets the number of keep-alive messages sent during a lease period.
+///
+/// @return 0 in case of success, negative error code otherwise.
+#[no_mangle]
+pub extern "C" fn zc_config_set_transport_keep_alive(
+    config: &mut z_loaned_config_t,
+    keep_alive: usize,
+) -> z_result_t {
+    try_arg!(validate_positive(
+        keep_alive as u64,
+        "transport/link/tx/keep_alive"
+    ));
+    insert(
+        config.as_rust_type_mut(),
+        "transport/link/tx/keep_alive",
+        json!(keep_alive),
+    )
+}
+
+/// @warning This API has been marked as unstable: it works as advertised, but it may be changed in a future release.
+/// @brief Sets the maximum size in bytes of a batch of messages sent on a link.
+///
+/// @return 0 in case of success, negative error code otherwise.
+#[no_mangle]
+pub extern "C" fn zc_config_set_transport_batch_size(
+    config: &mut z_loaned_config_t,
+    batch_size: u16,
+) -> z_result_t {
+    try_arg!(validate_positive(
+        batch_size as u64,
+        "transport/link/tx/batch_size"
+    ));
+    insert(
+        config.as_rust_type_mut(),
+        "transport/link/tx/batch_size",
+        json!(batch_size),
+    )
+}
+
+/// @warning This API has been marked as unstable: it works as advertised, but it may be changed in a future release.
+/// @brief Sets the maximum size in bytes of a received message.
+///
+/// @return 0 in case of success, negative error code otherwise.
+#[no_mangle]
+pub extern "C" fn zc_config_set_transport_max_message_size(
+    config: &mut z_loaned_config_t,
+    max_message_size: usize,
+) -> z_result_t {
+    try_arg!(validate_positive(
+        max_message_size as u64,
+        "transport/link/rx/max_message_size"
+    ));
+    insert(
+        config.as_rust_type_mut(),
+        "transport/link/rx/max_message_size",
+        json!(max_message_size),
+    )
+}
+
+/// @warning This API has been marked as unstable: it works as advertised, but it may be changed in a future release.
+/// @brief Access control permission.
+#[repr(C)]
+#[derive(Clone, Copy, Debug)]
+pub enum zc_access_control_permission_t {
+    ALLOW,
+    DENY,
+}
+
+impl zc_access_control_permission_t {
+    fn to_str(self) -> &'static str {
+        match self {
+            zc_access_control_permission_t::ALLOW => "allow",
+            zc_access_control_permission_t::DENY => "deny",
+        }
+    }
+}
+
+/// @warning This API has been marked as unstable: it works as advertised, but it may be changed in a future release.
+/// @brief Message flows an access control rule applies to.
+#[repr(C)]
+#[derive(Clone, Copy, Debug)]
+pub enum zc_access_control_flows_t {
+    INGRESS,
+    EGRESS,
+    INGRESS_AND_EGRESS,
+}
+
+impl zc_access_control_flows_t {
+    fn to_json(self) -> Value {
+        match self {
+            zc_access_control_flows_t::INGRESS => json!(["ingress"]),
+            zc_access_control_flows_t::EGRESS => json!(["egress"]),
+            zc_access_control_flows_t::INGRESS_AND_EGRESS => json!(["ingress", "egress"]),
+        }
+    }
+}
+
+/// @warning This API has been marked as unstable: it works as advertised, but it may be changed in a future release.
+/// @brief Kind of messages an access control rule applies to.
+#[repr(C)]
+#[derive(Clone, Copy, Debug)]
+pub enum zc_access_control_message_t {
+    PUT,
+    DELETE,
+    DECLARE_SUBSCRIBER,
+    QUERY,
+    REPLY,
+    DECLARE_QUERYABLE,
+    LIVELINESS_TOKEN,
+    LIVELINESS_QUERY,
+    DECLARE_LIVELINESS_SUBSCRIBER,
+}
+
+impl zc_access_control_message_t {
+    fn to_str(self) -> &'static str {
+        match self {
+            zc_access_control_message_t::PUT => "put",
+            zc_access_control_message_t::DELETE => "delete",
+            zc_access_control_message_t::DECLARE_SUBSCRIBER => "declare_subscriber",
+            zc_access_control_message_t::QUERY => "query",
+            zc_access_control_message_t::REPLY => "reply",
+            zc_access_control_message_t::DECLARE_QUERYABLE => "declare_queryable",
+            zc_access_control_message_t::LIVELINESS_TOKEN => "liveliness_token",
+            zc_access_control_message_t::LIVELINESS_QUERY => "liveliness_query",
+            zc_access_control_message_t::DECLARE_LIVELINESS_SUBSCRIBER => {
+                "declare_liveliness_subscriber"
+            }
+        }
+    }
+}
+
+/// @warning This API has been marked as unstable: it works as advertised, but it may be changed in a future release.
+/// @brief An access control rule, granting or denying a set of messages on a set of key expressions.
+#[repr(C)]
+pub struct zc_access_control_rule_t {
+    /// Unique identifier of the rule, used to reference it from policies.
+    pub id: *const c_char,
+    /// Permission granted by the rule.
+    pub permission: zc_access_control_permission_t,
+    /// Message flows the rule applies to.
+    pub flows: zc_access_control_flows_t,
+    /// Array of `messages_len` kinds of messages the rule applies to.
+    pub messages: *const zc_access_control_message_t,
+    pub messages_len: usize,
+    /// Array of `key_exprs_len` null-terminated key expressions the rule applies to.
+    pub key_exprs: *const *const c_char,
+    pub key_exprs_len: usize,
+}
+
+/// @warning This API has been marked as unstable: it works as advertised, but it may be changed in a future release.
+/// @brief An access control subject, matching remote nodes by network interface, certificate common name or username.
+///
+/// Empty arrays match any value.
+#[repr(C)]
+pub struct zc_access_control_subject_t {
+    /// Unique identifier of the subject, used to reference it from policies.
+    pub id: *const c_char,
+    /// Array of `interfaces_len` null-terminated network interface names.
+    pub interfaces: *const *const c_char,
+    pub interfaces_len: usize,
+    /// Array of `cert_common_names_len` null-terminated TLS certificate common names.
+    pub cert_common_names: *const *const c_char,
+    pub cert_common_names_len: usize,
+    /// Array of `usernames_len` null-terminated usernames.
+    pub usernames: *const *const c_char,
+    pub usernames_len: usize,
+}
+
+/// @warning This API has been marked as unstable: it works as advertised, but it may be changed in a future release.
+/// @brief An access control policy, applying a set of rules to a set of subjects.
+#[repr(C)]
+pub struct zc_access_control_policy_t {
+    /// Unique identifier of the policy.
+    pub id: *const c_char,
+    /// Array of `rules_len` null-terminated identifiers of previously added rules.
+    pub rules: *const *const c_char,
+    pub rules_len: usize,
+    /// Array of `subjects_len` null-terminated identifiers of previously added subjects.
+    pub subjects: *const *const c_char,
+    pub subjects_len: usize,
+}
+
+/// @warning This API has been marked as unstable: it works as advertised, but it may be changed in a future release.
+/// @brief Enables or disables access control.
+///
+/// @return 0 in case of success, negative error code otherwise.
+#[no_mangle]
+pub extern "C" fn zc_config_set_access_control_enabled(
+    config: &mut z_loaned_config_t,
+    enabled: bool,
+) -> z_result_t {
+    insert(
+        config.as_rust_type_mut(),
+        "access_control/enabled",
+        json!(enabled),
+    )
+}
+
+/// @warning This API has been marked as unstable: it works as advertised, but it may be changed in a future release.
+/// @brief Sets the permission applied to messages not matching any access control policy.
+///
+/// @return 0 in case of success, negative error code otherwise.
+#[no_mangle]
+pub extern "C" fn zc_config_set_access_control_default_permission(
+    config: &mut z_loaned_config_t,
+    permission: zc_access_control_permission_t,
+) -> z_result_t {
+    insert(
+        config.as_rust_type_mut(),
+        "access_control/default_permission",
+        json!(permission.to_str()),
+    )
+}
+
+/// Reads the identifier of an access control item, checking that it is not empty and not already used in `list`.
+unsafe fn new_id<'a>(id: *const c_char, list: &[Value], what: &str) -> Result<&'a str, z_result_t> {
+    let id = str_arg(id, strlen_or_zero(id), &format!("{} id", what))?;
+    if id.is_empty() {
+        crate::report_error!(
+            zc_error_kind_t::INVALID_ARGUMENT,
+            "{} id should not be empty",
+            what
+        );
+        return Err(result::Z_EINVAL);
+    }
+    if contains_id(list, id) {
+        crate::report_error!(
+            zc_error_kind_t::INVALID_ARGUMENT,
+            "{} '{}' already exists",
+            what,
+            id
+        );
+        return Err(result::Z_EINVAL);
+    }
+    Ok(id)
+}
+
+/// @warning This API has been marked as unstable: it works as advertised, but it may be changed in a future release.
+/// @brief Appends a rule to the access control configuration.
+///
+/// @return 0 in case of success, negative error code otherwise (the configuration is left unchanged).
+#[no_mangle]
+#[allow(clippy::missing_safety_doc)]
+pub unsafe extern "C" fn zc_config_add_access_control_rule(
+    config: &mut z_loaned_config_t,
+    rule: &zc_access_control_rule_t,
+) -> z_result_t {
+    let config = config.as_rust_type_mut();
+    let mut rules = get_list(config, "access_control/rules");
+    let id = try_arg!(new_id(rule.id, &rules, "Rule"));
+    if rule.messages_len == 0 || rule.messages.is_null() {
+        crate::report_error!(
+            zc_error_kind_t::INVALID_ARGUMENT,
+            "Rule '{}' should apply to at least one message",
+            id
+        );
+        return result::Z_EINVAL;
+    }
+    let messages: Vec<&str> = std::slice::from_raw_parts(rule.messages, rule.messages_len)
+        .iter()
+        .map(|m| m.to_str())
+        .collect();
+    let key_exprs = try_arg!(str_array_arg(
+        rule.key_exprs,
+        rule.key_exprs_len,
+        "Rule key expressions"
+    ));
+    if key_exprs.is_empty() {
+        crate::report_error!(
+            zc_error_kind_t::INVALID_ARGUMENT,
+            "Rule '{}' should apply to at least one key expression",
+            id
+        );
+        return result::Z_EINVAL;
+    }
+    for k in &key_exprs {
+        if let Err(e) = keyexpr::new(*k) {
+            crate::report_error!(
+                zc_error_kind_t::INVALID_ARGUMENT,
+                "Rule '{}' has an invalid key expression '{}': {}",
+                id,
+                k,
+                e
+            );
+            return result::Z_EINVAL;
+        }
+    }
+    rules.push(json!({
+        "id": id,
+        "permission": rule.permission.to_str(),
+        "flows": rule.flows.to_json(),
+        "messages": messages,
+        "key_exprs": key_exprs,
+    }));
+    insert(config, "access_control/rules", Value::Array(rules))
+}
+
+/// @warning This API has been marked as unstable: it works as advertised, but it may be changed in a future release.
+/// @brief Appends a subject to the access control configuration.
+///
+/// @return 0 in case of success, negative error code otherwise (the configuration is left unchanged).
+#[no_mangle]
+#[allow(clippy::missing_safety_doc)]
+pub unsafe extern "C" fn zc_config_add_access_control_subject(
+    config: &mut z_loaned_config_t,
+    subject: &zc_access_control_subject_t,
+) -> z_result_t {
+    let config = config.as_rust_type_mut();
+    let mut subjects = get_list(config, "access_control/subjects");
+    let id = try_arg!(new_id(subject.id, &subjects, "Subject"));
+    let mut value = json!({ "id": id });
+    for (name, array, len) in [
+        ("interfaces", subject.interfaces, subject.interfaces_len),
+        (
+            "cert_common_names",
+            subject.cert_common_names,
+            subject.cert_common_names_len,
+        ),
+        ("usernames", subject.usernames, subject.usernames_len),
+    ] {
+        let values = try_arg!(str_array_arg(array, len, &format!("Subject {}", name)));
+        if !values.is_empty() {
+            value[name] = json!(values);
+        }
+    }
+    subjects.push(value);
+    insert(config, "access_control/subjects", Value::Array(subjects))
+}
+
+/// @warning This API has been marked as unstable: it works as advertised, but it may be changed in a future release.
+/// @brief Appends a policy to the access control configuration. The rules and subjects it references should be added first.
+///
+/// @return 0 in case of success, negative error code otherwise (the configuration is left unchanged).
+#[no_mangle]
+#[allow(clippy::missing_safety_doc)]
+pub unsafe extern "C" fn zc_config_add_access_control_policy(
+    config: &mut z_loaned_config_t,
+    policy: &zc_access_control_policy_t,
+) -> z_result_t {
+    let config = config.as_rust_type_mut();
+    let mut policies = get_list(config, "access_control/policies");
+    let id = try_arg!(new_id(policy.id, &policies, "Policy"));
+    let rules = try_arg!(str_array_arg(
+        policy.rules,
+        policy.rules_len,
+        "Policy rules"
+    ));
+    let subjects = try_arg!(str_array_arg(
+        policy.subjects,
+        policy.subjects_len,
+        "Policy subjects"
+    ));
+    for (what, ids, existing) in [
+        ("rule", &rules, get_list(config, "access_control/rules")),
+        (
+            "subject",
+            &subjects,
+            get_list(config, "access_control/subjects"),
+        ),
+    ] {
+        if ids.is_empty() {
+            crate::report_error!(
+                zc_error_kind_t::INVALID_ARGUMENT,
+                "Policy '{}' should reference at least one {}",
+                id,
+                what
+            );
+            return result::Z_EINVAL;
+        }
+        if let Some(unknown) = ids.iter().find(|i| !contains_id(&existing, i)) {
+            crate::report_error!(
+                zc_error_kind_t::INVALID_ARGUMENT,
+                "Policy '{}' references unknown {} '{}'",
+                id,
+                what,
+                unknown
+            );
+            return result::Z_EINVAL;
+        }
+    }
+    policies.push(json!({ "id": id, "rules": rules, "subjects": subjects }));
+    insert(config, "access_control/policies", Value::Array(policies))
+}
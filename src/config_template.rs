@@ -0,0 +1,431 @@
+//
+// Copyright (c) 2017, 2024 ZettaScale Technology.
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ZettaScale Zenoh team, <zenoh@zettascale.tech>
+//
+
+//! Loading of configurations templated with environment variables, and split across several JSON5 files.
+//!
+//! Before being parsed, the text of each configuration has its `${VAR}` and `${VAR:-default}` patterns
+//! replaced by the value of the corresponding environment variable (`$${` stands for a literal `${`).
+//! Default values may contain braces, as long as they are balanced.
+//! Values substituted inside string literals are escaped; outside of them, only numbers, booleans and null are accepted.
+//! A configuration may contain a top-level `include` key, holding a path or a list of paths of JSON5 files.
+//! The included files are merged in order, then the configuration itself is merged on top of them:
+//! objects are merged recursively, any other value (including lists) replaces the previous one.
+
+use std::{
+    fmt::Write,
+    mem::MaybeUninit,
+    path::{Path, PathBuf},
+};
+
+use libc::c_char;
+use serde_json::Value;
+use zenoh::config::Config;
+
+use crate::{
+    result::{self, z_result_t},
+    strlen_or_zero,
+    transmute::RustTypeRefUninit,
+    z_owned_config_t, CStringView,
+};
+
+/// Key of the configuration listing the files to merge before it.
+const INCLUDE_KEY: &str = "include";
+
+struct LoadError {
+    code: z_result_t,
+    message: String,
+}
+
+impl LoadError {
+    fn new(code: z_result_t, message: String) -> Self {
+        LoadError { code, message }
+    }
+}
+
+/// Lexical context of a position in a JSON5 text.
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Context {
+    Value,
+    /// Inside a string literal delimited by the given quote.
+    String(char),
+    LineComment,
+    BlockComment,
+}
+
+/// Returns the position, after the leading `${`, of the `}` closing the pattern at the start of `s`.
+/// Braces are matched, so that default values may contain balanced braces.
+fn variable_reference_len(s: &str) -> Option<usize> {
+    let mut depth = 0usize;
+    for (i, c) in s[2..].char_indices() {
+        match c {
+            '{' => depth += 1,
+            '}' if depth == 0 => return Some(i),
+            '}' => depth -= 1,
+            _ => {}
+        }
+    }
+    None
+}
+
+/// Resolves the `${VAR}` or `${VAR:-default}` pattern at the start of `s`.
+/// Returns the value of the variable and the length of the pattern.
+fn resolve_variable(s: &str) -> Result<(String, usize), LoadError> {
+    let Some(len) = variable_reference_len(s) else {
+        return Err(LoadError::new(
+            result::Z_EPARSE,
+            format!("Unterminated variable reference: '{}'", s),
+        ));
+    };
+    let pattern = &s[2..2 + len];
+    let (name, default) = match pattern.split_once(":-") {
+        Some((name, default)) => (name, Some(default)),
+        None => (pattern, None),
+    };
+    if name.is_empty() {
+        return Err(LoadError::new(
+            result::Z_EPARSE,
+            format!("Empty variable name in '${{{}}}'", pattern),
+        ));
+    }
+    let value = match std::env::var(name) {
+        Ok(value) => Some(value),
+        Err(std::env::VarError::NotPresent) => None,
+        Err(e) => {
+            return Err(LoadError::new(
+                result::Z_EINVAL,
+                format!("Environment variable '{}' can not be read: {}", name, e),
+            ))
+        }
+    };
+    let value = match (
+        value.filter(|v| !v.is_empty() || default.is_none()),
+        default,
+    ) {
+        (Some(value), _) => value,
+        (None, Some(default)) => default.to_string(),
+        (None, None) => {
+            return Err(LoadError::new(
+                result::Z_EINVAL,
+                format!("Environment variable '{}' is not set", name),
+            ))
+        }
+    };
+    Ok((value, 2 + len + 1))
+}
+
+/// Appends `value` to `out`, escaped to be part of a string literal delimited by `quote`.
+fn push_escaped(out: &mut String, value: &str, quote: char) {
+    for c in value.chars() {
+        match c {
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            c if c == quote => {
+                out.push('\\');
+                out.push(c);
+            }
+            c if c.is_control() => {
+                let _ = write!(out, "\\u{:04x}", c as u32);
+            }
+            c => out.push(c),
+        }
+    }
+}
+
+/// Replaces `${VAR}` and `${VAR:-default}` patterns of `s` with the values of the corresponding environment variables.
+///
+/// Values substituted inside string literals are escaped, so that they can not end the literal. Outside of them,
+/// values should be numbers, booleans or null, so that they can not change the structure of the configuration.
+/// Patterns inside comments are left as is.
+fn expand_env(s: &str) -> Result<String, LoadError> {
+    let mut out = String::with_capacity(s.len());
+    let mut context = Context::Value;
+    let mut rest = s;
+    while let Some(c) = rest.chars().next() {
+        if matches!(context, Context::Value | Context::String(_)) {
+            if rest.starts_with("$${") {
+                out.push_str("${");
+                rest = &rest[3..];
+                continue;
+            }
+            if rest.starts_with("${") {
+                let (value, len) = resolve_variable(rest)?;
+                match context {
+                    Context::String(quote) => push_escaped(&mut out, &value, quote),
+                    _ => match json5::from_str::<Value>(&value) {
+                        Ok(v @ (Value::Number(_) | Value::Bool(_) | Value::Null)) => {
+                            out.push_str(&v.to_string())
+                        }
+                        _ => {
+                            return Err(LoadError::new(
+                                result::Z_EINVAL,
+                                format!(
+                                    "Value '{}' of '{}' should be a number, a boolean or null outside of a string",
+                                    value,
+                                    &rest[..len]
+                                ),
+                            ))
+                        }
+                    },
+                }
+                rest = &rest[len..];
+                continue;
+            }
+        }
+        let mut len = c.len_utf8();
+        match context {
+            Context::Value if c == '"' || c == '\'' => context = Context::String(c),
+            Context::Value if rest.starts_with("//") => {
+                context = Context::LineComment;
+                len = 2;
+            }
+            Context::Value if rest.starts_with("/*") => {
+                context = Context::BlockComment;
+                len = 2;
+            }
+            Context::String(_) if c == '\\' => {
+                // The escaped character can not end the literal.
+                len += rest[len..].chars().next().map_or(0, char::len_utf8);
+            }
+            Context::String(quote) if c == quote => context = Context::Value,
+            Context::LineComment if c == '\n' => context = Context::Value,
+            Context::BlockComment if rest.starts_with("*/") => {
+                context = Context::Value;
+                len = 2;
+            }
+            _ => {}
+        }
+        out.push_str(&rest[..len]);
+        rest = &rest[len..];
+    }
+    Ok(out)
+}
+
+/// Merges `overlay` into `base`: objects are merged recursively, other values replace the base ones.
+fn merge(base: &mut Value, overlay: Value) {
+    match (base, overlay) {
+        (Value::Object(base), Value::Object(overlay)) => {
+            for (k, v) in overlay {
+                match base.get_mut(&k) {
+                    Some(b) => merge(b, v),
+                    None => {
+                        base.insert(k, v);
+                    }
+                }
+            }
+        }
+        (base, overlay) => *base = overlay,
+    }
+}
+
+/// Parses the configuration text `s`, resolving its includes relative to `dir`.
+/// `stack` holds the files being loaded, to detect include cycles.
+fn load_str(s: &str, dir: &Path, stack: &mut Vec<PathBuf>) -> Result<Value, LoadError> {
+    let s = expand_env(s)?;
+    let mut value = match json5::from_str::<Value>(&s) {
+        Ok(v @ Value::Object(_)) => v,
+        Ok(_) => {
+            return Err(LoadError::new(
+                result::Z_EPARSE,
+                "Config is not a json5 object".to_string(),
+            ))
+        }
+        Err(e) => {
+            return Err(LoadError::new(
+                result::Z_EPARSE,
+                format!("Invalid config: {}", e),
+            ))
+        }
+    };
+    let includes = match value.as_object_mut().and_then(|o| o.remove(INCLUDE_KEY)) {
+        None => Vec::new(),
+        Some(Value::String(path)) => vec![path],
+        Some(Value::Array(paths)) => paths
+            .into_iter()
+            .map(|p| match p {
+                Value::String(path) => Ok(path),
+                p => Err(LoadError::new(
+                    result::Z_EPARSE,
+                    format!("Included path should be a string, got {}", p),
+                )),
+            })
+            .collect::<Result<_, _>>()?,
+        Some(v) => {
+            return Err(LoadError::new(
+                result::Z_EPARSE,
+                format!(
+                    "'{}' should be a path or a list of paths, got {}",
+                    INCLUDE_KEY, v
+                ),
+            ))
+        }
+    };
+    let mut merged = Value::Object(Default::default());
+    for path in includes {
+        merge(&mut merged, load_file(&dir.join(path), stack)?);
+    }
+    merge(&mut merged, value);
+    Ok(merged)
+}
+
+fn load_file(path: &Path, stack: &mut Vec<PathBuf>) -> Result<Value, LoadError> {
+    let canonical = path.canonicalize().map_err(|e| {
+        LoadError::new(
+            result::Z_EIO,
+            format!("Failed to read config from {}: {}", path.display(), e),
+        )
+    })?;
+    if stack.contains(&canonical) {
+        return Err(LoadError::new(
+            result::Z_EINVAL,
+            format!("Config {} includes itself", path.display()),
+        ));
+    }
+    let s = std::fs::read_to_string(&canonical).map_err(|e| {
+        LoadError::new(
+            result::Z_EIO,
+            format!("Failed to read config from {}: {}", path.display(), e),
+        )
+    })?;
+    let dir = canonical
+        .parent()
+        .map(Path::to_path_buf)
+        .unwrap_or_default();
+    stack.push(canonical);
+    let value = load_str(&s, &dir, stack).map_err(|e| LoadError {
+        message: format!("{}: {}", path.display(), e.message),
+        ..e
+    });
+    stack.pop();
+    value
+}
+
+fn to_config(value: Value) -> Result<Config, LoadError> {
+    Config::from_json5(&value.to_string())
+        .map_err(|e| LoadError::new(result::Z_EPARSE, format!("Invalid config: {}", e)))
+}
+
+fn write_config(
+    this: &mut MaybeUninit<z_owned_config_t>,
+    config: Result<Config, LoadError>,
+) -> z_result_t {
+    let this = this.as_rust_type_mut_uninit();
+    match config {
+        Ok(c) => {
+            this.write(Some(c));
+            result::Z_OK
+        }
+        Err(e) => {
+            match e.code {
+                result::Z_EPARSE => crate::report_error!(zc_error_kind_t::PARSE, "{}", e.message),
+                result::Z_EIO => crate::report_error!(zc_error_kind_t::IO, "{}", e.message),
+                _ => crate::report_error!(zc_error_kind_t::INVALID_ARGUMENT, "{}", e.message),
+            }
+            this.write(None);
+            e.code
+        }
+    }
+}
+
+/// @warning This API has been marked as unstable: it works as advertised, but it may be changed in a future release.
+/// @brief Constructs a configuration by parsing and merging several JSON5 files in order.
+///
+/// Each file may reference environment variables as `${VAR}` or `${VAR:-default}` (the default value being used
+/// if the variable is unset or empty, it may contain balanced braces). Values substituted inside string literals
+/// are escaped, while values substituted outside of them should be numbers, booleans or null. Each file may list files to merge before it in a top-level
+/// `include` key, as a path or a list of paths relative to the including file. Objects are merged recursively,
+/// while any other value (including lists) of a later file replaces the one of an earlier file.
+///
+/// @param this_: An uninitialized memory location where the configuration will be constructed.
+/// @param paths: An array of null-terminated file paths.
+/// @param len: The number of paths.
+/// @return 0 in case of success, negative error code otherwise (in this case the config will be in its gravestone state).
+#[allow(clippy::missing_safety_doc)]
+#[no_mangle]
+pub unsafe extern "C" fn zc_config_from_files(
+    this_: &mut MaybeUninit<z_owned_config_t>,
+    paths: *const *const c_char,
+    len: usize,
+) -> z_result_t {
+    if paths.is_null() && len > 0 {
+        crate::report_error!(
+            zc_error_kind_t::INVALID_ARGUMENT,
+            "Paths should not be NULL"
+        );
+        this_.as_rust_type_mut_uninit().write(None);
+        return result::Z_EINVAL;
+    }
+    let mut merged = Value::Object(Default::default());
+    for i in 0..len {
+        let path = *paths.add(i);
+        let path = match CStringView::new_borrowed(path, strlen_or_zero(path)).and_then(|p| {
+            <&str>::try_from(&p)
+                .map(str::to_string)
+                .map_err(|_| result::Z_EINVAL)
+        }) {
+            Ok(p) => p,
+            Err(r) => {
+                crate::report_error!(
+                    zc_error_kind_t::INVALID_ARGUMENT,
+                    "Path should be a valid utf-8 string"
+                );
+                this_.as_rust_type_mut_uninit().write(None);
+                return r;
+            }
+        };
+        match load_file(Path::new(&path), &mut Vec::new()) {
+            Ok(v) => merge(&mut merged, v),
+            Err(e) => return write_config(this_, Err(e)),
+        }
+    }
+    write_config(this_, to_config(merged))
+}
+
+/// @warning This API has been marked as unstable: it works as advertised, but it may be changed in a future release.
+/// @brief Constructs a configuration by parsing a JSON5 string, after substituting its environment variables.
+///
+/// Environment variables are referenced as `${VAR}` or `${VAR:-default}`, the default value being used if
+/// the variable is unset or empty (it may contain balanced braces); `$${` stands for a literal `${`.
+/// Values substituted inside string literals are escaped, while values substituted outside of them should be numbers,
+/// booleans or null.
+/// Files listed in a top-level `include` key are merged before the string, as for `zc_config_from_files()`, relative paths being resolved from the current directory.
+///
+/// @param this_: An uninitialized memory location where the configuration will be constructed.
+/// @param s: A null-terminated JSON5 string.
+/// @return 0 in case of success, negative error code otherwise (in this case the config will be in its gravestone state).
+#[allow(clippy::missing_safety_doc)]
+#[no_mangle]
+pub unsafe extern "C" fn zc_config_from_str_with_env(
+    this_: &mut MaybeUninit<z_owned_config_t>,
+    s: *const c_char,
+) -> z_result_t {
+    let s = match CStringView::new_borrowed(s, strlen_or_zero(s)).and_then(|s| {
+        <&str>::try_from(&s)
+            .map(str::to_string)
+            .map_err(|_| result::Z_EINVAL)
+    }) {
+        Ok(s) => s,
+        Err(r) => {
+            crate::report_error!(
+                zc_error_kind_t::INVALID_ARGUMENT,
+                "Config should be a valid utf-8 string"
+            );
+            this_.as_rust_type_mut_uninit().write(None);
+            return r;
+        }
+    };
+    let config = load_str(&s, Path::new(""), &mut Vec::new()).and_then(to_config);
+    write_config(this_, config)
+}
@@ -0,0 +1,554 @@
+//
+// Copyright (c) 2017, 2024 ZettaScale Technology.
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ZettaScale Zenoh team, <zenoh@zettascale.tech>
+//
+
+//! Static validation of configurations and comparison of two configurations, without opening a session.
+
+use std::{
+    collections::{BTreeMap, HashSet},
+    mem::MaybeUninit,
+    net::SocketAddr,
+    str::FromStr,
+};
+
+use libc::c_char;
+use serde_json::Value;
+use zenoh::{
+    config::{Config, EndPoint},
+    key_expr::keyexpr,
+};
+
+pub use crate::opaque_types::{
+    zc_loaned_config_diagnostic_t, zc_loaned_config_diagnostics_t, zc_moved_config_diagnostics_t,
+    zc_owned_config_diagnostics_t,
+};
+use crate::{
+    result::{self, z_result_t},
+    strlen_or_zero,
+    transmute::{LoanedCTypeRef, RustTypeRef, RustTypeRefUninit, TakeRustType},
+    z_loaned_config_t, z_loaned_string_t, z_owned_string_array_t, CStringInner, CStringView,
+    ZVector,
+};
+
+/// @warning This API has been marked as unstable: it works as advertised, but it may be changed in a future release.
+/// @brief Severity of a configuration diagnostic.
+#[repr(C)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum zc_config_diagnostic_severity_t {
+    /// The configuration is invalid and will be rejected or will not work as expected.
+    ERROR,
+    /// The configuration is valid, but likely contains a mistake.
+    WARNING,
+}
+
+pub struct ConfigDiagnostic {
+    severity: zc_config_diagnostic_severity_t,
+    key: CStringInner,
+    message: CStringInner,
+}
+
+decl_c_type!(
+    owned(zc_owned_config_diagnostics_t, option Vec<ConfigDiagnostic>),
+    loaned(zc_loaned_config_diagnostics_t),
+);
+decl_c_type!(loaned(zc_loaned_config_diagnostic_t, ConfigDiagnostic));
+
+#[derive(Default)]
+struct Diagnostics(Vec<ConfigDiagnostic>);
+
+impl Diagnostics {
+    fn push(&mut self, severity: zc_config_diagnostic_severity_t, key: &str, message: String) {
+        self.0.push(ConfigDiagnostic {
+            severity,
+            key: key.to_string().into(),
+            message: message.into(),
+        });
+    }
+
+    fn error(&mut self, key: &str, message: String) {
+        self.push(zc_config_diagnostic_severity_t::ERROR, key, message);
+    }
+
+    fn warning(&mut self, key: &str, message: String) {
+        self.push(zc_config_diagnostic_severity_t::WARNING, key, message);
+    }
+
+    fn has_errors(&self) -> bool {
+        self.0
+            .iter()
+            .any(|d| d.severity == zc_config_diagnostic_severity_t::ERROR)
+    }
+}
+
+/// Returns the value at the `/`-separated `key` position, if any.
+fn get<'a>(value: &'a Value, key: &str) -> Option<&'a Value> {
+    key.split('/')
+        .try_fold(value, |v, k| v.get(k))
+        .filter(|v| !v.is_null())
+}
+
+/// Returns the list at `key`, resolving mode-dependent values (e.g. `{router: [...], peer: [...]}`) for `mode`.
+fn get_mode_dependent_list<'a>(value: &'a Value, key: &str, mode: &str) -> Vec<&'a Value> {
+    match get(value, key) {
+        Some(Value::Array(a)) => a.iter().collect(),
+        Some(Value::Object(o)) => match o.get(mode) {
+            Some(Value::Array(a)) => a.iter().collect(),
+            _ => Vec::new(),
+        },
+        _ => Vec::new(),
+    }
+}
+
+fn get_bool(value: &Value, key: &str, mode: &str) -> Option<bool> {
+    match get(value, key)? {
+        Value::Bool(b) => Some(*b),
+        Value::Object(o) => o.get(mode).and_then(Value::as_bool),
+        _ => None,
+    }
+}
+
+fn validate_endpoints(value: &Value, mode: &str, diagnostics: &mut Diagnostics) {
+    for key in ["connect/endpoints", "listen/endpoints"] {
+        let mut seen = HashSet::new();
+        for endpoint in get_mode_dependent_list(value, key, mode) {
+            let Some(endpoint) = endpoint.as_str() else {
+                diagnostics.error(key, format!("Endpoint {} is not a string", endpoint));
+                continue;
+            };
+            if let Err(e) = EndPoint::from_str(endpoint) {
+                diagnostics.error(key, format!("Invalid endpoint '{}': {}", endpoint, e));
+            } else if !seen.insert(endpoint) {
+                diagnostics.warning(key, format!("Duplicated endpoint '{}'", endpoint));
+            }
+        }
+    }
+
+    let connect = get_mode_dependent_list(value, "connect/endpoints", mode);
+    let multicast = get_bool(value, "scouting/multicast/enabled", mode).unwrap_or(true);
+    if mode == "client" && connect.is_empty() && !multicast {
+        diagnostics.error(
+            "connect/endpoints",
+            "Client mode requires connect endpoints when multicast scouting is disabled".into(),
+        );
+    }
+
+    let listen = get_mode_dependent_list(value, "listen/endpoints", mode);
+    let secure_listen = listen.iter().filter_map(|e| e.as_str()).find(|e| {
+        e.starts_with("tls/") || e.starts_with("quic/") || e.starts_with("quic_datagram/")
+    });
+    if let Some(endpoint) = secure_listen {
+        for key in [
+            "transport/link/tls/listen_certificate",
+            "transport/link/tls/listen_private_key",
+        ] {
+            if get(value, key).is_none() {
+                diagnostics.error(
+                    key,
+                    format!("Listening on '{}' requires '{}' to be set", endpoint, key),
+                );
+            }
+        }
+    }
+}
+
+fn validate_scouting(value: &Value, diagnostics: &mut Diagnostics) {
+    let key = "scouting/multicast/address";
+    if let Some(address) = get(value, key).and_then(Value::as_str) {
+        match SocketAddr::from_str(address) {
+            Ok(a) if !a.ip().is_multicast() => {
+                diagnostics.error(key, format!("'{}' is not a multicast address", address))
+            }
+            Ok(_) => {}
+            Err(e) => diagnostics.error(key, format!("Invalid address '{}': {}", address, e)),
+        }
+    }
+}
+
+fn validate_transport(value: &Value, mode: &str, diagnostics: &mut Diagnostics) {
+    let lease = get(value, "transport/link/tx/lease").and_then(Value::as_u64);
+    let keep_alive = get(value, "transport/link/tx/keep_alive").and_then(Value::as_u64);
+    for (key, v) in [
+        ("transport/link/tx/lease", lease),
+        ("transport/link/tx/keep_alive", keep_alive),
+        (
+            "transport/link/tx/batch_size",
+            get(value, "transport/link/tx/batch_size").and_then(Value::as_u64),
+        ),
+        (
+            "transport/unicast/max_sessions",
+            get(value, "transport/unicast/max_sessions").and_then(Value::as_u64),
+        ),
+        (
+            "transport/unicast/max_links",
+            get(value, "transport/unicast/max_links").and_then(Value::as_u64),
+        ),
+    ] {
+        if v == Some(0) {
+            diagnostics.error(key, format!("'{}' should be greater than 0", key));
+        }
+    }
+    if let (Some(lease), Some(keep_alive)) = (lease, keep_alive) {
+        if keep_alive > lease {
+            diagnostics.warning(
+                "transport/link/tx/keep_alive",
+                format!(
+                    "Sending {} keep-alive messages per lease of {} ms is likely to saturate the link",
+                    keep_alive, lease
+                ),
+            );
+        }
+    }
+    if !cfg!(feature = "shared-memory")
+        && get_bool(value, "transport/shared_memory/enabled", mode) == Some(true)
+    {
+        diagnostics.warning(
+            "transport/shared_memory/enabled",
+            "Shared memory is enabled, but this library was built without shared memory support"
+                .into(),
+        );
+    }
+}
+
+/// Checks the ids of the items of the list at `key`, and returns the set of valid ids.
+fn collect_ids<'a>(value: &'a Value, key: &str, diagnostics: &mut Diagnostics) -> HashSet<&'a str> {
+    let mut ids = HashSet::new();
+    if let Some(Value::Array(items)) = get(value, key) {
+        for item in items {
+            match item.get("id").and_then(Value::as_str) {
+                Some(id) if !ids.insert(id) => {
+                    diagnostics.error(key, format!("Duplicated id '{}'", id))
+                }
+                Some(_) => {}
+                None => diagnostics.error(key, "Item without id".into()),
+            }
+        }
+    }
+    ids
+}
+
+fn validate_access_control(value: &Value, diagnostics: &mut Diagnostics) {
+    let enabled = get(value, "access_control/enabled")
+        .and_then(Value::as_bool)
+        .unwrap_or(false);
+    let rules = collect_ids(value, "access_control/rules", diagnostics);
+    let subjects = collect_ids(value, "access_control/subjects", diagnostics);
+    collect_ids(value, "access_control/policies", diagnostics);
+
+    if let Some(Value::Array(items)) = get(value, "access_control/rules") {
+        for rule in items {
+            let id = rule.get("id").and_then(Value::as_str).unwrap_or_default();
+            for k in rule
+                .get("key_exprs")
+                .and_then(Value::as_array)
+                .into_iter()
+                .flatten()
+                .filter_map(Value::as_str)
+            {
+                if let Err(e) = keyexpr::new(k) {
+                    diagnostics.error(
+                        "access_control/rules",
+                        format!("Rule '{}' has an invalid key expression '{}': {}", id, k, e),
+                    );
+                }
+            }
+        }
+    }
+    if let Some(Value::Array(items)) = get(value, "access_control/policies") {
+        for policy in items {
+            let id = policy.get("id").and_then(Value::as_str).unwrap_or_default();
+            for (what, field, known) in [
+                ("rule", "rules", &rules),
+                ("subject", "subjects", &subjects),
+            ] {
+                for reference in policy
+                    .get(field)
+                    .and_then(Value::as_array)
+                    .into_iter()
+                    .flatten()
+                    .filter_map(Value::as_str)
+                {
+                    if !known.contains(reference) {
+                        diagnostics.error(
+                            "access_control/policies",
+                            format!(
+                                "Policy '{}' references unknown {} '{}'",
+                                id, what, reference
+                            ),
+                        );
+                    }
+                }
+            }
+        }
+    }
+    if !enabled && !(rules.is_empty() && subjects.is_empty()) {
+        diagnostics.warning(
+            "access_control/enabled",
+            "Access control rules are defined, but access control is disabled".into(),
+        );
+    }
+}
+
+fn validate_plugins(value: &Value, diagnostics: &mut Diagnostics) {
+    let loading = get(value, "plugins_loading/enabled")
+        .and_then(Value::as_bool)
+        .unwrap_or(false);
+    if let Some(Value::Object(plugins)) = get(value, "plugins") {
+        for name in plugins.keys() {
+            if !loading {
+                diagnostics.warning(
+                    &format!("plugins/{}", name),
+                    format!(
+                        "Plugin '{}' is configured, but plugins loading is disabled",
+                        name
+                    ),
+                );
+            }
+        }
+    }
+}
+
+fn validate(config: &Config) -> Diagnostics {
+    let mut diagnostics = Diagnostics::default();
+    let value = match serde_json::to_value(config) {
+        Ok(v) => v,
+        Err(e) => {
+            diagnostics.error("", format!("Failed to serialize config: {}", e));
+            return diagnostics;
+        }
+    };
+    let mode = get(&value, "mode")
+        .and_then(Value::as_str)
+        .unwrap_or("peer")
+        .to_string();
+    validate_endpoints(&value, &mode, &mut diagnostics);
+    validate_scouting(&value, &mut diagnostics);
+    validate_transport(&value, &mode, &mut diagnostics);
+    validate_access_control(&value, &mut diagnostics);
+    validate_plugins(&value, &mut diagnostics);
+    diagnostics
+}
+
+fn write_diagnostics(
+    diagnostics: Diagnostics,
+    out: &mut MaybeUninit<zc_owned_config_diagnostics_t>,
+) -> z_result_t {
+    let res = if diagnostics.has_errors() {
+        result::Z_EINVAL
+    } else {
+        result::Z_OK
+    };
+    out.as_rust_type_mut_uninit().write(Some(diagnostics.0));
+    res
+}
+
+/// @warning This API has been marked as unstable: it works as advertised, but it may be changed in a future release.
+/// @brief Validates the configuration without opening a session.
+///
+/// Checks endpoint syntax, conflicting options (e.g. client mode without any way to find a router), transport limits,
+/// access control consistency and plugin sections.
+///
+/// @param config: The configuration to validate.
+/// @param diagnostics: An uninitialized memory location where the list of diagnostics will be constructed.
+/// @return 0 if the configuration has no error-level diagnostic (it may still have warnings), `Z_EINVAL` otherwise.
+#[no_mangle]
+pub extern "C" fn zc_config_validate(
+    config: &z_loaned_config_t,
+    diagnostics: &mut MaybeUninit<zc_owned_config_diagnostics_t>,
+) -> z_result_t {
+    write_diagnostics(validate(config.as_rust_type_ref()), diagnostics)
+}
+
+/// @warning This API has been marked as unstable: it works as advertised, but it may be changed in a future release.
+/// @brief Validates a JSON5 configuration string without opening a session.
+///
+/// Syntax errors and unknown keys are reported as error-level diagnostics, then the parsed configuration is
+/// validated as by `zc_config_validate()`.
+///
+/// @param s: A null-terminated JSON5 configuration string.
+/// @param diagnostics: An uninitialized memory location where the list of diagnostics will be constructed.
+/// @return 0 if the configuration has no error-level diagnostic (it may still have warnings), `Z_EINVAL` otherwise.
+#[no_mangle]
+#[allow(clippy::missing_safety_doc)]
+pub unsafe extern "C" fn zc_config_validate_str(
+    s: *const c_char,
+    diagnostics: &mut MaybeUninit<zc_owned_config_diagnostics_t>,
+) -> z_result_t {
+    let mut d = Diagnostics::default();
+    let s = match CStringView::new_borrowed(s, strlen_or_zero(s)) {
+        Ok(s) => s,
+        Err(r) => {
+            diagnostics.as_rust_type_mut_uninit().write(None);
+            return r;
+        }
+    };
+    match (&s).try_into() {
+        Ok(s) => match json5::from_str::<Config>(s) {
+            Ok(config) => d = validate(&config),
+            Err(e) => d.error("", format!("Invalid config: {}", e)),
+        },
+        Err(e) => d.error("", format!("Config is not a valid utf-8 string: {}", e)),
+    }
+    write_diagnostics(d, diagnostics)
+}
+
+/// @warning This API has been marked as unstable: it works as advertised, but it may be changed in a future release.
+/// @brief Constructs diagnostics in their gravestone state.
+#[no_mangle]
+pub extern "C" fn zc_internal_config_diagnostics_null(
+    this_: &mut MaybeUninit<zc_owned_config_diagnostics_t>,
+) {
+    this_.as_rust_type_mut_uninit().write(None);
+}
+
+/// @warning This API has been marked as unstable: it works as advertised, but it may be changed in a future release.
+/// @brief Returns ``true`` if diagnostics are valid, ``false`` if they are in gravestone state.
+#[no_mangle]
+pub extern "C" fn zc_internal_config_diagnostics_check(
+    this_: &zc_owned_config_diagnostics_t,
+) -> bool {
+    this_.as_rust_type_ref().is_some()
+}
+
+/// @warning This API has been marked as unstable: it works as advertised, but it may be changed in a future release.
+/// @brief Borrows diagnostics.
+#[no_mangle]
+#[allow(clippy::missing_safety_doc)]
+pub unsafe extern "C" fn zc_config_diagnostics_loan(
+    this_: &zc_owned_config_diagnostics_t,
+) -> &zc_loaned_config_diagnostics_t {
+    this_
+        .as_rust_type_ref()
+        .as_ref()
+        .unwrap_unchecked()
+        .as_loaned_c_type_ref()
+}
+
+/// @warning This API has been marked as unstable: it works as advertised, but it may be changed in a future release.
+/// @brief Frees diagnostics, resetting them to their gravestone state.
+#[no_mangle]
+pub extern "C" fn zc_config_diagnostics_drop(this_: &mut zc_moved_config_diagnostics_t) {
+    let _ = this_.take_rust_type();
+}
+
+/// @warning This API has been marked as unstable: it works as advertised, but it may be changed in a future release.
+/// @brief Returns the number of diagnostics.
+#[no_mangle]
+pub extern "C" fn zc_config_diagnostics_len(this_: &zc_loaned_config_diagnostics_t) -> usize {
+    this_.as_rust_type_ref().len()
+}
+
+/// @warning This API has been marked as unstable: it works as advertised, but it may be changed in a future release.
+/// @brief Returns the diagnostic at the position of index, or `NULL` if the index is out of bounds.
+#[no_mangle]
+pub extern "C" fn zc_config_diagnostics_get(
+    this_: &zc_loaned_config_diagnostics_t,
+    index: usize,
+) -> Option<&zc_loaned_config_diagnostic_t> {
+    this_
+        .as_rust_type_ref()
+        .get(index)
+        .map(|d| d.as_loaned_c_type_ref())
+}
+
+/// @warning This API has been marked as unstable: it works as advertised, but it may be changed in a future release.
+/// @brief Returns the severity of the diagnostic.
+#[no_mangle]
+pub extern "C" fn zc_config_diagnostic_severity(
+    this_: &zc_loaned_config_diagnostic_t,
+) -> zc_config_diagnostic_severity_t {
+    this_.as_rust_type_ref().severity
+}
+
+/// @warning This API has been marked as unstable: it works as advertised, but it may be changed in a future release.
+/// @brief Returns the configuration key the diagnostic refers to (e.g. "connect/endpoints"), empty if it refers to the whole configuration.
+#[no_mangle]
+pub extern "C" fn zc_config_diagnostic_key(
+    this_: &zc_loaned_config_diagnostic_t,
+) -> &z_loaned_string_t {
+    this_.as_rust_type_ref().key.as_loaned_c_type_ref()
+}
+
+/// @warning This API has been marked as unstable: it works as advertised, but it may be changed in a future release.
+/// @brief Returns the human-readable message of the diagnostic.
+#[no_mangle]
+pub extern "C" fn zc_config_diagnostic_message(
+    this_: &zc_loaned_config_diagnostic_t,
+) -> &z_loaned_string_t {
+    this_.as_rust_type_ref().message.as_loaned_c_type_ref()
+}
+
+/// Flattens `value` into `/`-separated keys of its non-object leaves (arrays are compared as a whole).
+pub(crate) fn flatten(prefix: String, value: Value, out: &mut BTreeMap<String, Value>) {
+    match value {
+        Value::Object(o) => {
+            for (k, v) in o {
+                let key = if prefix.is_empty() {
+                    k
+                } else {
+                    format!("{}/{}", prefix, k)
+                };
+                flatten(key, v, out);
+            }
+        }
+        v => {
+            out.insert(prefix, v);
+        }
+    }
+}
+
+/// @warning This API has been marked as unstable: it works as advertised, but it may be changed in a future release.
+/// @brief Computes the keys whose values differ between two configurations.
+///
+/// Keys are `/`-separated paths to the differing values (e.g. "scouting/multicast/enabled"), usable with `zc_config_get_from_str()`.
+/// Lists are compared as a whole.
+///
+/// @param this_: A configuration.
+/// @param other: Another configuration.
+/// @param keys: An uninitialized memory location where the sorted array of differing keys will be constructed.
+/// @return 0 in case of success, negative error code otherwise.
+#[no_mangle]
+pub extern "C" fn zc_config_diff(
+    this_: &z_loaned_config_t,
+    other: &z_loaned_config_t,
+    keys: &mut MaybeUninit<z_owned_string_array_t>,
+) -> z_result_t {
+    let keys = keys.as_rust_type_mut_uninit();
+    let (a, b) = match (
+        serde_json::to_value(this_.as_rust_type_ref()),
+        serde_json::to_value(other.as_rust_type_ref()),
+    ) {
+        (Ok(a), Ok(b)) => (a, b),
+        (Err(e), _) | (_, Err(e)) => {
+            crate::report_error!(zc_error_kind_t::OTHER, "Failed to serialize config: {}", e);
+            keys.write(ZVector::new());
+            return result::Z_EGENERIC;
+        }
+    };
+    let (mut a_leaves, mut b_leaves) = (BTreeMap::new(), BTreeMap::new());
+    flatten(String::new(), a, &mut a_leaves);
+    flatten(String::new(), b, &mut b_leaves);
+    let mut diff: Vec<String> = a_leaves
+        .iter()
+        .filter(|(k, v)| b_leaves.get(*k) != Some(*v))
+        .map(|(k, _)| k.clone())
+        .collect();
+    diff.extend(
+        b_leaves
+            .keys()
+            .filter(|k| !a_leaves.contains_key(*k))
+            .cloned(),
+    );
+    diff.sort();
+    keys.write(diff.into_iter().map(CStringInner::from).collect());
+    result::Z_OK
+}
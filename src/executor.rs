@@ -0,0 +1,419 @@
+//
+// Copyright (c) 2017, 2024 ZettaScale Technology.
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ZettaScale Zenoh team, <zenoh@zettascale.tech>
+//
+
+//! Dispatching of entity callbacks through a user-provided executor, registered with `z_open_options_t::executor`.
+//!
+//! Each entity (subscriber, queryable, get operation, listener...) declared on a session with an executor gets its own queue.
+//! Callbacks are pushed to this queue from zenoh threads, and the executor receives a work item whenever the queue
+//! becomes non-empty. Since only one work item per queue exists at any time, callbacks of the same entity are never
+//! run concurrently and are run in the order they were received.
+//!
+//! Zenoh drops the callback of an entity when it is undeclared or when its session is closed. At this point, the queue
+//! is closed: pending callbacks are discarded and the callback being run, if any, is waited for, so that no callback runs
+//! once the undeclaration returns. Get operations are the exception: their callback is dropped once the last reply is
+//! received, so the replies still pending are run.
+
+#[cfg(feature = "unstable")]
+use std::{
+    collections::{HashMap, VecDeque},
+    mem::MaybeUninit,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc, Condvar, Mutex, RwLock, Weak,
+    },
+    thread::{self, ThreadId},
+};
+
+#[cfg(feature = "unstable")]
+use lazy_static::lazy_static;
+#[cfg(feature = "unstable")]
+use zenoh::{
+    pubsub::Publisher,
+    query::Querier,
+    session::{EntityGlobalId, Session},
+};
+
+#[cfg(feature = "unstable")]
+pub use crate::opaque_types::{zc_loaned_work_item_t, zc_moved_work_item_t, zc_owned_work_item_t};
+#[cfg(feature = "unstable")]
+use crate::{
+    transmute::{LoanedCTypeRef, RustTypeRef, RustTypeRefUninit, TakeRustType},
+    zc_closure_work_item_call, zc_closure_work_item_loan, zc_owned_closure_work_item_t,
+};
+
+#[cfg(feature = "unstable")]
+type Task = Box<dyn FnOnce() + Send>;
+
+#[cfg(feature = "unstable")]
+pub(crate) struct Executor {
+    closure: zc_owned_closure_work_item_t,
+    /// Set once the session is closed, so that no new entity uses the executor.
+    closed: AtomicBool,
+}
+
+#[cfg(feature = "unstable")]
+impl Executor {
+    fn submit(&self, work_item: WorkItem) {
+        let mut work_item = Some(work_item);
+        zc_closure_work_item_call(zc_closure_work_item_loan(&self.closure), unsafe {
+            work_item.as_mut().unwrap_unchecked().as_loaned_c_type_mut()
+        });
+        // The executor did not take the work item: run it in place.
+        if let Some(work_item) = work_item {
+            work_item.run();
+        }
+    }
+}
+
+#[cfg(feature = "unstable")]
+lazy_static! {
+    /// Executors of the open sessions, by session id.
+    static ref EXECUTORS: RwLock<HashMap<EntityGlobalId, Arc<Executor>>> = RwLock::new(HashMap::new());
+    /// Executors of the entities from which other entities can be declared (e.g. matching listeners of publishers),
+    /// by entity id. These are looked up when the derived entities are declared.
+    static ref ENTITY_EXECUTORS: RwLock<HashMap<EntityGlobalId, Weak<Executor>>> = RwLock::new(HashMap::new());
+}
+
+#[cfg(feature = "unstable")]
+pub(crate) fn register_executor(session: &Session, closure: zc_owned_closure_work_item_t) {
+    let executor = Arc::new(Executor {
+        closure,
+        closed: AtomicBool::new(false),
+    });
+    EXECUTORS.write().unwrap().insert(session.id(), executor);
+}
+
+/// Called when the session is closed. The executor itself is dropped once the work items of all its entities are dropped.
+#[cfg(feature = "unstable")]
+pub(crate) fn unregister_executor(session: &Session) {
+    if let Some(executor) = EXECUTORS.write().unwrap().remove(&session.id()) {
+        executor.closed.store(true, Ordering::SeqCst);
+    }
+}
+
+#[cfg(feature = "unstable")]
+fn session_executor(session: &Session) -> Option<Arc<Executor>> {
+    EXECUTORS.read().unwrap().get(&session.id()).cloned()
+}
+
+/// Makes the entities declared from the entity `id` (e.g. matching listeners of a publisher) use the executor of `session`.
+/// Should be called when the entity is declared.
+#[cfg(feature = "unstable")]
+pub(crate) fn inherit_executor(session: &Session, id: EntityGlobalId) {
+    let Some(executor) = session_executor(session) else {
+        return;
+    };
+    let mut executors = ENTITY_EXECUTORS.write().unwrap();
+    executors.retain(|_, e| e.strong_count() > 0);
+    executors.insert(id, Arc::downgrade(&executor));
+}
+
+#[cfg(feature = "unstable")]
+fn entity_executor(id: EntityGlobalId) -> Option<Arc<Executor>> {
+    ENTITY_EXECUTORS
+        .read()
+        .unwrap()
+        .get(&id)
+        .and_then(Weak::upgrade)
+        .filter(|e| !e.closed.load(Ordering::SeqCst))
+}
+
+/// Maximum number of callbacks run by a single work item, so that a busy entity does not monopolize the executor.
+#[cfg(feature = "unstable")]
+const MAX_TASKS_PER_WORK_ITEM: usize = 64;
+
+#[cfg(feature = "unstable")]
+#[derive(Default)]
+struct EntityQueueState {
+    tasks: VecDeque<Task>,
+    scheduled: bool,
+    closed: bool,
+    /// Thread running a callback of the entity, if any.
+    running: Option<ThreadId>,
+}
+
+#[cfg(feature = "unstable")]
+struct EntityQueue {
+    executor: Arc<Executor>,
+    state: Mutex<EntityQueueState>,
+    /// Signaled when a callback returns.
+    idle: Condvar,
+}
+
+#[cfg(feature = "unstable")]
+impl EntityQueue {
+    fn new(executor: Arc<Executor>) -> Arc<Self> {
+        Arc::new(EntityQueue {
+            executor,
+            state: Mutex::default(),
+            idle: Condvar::new(),
+        })
+    }
+
+    fn push(self: &Arc<Self>, task: Task) {
+        let mut state = self.state.lock().unwrap();
+        if state.closed {
+            drop(state);
+            drop(task);
+            return;
+        }
+        state.tasks.push_back(task);
+        if !state.scheduled {
+            state.scheduled = true;
+            drop(state);
+            self.executor.submit(WorkItem(Some(self.clone())));
+        }
+    }
+
+    fn run(self: Arc<Self>) {
+        for _ in 0..MAX_TASKS_PER_WORK_ITEM {
+            let task = {
+                let mut state = self.state.lock().unwrap();
+                match state.tasks.pop_front() {
+                    Some(task) => {
+                        state.running = Some(thread::current().id());
+                        task
+                    }
+                    None => {
+                        state.scheduled = false;
+                        return;
+                    }
+                }
+            };
+            task();
+            self.state.lock().unwrap().running = None;
+            self.idle.notify_all();
+        }
+        let reschedule = {
+            let mut state = self.state.lock().unwrap();
+            state.scheduled = !state.tasks.is_empty();
+            state.scheduled
+        };
+        if reschedule {
+            self.executor.submit(WorkItem(Some(self.clone())));
+        }
+    }
+
+    fn discard(&self) {
+        let tasks = {
+            let mut state = self.state.lock().unwrap();
+            state.scheduled = false;
+            std::mem::take(&mut state.tasks)
+        };
+        // Dropping the callbacks outside of the lock, since dropping the last one may drop the user closure.
+        drop(tasks);
+    }
+
+    /// Discards the pending callbacks and prevents new ones from being queued. Waits for the callback being run,
+    /// unless it is run by the calling thread (i.e. the entity is undeclared from its own callback).
+    fn close(&self) {
+        let tasks = {
+            let mut state = self.state.lock().unwrap();
+            state.closed = true;
+            let current = thread::current().id();
+            while state.running.is_some_and(|t| t != current) {
+                state = self.idle.wait(state).unwrap();
+            }
+            std::mem::take(&mut state.tasks)
+        };
+        drop(tasks);
+    }
+}
+
+/// Closes the queue of an entity when the callback given to zenoh is dropped.
+#[cfg(feature = "unstable")]
+struct EntityQueueGuard(Arc<EntityQueue>);
+
+#[cfg(feature = "unstable")]
+impl Drop for EntityQueueGuard {
+    fn drop(&mut self) {
+        self.0.close();
+    }
+}
+
+/// Pending callbacks of an entity, to be run by the executor. An empty work item does nothing.
+#[cfg(feature = "unstable")]
+pub struct WorkItem(Option<Arc<EntityQueue>>);
+
+#[cfg(feature = "unstable")]
+impl WorkItem {
+    fn run(mut self) {
+        if let Some(queue) = self.0.take() {
+            queue.run();
+        }
+    }
+}
+
+#[cfg(feature = "unstable")]
+impl Drop for WorkItem {
+    fn drop(&mut self) {
+        if let Some(queue) = self.0.take() {
+            queue.discard();
+        }
+    }
+}
+
+#[cfg(feature = "unstable")]
+decl_c_type!(
+    owned(zc_owned_work_item_t, option WorkItem),
+    loaned(zc_loaned_work_item_t),
+);
+
+/// An entity whose callbacks may be dispatched through the executor of its session.
+#[cfg(feature = "unstable")]
+pub(crate) trait DispatchSource {
+    fn executor(&self) -> Option<Arc<Executor>>;
+}
+
+#[cfg(feature = "unstable")]
+impl DispatchSource for Session {
+    fn executor(&self) -> Option<Arc<Executor>> {
+        session_executor(self)
+    }
+}
+
+#[cfg(feature = "unstable")]
+macro_rules! impl_dispatch_source_by_entity_id {
+    ($($t:ty),*) => {
+        $(impl DispatchSource for $t {
+            fn executor(&self) -> Option<Arc<Executor>> {
+                entity_executor(self.id())
+            }
+        })*
+    };
+}
+
+#[cfg(feature = "unstable")]
+impl_dispatch_source_by_entity_id!(
+    Publisher<'_>,
+    Querier<'_>,
+    zenoh_ext::AdvancedPublisher<'_>,
+    zenoh_ext::AdvancedSubscriber<()>
+);
+
+/// Wraps the callback of an entity declared on `source`, so that it is run by the executor of its session (if any),
+/// preserving the order in which the callback is invoked by zenoh.
+///
+/// Once zenoh drops the returned callback, i.e. once the entity is undeclared, the pending callbacks are discarded.
+#[cfg(feature = "unstable")]
+pub(crate) fn dispatch_callback<S, T, F>(
+    source: &S,
+    callback: F,
+) -> impl Fn(T) + Send + Sync + 'static
+where
+    S: DispatchSource + ?Sized,
+    T: Send + 'static,
+    F: Fn(T) + Send + Sync + 'static,
+{
+    let guard = source
+        .executor()
+        .map(|executor| EntityQueueGuard(EntityQueue::new(executor)));
+    let callback = Arc::new(callback);
+    move |value: T| match &guard {
+        Some(guard) => {
+            let callback = callback.clone();
+            guard.0.push(Box::new(move || callback(value)));
+        }
+        None => callback(value),
+    }
+}
+
+/// Same as `dispatch_callback()`, but for the replies of a get operation: the pending replies are still run
+/// once zenoh drops the returned callback after the last reply is received.
+#[cfg(feature = "unstable")]
+pub(crate) fn dispatch_reply_callback<S, T, F>(
+    source: &S,
+    callback: F,
+) -> impl Fn(T) + Send + Sync + 'static
+where
+    S: DispatchSource + ?Sized,
+    T: Send + 'static,
+    F: Fn(T) + Send + Sync + 'static,
+{
+    let queue = source.executor().map(EntityQueue::new);
+    let callback = Arc::new(callback);
+    move |value: T| match &queue {
+        Some(queue) => {
+            let callback = callback.clone();
+            queue.push(Box::new(move || callback(value)));
+        }
+        None => callback(value),
+    }
+}
+
+/// Without the executor API, callbacks are always run on zenoh threads.
+#[cfg(not(feature = "unstable"))]
+pub(crate) fn dispatch_callback<S: ?Sized, T, F: Fn(T)>(_source: &S, callback: F) -> F {
+    callback
+}
+
+/// Without the executor API, callbacks are always run on zenoh threads.
+#[cfg(not(feature = "unstable"))]
+pub(crate) fn dispatch_reply_callback<S: ?Sized, T, F: Fn(T)>(_source: &S, callback: F) -> F {
+    callback
+}
+
+/// @warning This API has been marked as unstable: it works as advertised, but it may be changed in a future release.
+/// @brief Constructs a work item in its gravestone state.
+#[cfg(feature = "unstable")]
+#[no_mangle]
+pub extern "C" fn zc_internal_work_item_null(this_: &mut MaybeUninit<zc_owned_work_item_t>) {
+    this_.as_rust_type_mut_uninit().write(None);
+}
+
+/// @warning This API has been marked as unstable: it works as advertised, but it may be changed in a future release.
+/// @brief Returns ``true`` if work item is valid, ``false`` if it is in gravestone state.
+#[cfg(feature = "unstable")]
+#[no_mangle]
+pub extern "C" fn zc_internal_work_item_check(this_: &zc_owned_work_item_t) -> bool {
+    this_.as_rust_type_ref().is_some()
+}
+
+/// @warning This API has been marked as unstable: it works as advertised, but it may be changed in a future release.
+/// @brief Takes ownership of the mutably borrowed work item. This should be called by the executor closure,
+/// if the work item is to be run later or on another thread. Otherwise, it is run in place once the executor closure returns.
+#[cfg(feature = "unstable")]
+#[no_mangle]
+pub extern "C" fn zc_work_item_take_from_loaned(
+    dst: &mut MaybeUninit<zc_owned_work_item_t>,
+    src: &mut zc_loaned_work_item_t,
+) {
+    let src = src.as_rust_type_mut();
+    dst.as_rust_type_mut_uninit()
+        .write(Some(WorkItem(src.0.take())));
+}
+
+/// @warning This API has been marked as unstable: it works as advertised, but it may be changed in a future release.
+/// @brief Runs the pending callbacks of the entity this work item was created for, then consumes the work item.
+///
+/// The work item may be run on any thread. The callbacks of an entity are always run in order and never concurrently.
+/// To avoid starving other entities, a work item runs a bounded number of callbacks; if more are pending,
+/// a new work item is submitted to the executor.
+#[cfg(feature = "unstable")]
+#[no_mangle]
+pub extern "C" fn zc_work_item_run(this_: &mut zc_moved_work_item_t) {
+    if let Some(work_item) = this_.take_rust_type() {
+        work_item.run();
+    }
+}
+
+/// @warning This API has been marked as unstable: it works as advertised, but it may be changed in a future release.
+/// @brief Drops the work item without running it, resetting it to its gravestone state.
+///
+/// The pending callbacks of the corresponding entity are discarded. This is intended for executors shutting down.
+#[cfg(feature = "unstable")]
+#[no_mangle]
+pub extern "C" fn zc_work_item_drop(this_: &mut zc_moved_work_item_t) {
+    let _ = this_.take_rust_type();
+}
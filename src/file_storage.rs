@@ -0,0 +1,510 @@
+//
+// Copyright (c) 2017, 2024 ZettaScale Technology.
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ZettaScale Zenoh team, <zenoh@zettascale.tech>
+//
+
+//! Storage persisting its samples to an append-only file.
+//!
+//! The file starts with [`MAGIC`], followed by records each made of a header and of the encoded sample. The header holds
+//! the little-endian `u32` length of the encoded sample, the bitwise complement of that length and the CRC-32 of the
+//! encoded sample. The encoded sample is made of its kind (`u8`), the NTP64 time (`u64`) and id (16 bytes) of its timestamp,
+//! its key expression, encoding and payload (each one as a `u32` length followed by the bytes), and its optional attachment
+//! (a `u8` flag, then a length and the bytes).
+//!
+//! A record torn by an interrupted append (e.g. after a power loss) is discarded when the file is opened: such a record
+//! is the last one, and either its length is incomplete or it goes past the end of the file. The complement of the length
+//! tells it apart from a corrupted length, which like any other invalid record makes the opening fail, leaving the file
+//! untouched.
+
+use std::{
+    fs::{File, OpenOptions},
+    io::{self, Read, Seek, SeekFrom, Write},
+    mem::MaybeUninit,
+    path::{Path, PathBuf},
+    sync::{Arc, Mutex},
+};
+
+use libc::c_char;
+use zenoh::{
+    bytes::{Encoding, ZBytes},
+    key_expr::OwnedKeyExpr,
+    sample::SampleKind,
+    time::{Timestamp, TimestampId, NTP64},
+};
+
+pub use crate::opaque_types::{
+    ze_loaned_file_storage_t, ze_moved_file_storage_t, ze_owned_file_storage_t,
+};
+use crate::{
+    memory_storage::{MemoryStorage, StorageState, StoredSample},
+    result, strlen_or_zero,
+    transmute::{LoanedCTypeRef, RustTypeRef, RustTypeRefUninit, TakeRustType},
+    z_loaned_keyexpr_t, z_loaned_session_t, z_locality_default, z_locality_t, CStringView,
+};
+
+/// Header identifying storage files.
+const MAGIC: &[u8; 8] = b"ZCSTOR02";
+/// Size of the header preceding each record.
+const HEADER_LEN: usize = 12;
+/// Minimal number of outdated records in the file before it gets compacted.
+const COMPACTION_MIN_OUTDATED: usize = 64;
+
+pub(crate) struct StorageFile {
+    path: PathBuf,
+    file: File,
+    records: usize,
+    sync: bool,
+}
+
+fn invalid_data(message: &str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, message)
+}
+
+/// Computes the CRC-32 (IEEE) of `bytes`.
+fn crc32(bytes: &[u8]) -> u32 {
+    let mut crc = !0u32;
+    for &b in bytes {
+        crc ^= b as u32;
+        for _ in 0..8 {
+            crc = if crc & 1 != 0 {
+                (crc >> 1) ^ 0xEDB8_8320
+            } else {
+                crc >> 1
+            };
+        }
+    }
+    !crc
+}
+
+fn put_bytes(buf: &mut Vec<u8>, bytes: &[u8]) {
+    buf.extend_from_slice(&(bytes.len() as u32).to_le_bytes());
+    buf.extend_from_slice(bytes);
+}
+
+fn encode(sample: &StoredSample) -> Vec<u8> {
+    let mut buf = vec![0u8; HEADER_LEN];
+    buf.push(match sample.kind {
+        SampleKind::Put => 0,
+        SampleKind::Delete => 1,
+    });
+    buf.extend_from_slice(&sample.timestamp.get_time().0.to_le_bytes());
+    buf.extend_from_slice(&sample.timestamp.get_id().to_le_bytes());
+    put_bytes(&mut buf, sample.key_expr.as_str().as_bytes());
+    put_bytes(&mut buf, sample.encoding.to_string().as_bytes());
+    put_bytes(&mut buf, &sample.payload.to_bytes());
+    match &sample.attachment {
+        Some(attachment) => {
+            buf.push(1);
+            put_bytes(&mut buf, &attachment.to_bytes());
+        }
+        None => buf.push(0),
+    }
+    let len = (buf.len() - HEADER_LEN) as u32;
+    let crc = crc32(&buf[HEADER_LEN..]);
+    buf[..4].copy_from_slice(&len.to_le_bytes());
+    buf[4..8].copy_from_slice(&(!len).to_le_bytes());
+    buf[8..HEADER_LEN].copy_from_slice(&crc.to_le_bytes());
+    buf
+}
+
+struct Reader<'a>(&'a [u8]);
+
+impl<'a> Reader<'a> {
+    fn take(&mut self, n: usize) -> io::Result<&'a [u8]> {
+        if self.0.len() < n {
+            return Err(invalid_data("unexpected end of record"));
+        }
+        let (head, tail) = self.0.split_at(n);
+        self.0 = tail;
+        Ok(head)
+    }
+
+    fn u8(&mut self) -> io::Result<u8> {
+        Ok(self.take(1)?[0])
+    }
+
+    fn u32(&mut self) -> io::Result<u32> {
+        Ok(u32::from_le_bytes(self.take(4)?.try_into().unwrap()))
+    }
+
+    fn u64(&mut self) -> io::Result<u64> {
+        Ok(u64::from_le_bytes(self.take(8)?.try_into().unwrap()))
+    }
+
+    fn bytes(&mut self) -> io::Result<&'a [u8]> {
+        let len = self.u32()? as usize;
+        self.take(len)
+    }
+
+    fn string(&mut self) -> io::Result<&'a str> {
+        std::str::from_utf8(self.bytes()?).map_err(|_| invalid_data("invalid utf-8 string"))
+    }
+
+    /// Reads a record of the file, returning `None` if it was torn by an interrupted append.
+    fn record(&mut self) -> io::Result<Option<&'a [u8]>> {
+        let (Ok(len), Ok(check)) = (self.u32(), self.u32()) else {
+            return Ok(None);
+        };
+        if check != !len {
+            return Err(invalid_data("corrupted record length"));
+        }
+        let (Ok(crc), Ok(record)) = (self.u32(), self.take(len as usize)) else {
+            return Ok(None);
+        };
+        if crc32(record) != crc {
+            return Err(invalid_data("record checksum mismatch"));
+        }
+        Ok(Some(record))
+    }
+}
+
+fn decode(record: &[u8]) -> io::Result<StoredSample> {
+    let mut r = Reader(record);
+    let kind = match r.u8()? {
+        0 => SampleKind::Put,
+        1 => SampleKind::Delete,
+        _ => return Err(invalid_data("invalid sample kind")),
+    };
+    let time = NTP64(r.u64()?);
+    let id =
+        TimestampId::try_from(r.take(16)?).map_err(|_| invalid_data("invalid timestamp id"))?;
+    let key_expr = OwnedKeyExpr::try_from(r.string()?.to_string())
+        .map_err(|_| invalid_data("invalid key expression"))?;
+    let encoding = Encoding::from(r.string()?.to_string());
+    let payload = ZBytes::from(r.bytes()?.to_vec());
+    let attachment = match r.u8()? {
+        0 => None,
+        _ => Some(ZBytes::from(r.bytes()?.to_vec())),
+    };
+    Ok(StoredSample {
+        kind,
+        timestamp: Timestamp::new(time, id),
+        key_expr,
+        payload,
+        encoding,
+        attachment,
+    })
+}
+
+impl StorageFile {
+    /// Opens the storage file at `path`, creating it if needed, and returns the samples it holds.
+    pub(crate) fn open(path: &Path, sync: bool) -> io::Result<(Self, Vec<StoredSample>)> {
+        let mut file = OpenOptions::new()
+            .read(true)
+            .write(true)
+            .create(true)
+            .truncate(false)
+            .open(path)?;
+        let mut content = Vec::new();
+        file.read_to_end(&mut content)?;
+        if content.is_empty() {
+            file.write_all(MAGIC)?;
+            content.extend_from_slice(MAGIC);
+        } else if !content.starts_with(MAGIC) {
+            return Err(invalid_data("not a storage file"));
+        }
+        let mut samples = Vec::new();
+        let mut offset = MAGIC.len();
+        while offset < content.len() {
+            let mut r = Reader(&content[offset..]);
+            let record = r.record().map_err(|e| {
+                invalid_data(&format!("invalid record at offset {}: {}", offset, e))
+            })?;
+            // Only the last record can be torn, by an interrupted append.
+            let Some(record) = record else {
+                tracing::warn!(
+                    "Discarding truncated record at the end of storage file {}",
+                    path.display()
+                );
+                file.set_len(offset as u64)?;
+                break;
+            };
+            samples.push(decode(record).map_err(|e| {
+                invalid_data(&format!("invalid record at offset {}: {}", offset, e))
+            })?);
+            offset = content.len() - r.0.len();
+        }
+        file.seek(SeekFrom::Start(offset as u64))?;
+        Ok((
+            StorageFile {
+                path: path.to_path_buf(),
+                file,
+                records: samples.len(),
+                sync,
+            },
+            samples,
+        ))
+    }
+
+    pub(crate) fn append(&mut self, sample: &StoredSample) -> io::Result<()> {
+        self.file.write_all(&encode(sample))?;
+        if self.sync {
+            self.file.sync_data()?;
+        }
+        self.records += 1;
+        Ok(())
+    }
+
+    /// Returns ``true`` if most records of the file are outdated, `live` being the number of records that are not.
+    pub(crate) fn needs_compaction(&self, live: usize) -> bool {
+        let outdated = self.records.saturating_sub(live);
+        outdated >= COMPACTION_MIN_OUTDATED && outdated > live
+    }
+
+    /// Replaces the content of the file by `samples`.
+    ///
+    /// The samples are written to a temporary file which is then renamed, so that the file is never left partially written.
+    pub(crate) fn rewrite<'a>(
+        &mut self,
+        samples: impl Iterator<Item = &'a StoredSample>,
+    ) -> io::Result<()> {
+        let mut tmp_path = self.path.clone().into_os_string();
+        tmp_path.push(".tmp");
+        let tmp_path = PathBuf::from(tmp_path);
+        let mut tmp = File::create(&tmp_path)?;
+        let mut content = MAGIC.to_vec();
+        let mut records = 0;
+        for sample in samples {
+            content.extend_from_slice(&encode(sample));
+            records += 1;
+        }
+        tmp.write_all(&content)?;
+        tmp.sync_all()?;
+        std::fs::rename(&tmp_path, &self.path)?;
+        self.file = OpenOptions::new().append(true).open(&self.path)?;
+        self.records = records;
+        Ok(())
+    }
+}
+
+/// @warning This API has been marked as unstable: it works as advertised, but it may be changed in a future release.
+/// @brief Options passed to the `ze_declare_file_storage()` function.
+#[repr(C)]
+pub struct ze_file_storage_options_t {
+    /// The maximum number of samples stored for each key, older ones being discarded (should be at least 1).
+    pub history: usize,
+    /// The restriction for the matching publications that will be stored.
+    pub subscriber_allowed_origin: z_locality_t,
+    /// The restriction for the matching queries that will be answered.
+    pub queryable_allowed_origin: z_locality_t,
+    /// The `complete` option for the queryable.
+    pub queryable_complete: bool,
+    /// The time in milliseconds after which the tombstone left by a deletion is purged, 0 to keep tombstones forever.
+    /// Samples older than a purged deletion are no longer ignored.
+    pub tombstone_ttl_ms: u64,
+    /// If ``true``, the file is synchronized to the disk after each stored sample, otherwise this is left to the operating system.
+    pub sync_on_write: bool,
+}
+
+impl Default for ze_file_storage_options_t {
+    fn default() -> Self {
+        Self {
+            history: 1,
+            subscriber_allowed_origin: z_locality_default(),
+            queryable_allowed_origin: z_locality_default(),
+            queryable_complete: false,
+            tombstone_ttl_ms: 0,
+            sync_on_write: false,
+        }
+    }
+}
+
+/// @warning This API has been marked as unstable: it works as advertised, but it may be changed in a future release.
+/// @brief Constructs the default value for `ze_file_storage_options_t`.
+#[no_mangle]
+pub extern "C" fn ze_file_storage_options_default(
+    this: &mut MaybeUninit<ze_file_storage_options_t>,
+) {
+    this.write(ze_file_storage_options_t::default());
+}
+
+pub struct FileStorage {
+    storage: MemoryStorage,
+    state: Arc<Mutex<StorageState>>,
+}
+
+decl_c_type!(
+    owned(ze_owned_file_storage_t, option FileStorage),
+    loaned(ze_loaned_file_storage_t),
+);
+
+/// @warning This API has been marked as unstable: it works as advertised, but it may be changed in a future release.
+/// @brief Constructs and declares a storage persisting its samples to a file.
+///
+/// The storage behaves as a storage declared with `ze_declare_memory_storage()`, with each stored sample (and deletion)
+/// being also appended to the file at `path`. When declared, the storage restores the samples held by the file, so that
+/// they survive restarts of the application. The file is compacted when most of its records are outdated.
+///
+/// Samples are written (and compactions are run) from the subscriber callback, so that a sample is persisted once it
+/// is stored: each write delays the delivery of the next samples to the storage, by the time of a disk synchronization
+/// if `sync_on_write` is set.
+///
+/// A record torn at the end of the file by an interrupted append is discarded; if the file holds any other invalid
+/// record (including a record with a corrupted length or checksum), the declaration fails with `Z_EIO` and the file is
+/// left untouched.
+///
+/// @param session: A Zenoh session.
+/// @param storage: An uninitialized location in memory where the storage will be constructed.
+/// @param key_expr: The key expression to store.
+/// @param path: A null-terminated path of the storage file, which is created if it does not exist.
+/// @param options: Additional options for the storage.
+///
+/// @returns 0 in case of success, negative error code otherwise.
+#[allow(clippy::missing_safety_doc)]
+#[no_mangle]
+pub unsafe extern "C" fn ze_declare_file_storage(
+    session: &z_loaned_session_t,
+    storage: &mut MaybeUninit<ze_owned_file_storage_t>,
+    key_expr: &z_loaned_keyexpr_t,
+    path: *const c_char,
+    options: Option<&mut ze_file_storage_options_t>,
+) -> result::z_result_t {
+    let this = storage.as_rust_type_mut_uninit();
+    this.write(None);
+    let session = session.as_rust_type_ref();
+    let key_expr = key_expr.as_rust_type_ref().clone().into_owned();
+    let default_options = ze_file_storage_options_t::default();
+    let options = options.map(|o| &*o).unwrap_or(&default_options);
+    if options.history == 0 {
+        crate::report_error!(
+            zc_error_kind_t::INVALID_ARGUMENT,
+            "Storage history should be at least 1"
+        );
+        return result::Z_EINVAL;
+    }
+    let path = match CStringView::new_borrowed(path, strlen_or_zero(path)) {
+        Ok(path) => path,
+        Err(r) => return r,
+    };
+    let path = match <&str>::try_from(&path) {
+        Ok(path) if !path.is_empty() => Path::new(path),
+        _ => {
+            crate::report_error!(
+                zc_error_kind_t::INVALID_ARGUMENT,
+                "Storage path should be a non-empty utf-8 string"
+            );
+            return result::Z_EINVAL;
+        }
+    };
+    let file = match StorageFile::open(path, options.sync_on_write) {
+        Ok(file) => file,
+        Err(e) => {
+            crate::report_error!(
+                zc_error_kind_t::IO,
+                "Failed to open storage file {}: {}",
+                path.display(),
+                e
+            );
+            return result::Z_EIO;
+        }
+    };
+    let state = Arc::new(Mutex::new(StorageState::new(
+        session,
+        options.history,
+        options.tombstone_ttl_ms,
+        Some(file),
+    )));
+    match MemoryStorage::declare(
+        session,
+        key_expr,
+        state.clone(),
+        options.subscriber_allowed_origin,
+        options.queryable_allowed_origin,
+        options.queryable_complete,
+    ) {
+        Ok(storage) => {
+            this.write(Some(FileStorage { storage, state }));
+            result::Z_OK
+        }
+        Err(e) => {
+            crate::report_error!(zc_error_kind_t::NETWORK, "{}", e);
+            result::Z_ENETWORK
+        }
+    }
+}
+
+/// @warning This API has been marked as unstable: it works as advertised, but it may be changed in a future release.
+/// @brief Compacts the file of the storage, so that it only holds the samples and deletions currently stored.
+/// @return 0 in case of success, negative error code otherwise.
+#[no_mangle]
+pub extern "C" fn ze_file_storage_compact(this_: &ze_loaned_file_storage_t) -> result::z_result_t {
+    match this_.as_rust_type_ref().state.lock().unwrap().compact() {
+        Ok(()) => result::Z_OK,
+        Err(e) => {
+            crate::report_error!(zc_error_kind_t::IO, "Failed to compact storage file: {}", e);
+            result::Z_EIO
+        }
+    }
+}
+
+/// @warning This API has been marked as unstable: it works as advertised, but it may be changed in a future release.
+/// @brief Constructs a file storage in a gravestone state.
+#[no_mangle]
+pub extern "C" fn ze_internal_file_storage_null(this_: &mut MaybeUninit<ze_owned_file_storage_t>) {
+    this_.as_rust_type_mut_uninit().write(None);
+}
+
+/// @warning This API has been marked as unstable: it works as advertised, but it may be changed in a future release.
+/// @brief Returns ``true`` if file storage is valid, ``false`` otherwise.
+#[no_mangle]
+pub extern "C" fn ze_internal_file_storage_check(this_: &ze_owned_file_storage_t) -> bool {
+    this_.as_rust_type_ref().is_some()
+}
+
+/// @warning This API has been marked as unstable: it works as advertised, but it may be changed in a future release.
+/// @brief Borrows file storage.
+#[no_mangle]
+#[allow(clippy::missing_safety_doc)]
+pub unsafe extern "C" fn ze_file_storage_loan(
+    this_: &ze_owned_file_storage_t,
+) -> &ze_loaned_file_storage_t {
+    this_
+        .as_rust_type_ref()
+        .as_ref()
+        .unwrap_unchecked()
+        .as_loaned_c_type_ref()
+}
+
+/// @warning This API has been marked as unstable: it works as advertised, but it may be changed in a future release.
+/// @brief Returns the key expression of the file storage.
+#[no_mangle]
+pub extern "C" fn ze_file_storage_keyexpr(this_: &ze_loaned_file_storage_t) -> &z_loaned_keyexpr_t {
+    this_
+        .as_rust_type_ref()
+        .storage
+        .key_expr()
+        .as_loaned_c_type_ref()
+}
+
+/// @warning This API has been marked as unstable: it works as advertised, but it may be changed in a future release.
+/// @brief Drops file storage and resets it to its gravestone state, its samples remaining in its file.
+/// This is equivalent to calling `ze_undeclare_file_storage()` and discarding its return value.
+#[no_mangle]
+pub extern "C" fn ze_file_storage_drop(this: &mut ze_moved_file_storage_t) {
+    std::mem::drop(this.take_rust_type())
+}
+
+/// @warning This API has been marked as unstable: it works as advertised, but it may be changed in a future release.
+/// @brief Undeclares file storage, its samples remaining in its file.
+/// @return 0 in case of success, negative error code otherwise.
+#[no_mangle]
+pub extern "C" fn ze_undeclare_file_storage(
+    this: &mut ze_moved_file_storage_t,
+) -> result::z_result_t {
+    if let Some(s) = this.take_rust_type() {
+        if let Err(e) = s.storage.undeclare() {
+            crate::report_error!(zc_error_kind_t::NETWORK, "{}", e);
+            return result::Z_EGENERIC;
+        }
+    }
+    result::Z_OK
+}
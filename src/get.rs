@@ -27,6 +27,7 @@ use zenoh::{
 
 pub use crate::opaque_types::{z_loaned_reply_err_t, z_moved_reply_err_t, z_owned_reply_err_t};
 use crate::{
+    executor::dispatch_reply_callback,
     result::{self, Z_EINVAL},
     strlen_or_zero,
     transmute::{Gravestone, LoanedCTypeRef, RustTypeRef, RustTypeRefUninit, TakeRustType},
@@ -39,6 +40,7 @@ use crate::{
 #[cfg(feature = "unstable")]
 use crate::{
     transmute::IntoCType, z_entity_global_id_t, z_moved_cancellation_token_t, z_source_info_t,
+    z_time_range_t,
 };
 decl_c_type!(
     owned(z_owned_reply_err_t, ReplyError),
@@ -241,6 +243,12 @@ pub struct z_get_options_t {
     ///
     /// Cancellation token to interrupt the query.
     pub cancellation_token: Option<&'static mut z_moved_cancellation_token_t>,
+    #[cfg(feature = "unstable")]
+    /// @warning This API has been marked as unstable: it works as advertised, but it may be changed in a future release.
+    ///
+    /// An optional time range restricting the query to historical data in this range.
+    /// It is passed to queryables as the `_time` selector parameter, replacing the one of the query parameters if any.
+    pub time_range: Option<&'static z_time_range_t>,
 }
 
 impl z_get_options_t {
@@ -280,6 +288,8 @@ pub extern "C" fn z_get_options_default(this_: &mut MaybeUninit<z_get_options_t>
         attachment: None,
         #[cfg(feature = "unstable")]
         cancellation_token: None,
+        #[cfg(feature = "unstable")]
+        time_range: None,
     });
 }
 
@@ -350,14 +360,23 @@ pub unsafe extern "C" fn z_get_with_parameters_substr(
             if let Some(o) = options {
                 o.clear();
             }
-            crate::report_error!("Parameters is not a valid utf-8 string: {e}");
+            crate::report_error!(
+                zc_error_kind_t::INVALID_ARGUMENT,
+                "Parameters is not a valid utf-8 string: {e}"
+            );
             return Z_EINVAL;
         }
     };
 
+    #[cfg(feature = "unstable")]
+    let p = crate::time_range::with_time_range(p, options.as_ref().and_then(|o| o.time_range));
     let session = session.as_rust_type_ref();
     let key_expr = key_expr.as_rust_type_ref();
     let mut get = session.get(Selector::from((key_expr, p)));
+    #[cfg(feature = "unstable")]
+    if let Some(timeout) = crate::session::queries_default_timeout(session) {
+        get = get.timeout(timeout);
+    }
     if let Some(options) = options {
         if let Some(payload) = options.payload.take() {
             get = get.payload(payload.take_rust_type());
@@ -396,7 +415,7 @@ pub unsafe extern "C" fn z_get_with_parameters_substr(
         }
     }
     match get
-        .callback(move |response| {
+        .callback(dispatch_reply_callback(session, move |response: Reply| {
             let mut owned_response = Some(response);
             z_closure_reply_call(
                 z_closure_reply_loan(&callback),
@@ -405,13 +424,13 @@ pub unsafe extern "C" fn z_get_with_parameters_substr(
                     .unwrap_unchecked()
                     .as_loaned_c_type_mut(),
             )
-        })
+        }))
         .wait()
     {
         Ok(()) => result::Z_OK,
         Err(e) if e.downcast_ref::<SessionClosedError>().is_some() => result::Z_ESESSION_CLOSED,
         Err(e) => {
-            crate::report_error!("{}", e);
+            crate::report_error!(zc_error_kind_t::NETWORK, "{}", e);
             result::Z_EGENERIC
         }
     }
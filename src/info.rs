@@ -19,8 +19,8 @@ use zenoh::config::WhatAmI;
 use zenoh::handlers::Callback;
 #[cfg(feature = "unstable")]
 use zenoh::session::{
-    Link, LinkEvent, LinkEventsListener, LinkEventsListenerBuilder, SessionInfo, Transport,
-    TransportEvent, TransportEventsListener, TransportEventsListenerBuilder,
+    Link, LinkEvent, LinkEventsListener, LinkEventsListenerBuilder, Session, SessionInfo,
+    Transport, TransportEvent, TransportEventsListener, TransportEventsListenerBuilder,
 };
 use zenoh::{session::ZenohId, Wait};
 
@@ -41,17 +41,17 @@ decl_c_type!(copy(z_id_t, ZenohId));
 
 #[cfg(feature = "unstable")]
 use crate::{
-    transmute::LoanedCTypeRef, z_closure_link_call, z_closure_link_event_call,
-    z_closure_link_event_loan, z_closure_link_loan, z_closure_transport_call,
-    z_closure_transport_event_call, z_closure_transport_event_loan, z_closure_transport_loan,
-    z_loaned_link_event_t, z_loaned_link_events_listener_t, z_loaned_link_t,
-    z_loaned_transport_event_t, z_loaned_transport_events_listener_t, z_loaned_transport_t,
-    z_moved_closure_link_event_t, z_moved_closure_link_t, z_moved_closure_transport_event_t,
-    z_moved_closure_transport_t, z_moved_link_event_t, z_moved_link_events_listener_t,
-    z_moved_link_t, z_moved_transport_event_t, z_moved_transport_events_listener_t,
-    z_moved_transport_t, z_owned_link_event_t, z_owned_link_events_listener_t, z_owned_link_t,
-    z_owned_transport_event_t, z_owned_transport_events_listener_t, z_owned_transport_t,
-    z_sample_kind_t,
+    executor::dispatch_callback, transmute::LoanedCTypeRef, z_closure_link_call,
+    z_closure_link_event_call, z_closure_link_event_loan, z_closure_link_loan,
+    z_closure_transport_call, z_closure_transport_event_call, z_closure_transport_event_loan,
+    z_closure_transport_loan, z_loaned_link_event_t, z_loaned_link_events_listener_t,
+    z_loaned_link_t, z_loaned_transport_event_t, z_loaned_transport_events_listener_t,
+    z_loaned_transport_t, z_moved_closure_link_event_t, z_moved_closure_link_t,
+    z_moved_closure_transport_event_t, z_moved_closure_transport_t, z_moved_link_event_t,
+    z_moved_link_events_listener_t, z_moved_link_t, z_moved_transport_event_t,
+    z_moved_transport_events_listener_t, z_moved_transport_t, z_owned_link_event_t,
+    z_owned_link_events_listener_t, z_owned_link_t, z_owned_transport_event_t,
+    z_owned_transport_events_listener_t, z_owned_transport_t, z_sample_kind_t,
 };
 
 #[cfg(feature = "unstable")]
@@ -852,6 +852,7 @@ pub extern "C" fn z_transport_events_listener_options_default(
 
 #[cfg(feature = "unstable")]
 fn _declare_transport_events_listener_inner<'a>(
+    session: &Session,
     session_info: &'a SessionInfo,
     callback: &mut z_moved_closure_transport_event_t,
     options: Option<&z_transport_events_listener_options_t>,
@@ -860,12 +861,15 @@ fn _declare_transport_events_listener_inner<'a>(
 
     let mut builder = session_info
         .transport_events_listener()
-        .callback(move |mut event| {
-            z_closure_transport_event_call(
-                z_closure_transport_event_loan(&callback),
-                event.as_loaned_c_type_mut(),
-            );
-        });
+        .callback(dispatch_callback(
+            session,
+            move |mut event: TransportEvent| {
+                z_closure_transport_event_call(
+                    z_closure_transport_event_loan(&callback),
+                    event.as_loaned_c_type_mut(),
+                );
+            },
+        ));
 
     if let Some(opts) = options {
         builder = builder.history(opts.history);
@@ -895,14 +899,19 @@ pub extern "C" fn z_declare_transport_events_listener(
 ) -> result::z_result_t {
     let session = session.as_rust_type_ref();
     let session_info = session.info();
-    let builder = _declare_transport_events_listener_inner(&session_info, callback, options);
+    let builder =
+        _declare_transport_events_listener_inner(session, &session_info, callback, options);
     match builder.wait() {
         Ok(l) => {
             listener.as_rust_type_mut_uninit().write(Some(l));
             result::Z_OK
         }
         Err(e) => {
-            crate::report_error!("Failed to declare transport events listener: {}", e);
+            crate::report_error!(
+                zc_error_kind_t::NETWORK,
+                "Failed to declare transport events listener: {}",
+                e
+            );
             result::Z_EGENERIC
         }
     }
@@ -928,11 +937,13 @@ pub extern "C" fn z_declare_background_transport_events_listener(
 ) -> result::z_result_t {
     let session = session.as_rust_type_ref();
     let session_info = session.info();
-    let builder = _declare_transport_events_listener_inner(&session_info, callback, options);
+    let builder =
+        _declare_transport_events_listener_inner(session, &session_info, callback, options);
     match builder.background().wait() {
         Ok(_) => result::Z_OK,
         Err(e) => {
             crate::report_error!(
+                zc_error_kind_t::NETWORK,
                 "Failed to declare background transport events listener: {}",
                 e
             );
@@ -954,7 +965,11 @@ pub extern "C" fn z_undeclare_transport_events_listener(
 ) -> result::z_result_t {
     if let Some(listener) = this_.take_rust_type() {
         if let Err(e) = listener.undeclare().wait_callbacks().wait() {
-            crate::report_error!("Failed to undeclare transport events listener: {}", e);
+            crate::report_error!(
+                zc_error_kind_t::NETWORK,
+                "Failed to undeclare transport events listener: {}",
+                e
+            );
             return result::Z_ENETWORK;
         }
     }
@@ -1150,6 +1165,7 @@ pub extern "C" fn z_link_events_listener_options_default(
 
 #[cfg(feature = "unstable")]
 fn _declare_link_events_listener_inner<'a>(
+    session: &Session,
     session_info: &'a SessionInfo,
     callback: &mut z_moved_closure_link_event_t,
     options: Option<&mut z_link_events_listener_options_t>,
@@ -1157,12 +1173,12 @@ fn _declare_link_events_listener_inner<'a>(
     let callback = callback.take_rust_type();
     let mut builder = session_info
         .link_events_listener()
-        .callback(move |mut event| {
+        .callback(dispatch_callback(session, move |mut event: LinkEvent| {
             z_closure_link_event_call(
                 z_closure_link_event_loan(&callback),
                 event.as_loaned_c_type_mut(),
             );
-        });
+        }));
 
     if let Some(opts) = options {
         builder = builder.history(opts.history);
@@ -1199,14 +1215,18 @@ pub extern "C" fn z_declare_link_events_listener(
 ) -> result::z_result_t {
     let session = session.as_rust_type_ref();
     let session_info = session.info();
-    let builder = _declare_link_events_listener_inner(&session_info, callback, options);
+    let builder = _declare_link_events_listener_inner(session, &session_info, callback, options);
     match builder.wait() {
         Ok(l) => {
             listener.as_rust_type_mut_uninit().write(Some(l));
             result::Z_OK
         }
         Err(e) => {
-            crate::report_error!("Failed to declare link events listener: {}", e);
+            crate::report_error!(
+                zc_error_kind_t::NETWORK,
+                "Failed to declare link events listener: {}",
+                e
+            );
             result::Z_EGENERIC
         }
     }
@@ -1232,11 +1252,15 @@ pub extern "C" fn z_declare_background_link_events_listener(
 ) -> result::z_result_t {
     let session = session.as_rust_type_ref();
     let session_info = session.info();
-    let builder = _declare_link_events_listener_inner(&session_info, callback, options);
+    let builder = _declare_link_events_listener_inner(session, &session_info, callback, options);
     match builder.background().wait() {
         Ok(_) => result::Z_OK,
         Err(e) => {
-            crate::report_error!("Failed to declare background link events listener: {}", e);
+            crate::report_error!(
+                zc_error_kind_t::NETWORK,
+                "Failed to declare background link events listener: {}",
+                e
+            );
             result::Z_EGENERIC
         }
     }
@@ -1255,7 +1279,11 @@ pub extern "C" fn z_undeclare_link_events_listener(
 ) -> result::z_result_t {
     if let Some(listener) = this_.take_rust_type() {
         if let Err(e) = listener.undeclare().wait_callbacks().wait() {
-            crate::report_error!("Failed to undeclare link events listener: {}", e);
+            crate::report_error!(
+                zc_error_kind_t::NETWORK,
+                "Failed to undeclare link events listener: {}",
+                e
+            );
             return result::Z_ENETWORK;
         }
     }
@@ -88,12 +88,20 @@ unsafe fn keyexpr_create(
         Ok(name) => match keyexpr_create_inner(name, should_auto_canonize, should_copy) {
             Ok(v) => Ok(v),
             Err(e) => {
-                crate::report_error!("Couldn't construct keyexpr: {}", e);
+                crate::report_error!(
+                    zc_error_kind_t::INVALID_ARGUMENT,
+                    "Couldn't construct keyexpr: {}",
+                    e
+                );
                 Err(result::Z_EINVAL)
             }
         },
         Err(e) => {
-            crate::report_error!("Key expression is not a valid utf-8 string: {}", e);
+            crate::report_error!(
+                zc_error_kind_t::INVALID_ARGUMENT,
+                "Key expression is not a valid utf-8 string: {}",
+                e
+            );
             Err(result::Z_EPARSE)
         }
     }
@@ -195,7 +203,10 @@ pub unsafe extern "C" fn z_keyexpr_canonize_null_terminated(start: *mut c_char)
 #[no_mangle]
 pub unsafe extern "C" fn z_keyexpr_canonize(start: *mut c_char, len: &mut usize) -> z_result_t {
     if start.is_null() {
-        crate::report_error!("Key expression can not be constructed from null string");
+        crate::report_error!(
+            zc_error_kind_t::INVALID_ARGUMENT,
+            "Key expression can not be constructed from null string"
+        );
         return result::Z_EINVAL;
     }
     let name = std::slice::from_raw_parts_mut(start as _, *len);
@@ -535,6 +546,7 @@ pub unsafe extern "C" fn z_keyexpr_concat(
         Ok(r) => r,
         Err(e) => {
             crate::report_error!(
+                zc_error_kind_t::INVALID_ARGUMENT,
                 "Couldn't concatenate {:02x?} to {} because it is not valid UTF8: {}",
                 right,
                 left,
@@ -550,7 +562,7 @@ pub unsafe extern "C" fn z_keyexpr_concat(
             result::Z_OK
         }
         Err(e) => {
-            crate::report_error!("{}", e);
+            crate::report_error!(zc_error_kind_t::INVALID_ARGUMENT, "{}", e);
             this.write(KeyExpr::gravestone());
             result::Z_EGENERIC
         }
@@ -574,7 +586,7 @@ pub extern "C" fn z_keyexpr_join(
             result::Z_OK
         }
         Err(e) => {
-            crate::report_error!("{}", e);
+            crate::report_error!(zc_error_kind_t::INVALID_ARGUMENT, "{}", e);
             this.write(KeyExpr::gravestone());
             result::Z_EGENERIC
         }
@@ -627,3 +639,189 @@ extern "C" fn z_keyexpr_clone(dst: &mut MaybeUninit<z_owned_keyexpr_t>, this: &z
     dst.as_rust_type_mut_uninit()
         .write(this.as_rust_type_ref().clone());
 }
+
+#[cfg(feature = "unstable")]
+pub use crate::opaque_types::z_keyexpr_chunk_iterator_t;
+#[cfg(feature = "unstable")]
+decl_c_type!(loaned(z_keyexpr_chunk_iterator_t, std::str::Split<'static, char>));
+
+/// Constructs a view of the part of `this` spanning from the start of chunk `start` to the end of chunk `end - 1`.
+#[cfg(feature = "unstable")]
+fn write_chunk_range(
+    this: &'static keyexpr,
+    start: usize,
+    end: usize,
+    view: &mut MaybeUninit<z_view_keyexpr_t>,
+) -> z_result_t {
+    let view = view.as_rust_type_mut_uninit();
+    let s = this.as_str();
+    let mut bounds = std::iter::once(0)
+        .chain(s.match_indices('/').map(|(i, _)| i + 1))
+        .chain(std::iter::once(s.len() + 1));
+    let (Some(from), Some(to)) = (bounds.nth(start), bounds.nth(end - start - 1)) else {
+        crate::report_error!(
+            zc_error_kind_t::INVALID_ARGUMENT,
+            "Key expression '{}' has less than {} chunks",
+            s,
+            end
+        );
+        view.write(KeyExpr::gravestone());
+        return result::Z_EINVAL;
+    };
+    // A sub-range of chunks of a key expression is a valid key expression.
+    view.write(unsafe { keyexpr::from_str_unchecked(&s[from..to - 1]) }.into());
+    Z_OK
+}
+
+#[cfg(feature = "unstable")]
+/// @warning This API has been marked as unstable: it works as advertised, but it may be changed in a future release.
+/// @brief Returns an iterator over the `/`-separated chunks of a key expression.
+///
+/// The iterator and the chunks it yields are views into `this`, and should not outlive it.
+#[no_mangle]
+pub extern "C" fn z_keyexpr_get_chunk_iterator(
+    this: &'static z_loaned_keyexpr_t,
+) -> z_keyexpr_chunk_iterator_t {
+    let this: &'static keyexpr = this.as_rust_type_ref();
+    *this.as_str().split('/').as_loaned_c_type_ref()
+}
+
+#[cfg(feature = "unstable")]
+/// @warning This API has been marked as unstable: it works as advertised, but it may be changed in a future release.
+/// @brief Gets next chunk.
+/// @param this_: Chunk iterator.
+/// @param chunk: An uninitialized memory location where the view of the next chunk will be constructed.
+/// @return `false` if there are no more chunks (in this case chunk will stay unchanged), `true` otherwise.
+#[no_mangle]
+pub extern "C" fn z_keyexpr_chunk_iterator_next(
+    this_: &mut z_keyexpr_chunk_iterator_t,
+    chunk: &mut MaybeUninit<z_view_keyexpr_t>,
+) -> bool {
+    match this_.as_rust_type_mut().next() {
+        Some(c) => {
+            // A chunk of a key expression is a valid key expression.
+            chunk
+                .as_rust_type_mut_uninit()
+                .write(unsafe { keyexpr::from_str_unchecked(c) }.into());
+            true
+        }
+        None => false,
+    }
+}
+
+#[cfg(feature = "unstable")]
+/// @warning This API has been marked as unstable: it works as advertised, but it may be changed in a future release.
+/// @brief Returns the number of `/`-separated chunks of a key expression.
+#[no_mangle]
+pub extern "C" fn z_keyexpr_chunk_count(this: &z_loaned_keyexpr_t) -> usize {
+    this.as_rust_type_ref().as_str().split('/').count()
+}
+
+#[cfg(feature = "unstable")]
+/// @warning This API has been marked as unstable: it works as advertised, but it may be changed in a future release.
+/// @brief Constructs a view of the first `n` chunks of a key expression (e.g. `a/b` for `a/b/c` and `n` = 2).
+///
+/// @param this: The key expression.
+/// @param n: The number of chunks, in the range `[1, z_keyexpr_chunk_count(this)]`.
+/// @param prefix: An uninitialized memory location where the view will be constructed, valid as long as `this` is.
+/// @return 0 in case of success, negative error code if `n` is out of range (in this case the view is empty).
+#[no_mangle]
+pub extern "C" fn z_keyexpr_prefix(
+    this: &'static z_loaned_keyexpr_t,
+    n: usize,
+    prefix: &mut MaybeUninit<z_view_keyexpr_t>,
+) -> z_result_t {
+    if n == 0 {
+        crate::report_error!(
+            zc_error_kind_t::INVALID_ARGUMENT,
+            "Prefix should have at least one chunk"
+        );
+        prefix
+            .as_rust_type_mut_uninit()
+            .write(KeyExpr::gravestone());
+        return result::Z_EINVAL;
+    }
+    write_chunk_range(this.as_rust_type_ref(), 0, n, prefix)
+}
+
+#[cfg(feature = "unstable")]
+/// @warning This API has been marked as unstable: it works as advertised, but it may be changed in a future release.
+/// @brief Constructs a view of the last `n` chunks of a key expression (e.g. `b/c` for `a/b/c` and `n` = 2).
+///
+/// @param this: The key expression.
+/// @param n: The number of chunks, in the range `[1, z_keyexpr_chunk_count(this)]`.
+/// @param suffix: An uninitialized memory location where the view will be constructed, valid as long as `this` is.
+/// @return 0 in case of success, negative error code if `n` is out of range (in this case the view is empty).
+#[no_mangle]
+pub extern "C" fn z_keyexpr_suffix(
+    this: &'static z_loaned_keyexpr_t,
+    n: usize,
+    suffix: &mut MaybeUninit<z_view_keyexpr_t>,
+) -> z_result_t {
+    let count = z_keyexpr_chunk_count(this);
+    if n == 0 || n > count {
+        crate::report_error!(
+            zc_error_kind_t::INVALID_ARGUMENT,
+            "Suffix should have between 1 and {} chunks",
+            count
+        );
+        suffix
+            .as_rust_type_mut_uninit()
+            .write(KeyExpr::gravestone());
+        return result::Z_EINVAL;
+    }
+    write_chunk_range(this.as_rust_type_ref(), count - n, count, suffix)
+}
+
+#[cfg(feature = "unstable")]
+/// @warning This API has been marked as unstable: it works as advertised, but it may be changed in a future release.
+/// @brief Constructs a view of a key expression without its leading chunks equal to `prefix` (e.g. `c` for `a/b/c` and `a/b`).
+///
+/// Chunks are compared literally: wildcards of `prefix` only match the same wildcards in `this`.
+///
+/// @param this: The key expression.
+/// @param prefix: The chunks to strip.
+/// @param stripped: An uninitialized memory location where the view will be constructed, valid as long as `this` is.
+/// @return 0 in case of success, negative error code if `prefix` is not a strict chunk prefix of `this` (in this case the view is empty).
+#[no_mangle]
+pub extern "C" fn z_keyexpr_strip_prefix(
+    this: &'static z_loaned_keyexpr_t,
+    prefix: &z_loaned_keyexpr_t,
+    stripped: &mut MaybeUninit<z_view_keyexpr_t>,
+) -> z_result_t {
+    let this: &'static keyexpr = this.as_rust_type_ref();
+    let prefix: &keyexpr = prefix.as_rust_type_ref();
+    match this
+        .as_str()
+        .strip_prefix(prefix.as_str())
+        .and_then(|s| s.strip_prefix('/'))
+    {
+        Some(s) => {
+            // The chunks following a chunk prefix of a key expression are a valid key expression.
+            stripped
+                .as_rust_type_mut_uninit()
+                .write(unsafe { keyexpr::from_str_unchecked(s) }.into());
+            Z_OK
+        }
+        None => {
+            crate::report_error!(
+                zc_error_kind_t::INVALID_ARGUMENT,
+                "'{}' is not a chunk prefix of '{}'",
+                prefix,
+                this
+            );
+            stripped
+                .as_rust_type_mut_uninit()
+                .write(KeyExpr::gravestone());
+            result::Z_EINVAL
+        }
+    }
+}
+
+#[cfg(feature = "unstable")]
+/// @warning This API has been marked as unstable: it works as advertised, but it may be changed in a future release.
+/// @brief Returns ``true`` if the key expression contains wildcards (`*`, `**` or `$*`), ``false`` otherwise.
+#[no_mangle]
+pub extern "C" fn z_keyexpr_is_wild(this: &z_loaned_keyexpr_t) -> bool {
+    this.as_rust_type_ref().is_wild()
+}
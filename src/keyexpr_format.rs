@@ -0,0 +1,263 @@
+//
+// Copyright (c) 2017, 2024 ZettaScale Technology.
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ZettaScale Zenoh team, <zenoh@zettascale.tech>
+//
+
+use std::mem::MaybeUninit;
+
+use libc::c_char;
+use zenoh::key_expr::{format::KeFormat, KeyExpr};
+
+pub use crate::opaque_types::{
+    z_loaned_keyexpr_format_t, z_moved_keyexpr_format_t, z_owned_keyexpr_format_t,
+};
+use crate::{
+    result::{self, z_result_t},
+    str_arg, strlen_or_zero,
+    transmute::{Gravestone, LoanedCTypeRef, RustTypeRef, RustTypeRefUninit, TakeRustType},
+    z_loaned_keyexpr_t, z_owned_keyexpr_t, z_view_string_t, CStringView,
+};
+
+pub struct KeyExprFormat {
+    // Declared before `_source` so that it is dropped first, since it borrows it.
+    format: KeFormat<'static>,
+    _source: Box<str>,
+}
+
+decl_c_type!(
+    owned(z_owned_keyexpr_format_t, option KeyExprFormat),
+    loaned(z_loaned_keyexpr_format_t),
+);
+
+/// @warning This API has been marked as unstable: it works as advertised, but it may be changed in a future release.
+/// @brief Compiles a key expression format.
+///
+/// A format is a key expression where some chunks are replaced by named specifications `${id:pattern#default}`
+/// (e.g. `robot/${id:*}/sensor/${kind:*}`), `pattern` being a key expression that the value of the chunk should be
+/// included by, and `#default` being an optional default value.
+///
+/// @param this_: An uninitialized memory location where the format will be constructed.
+/// @param format: A null-terminated format string.
+/// @return 0 in case of success, negative error code otherwise (in this case the format will be in its gravestone state).
+#[allow(clippy::missing_safety_doc)]
+#[no_mangle]
+pub unsafe extern "C" fn z_keyexpr_format_from_str(
+    this_: &mut MaybeUninit<z_owned_keyexpr_format_t>,
+    format: *const c_char,
+) -> z_result_t {
+    let this = this_.as_rust_type_mut_uninit();
+    let source: Box<str> = match str_arg(format, strlen_or_zero(format), "Format") {
+        Ok(s) => s.into(),
+        Err(r) => {
+            this.write(None);
+            return r;
+        }
+    };
+    // SAFETY: the format is stored along with its source, and is dropped before it.
+    let static_source = std::mem::transmute::<&str, &'static str>(&source);
+    match KeFormat::new(static_source) {
+        Ok(format) => {
+            this.write(Some(KeyExprFormat {
+                format,
+                _source: source,
+            }));
+            result::Z_OK
+        }
+        Err(e) => {
+            crate::report_error!(
+                zc_error_kind_t::PARSE,
+                "Invalid key expression format '{}': {}",
+                source,
+                e
+            );
+            this.write(None);
+            result::Z_EPARSE
+        }
+    }
+}
+
+/// @warning This API has been marked as unstable: it works as advertised, but it may be changed in a future release.
+/// @brief Constructs a key expression format in its gravestone state.
+#[no_mangle]
+pub extern "C" fn z_internal_keyexpr_format_null(
+    this_: &mut MaybeUninit<z_owned_keyexpr_format_t>,
+) {
+    this_.as_rust_type_mut_uninit().write(None);
+}
+
+/// @warning This API has been marked as unstable: it works as advertised, but it may be changed in a future release.
+/// @brief Returns ``true`` if the key expression format is valid, ``false`` if it is in gravestone state.
+#[no_mangle]
+pub extern "C" fn z_internal_keyexpr_format_check(this_: &z_owned_keyexpr_format_t) -> bool {
+    this_.as_rust_type_ref().is_some()
+}
+
+/// @warning This API has been marked as unstable: it works as advertised, but it may be changed in a future release.
+/// @brief Borrows key expression format.
+#[no_mangle]
+#[allow(clippy::missing_safety_doc)]
+pub unsafe extern "C" fn z_keyexpr_format_loan(
+    this_: &z_owned_keyexpr_format_t,
+) -> &z_loaned_keyexpr_format_t {
+    this_
+        .as_rust_type_ref()
+        .as_ref()
+        .unwrap_unchecked()
+        .as_loaned_c_type_ref()
+}
+
+/// @warning This API has been marked as unstable: it works as advertised, but it may be changed in a future release.
+/// @brief Frees key expression format, resetting it to its gravestone state.
+#[no_mangle]
+pub extern "C" fn z_keyexpr_format_drop(this_: &mut z_moved_keyexpr_format_t) {
+    let _ = this_.take_rust_type();
+}
+
+/// @warning This API has been marked as unstable: it works as advertised, but it may be changed in a future release.
+/// @brief Builds a key expression from a format and the values of its named chunks.
+///
+/// Chunks that are not given a value take their default value, if any.
+///
+/// @param this_: The key expression format.
+/// @param names: An array of `len` null-terminated chunk names.
+/// @param values: An array of `len` null-terminated chunk values, each one should be included by the pattern of its chunk.
+/// @param len: The number of named values.
+/// @param key_expr: An uninitialized memory location where the key expression will be constructed.
+/// @return 0 in case of success, negative error code otherwise (in this case the key expression will be in its gravestone state).
+#[allow(clippy::missing_safety_doc)]
+#[no_mangle]
+pub unsafe extern "C" fn z_keyexpr_format_build(
+    this_: &z_loaned_keyexpr_format_t,
+    names: *const *const c_char,
+    values: *const *const c_char,
+    len: usize,
+    key_expr: &mut MaybeUninit<z_owned_keyexpr_t>,
+) -> z_result_t {
+    let key_expr = key_expr.as_rust_type_mut_uninit();
+    // Shortening the lifetime of the format to the one of the borrow, as required by its methods.
+    let format: &KeFormat<'_> = &this_.as_rust_type_ref().format;
+    if len > 0 && (names.is_null() || values.is_null()) {
+        crate::report_error!(
+            zc_error_kind_t::INVALID_ARGUMENT,
+            "Names and values should not be NULL"
+        );
+        key_expr.write(KeyExpr::gravestone());
+        return result::Z_EINVAL;
+    }
+    let mut formatter = format.formatter();
+    for i in 0..len {
+        let (name, value) = match (
+            str_arg(*names.add(i), strlen_or_zero(*names.add(i)), "Chunk name"),
+            str_arg(
+                *values.add(i),
+                strlen_or_zero(*values.add(i)),
+                "Chunk value",
+            ),
+        ) {
+            (Ok(name), Ok(value)) => (name, value),
+            (Err(r), _) | (_, Err(r)) => {
+                key_expr.write(KeyExpr::gravestone());
+                return r;
+            }
+        };
+        if let Err(e) = formatter.set(name, value) {
+            crate::report_error!(
+                zc_error_kind_t::INVALID_ARGUMENT,
+                "Invalid value '{}' for chunk '{}': {}",
+                value,
+                name,
+                e
+            );
+            key_expr.write(KeyExpr::gravestone());
+            return result::Z_EINVAL;
+        }
+    }
+    match formatter.build() {
+        Ok(ke) => {
+            key_expr.write(ke.into());
+            result::Z_OK
+        }
+        Err(e) => {
+            crate::report_error!(
+                zc_error_kind_t::INVALID_ARGUMENT,
+                "Failed to build key expression: {}",
+                e
+            );
+            key_expr.write(KeyExpr::gravestone());
+            result::Z_EINVAL
+        }
+    }
+}
+
+/// @warning This API has been marked as unstable: it works as advertised, but it may be changed in a future release.
+/// @brief Extracts named chunks of a key expression matching a format.
+///
+/// @param this_: The key expression format.
+/// @param key_expr: The key expression to parse, such as the key expression of a received sample.
+/// @param names: An array of `len` null-terminated chunk names.
+/// @param len: The number of chunks to extract.
+/// @param values: An array of `len` uninitialized memory locations where views of the chunk values will be constructed.
+/// The views are valid as long as `key_expr` is. A chunk that matched nothing (e.g. `${id:**}` matching zero chunks) is empty.
+/// @return 0 in case of success, negative error code if `key_expr` does not match the format or if a name is not part of it.
+#[allow(clippy::missing_safety_doc)]
+#[no_mangle]
+pub unsafe extern "C" fn z_keyexpr_format_parse(
+    this_: &z_loaned_keyexpr_format_t,
+    key_expr: &z_loaned_keyexpr_t,
+    names: *const *const c_char,
+    len: usize,
+    values: *mut MaybeUninit<z_view_string_t>,
+) -> z_result_t {
+    if len > 0 && (names.is_null() || values.is_null()) {
+        crate::report_error!(
+            zc_error_kind_t::INVALID_ARGUMENT,
+            "Names and values should not be NULL"
+        );
+        return result::Z_EINVAL;
+    }
+    let format: &KeFormat<'_> = &this_.as_rust_type_ref().format;
+    let key_expr: &KeyExpr = key_expr.as_rust_type_ref();
+    let parsed = match format.parse(key_expr) {
+        Ok(parsed) => parsed,
+        Err(e) => {
+            crate::report_error!(
+                zc_error_kind_t::INVALID_ARGUMENT,
+                "Key expression '{}' does not match the format: {}",
+                key_expr,
+                e
+            );
+            return result::Z_EINVAL;
+        }
+    };
+    for i in 0..len {
+        let name = match str_arg(*names.add(i), strlen_or_zero(*names.add(i)), "Chunk name") {
+            Ok(name) => name,
+            Err(r) => return r,
+        };
+        let value = match parsed.get(name) {
+            Ok(value) => value.map(|v| v.as_str()).unwrap_or_default(),
+            Err(e) => {
+                crate::report_error!(
+                    zc_error_kind_t::INVALID_ARGUMENT,
+                    "Unknown chunk '{}': {}",
+                    name,
+                    e
+                );
+                return result::Z_EINVAL;
+            }
+        };
+        (*values.add(i))
+            .as_rust_type_mut_uninit()
+            .write(CStringView::new_borrowed_from_slice(value.as_bytes()));
+    }
+    result::Z_OK
+}
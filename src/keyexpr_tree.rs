@@ -0,0 +1,328 @@
+//
+// Copyright (c) 2017, 2024 ZettaScale Technology.
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ZettaScale Zenoh team, <zenoh@zettascale.tech>
+//
+
+use std::{ffi::c_void, mem::MaybeUninit};
+
+use zenoh::key_expr::{
+    keyexpr,
+    keyexpr_tree::{IKeyExprTree, IKeyExprTreeMut, IKeyExprTreeNode, KeBoxTree},
+    KeyExpr, OwnedKeyExpr,
+};
+
+pub use crate::opaque_types::{
+    z_loaned_keyexpr_tree_iterator_t, z_loaned_keyexpr_tree_t, z_moved_keyexpr_tree_iterator_t,
+    z_moved_keyexpr_tree_t, z_owned_keyexpr_tree_iterator_t, z_owned_keyexpr_tree_t,
+};
+use crate::{
+    transmute::{LoanedCTypeRef, RustTypeRef, RustTypeRefUninit, TakeRustType},
+    z_loaned_keyexpr_t, z_view_keyexpr_t,
+};
+
+pub struct KeyExprTree {
+    tree: KeBoxTree<*mut c_void>,
+    drop: Option<extern "C" fn(value: *mut c_void)>,
+}
+
+impl KeyExprTree {
+    fn drop_value(&self, value: *mut c_void) {
+        if let Some(drop) = self.drop {
+            drop(value);
+        }
+    }
+}
+
+impl Drop for KeyExprTree {
+    fn drop(&mut self) {
+        if let Some(drop) = self.drop {
+            for node in self.tree.tree_iter() {
+                if let Some(value) = node.weight() {
+                    drop(*value);
+                }
+            }
+        }
+    }
+}
+
+decl_c_type!(
+    owned(z_owned_keyexpr_tree_t, option KeyExprTree),
+    loaned(z_loaned_keyexpr_tree_t),
+);
+
+/// Entries of a tree matching a key expression, collected when the iterator is created.
+pub struct KeyExprTreeIterator {
+    entries: Vec<(OwnedKeyExpr, *mut c_void)>,
+    next: usize,
+}
+
+decl_c_type!(
+    owned(z_owned_keyexpr_tree_iterator_t, option KeyExprTreeIterator),
+    loaned(z_loaned_keyexpr_tree_iterator_t),
+);
+
+/// @warning This API has been marked as unstable: it works as advertised, but it may be changed in a future release.
+/// @brief Constructs an empty key expression tree.
+///
+/// @param this_: An uninitialized memory location where the tree will be constructed.
+/// @param drop: An optional function called on each value still stored in the tree when it is dropped,
+/// and on values replaced or removed without being returned to the caller.
+#[no_mangle]
+pub extern "C" fn z_keyexpr_tree_new(
+    this_: &mut MaybeUninit<z_owned_keyexpr_tree_t>,
+    drop: Option<extern "C" fn(value: *mut c_void)>,
+) {
+    this_.as_rust_type_mut_uninit().write(Some(KeyExprTree {
+        tree: KeBoxTree::new(),
+        drop,
+    }));
+}
+
+/// @warning This API has been marked as unstable: it works as advertised, but it may be changed in a future release.
+/// @brief Constructs a key expression tree in its gravestone state.
+#[no_mangle]
+pub extern "C" fn z_internal_keyexpr_tree_null(this_: &mut MaybeUninit<z_owned_keyexpr_tree_t>) {
+    this_.as_rust_type_mut_uninit().write(None);
+}
+
+/// @warning This API has been marked as unstable: it works as advertised, but it may be changed in a future release.
+/// @brief Returns ``true`` if the key expression tree is valid, ``false`` if it is in gravestone state.
+#[no_mangle]
+pub extern "C" fn z_internal_keyexpr_tree_check(this_: &z_owned_keyexpr_tree_t) -> bool {
+    this_.as_rust_type_ref().is_some()
+}
+
+/// @warning This API has been marked as unstable: it works as advertised, but it may be changed in a future release.
+/// @brief Borrows key expression tree.
+#[no_mangle]
+#[allow(clippy::missing_safety_doc)]
+pub unsafe extern "C" fn z_keyexpr_tree_loan(
+    this_: &z_owned_keyexpr_tree_t,
+) -> &z_loaned_keyexpr_tree_t {
+    this_
+        .as_rust_type_ref()
+        .as_ref()
+        .unwrap_unchecked()
+        .as_loaned_c_type_ref()
+}
+
+/// @warning This API has been marked as unstable: it works as advertised, but it may be changed in a future release.
+/// @brief Mutably borrows key expression tree.
+#[no_mangle]
+#[allow(clippy::missing_safety_doc)]
+pub unsafe extern "C" fn z_keyexpr_tree_loan_mut(
+    this_: &mut z_owned_keyexpr_tree_t,
+) -> &mut z_loaned_keyexpr_tree_t {
+    this_
+        .as_rust_type_mut()
+        .as_mut()
+        .unwrap_unchecked()
+        .as_loaned_c_type_mut()
+}
+
+/// @warning This API has been marked as unstable: it works as advertised, but it may be changed in a future release.
+/// @brief Frees key expression tree and its values, resetting it to its gravestone state.
+#[no_mangle]
+pub extern "C" fn z_keyexpr_tree_drop(this_: &mut z_moved_keyexpr_tree_t) {
+    let _ = this_.take_rust_type();
+}
+
+/// @warning This API has been marked as unstable: it works as advertised, but it may be changed in a future release.
+/// @brief Associates a value to a key expression, which may contain wildcards.
+///
+/// @param this_: The key expression tree.
+/// @param key_expr: The key expression.
+/// @param value: The value to store.
+/// @param previous: An optional location where the value previously associated to `key_expr` will be written.
+/// If `NULL`, the previous value is freed with the drop function of the tree.
+/// @return ``true`` if a value was previously associated to `key_expr`, ``false`` otherwise.
+#[no_mangle]
+pub extern "C" fn z_keyexpr_tree_insert(
+    this_: &mut z_loaned_keyexpr_tree_t,
+    key_expr: &z_loaned_keyexpr_t,
+    value: *mut c_void,
+    previous: Option<&mut *mut c_void>,
+) -> bool {
+    let this = this_.as_rust_type_mut();
+    match this.tree.insert(key_expr.as_rust_type_ref(), value) {
+        Some(old) => {
+            match previous {
+                Some(previous) => *previous = old,
+                None => this.drop_value(old),
+            }
+            true
+        }
+        None => false,
+    }
+}
+
+/// @warning This API has been marked as unstable: it works as advertised, but it may be changed in a future release.
+/// @brief Removes the value associated to a key expression.
+///
+/// @param this_: The key expression tree.
+/// @param key_expr: The key expression, compared exactly to the keys of the tree.
+/// @param value: An optional location where the removed value will be written.
+/// If `NULL`, the value is freed with the drop function of the tree.
+/// @return ``true`` if a value was associated to `key_expr`, ``false`` otherwise.
+#[no_mangle]
+pub extern "C" fn z_keyexpr_tree_remove(
+    this_: &mut z_loaned_keyexpr_tree_t,
+    key_expr: &z_loaned_keyexpr_t,
+    value: Option<&mut *mut c_void>,
+) -> bool {
+    let this = this_.as_rust_type_mut();
+    match this.tree.remove(key_expr.as_rust_type_ref()) {
+        Some(old) => {
+            match value {
+                Some(value) => *value = old,
+                None => this.drop_value(old),
+            }
+            true
+        }
+        None => false,
+    }
+}
+
+/// @warning This API has been marked as unstable: it works as advertised, but it may be changed in a future release.
+/// @brief Gets the value associated to a key expression.
+///
+/// @param this_: The key expression tree.
+/// @param key_expr: The key expression, compared exactly to the keys of the tree.
+/// @param value: A location where the value will be written if found (left unchanged otherwise).
+/// @return ``true`` if a value is associated to `key_expr`, ``false`` otherwise.
+#[no_mangle]
+pub extern "C" fn z_keyexpr_tree_get(
+    this_: &z_loaned_keyexpr_tree_t,
+    key_expr: &z_loaned_keyexpr_t,
+    value: &mut *mut c_void,
+) -> bool {
+    match this_
+        .as_rust_type_ref()
+        .tree
+        .weight_at(key_expr.as_rust_type_ref())
+    {
+        Some(v) => {
+            *value = *v;
+            true
+        }
+        None => false,
+    }
+}
+
+/// @warning This API has been marked as unstable: it works as advertised, but it may be changed in a future release.
+/// @brief Constructs an iterator over the entries of the tree whose key intersects with `key_expr`.
+///
+/// The iterator holds a snapshot of the matching entries: it is not affected by later modifications of the tree,
+/// but its values should not be used once they are removed from the tree.
+#[no_mangle]
+pub extern "C" fn z_keyexpr_tree_intersecting(
+    this_: &z_loaned_keyexpr_tree_t,
+    key_expr: &z_loaned_keyexpr_t,
+    iterator: &mut MaybeUninit<z_owned_keyexpr_tree_iterator_t>,
+) {
+    let entries = this_
+        .as_rust_type_ref()
+        .tree
+        .intersecting_nodes(key_expr.as_rust_type_ref())
+        .filter_map(|node| node.weight().map(|value| (node.keyexpr(), *value)))
+        .collect();
+    iterator
+        .as_rust_type_mut_uninit()
+        .write(Some(KeyExprTreeIterator { entries, next: 0 }));
+}
+
+/// @warning This API has been marked as unstable: it works as advertised, but it may be changed in a future release.
+/// @brief Constructs an iterator over the entries of the tree whose key is included by `key_expr`.
+///
+/// The iterator holds a snapshot of the matching entries: it is not affected by later modifications of the tree,
+/// but its values should not be used once they are removed from the tree.
+#[no_mangle]
+pub extern "C" fn z_keyexpr_tree_included(
+    this_: &z_loaned_keyexpr_tree_t,
+    key_expr: &z_loaned_keyexpr_t,
+    iterator: &mut MaybeUninit<z_owned_keyexpr_tree_iterator_t>,
+) {
+    let entries = this_
+        .as_rust_type_ref()
+        .tree
+        .included_nodes(key_expr.as_rust_type_ref())
+        .filter_map(|node| node.weight().map(|value| (node.keyexpr(), *value)))
+        .collect();
+    iterator
+        .as_rust_type_mut_uninit()
+        .write(Some(KeyExprTreeIterator { entries, next: 0 }));
+}
+
+/// @warning This API has been marked as unstable: it works as advertised, but it may be changed in a future release.
+/// @brief Constructs a key expression tree iterator in its gravestone state.
+#[no_mangle]
+pub extern "C" fn z_internal_keyexpr_tree_iterator_null(
+    this_: &mut MaybeUninit<z_owned_keyexpr_tree_iterator_t>,
+) {
+    this_.as_rust_type_mut_uninit().write(None);
+}
+
+/// @warning This API has been marked as unstable: it works as advertised, but it may be changed in a future release.
+/// @brief Returns ``true`` if the key expression tree iterator is valid, ``false`` if it is in gravestone state.
+#[no_mangle]
+pub extern "C" fn z_internal_keyexpr_tree_iterator_check(
+    this_: &z_owned_keyexpr_tree_iterator_t,
+) -> bool {
+    this_.as_rust_type_ref().is_some()
+}
+
+/// @warning This API has been marked as unstable: it works as advertised, but it may be changed in a future release.
+/// @brief Mutably borrows key expression tree iterator.
+#[no_mangle]
+#[allow(clippy::missing_safety_doc)]
+pub unsafe extern "C" fn z_keyexpr_tree_iterator_loan_mut(
+    this_: &mut z_owned_keyexpr_tree_iterator_t,
+) -> &mut z_loaned_keyexpr_tree_iterator_t {
+    this_
+        .as_rust_type_mut()
+        .as_mut()
+        .unwrap_unchecked()
+        .as_loaned_c_type_mut()
+}
+
+/// @warning This API has been marked as unstable: it works as advertised, but it may be changed in a future release.
+/// @brief Frees key expression tree iterator, resetting it to its gravestone state. The values of the tree are not freed.
+#[no_mangle]
+pub extern "C" fn z_keyexpr_tree_iterator_drop(this_: &mut z_moved_keyexpr_tree_iterator_t) {
+    let _ = this_.take_rust_type();
+}
+
+/// @warning This API has been marked as unstable: it works as advertised, but it may be changed in a future release.
+/// @brief Gets the next entry of the iterator.
+///
+/// @param this_: The key expression tree iterator.
+/// @param key_expr: An uninitialized memory location where a view of the key of the entry will be constructed.
+/// The view is valid as long as the iterator is.
+/// @param value: A location where the value of the entry will be written.
+/// @return ``false`` if there are no more entries (in this case `key_expr` and `value` stay unchanged), ``true`` otherwise.
+#[no_mangle]
+pub extern "C" fn z_keyexpr_tree_iterator_next(
+    this_: &mut z_loaned_keyexpr_tree_iterator_t,
+    key_expr: &mut MaybeUninit<z_view_keyexpr_t>,
+    value: &mut *mut c_void,
+) -> bool {
+    let this = this_.as_rust_type_mut();
+    let Some((key, v)) = this.entries.get(this.next) else {
+        return false;
+    };
+    this.next += 1;
+    // SAFETY: the key is owned by the iterator, which the view is documented not to outlive.
+    let key: &'static keyexpr = unsafe { std::mem::transmute::<&keyexpr, _>(&**key) };
+    key_expr.as_rust_type_mut_uninit().write(KeyExpr::from(key));
+    *value = *v;
+    true
+}
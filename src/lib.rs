@@ -30,6 +30,18 @@ pub use crate::collections::*;
 mod config;
 pub use crate::config::*;
 #[cfg(feature = "unstable")]
+mod config_builder;
+#[cfg(feature = "unstable")]
+pub use crate::config_builder::*;
+#[cfg(feature = "unstable")]
+mod config_template;
+#[cfg(feature = "unstable")]
+pub use crate::config_template::*;
+#[cfg(feature = "unstable")]
+mod config_validation;
+#[cfg(feature = "unstable")]
+pub use crate::config_validation::*;
+#[cfg(feature = "unstable")]
 mod close;
 #[cfg(feature = "unstable")]
 pub use crate::close::*;
@@ -41,6 +53,22 @@ mod zbytes;
 pub use crate::zbytes::*;
 mod keyexpr;
 pub use crate::keyexpr::*;
+#[cfg(feature = "unstable")]
+mod keyexpr_tree;
+#[cfg(feature = "unstable")]
+pub use crate::keyexpr_tree::*;
+#[cfg(feature = "unstable")]
+mod keyexpr_format;
+#[cfg(feature = "unstable")]
+pub use crate::keyexpr_format::*;
+#[cfg(feature = "unstable")]
+mod parameters;
+#[cfg(feature = "unstable")]
+pub use crate::parameters::*;
+#[cfg(feature = "unstable")]
+mod time_range;
+#[cfg(feature = "unstable")]
+pub use crate::time_range::*;
 mod info;
 pub use crate::info::*;
 mod get;
@@ -61,6 +89,10 @@ mod publisher;
 pub use crate::publisher::*;
 mod closures;
 pub use closures::*;
+mod executor;
+pub use executor::*;
+mod runtime;
+pub use runtime::*;
 pub mod platform;
 pub use platform::*;
 mod liveliness;
@@ -72,6 +104,14 @@ mod publication_cache;
 #[cfg(feature = "unstable")]
 pub use publication_cache::*;
 #[cfg(feature = "unstable")]
+mod memory_storage;
+#[cfg(feature = "unstable")]
+pub use memory_storage::*;
+#[cfg(feature = "unstable")]
+mod file_storage;
+#[cfg(feature = "unstable")]
+pub use file_storage::*;
+#[cfg(feature = "unstable")]
 mod querying_subscriber;
 #[cfg(feature = "unstable")]
 pub use querying_subscriber::*;
@@ -89,7 +129,15 @@ pub mod context;
 #[cfg(all(feature = "shared-memory", feature = "unstable"))]
 pub mod shm;
 
+#[cfg(feature = "unstable")]
+mod schema;
 mod serialization;
+#[cfg(feature = "unstable")]
+pub use schema::*;
+#[cfg(feature = "unstable")]
+mod serialization_json;
+#[cfg(feature = "unstable")]
+pub use serialization_json::*;
 
 #[cfg(feature = "unstable")]
 mod cancellation_token;
@@ -218,3 +266,33 @@ pub(crate) unsafe fn strlen_or_zero(ptr: *const libc::c_char) -> usize {
         false => libc::strlen(ptr),
     }
 }
+
+/// Converts a string argument of `len` bytes to `&str`, reporting an error mentioning `what` if it is not valid utf-8.
+/// A NULL string is converted to an empty one if `len` is 0, and rejected otherwise.
+#[allow(clippy::missing_safety_doc)]
+pub(crate) unsafe fn str_arg<'a>(
+    s: *const libc::c_char,
+    len: usize,
+    what: &str,
+) -> Result<&'a str, result::z_result_t> {
+    if len == 0 {
+        return Ok("");
+    }
+    if s.is_null() {
+        crate::report_error!(
+            zc_error_kind_t::INVALID_ARGUMENT,
+            "{} should not be NULL",
+            what
+        );
+        return Err(result::Z_EINVAL);
+    }
+    std::str::from_utf8(std::slice::from_raw_parts(s as *const u8, len)).map_err(|e| {
+        crate::report_error!(
+            zc_error_kind_t::INVALID_ARGUMENT,
+            "{} should be a valid utf-8 string: {}",
+            what,
+            e
+        );
+        result::Z_EINVAL
+    })
+}
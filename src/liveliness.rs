@@ -17,6 +17,7 @@ use std::mem::MaybeUninit;
 use zenoh::{
     handlers::Callback,
     liveliness::{LivelinessSubscriberBuilder, LivelinessToken},
+    query::Reply,
     sample::Sample,
     Wait,
 };
@@ -24,6 +25,7 @@ use zenoh::{
 #[cfg(feature = "unstable")]
 use crate::opaque_types::z_moved_cancellation_token_t;
 use crate::{
+    executor::{dispatch_callback, dispatch_reply_callback},
     opaque_types::{z_loaned_liveliness_token_t, z_owned_liveliness_token_t},
     result,
     transmute::{LoanedCTypeRef, RustTypeRef, RustTypeRefUninit, TakeRustType},
@@ -108,7 +110,10 @@ pub extern "C" fn z_liveliness_declare_token(
             result::Z_OK
         }
         Err(e) => {
-            crate::report_error!("Failed to undeclare liveliness token: {e}");
+            crate::report_error!(
+                zc_error_kind_t::NETWORK,
+                "Failed to undeclare liveliness token: {e}"
+            );
             this.write(None);
             result::Z_EGENERIC
         }
@@ -122,7 +127,7 @@ pub extern "C" fn z_liveliness_undeclare_token(
 ) -> result::z_result_t {
     if let Some(token) = this.take_rust_type() {
         if let Err(e) = token.undeclare().wait() {
-            crate::report_error!("Failed to undeclare token: {e}");
+            crate::report_error!(zc_error_kind_t::NETWORK, "Failed to undeclare token: {e}");
             return result::Z_EGENERIC;
         }
     }
@@ -157,7 +162,7 @@ fn _liveliness_declare_subscriber_inner<'a, 'b>(
         .liveliness()
         .declare_subscriber(key_expr)
         .history(options.is_some_and(|o| o.history))
-        .callback(move |sample| {
+        .callback(dispatch_callback(session, move |sample: Sample| {
             let mut owned_sample = Some(sample);
             z_closure_sample_call(z_closure_sample_loan(&callback), unsafe {
                 owned_sample
@@ -165,7 +170,7 @@ fn _liveliness_declare_subscriber_inner<'a, 'b>(
                     .unwrap_unchecked()
                     .as_loaned_c_type_mut()
             })
-        });
+        }));
     sub
 }
 /// @brief Declares a subscriber on liveliness tokens that intersect `key_expr`.
@@ -193,7 +198,10 @@ pub extern "C" fn z_liveliness_declare_subscriber(
             result::Z_OK
         }
         Err(e) => {
-            crate::report_error!("Failed to subscribe to liveliness: {e}");
+            crate::report_error!(
+                zc_error_kind_t::NETWORK,
+                "Failed to subscribe to liveliness: {e}"
+            );
             this.write(None);
             result::Z_EGENERIC
         }
@@ -219,7 +227,10 @@ pub extern "C" fn z_liveliness_declare_background_subscriber(
     match subscriber.background().wait() {
         Ok(_) => result::Z_OK,
         Err(e) => {
-            crate::report_error!("Failed to subscribe to liveliness: {e}");
+            crate::report_error!(
+                zc_error_kind_t::NETWORK,
+                "Failed to subscribe to liveliness: {e}"
+            );
             result::Z_EGENERIC
         }
     }
@@ -266,15 +277,18 @@ pub extern "C" fn z_liveliness_get(
     let key_expr = key_expr.as_rust_type_ref();
     let callback = callback.take_rust_type();
     let liveliness = session.liveliness();
-    let mut builder = liveliness.get(key_expr).callback(move |response| {
-        let mut owned_response = Some(response);
-        z_closure_reply_call(z_closure_reply_loan(&callback), unsafe {
-            owned_response
-                .as_mut()
-                .unwrap_unchecked()
-                .as_loaned_c_type_mut()
-        })
-    });
+    let mut builder = liveliness.get(key_expr).callback(dispatch_reply_callback(
+        session,
+        move |response: Reply| {
+            let mut owned_response = Some(response);
+            z_closure_reply_call(z_closure_reply_loan(&callback), unsafe {
+                owned_response
+                    .as_mut()
+                    .unwrap_unchecked()
+                    .as_loaned_c_type_mut()
+            })
+        },
+    ));
     if let Some(options) = options {
         builder = builder.timeout(core::time::Duration::from_millis(options.timeout_ms));
         #[cfg(feature = "unstable")]
@@ -289,7 +303,10 @@ pub extern "C" fn z_liveliness_get(
     match builder.wait() {
         Ok(()) => result::Z_OK,
         Err(e) => {
-            crate::report_error!("Failed to subscribe to liveliness: {e}");
+            crate::report_error!(
+                zc_error_kind_t::NETWORK,
+                "Failed to subscribe to liveliness: {e}"
+            );
             result::Z_EGENERIC
         }
     }
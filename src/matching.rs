@@ -63,7 +63,7 @@ pub extern "C" fn z_undeclare_matching_listener(
 ) -> result::z_result_t {
     if let Some(m) = this.take_rust_type() {
         if let Err(e) = m.undeclare().wait_callbacks().wait() {
-            crate::report_error!("{}", e);
+            crate::report_error!(zc_error_kind_t::NETWORK, "{}", e);
             return result::Z_ENETWORK;
         }
     }
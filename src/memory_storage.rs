@@ -0,0 +1,500 @@
+//
+// Copyright (c) 2017, 2024 ZettaScale Technology.
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ZettaScale Zenoh team, <zenoh@zettascale.tech>
+//
+
+use std::{
+    collections::{HashMap, VecDeque},
+    mem::MaybeUninit,
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+
+use zenoh::{
+    bytes::{Encoding, ZBytes},
+    key_expr::{KeyExpr, OwnedKeyExpr},
+    pubsub::Subscriber,
+    query::{Query, Queryable},
+    sample::{Sample, SampleKind},
+    session::Session,
+    time::Timestamp,
+    Wait,
+};
+
+pub use crate::opaque_types::{
+    ze_loaned_memory_storage_t, ze_moved_memory_storage_t, ze_owned_memory_storage_t,
+};
+use crate::{
+    file_storage::StorageFile,
+    result,
+    time_range::{parameters_time_range, z_time_range_t},
+    transmute::{LoanedCTypeRef, RustTypeRef, RustTypeRefUninit, TakeRustType},
+    z_loaned_keyexpr_t, z_loaned_session_t, z_locality_default, z_locality_t,
+};
+
+/// @warning This API has been marked as unstable: it works as advertised, but it may be changed in a future release.
+/// @brief Options passed to the `ze_declare_memory_storage()` function.
+#[repr(C)]
+pub struct ze_memory_storage_options_t {
+    /// The maximum number of samples stored for each key, older ones being discarded (should be at least 1).
+    pub history: usize,
+    /// The restriction for the matching publications that will be stored.
+    pub subscriber_allowed_origin: z_locality_t,
+    /// The restriction for the matching queries that will be answered.
+    pub queryable_allowed_origin: z_locality_t,
+    /// The `complete` option for the queryable.
+    pub queryable_complete: bool,
+    /// The time in milliseconds after which the tombstone left by a deletion is purged, 0 to keep tombstones forever.
+    /// Samples older than a purged deletion are no longer ignored.
+    pub tombstone_ttl_ms: u64,
+}
+
+impl Default for ze_memory_storage_options_t {
+    fn default() -> Self {
+        Self {
+            history: 1,
+            subscriber_allowed_origin: z_locality_default(),
+            queryable_allowed_origin: z_locality_default(),
+            queryable_complete: false,
+            tombstone_ttl_ms: 0,
+        }
+    }
+}
+
+/// @warning This API has been marked as unstable: it works as advertised, but it may be changed in a future release.
+/// @brief Constructs the default value for `ze_memory_storage_options_t`.
+#[no_mangle]
+pub extern "C" fn ze_memory_storage_options_default(
+    this: &mut MaybeUninit<ze_memory_storage_options_t>,
+) {
+    this.write(ze_memory_storage_options_t::default());
+}
+
+/// A sample as stored, with its timestamp.
+#[derive(Clone)]
+pub(crate) struct StoredSample {
+    pub(crate) kind: SampleKind,
+    pub(crate) timestamp: Timestamp,
+    pub(crate) key_expr: OwnedKeyExpr,
+    pub(crate) payload: ZBytes,
+    pub(crate) encoding: Encoding,
+    pub(crate) attachment: Option<ZBytes>,
+}
+
+#[derive(Default)]
+struct StoredKey {
+    /// Stored samples, sorted by timestamp.
+    samples: VecDeque<StoredSample>,
+    /// The latest deletion, older samples are ignored.
+    tombstone: Option<StoredSample>,
+}
+
+pub(crate) struct StorageState {
+    entries: HashMap<OwnedKeyExpr, StoredKey>,
+    history: usize,
+    tombstone_ttl: Option<Duration>,
+    last_purge: Instant,
+    /// Session whose clock stamps the samples received without a timestamp.
+    session: Session,
+    file: Option<StorageFile>,
+}
+
+impl StorageState {
+    /// Constructs the state of a storage, restoring the samples of `file` if any.
+    /// A `tombstone_ttl_ms` of 0 keeps tombstones forever.
+    pub(crate) fn new(
+        session: &Session,
+        history: usize,
+        tombstone_ttl_ms: u64,
+        file: Option<(StorageFile, Vec<StoredSample>)>,
+    ) -> Self {
+        let (file, restored) = match file {
+            Some((file, restored)) => (Some(file), restored),
+            None => (None, Vec::new()),
+        };
+        let mut state = StorageState {
+            entries: HashMap::new(),
+            history,
+            tombstone_ttl: (tombstone_ttl_ms != 0).then(|| Duration::from_millis(tombstone_ttl_ms)),
+            last_purge: Instant::now(),
+            session: session.clone(),
+            file: None,
+        };
+        for sample in restored {
+            state.insert(sample);
+        }
+        state.purge_tombstones();
+        state.file = file;
+        state
+    }
+
+    /// Returns a timestamp of the session clock, which is unique and monotonic, unlike the system time.
+    fn new_timestamp(&self) -> Timestamp {
+        self.session.new_timestamp()
+    }
+
+    fn store(&mut self, sample: Sample) {
+        // Tombstones are checked at most once per TTL, so that they are kept between one and two TTLs.
+        if self
+            .tombstone_ttl
+            .is_some_and(|ttl| self.last_purge.elapsed() >= ttl)
+        {
+            self.purge_tombstones();
+        }
+        let sample = StoredSample {
+            kind: sample.kind(),
+            timestamp: sample
+                .timestamp()
+                .copied()
+                .unwrap_or_else(|| self.new_timestamp()),
+            key_expr: OwnedKeyExpr::from(sample.key_expr().clone()),
+            payload: sample.payload().clone(),
+            encoding: sample.encoding().clone(),
+            attachment: sample.attachment().cloned(),
+        };
+        let logged = self.file.is_some().then(|| sample.clone());
+        if !self.insert(sample) {
+            return;
+        }
+        if let Some(sample) = logged {
+            if let Err(e) = self.log(&sample) {
+                crate::report_error!(zc_error_kind_t::IO, "Failed to persist sample: {}", e);
+            }
+        }
+    }
+
+    /// Appends the sample to the storage file, compacting it if it holds too many outdated records.
+    fn log(&mut self, sample: &StoredSample) -> std::io::Result<()> {
+        let live = self.len();
+        let Some(file) = self.file.as_mut() else {
+            return Ok(());
+        };
+        file.append(sample)?;
+        if file.needs_compaction(live) {
+            self.compact()?;
+        }
+        Ok(())
+    }
+
+    /// Rewrites the storage file with the samples and tombstones currently stored.
+    pub(crate) fn compact(&mut self) -> std::io::Result<()> {
+        let Some(file) = self.file.as_mut() else {
+            return Ok(());
+        };
+        file.rewrite(
+            self.entries
+                .values()
+                .flat_map(|e| e.tombstone.iter().chain(e.samples.iter())),
+        )
+    }
+
+    /// Removes the tombstones older than the TTL, along with the keys left without samples.
+    fn purge_tombstones(&mut self) {
+        let Some(ttl) = self.tombstone_ttl else {
+            return;
+        };
+        self.last_purge = Instant::now();
+        let limit = self
+            .new_timestamp()
+            .get_time()
+            .to_duration()
+            .saturating_sub(ttl);
+        self.entries.retain(|_, entry| {
+            if entry
+                .tombstone
+                .as_ref()
+                .is_some_and(|t| t.timestamp.get_time().to_duration() < limit)
+            {
+                entry.tombstone = None;
+            }
+            entry.tombstone.is_some() || !entry.samples.is_empty()
+        });
+    }
+
+    /// Returns the number of samples and tombstones stored.
+    fn len(&self) -> usize {
+        self.entries
+            .values()
+            .map(|e| e.samples.len() + e.tombstone.is_some() as usize)
+            .sum()
+    }
+
+    /// Stores the sample, returns ``false`` if it was ignored as outdated.
+    fn insert(&mut self, sample: StoredSample) -> bool {
+        let timestamp = sample.timestamp;
+        let entry = self.entries.entry(sample.key_expr.clone()).or_default();
+        if entry
+            .tombstone
+            .as_ref()
+            .is_some_and(|t| timestamp <= t.timestamp)
+        {
+            return false;
+        }
+        match sample.kind {
+            SampleKind::Put => {
+                let pos = entry.samples.partition_point(|s| s.timestamp < timestamp);
+                if entry
+                    .samples
+                    .get(pos)
+                    .is_some_and(|s| s.timestamp == timestamp)
+                {
+                    return false;
+                }
+                entry.samples.insert(pos, sample);
+                while entry.samples.len() > self.history {
+                    entry.samples.pop_front();
+                }
+            }
+            SampleKind::Delete => {
+                entry.samples.retain(|s| s.timestamp > timestamp);
+                entry.tombstone = Some(sample);
+            }
+        }
+        true
+    }
+
+    /// Returns the samples matching the key expression: the latest one of each key if `time_range` is not set,
+    /// all the ones in the range otherwise.
+    fn matching(
+        &self,
+        key_expr: &KeyExpr,
+        time_range: Option<&z_time_range_t>,
+    ) -> Vec<StoredSample> {
+        let mut matching = Vec::new();
+        for (key, entry) in self.entries.iter() {
+            if !key_expr.intersects(key) {
+                continue;
+            }
+            match time_range {
+                Some(range) => matching.extend(
+                    entry
+                        .samples
+                        .iter()
+                        .filter(|s| range.contains(&s.timestamp))
+                        .cloned(),
+                ),
+                None => matching.extend(entry.samples.back().cloned()),
+            }
+        }
+        matching
+    }
+}
+
+fn reply(state: &Mutex<StorageState>, query: &Query) {
+    let time_range = match parameters_time_range(query.parameters()) {
+        Some(Ok(range)) => Some(range),
+        Some(Err(e)) => {
+            let _ = query.reply_err(format!("Invalid time range: {}", e)).wait();
+            return;
+        }
+        None => None,
+    };
+    // Replies are sent without holding the lock, so that samples can be stored meanwhile.
+    let samples = state
+        .lock()
+        .unwrap()
+        .matching(query.key_expr(), time_range.as_ref());
+    for sample in samples {
+        let r = query
+            .reply(sample.key_expr, sample.payload)
+            .encoding(sample.encoding)
+            .timestamp(Some(sample.timestamp))
+            .attachment(sample.attachment)
+            .wait();
+        if let Err(e) = r {
+            crate::report_error!(zc_error_kind_t::NETWORK, "Failed to reply to query: {}", e);
+        }
+    }
+}
+
+pub struct MemoryStorage {
+    key_expr: KeyExpr<'static>,
+    subscriber: Subscriber<()>,
+    queryable: Queryable<()>,
+}
+
+impl MemoryStorage {
+    /// Declares the subscriber and the queryable of a storage sharing `state`.
+    pub(crate) fn declare(
+        session: &Session,
+        key_expr: KeyExpr<'static>,
+        state: Arc<Mutex<StorageState>>,
+        subscriber_allowed_origin: z_locality_t,
+        queryable_allowed_origin: z_locality_t,
+        queryable_complete: bool,
+    ) -> zenoh::Result<Self> {
+        let sub_state = state.clone();
+        let subscriber = session
+            .declare_subscriber(key_expr.clone())
+            .allowed_origin(subscriber_allowed_origin.into())
+            .callback(move |sample| sub_state.lock().unwrap().store(sample))
+            .wait()?;
+        let queryable = match session
+            .declare_queryable(key_expr.clone())
+            .complete(queryable_complete)
+            .allowed_origin(queryable_allowed_origin.into())
+            .callback(move |query| reply(&state, &query))
+            .wait()
+        {
+            Ok(q) => q,
+            Err(e) => {
+                let _ = subscriber.undeclare().wait();
+                return Err(e);
+            }
+        };
+        Ok(MemoryStorage {
+            key_expr,
+            subscriber,
+            queryable,
+        })
+    }
+
+    pub(crate) fn key_expr(&self) -> &KeyExpr<'static> {
+        &self.key_expr
+    }
+
+    pub(crate) fn undeclare(self) -> zenoh::Result<()> {
+        let r = self.subscriber.undeclare().wait();
+        self.queryable.undeclare().wait()?;
+        r
+    }
+}
+
+decl_c_type!(
+    owned(ze_owned_memory_storage_t, option MemoryStorage),
+    loaned(ze_loaned_memory_storage_t),
+);
+
+/// @warning This API has been marked as unstable: it works as advertised, but it may be changed in a future release.
+/// @brief Constructs and declares an in-memory storage.
+///
+/// The storage subscribes to `key_expr` and keeps the latest `history` samples of each key, ordered by timestamp
+/// (samples without timestamp are timestamped on reception). A deletion removes the samples of its key and leaves a tombstone,
+/// so that samples older than the deletion received later are ignored. Unless `tombstone_ttl_ms` is 0, tombstones are purged
+/// once older than it (at the latest twice older), so that the storage does not grow with the deleted keys.
+///
+/// The storage answers queries intersecting `key_expr` with the latest sample of each matching key, or with all the stored samples
+/// in the time range if the query selector has a `_time` parameter (e.g. `_time=[now(-1h)..]`).
+///
+/// @param session: A Zenoh session.
+/// @param storage: An uninitialized location in memory where the storage will be constructed.
+/// @param key_expr: The key expression to store.
+/// @param options: Additional options for the storage.
+///
+/// @returns 0 in case of success, negative error code otherwise.
+#[no_mangle]
+pub extern "C" fn ze_declare_memory_storage(
+    session: &z_loaned_session_t,
+    storage: &mut MaybeUninit<ze_owned_memory_storage_t>,
+    key_expr: &z_loaned_keyexpr_t,
+    options: Option<&mut ze_memory_storage_options_t>,
+) -> result::z_result_t {
+    let this = storage.as_rust_type_mut_uninit();
+    this.write(None);
+    let session = session.as_rust_type_ref();
+    let key_expr = key_expr.as_rust_type_ref().clone().into_owned();
+    let default_options = ze_memory_storage_options_t::default();
+    let options = options.map(|o| &*o).unwrap_or(&default_options);
+    if options.history == 0 {
+        crate::report_error!(
+            zc_error_kind_t::INVALID_ARGUMENT,
+            "Storage history should be at least 1"
+        );
+        return result::Z_EINVAL;
+    }
+    let state = Arc::new(Mutex::new(StorageState::new(
+        session,
+        options.history,
+        options.tombstone_ttl_ms,
+        None,
+    )));
+    match MemoryStorage::declare(
+        session,
+        key_expr,
+        state,
+        options.subscriber_allowed_origin,
+        options.queryable_allowed_origin,
+        options.queryable_complete,
+    ) {
+        Ok(s) => {
+            this.write(Some(s));
+            result::Z_OK
+        }
+        Err(e) => {
+            crate::report_error!(zc_error_kind_t::NETWORK, "{}", e);
+            result::Z_ENETWORK
+        }
+    }
+}
+
+/// @warning This API has been marked as unstable: it works as advertised, but it may be changed in a future release.
+/// @brief Constructs a memory storage in a gravestone state.
+#[no_mangle]
+pub extern "C" fn ze_internal_memory_storage_null(
+    this_: &mut MaybeUninit<ze_owned_memory_storage_t>,
+) {
+    this_.as_rust_type_mut_uninit().write(None);
+}
+
+/// @warning This API has been marked as unstable: it works as advertised, but it may be changed in a future release.
+/// @brief Returns ``true`` if memory storage is valid, ``false`` otherwise.
+#[no_mangle]
+pub extern "C" fn ze_internal_memory_storage_check(this_: &ze_owned_memory_storage_t) -> bool {
+    this_.as_rust_type_ref().is_some()
+}
+
+/// @warning This API has been marked as unstable: it works as advertised, but it may be changed in a future release.
+/// @brief Borrows memory storage.
+#[no_mangle]
+#[allow(clippy::missing_safety_doc)]
+pub unsafe extern "C" fn ze_memory_storage_loan(
+    this_: &ze_owned_memory_storage_t,
+) -> &ze_loaned_memory_storage_t {
+    this_
+        .as_rust_type_ref()
+        .as_ref()
+        .unwrap_unchecked()
+        .as_loaned_c_type_ref()
+}
+
+/// @warning This API has been marked as unstable: it works as advertised, but it may be changed in a future release.
+/// @brief Returns the key expression of the memory storage.
+#[no_mangle]
+pub extern "C" fn ze_memory_storage_keyexpr(
+    this_: &ze_loaned_memory_storage_t,
+) -> &z_loaned_keyexpr_t {
+    this_.as_rust_type_ref().key_expr().as_loaned_c_type_ref()
+}
+
+/// @warning This API has been marked as unstable: it works as advertised, but it may be changed in a future release.
+/// @brief Drops memory storage and resets it to its gravestone state, discarding its content.
+/// This is equivalent to calling `ze_undeclare_memory_storage()` and discarding its return value.
+#[no_mangle]
+pub extern "C" fn ze_memory_storage_drop(this: &mut ze_moved_memory_storage_t) {
+    std::mem::drop(this.take_rust_type())
+}
+
+/// @warning This API has been marked as unstable: it works as advertised, but it may be changed in a future release.
+/// @brief Undeclares memory storage, discarding its content.
+/// @return 0 in case of success, negative error code otherwise.
+#[no_mangle]
+pub extern "C" fn ze_undeclare_memory_storage(
+    this: &mut ze_moved_memory_storage_t,
+) -> result::z_result_t {
+    if let Some(s) = this.take_rust_type() {
+        if let Err(e) = s.undeclare() {
+            crate::report_error!(zc_error_kind_t::NETWORK, "{}", e);
+            return result::Z_EGENERIC;
+        }
+    }
+    result::Z_OK
+}
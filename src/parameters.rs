@@ -0,0 +1,366 @@
+//
+// Copyright (c) 2017, 2024 ZettaScale Technology.
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ZettaScale Zenoh team, <zenoh@zettascale.tech>
+//
+
+use std::{borrow::Cow, fmt::Write, mem::MaybeUninit};
+
+use libc::c_char;
+use zenoh::query::Parameters;
+
+pub use crate::opaque_types::{
+    z_loaned_parameters_t, z_moved_parameters_t, z_owned_parameters_t, z_parameters_iterator_t,
+};
+use crate::{
+    result::{self, z_result_t},
+    str_arg, strlen_or_zero,
+    transmute::{Gravestone, LoanedCTypeRef, RustTypeRef, RustTypeRefUninit, TakeRustType},
+    z_owned_string_t, z_view_string_t, CStringOwned, CStringView,
+};
+
+decl_c_type!(
+    owned(z_owned_parameters_t, option Parameters<'static>),
+    loaned(z_loaned_parameters_t),
+);
+
+/// Separator of the `key=value` pairs of parameters.
+const LIST_SEPARATOR: char = ';';
+/// Separator of the key and the value of a pair.
+const FIELD_SEPARATOR: char = '=';
+/// Separator of the values of a key holding several ones.
+const VALUE_SEPARATOR: char = '|';
+/// Prefix of percent-encoded characters.
+const ESCAPE: char = '%';
+
+/// Percent-encodes the separators (and the escape character itself) of a key or a value.
+fn encode(s: &str) -> Cow<'_, str> {
+    if !s.contains([LIST_SEPARATOR, FIELD_SEPARATOR, VALUE_SEPARATOR, ESCAPE]) {
+        return Cow::Borrowed(s);
+    }
+    let mut encoded = String::with_capacity(s.len());
+    for c in s.chars() {
+        match c {
+            LIST_SEPARATOR | FIELD_SEPARATOR | VALUE_SEPARATOR | ESCAPE => {
+                let _ = write!(encoded, "%{:02X}", c as u32);
+            }
+            c => encoded.push(c),
+        }
+    }
+    Cow::Owned(encoded)
+}
+
+/// Decodes percent-encoded characters of a key or a value.
+/// Invalid escape sequences are kept as is, as well as the whole string if it does not decode to valid utf-8.
+fn decode(s: &str) -> String {
+    if !s.contains(ESCAPE) {
+        return s.to_string();
+    }
+    let bytes = s.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        let escaped = (bytes[i] == ESCAPE as u8)
+            .then(|| s.get(i + 1..i + 3))
+            .flatten()
+            .filter(|hex| hex.bytes().all(|b| b.is_ascii_hexdigit()))
+            .and_then(|hex| u8::from_str_radix(hex, 16).ok());
+        match escaped {
+            Some(b) => {
+                decoded.push(b);
+                i += 3;
+            }
+            None => {
+                decoded.push(bytes[i]);
+                i += 1;
+            }
+        }
+    }
+    String::from_utf8(decoded).unwrap_or_else(|_| s.to_string())
+}
+
+fn write_decoded(out: &mut MaybeUninit<z_owned_string_t>, s: &str) {
+    out.as_rust_type_mut_uninit()
+        .write(CStringOwned::from(decode(s)));
+}
+
+pub struct ParametersIterator(std::str::Split<'static, char>);
+decl_c_type!(loaned(z_parameters_iterator_t, ParametersIterator));
+
+fn write_view(view: &mut MaybeUninit<z_view_string_t>, s: &str) {
+    view.as_rust_type_mut_uninit()
+        .write(CStringView::new_borrowed_from_slice(s.as_bytes()));
+}
+
+/// @warning This API has been marked as unstable: it works as advertised, but it may be changed in a future release.
+/// @brief Constructs empty parameters.
+#[no_mangle]
+pub extern "C" fn z_parameters_empty(this_: &mut MaybeUninit<z_owned_parameters_t>) {
+    this_
+        .as_rust_type_mut_uninit()
+        .write(Some(Parameters::empty()));
+}
+
+/// @warning This API has been marked as unstable: it works as advertised, but it may be changed in a future release.
+/// @brief Constructs parameters by parsing a null-terminated string of `;`-separated `key=value` pairs (e.g. `a=1;b=2|3`).
+///
+/// Separators may be percent-encoded in keys and values (`%3B` for `;`, `%3D` for `=`, `%7C` for `|` and `%25` for `%`),
+/// as done by `z_parameters_insert()`; they are decoded by `z_parameters_get()` and `z_parameters_iterator_next()`.
+///
+/// @return 0 in case of success, negative error code if the string is not a valid utf-8 string
+/// (in this case the parameters will be in their gravestone state).
+#[allow(clippy::missing_safety_doc)]
+#[no_mangle]
+pub unsafe extern "C" fn z_parameters_from_str(
+    this_: &mut MaybeUninit<z_owned_parameters_t>,
+    s: *const c_char,
+) -> z_result_t {
+    z_parameters_from_substr(this_, s, strlen_or_zero(s))
+}
+
+/// @warning This API has been marked as unstable: it works as advertised, but it may be changed in a future release.
+/// @brief Constructs parameters by parsing a substring of `;`-separated `key=value` pairs (e.g. `a=1;b=2|3`).
+///
+/// @return 0 in case of success, negative error code if the string is not a valid utf-8 string
+/// (in this case the parameters will be in their gravestone state).
+#[allow(clippy::missing_safety_doc)]
+#[no_mangle]
+pub unsafe extern "C" fn z_parameters_from_substr(
+    this_: &mut MaybeUninit<z_owned_parameters_t>,
+    s: *const c_char,
+    len: usize,
+) -> z_result_t {
+    let this = this_.as_rust_type_mut_uninit();
+    match str_arg(s, len, "Parameters") {
+        Ok(s) => {
+            this.write(Some(Parameters::from(s.to_string())));
+            result::Z_OK
+        }
+        Err(r) => {
+            this.write(None);
+            r
+        }
+    }
+}
+
+/// @warning This API has been marked as unstable: it works as advertised, but it may be changed in a future release.
+/// @brief Constructs parameters in their gravestone state.
+#[no_mangle]
+pub extern "C" fn z_internal_parameters_null(this_: &mut MaybeUninit<z_owned_parameters_t>) {
+    this_.as_rust_type_mut_uninit().write(None);
+}
+
+/// @warning This API has been marked as unstable: it works as advertised, but it may be changed in a future release.
+/// @brief Returns ``true`` if parameters are valid, ``false`` if they are in gravestone state.
+#[no_mangle]
+pub extern "C" fn z_internal_parameters_check(this_: &z_owned_parameters_t) -> bool {
+    this_.as_rust_type_ref().is_some()
+}
+
+/// @warning This API has been marked as unstable: it works as advertised, but it may be changed in a future release.
+/// @brief Borrows parameters.
+#[no_mangle]
+#[allow(clippy::missing_safety_doc)]
+pub unsafe extern "C" fn z_parameters_loan(this_: &z_owned_parameters_t) -> &z_loaned_parameters_t {
+    this_
+        .as_rust_type_ref()
+        .as_ref()
+        .unwrap_unchecked()
+        .as_loaned_c_type_ref()
+}
+
+/// @warning This API has been marked as unstable: it works as advertised, but it may be changed in a future release.
+/// @brief Mutably borrows parameters.
+#[no_mangle]
+#[allow(clippy::missing_safety_doc)]
+pub unsafe extern "C" fn z_parameters_loan_mut(
+    this_: &mut z_owned_parameters_t,
+) -> &mut z_loaned_parameters_t {
+    this_
+        .as_rust_type_mut()
+        .as_mut()
+        .unwrap_unchecked()
+        .as_loaned_c_type_mut()
+}
+
+/// @warning This API has been marked as unstable: it works as advertised, but it may be changed in a future release.
+/// @brief Constructs a copy of parameters.
+#[no_mangle]
+pub extern "C" fn z_parameters_clone(
+    dst: &mut MaybeUninit<z_owned_parameters_t>,
+    this_: &z_loaned_parameters_t,
+) {
+    dst.as_rust_type_mut_uninit()
+        .write(Some(this_.as_rust_type_ref().clone()));
+}
+
+/// @warning This API has been marked as unstable: it works as advertised, but it may be changed in a future release.
+/// @brief Frees parameters, resetting them to their gravestone state.
+#[no_mangle]
+pub extern "C" fn z_parameters_drop(this_: &mut z_moved_parameters_t) {
+    let _ = this_.take_rust_type();
+}
+
+/// @warning This API has been marked as unstable: it works as advertised, but it may be changed in a future release.
+/// @brief Returns ``true`` if there are no parameters, ``false`` otherwise.
+#[no_mangle]
+pub extern "C" fn z_parameters_is_empty(this_: &z_loaned_parameters_t) -> bool {
+    this_.as_rust_type_ref().is_empty()
+}
+
+/// @warning This API has been marked as unstable: it works as advertised, but it may be changed in a future release.
+/// @brief Gets the value associated to a key. If the key is repeated, the value of its first occurrence is returned.
+///
+/// @param this_: The parameters.
+/// @param key: A null-terminated key, percent-encoded as by `z_parameters_insert()` before being looked up.
+/// @param value: An uninitialized memory location where the percent-decoded value will be constructed if the key
+/// is present (it will be in its gravestone state otherwise). Values of keys holding several ones are `|`-separated.
+/// @return ``true`` if the key is present, ``false`` otherwise.
+#[allow(clippy::missing_safety_doc)]
+#[no_mangle]
+pub unsafe extern "C" fn z_parameters_get(
+    this_: &z_loaned_parameters_t,
+    key: *const c_char,
+    value: &mut MaybeUninit<z_owned_string_t>,
+) -> bool {
+    let found = str_arg(key, strlen_or_zero(key), "Key")
+        .ok()
+        .and_then(|key| this_.as_rust_type_ref().get(encode(key).as_ref()));
+    match found {
+        Some(v) => {
+            write_decoded(value, v);
+            true
+        }
+        None => {
+            value
+                .as_rust_type_mut_uninit()
+                .write(CStringOwned::gravestone());
+            false
+        }
+    }
+}
+
+/// @warning This API has been marked as unstable: it works as advertised, but it may be changed in a future release.
+/// @brief Returns an iterator over the `key=value` pairs of the parameters, in order, including repeated keys.
+///
+/// The iterator is valid until the parameters are modified or dropped.
+#[no_mangle]
+pub extern "C" fn z_parameters_get_iterator(
+    this_: &'static z_loaned_parameters_t,
+) -> z_parameters_iterator_t {
+    let this: &'static Parameters<'static> = this_.as_rust_type_ref();
+    *ParametersIterator(this.as_str().split(LIST_SEPARATOR)).as_loaned_c_type_ref()
+}
+
+/// @warning This API has been marked as unstable: it works as advertised, but it may be changed in a future release.
+/// @brief Gets the next `key=value` pair.
+///
+/// @param this_: Parameters iterator.
+/// @param key: An uninitialized memory location where the percent-decoded key will be constructed.
+/// @param value: An uninitialized memory location where the percent-decoded value will be constructed, empty if
+/// the pair has no `=`. Values of keys holding several ones are `|`-separated.
+/// @return `false` if there are no more pairs (in this case key and value will be in their gravestone state),
+/// `true` otherwise.
+#[no_mangle]
+pub extern "C" fn z_parameters_iterator_next(
+    this_: &mut z_parameters_iterator_t,
+    key: &mut MaybeUninit<z_owned_string_t>,
+    value: &mut MaybeUninit<z_owned_string_t>,
+) -> bool {
+    let Some(pair) = this_.as_rust_type_mut().0.find(|p| !p.is_empty()) else {
+        key.as_rust_type_mut_uninit()
+            .write(CStringOwned::gravestone());
+        value
+            .as_rust_type_mut_uninit()
+            .write(CStringOwned::gravestone());
+        return false;
+    };
+    let (k, v) = pair.split_once(FIELD_SEPARATOR).unwrap_or((pair, ""));
+    write_decoded(key, k);
+    write_decoded(value, v);
+    true
+}
+
+/// @warning This API has been marked as unstable: it works as advertised, but it may be changed in a future release.
+/// @brief Associates a value to a key, replacing the previous value of the key (and its repetitions) if any.
+///
+/// The separators `;`, `=` and `|` and the `%` character of the key and the value are percent-encoded, so that
+/// they can hold any string: the value is stored as a single value, even if it contains `|`.
+/// `z_parameters_get()` and `z_parameters_iterator_next()` decode them, while other zenoh implementations receive
+/// them percent-encoded.
+///
+/// @param this_: The parameters.
+/// @param key: A null-terminated non-empty key.
+/// @param value: A null-terminated value.
+/// @return 0 in case of success, negative error code if the key or the value is invalid.
+#[allow(clippy::missing_safety_doc)]
+#[no_mangle]
+pub unsafe extern "C" fn z_parameters_insert(
+    this_: &mut z_loaned_parameters_t,
+    key: *const c_char,
+    value: *const c_char,
+) -> z_result_t {
+    let (key, value) = match (
+        str_arg(key, strlen_or_zero(key), "Key"),
+        str_arg(value, strlen_or_zero(value), "Value"),
+    ) {
+        (Ok(k), Ok(v)) => (k, v),
+        (Err(r), _) | (_, Err(r)) => return r,
+    };
+    if key.is_empty() {
+        crate::report_error!(
+            zc_error_kind_t::INVALID_ARGUMENT,
+            "Parameter key should be non-empty"
+        );
+        return result::Z_EINVAL;
+    }
+    let (key, value) = (encode(key), encode(value));
+    let this = this_.as_rust_type_mut();
+    // Removing repetitions of the key, so that the inserted value is the only one.
+    while this.remove(key.as_ref()).is_some() {}
+    this.insert(key.as_ref(), value.as_ref());
+    result::Z_OK
+}
+
+/// @warning This API has been marked as unstable: it works as advertised, but it may be changed in a future release.
+/// @brief Removes a key (and its repetitions) from the parameters.
+///
+/// The key is percent-encoded as by `z_parameters_insert()` before being looked up.
+/// @return ``true`` if the key was present, ``false`` otherwise.
+#[allow(clippy::missing_safety_doc)]
+#[no_mangle]
+pub unsafe extern "C" fn z_parameters_remove(
+    this_: &mut z_loaned_parameters_t,
+    key: *const c_char,
+) -> bool {
+    let Ok(key) = str_arg(key, strlen_or_zero(key), "Key") else {
+        return false;
+    };
+    let key = encode(key);
+    let this = this_.as_rust_type_mut();
+    let mut removed = false;
+    while this.remove(key.as_ref()).is_some() {
+        removed = true;
+    }
+    removed
+}
+
+/// @warning This API has been marked as unstable: it works as advertised, but it may be changed in a future release.
+/// @brief Constructs a non-owned non-null-terminated string of `;`-separated `key=value` pairs from parameters.
+///
+/// The view is valid until the parameters are modified or dropped.
+#[no_mangle]
+pub extern "C" fn z_parameters_as_view_string(
+    this_: &z_loaned_parameters_t,
+    out_string: &mut MaybeUninit<z_view_string_t>,
+) {
+    write_view(out_string, this_.as_rust_type_ref().as_str());
+}
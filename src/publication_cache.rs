@@ -114,7 +114,7 @@ pub extern "C" fn ze_declare_publication_cache(
             result::Z_OK
         }
         Err(e) => {
-            crate::report_error!("{}", e);
+            crate::report_error!(zc_error_kind_t::NETWORK, "{}", e);
             this.write(None);
             result::Z_EGENERIC
         }
@@ -139,7 +139,7 @@ pub extern "C" fn ze_declare_background_publication_cache(
     match p.background().wait() {
         Ok(_) => result::Z_OK,
         Err(e) => {
-            crate::report_error!("{}", e);
+            crate::report_error!(zc_error_kind_t::NETWORK, "{}", e);
             result::Z_EGENERIC
         }
     }
@@ -206,7 +206,7 @@ pub extern "C" fn ze_undeclare_publication_cache(
 ) -> result::z_result_t {
     if let Some(p) = this.take_rust_type() {
         if let Err(e) = p.undeclare().wait() {
-            crate::report_error!("{}", e);
+            crate::report_error!(zc_error_kind_t::NETWORK, "{}", e);
             return result::Z_EGENERIC;
         }
     }
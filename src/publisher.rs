@@ -25,6 +25,7 @@ use zenoh::{
 };
 
 use crate::{
+    executor::dispatch_callback,
     result::{self},
     transmute::{LoanedCTypeRef, RustTypeRef, RustTypeRefUninit, TakeRustType},
     z_closure_matching_status_call, z_closure_matching_status_loan, z_congestion_control_t,
@@ -130,11 +131,13 @@ pub extern "C" fn z_declare_publisher(
     let p = _declare_publisher_inner(session, key_expr, options);
     match p.wait() {
         Err(e) => {
-            crate::report_error!("{}", e);
+            crate::report_error!(zc_error_kind_t::NETWORK, "{}", e);
             this.write(None);
             result::Z_EGENERIC
         }
         Ok(publisher) => {
+            #[cfg(feature = "unstable")]
+            crate::executor::inherit_executor(session.as_rust_type_ref(), publisher.id());
             this.write(Some(publisher));
             result::Z_OK
         }
@@ -259,7 +262,7 @@ pub unsafe extern "C" fn z_publisher_put(
         Ok(_) => result::Z_OK,
         Err(e) if e.downcast_ref::<SessionClosedError>().is_some() => result::Z_ESESSION_CLOSED,
         Err(e) => {
-            crate::report_error!("{}", e);
+            crate::report_error!(zc_error_kind_t::NETWORK, "{}", e);
             result::Z_EGENERIC
         }
     }
@@ -309,7 +312,7 @@ pub extern "C" fn z_publisher_delete(
         del = _apply_publisher_delete_options(del, options);
     }
     if let Err(e) = del.wait() {
-        crate::report_error!("{}", e);
+        crate::report_error!(zc_error_kind_t::NETWORK, "{}", e);
         result::Z_EGENERIC
     } else {
         result::Z_OK
@@ -338,14 +341,15 @@ fn _publisher_matching_listener_declare_inner<'a>(
 ) -> zenoh::matching::MatchingListenerBuilder<'a, Callback<MatchingStatus>> {
     let publisher = publisher.as_rust_type_ref();
     let callback = callback.take_rust_type();
-    let listener = publisher
-        .matching_listener()
-        .callback(move |matching_status| {
+    let listener = publisher.matching_listener().callback(dispatch_callback(
+        publisher,
+        move |matching_status: MatchingStatus| {
             let status = z_matching_status_t {
                 matching: matching_status.matching(),
             };
             z_closure_matching_status_call(z_closure_matching_status_loan(&callback), &status);
-        });
+        },
+    ));
     listener
 }
 
@@ -371,7 +375,7 @@ pub extern "C" fn z_publisher_declare_matching_listener(
         }
         Err(e) => {
             this.write(None);
-            crate::report_error!("{}", e);
+            crate::report_error!(zc_error_kind_t::NETWORK, "{}", e);
             result::Z_EGENERIC
         }
     }
@@ -393,7 +397,7 @@ pub extern "C" fn z_publisher_declare_background_matching_listener(
     match listener.background().wait() {
         Ok(_) => result::Z_OK,
         Err(e) => {
-            crate::report_error!("{}", e);
+            crate::report_error!(zc_error_kind_t::NETWORK, "{}", e);
             result::Z_EGENERIC
         }
     }
@@ -416,7 +420,7 @@ pub extern "C" fn z_publisher_get_matching_status(
             result::Z_OK
         }
         Err(e) => {
-            crate::report_error!("{}", e);
+            crate::report_error!(zc_error_kind_t::NETWORK, "{}", e);
             result::Z_ENETWORK
         }
     }
@@ -437,7 +441,7 @@ pub extern "C" fn z_publisher_drop(this: &mut z_moved_publisher_t) {
 pub extern "C" fn z_undeclare_publisher(this_: &mut z_moved_publisher_t) -> result::z_result_t {
     if let Some(p) = this_.take_rust_type() {
         if let Err(e) = p.undeclare().wait_callbacks().wait() {
-            crate::report_error!("{}", e);
+            crate::report_error!(zc_error_kind_t::NETWORK, "{}", e);
             return result::Z_ENETWORK;
         }
     }
@@ -19,12 +19,13 @@ use zenoh::{
     handlers::Callback,
     matching::MatchingStatus,
     qos::{CongestionControl, Priority},
-    query::{Querier, QueryConsolidation, QueryTarget},
+    query::{Querier, QueryConsolidation, QueryTarget, Reply},
     session::SessionClosedError,
     Wait,
 };
 
 use crate::{
+    executor::{dispatch_callback, dispatch_reply_callback},
     result, strlen_or_zero,
     transmute::{LoanedCTypeRef, RustTypeRef, RustTypeRefUninit, TakeRustType},
     z_closure_matching_status_call, z_closure_matching_status_loan, z_closure_reply_call,
@@ -102,6 +103,10 @@ pub extern "C" fn z_declare_querier(
     let session = session.as_rust_type_ref();
     let key_expr = key_expr.as_rust_type_ref().clone().into_owned();
     let mut q = session.declare_querier(key_expr);
+    #[cfg(feature = "unstable")]
+    if let Some(timeout) = crate::session::queries_default_timeout(session) {
+        q = q.timeout(timeout);
+    }
     if let Some(options) = options {
         q = q
             .congestion_control(options.congestion_control.into())
@@ -117,11 +122,13 @@ pub extern "C" fn z_declare_querier(
     }
     match q.wait() {
         Err(e) => {
-            crate::report_error!("{}", e);
+            crate::report_error!(zc_error_kind_t::NETWORK, "{}", e);
             this.write(None);
             result::Z_EGENERIC
         }
         Ok(querier) => {
+            #[cfg(feature = "unstable")]
+            crate::executor::inherit_executor(session, querier.id());
             this.write(Some(querier));
             result::Z_OK
         }
@@ -283,7 +290,10 @@ pub unsafe extern "C" fn z_querier_get_with_parameters_substr(
             if let Some(o) = options {
                 o.clear();
             }
-            crate::report_error!("Parameters is not a valid utf-8 string: {e}");
+            crate::report_error!(
+                zc_error_kind_t::INVALID_ARGUMENT,
+                "Parameters is not a valid utf-8 string: {e}"
+            );
             return result::Z_EINVAL;
         }
     };
@@ -316,7 +326,7 @@ pub unsafe extern "C" fn z_querier_get_with_parameters_substr(
         get = get.parameters(p);
     }
     match get
-        .callback(move |response| {
+        .callback(dispatch_reply_callback(querier, move |response: Reply| {
             let mut owned_response = Some(response);
             z_closure_reply_call(
                 z_closure_reply_loan(&callback),
@@ -325,13 +335,13 @@ pub unsafe extern "C" fn z_querier_get_with_parameters_substr(
                     .unwrap_unchecked()
                     .as_loaned_c_type_mut(),
             )
-        })
+        }))
         .wait()
     {
         Ok(()) => result::Z_OK,
         Err(e) if e.downcast_ref::<SessionClosedError>().is_some() => result::Z_ESESSION_CLOSED,
         Err(e) => {
-            crate::report_error!("{}", e);
+            crate::report_error!(zc_error_kind_t::NETWORK, "{}", e);
             result::Z_EGENERIC
         }
     }
@@ -357,14 +367,15 @@ fn _querier_matching_listener_declare_inner<'a>(
 ) -> zenoh::matching::MatchingListenerBuilder<'a, Callback<MatchingStatus>> {
     let querier = querier.as_rust_type_ref();
     let callback = callback.take_rust_type();
-    let listener = querier
-        .matching_listener()
-        .callback(move |matching_status| {
+    let listener = querier.matching_listener().callback(dispatch_callback(
+        querier,
+        move |matching_status: MatchingStatus| {
             let status = z_matching_status_t {
                 matching: matching_status.matching(),
             };
             z_closure_matching_status_call(z_closure_matching_status_loan(&callback), &status);
-        });
+        },
+    ));
     listener
 }
 
@@ -390,7 +401,7 @@ pub extern "C" fn z_querier_declare_matching_listener(
         }
         Err(e) => {
             this.write(None);
-            crate::report_error!("{}", e);
+            crate::report_error!(zc_error_kind_t::NETWORK, "{}", e);
             result::Z_EGENERIC
         }
     }
@@ -412,7 +423,7 @@ pub extern "C" fn z_querier_declare_background_matching_listener(
     match listener.background().wait() {
         Ok(_) => result::Z_OK,
         Err(e) => {
-            crate::report_error!("{}", e);
+            crate::report_error!(zc_error_kind_t::NETWORK, "{}", e);
             result::Z_EGENERIC
         }
     }
@@ -435,7 +446,7 @@ pub extern "C" fn z_querier_get_matching_status(
             result::Z_OK
         }
         Err(e) => {
-            crate::report_error!("{}", e);
+            crate::report_error!(zc_error_kind_t::NETWORK, "{}", e);
             result::Z_ENETWORK
         }
     }
@@ -456,7 +467,7 @@ pub extern "C" fn z_querier_drop(this: &mut z_moved_querier_t) {
 pub extern "C" fn z_undeclare_querier(this_: &mut z_moved_querier_t) -> result::z_result_t {
     if let Some(q) = this_.take_rust_type() {
         if let Err(e) = q.undeclare().wait_callbacks().wait() {
-            crate::report_error!("{}", e);
+            crate::report_error!(zc_error_kind_t::NETWORK, "{}", e);
             return result::Z_ENETWORK;
         }
     }
@@ -23,6 +23,7 @@ use zenoh::{
 
 pub use crate::opaque_types::{z_loaned_queryable_t, z_owned_queryable_t};
 use crate::{
+    executor::dispatch_callback,
     result,
     transmute::{IntoRustType, LoanedCTypeRef, RustTypeRef, RustTypeRefUninit, TakeRustType},
     z_closure_query_call, z_closure_query_loan, z_congestion_control_t, z_loaned_bytes_t,
@@ -258,7 +259,7 @@ fn _declare_queryable_inner<'a, 'b>(
             .complete(options.complete)
             .allowed_origin(options.allowed_origin.into());
     }
-    let queryable = builder.callback(move |query| {
+    let queryable = builder.callback(dispatch_callback(session, move |query: Query| {
         let mut owned_query = Some(query);
         z_closure_query_call(z_closure_query_loan(&callback), unsafe {
             owned_query
@@ -266,7 +267,7 @@ fn _declare_queryable_inner<'a, 'b>(
                 .unwrap_unchecked()
                 .as_loaned_c_type_mut()
         })
-    });
+    }));
     queryable
 }
 
@@ -295,7 +296,7 @@ pub extern "C" fn z_declare_queryable(
             result::Z_OK
         }
         Err(e) => {
-            crate::report_error!("{}", e);
+            crate::report_error!(zc_error_kind_t::NETWORK, "{}", e);
             this.write(None);
             result::Z_EGENERIC
         }
@@ -322,7 +323,7 @@ pub extern "C" fn z_declare_background_queryable(
     match queryable.background().wait() {
         Ok(_) => result::Z_OK,
         Err(e) => {
-            crate::report_error!("{}", e);
+            crate::report_error!(zc_error_kind_t::NETWORK, "{}", e);
             result::Z_EGENERIC
         }
     }
@@ -393,7 +394,7 @@ pub extern "C" fn z_query_reply(
     }
 
     if let Err(e) = reply.wait() {
-        crate::report_error!("{}", e);
+        crate::report_error!(zc_error_kind_t::NETWORK, "{}", e);
         return result::Z_EGENERIC;
     }
     result::Z_OK
@@ -428,7 +429,7 @@ pub unsafe extern "C" fn z_query_reply_err(
     );
 
     if let Err(e) = reply.wait() {
-        crate::report_error!("{}", e);
+        crate::report_error!(zc_error_kind_t::NETWORK, "{}", e);
         return result::Z_EGENERIC;
     }
     result::Z_OK
@@ -472,7 +473,7 @@ pub unsafe extern "C" fn z_query_reply_del(
     }
 
     if let Err(e) = reply.wait() {
-        crate::report_error!("{}", e);
+        crate::report_error!(zc_error_kind_t::NETWORK, "{}", e);
         return result::Z_EGENERIC;
     }
     result::Z_OK
@@ -497,6 +498,20 @@ pub unsafe extern "C" fn z_query_parameters(
     unsafe { z_view_string_from_substr(parameters, params.as_ptr() as _, params.len()) };
 }
 
+#[cfg(feature = "unstable")]
+/// @warning This API has been marked as unstable: it works as advertised, but it may be changed in a future release.
+/// @brief Constructs parsed query <a href="https://github.com/eclipse-zenoh/roadmap/tree/main/rfcs/ALL/Selectors">value selector</a> parameters.
+#[no_mangle]
+pub extern "C" fn z_query_parameters_parsed(
+    this_: &z_loaned_query_t,
+    parameters: &mut MaybeUninit<crate::z_owned_parameters_t>,
+) {
+    let params = this_.as_rust_type_ref().parameters().as_str().to_string();
+    parameters
+        .as_rust_type_mut_uninit()
+        .write(Some(zenoh::query::Parameters::from(params)));
+}
+
 /// Gets query <a href="https://github.com/eclipse-zenoh/roadmap/blob/main/rfcs/ALL/Query%20Payload.md">payload</a>.
 ///
 /// Returns NULL if query does not contain a payload.
@@ -582,7 +597,7 @@ pub extern "C" fn z_query_accepts_replies(this_: &z_loaned_query_t) -> z_reply_k
 pub extern "C" fn z_undeclare_queryable(this_: &mut z_moved_queryable_t) -> result::z_result_t {
     if let Some(qable) = this_.take_rust_type() {
         if let Err(e) = qable.undeclare().wait_callbacks().wait() {
-            crate::report_error!("{}", e);
+            crate::report_error!(zc_error_kind_t::NETWORK, "{}", e);
             return result::Z_EGENERIC;
         }
     }
@@ -21,6 +21,7 @@ use zenoh_ext::*;
 #[cfg(feature = "unstable")]
 use crate::ze_moved_querying_subscriber_t;
 use crate::{
+    executor::dispatch_callback,
     opaque_types::{ze_loaned_querying_subscriber_t, ze_owned_querying_subscriber_t},
     result,
     transmute::{LoanedCTypeRef, RustTypeRef, RustTypeRefUninit, TakeRustType},
@@ -108,7 +109,7 @@ unsafe fn _declare_querying_subscriber_inner<'a, 'b>(
             sub = sub.query_timeout(std::time::Duration::from_millis(options.query_timeout_ms));
         }
     }
-    let sub = sub.callback(move |sample| {
+    let sub = sub.callback(dispatch_callback(session, move |sample: Sample| {
         let mut owned_sample = Some(sample);
         z_closure_sample_call(
             z_closure_sample_loan(&callback),
@@ -117,7 +118,7 @@ unsafe fn _declare_querying_subscriber_inner<'a, 'b>(
                 .unwrap_unchecked()
                 .as_loaned_c_type_mut(),
         );
-    });
+    }));
     sub
 }
 /// @warning This API is deprecated. Please use ze_advanced_subscriber.
@@ -285,7 +286,7 @@ pub extern "C" fn ze_undeclare_querying_subscriber(
 ) -> result::z_result_t {
     if let Some(s) = this_.take_rust_type() {
         if let Err(e) = s.0.undeclare().wait() {
-            crate::report_error!("{}", e);
+            crate::report_error!(zc_error_kind_t::NETWORK, "{}", e);
             return result::Z_EGENERIC;
         }
     }
@@ -13,7 +13,11 @@
 //
 
 #[cfg(feature = "unstable")]
-use std::{cell::RefCell, mem::MaybeUninit, str};
+use std::{
+    cell::{Cell, RefCell},
+    mem::MaybeUninit,
+    str,
+};
 
 #[cfg(feature = "unstable")]
 use crate::{z_view_string_from_substr, z_view_string_t};
@@ -64,9 +68,36 @@ impl<const N: usize> Buffer<N> {
     }
 }
 
+#[cfg(feature = "unstable")]
+/// @warning This API has been marked as unstable: it works as advertised, but it may be changed in a future release.
+/// @brief The category of the last error reported by a faillable zenoh API call.
+#[allow(non_camel_case_types, clippy::upper_case_acronyms)]
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum zc_error_kind_t {
+    /// No error was reported on this thread yet.
+    #[default]
+    NONE = 0,
+    /// An error which does not fall into any other category.
+    OTHER = 1,
+    /// An argument passed to the function is invalid (null pointer, non UTF-8 string, malformed key expression, etc).
+    INVALID_ARGUMENT = 2,
+    /// A textual input (configuration, key expression, etc) could not be parsed.
+    PARSE = 3,
+    /// An I/O operation failed.
+    IO = 4,
+    /// A network operation failed (e.g. session could not be opened, entity could not be declared).
+    NETWORK = 5,
+    /// The requested resource or value is not available.
+    UNAVAILABLE = 6,
+    /// A payload could not be deserialized.
+    DESERIALIZE = 7,
+}
+
 #[cfg(feature = "unstable")]
 thread_local! {
     pub static ERROR_DESCRIPTION: RefCell<Buffer<1024>> = RefCell::default();
+    pub static ERROR_KIND: Cell<zc_error_kind_t> = Cell::default();
 }
 
 #[cfg(feature = "unstable")]
@@ -80,13 +111,30 @@ extern "C" fn zc_get_last_error(out: &mut MaybeUninit<z_view_string_t>) {
     });
 }
 
+#[cfg(feature = "unstable")]
+/// @warning This API has been marked as unstable: it works as advertised, but it may be changed in a future release.
+/// @brief Returns the category of the last error reported by a faillable zenoh API call from the same thread.
+///
+/// The value is updated together with the message returned by `zc_get_last_error()`.
+/// `ZC_ERROR_KIND_NONE` is returned if no error was reported on this thread yet.
+#[no_mangle]
+extern "C" fn zc_get_last_error_kind() -> zc_error_kind_t {
+    ERROR_KIND.get()
+}
+
+/// Logs an error and records it as the last error of the thread.
+///
+/// The kind of the error is given first, as in `report_error!(zc_error_kind_t::IO, "...", ...)`.
 #[macro_export]
 macro_rules! report_error{
-    ($($t: tt)*) => {
+    (zc_error_kind_t::$kind: ident, $($t: tt)*) => {
         {
             tracing::error!($($t)*);
             #[cfg(feature = "unstable")]
-            $crate::result::ERROR_DESCRIPTION.with_borrow_mut(|b| b.update(&format!($($t)*)));
+            {
+                $crate::result::ERROR_DESCRIPTION.with_borrow_mut(|b| b.update(&format!($($t)*)));
+                $crate::result::ERROR_KIND.set($crate::result::zc_error_kind_t::$kind);
+            }
         }
     };
 }
@@ -0,0 +1,169 @@
+//
+// Copyright (c) 2017, 2024 ZettaScale Technology.
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ZettaScale Zenoh team, <zenoh@zettascale.tech>
+//
+
+#[cfg(feature = "unstable")]
+use std::{fmt::Write, mem::MaybeUninit, sync::Mutex};
+
+#[cfg(feature = "unstable")]
+use crate::result;
+
+/// Environment variable read by zenoh when its runtime thread pools are first used.
+#[cfg(feature = "unstable")]
+const ZENOH_RUNTIME_ENV: &str = "ZENOH_RUNTIME";
+
+#[cfg(feature = "unstable")]
+#[derive(Default)]
+struct RuntimeState {
+    /// Set once zenoh runtime thread pools may have been created.
+    started: bool,
+    /// Set once `ZENOH_RUNTIME` was set by `zc_init_runtime()`, as opposed to the user.
+    env_set: bool,
+}
+
+#[cfg(feature = "unstable")]
+static RUNTIME_STATE: Mutex<RuntimeState> = Mutex::new(RuntimeState {
+    started: false,
+    env_set: false,
+});
+
+/// Records that zenoh runtime thread pools may have been created, so that they can no longer be configured.
+#[cfg(feature = "unstable")]
+pub(crate) fn mark_runtime_started() {
+    RUNTIME_STATE.lock().unwrap().started = true;
+}
+
+#[cfg(not(feature = "unstable"))]
+pub(crate) fn mark_runtime_started() {}
+
+/// @warning This API has been marked as unstable: it works as advertised, but it may be changed in a future release.
+/// @brief Parameters of a single zenoh runtime thread pool.
+///
+/// Zenoh creates its thread pools internally and only allows to configure their number of threads:
+/// thread names, stack size, CPU affinity and scheduling priority can not be configured.
+#[cfg(feature = "unstable")]
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Default)]
+pub struct zc_runtime_pool_config_t {
+    /// Number of worker threads of the pool, 0 to use zenoh default.
+    pub worker_threads: usize,
+    /// Maximum number of threads spawned by the pool for blocking operations, 0 to use zenoh default.
+    pub max_blocking_threads: usize,
+}
+
+/// @warning This API has been marked as unstable: it works as advertised, but it may be changed in a future release.
+/// @brief Configuration of zenoh runtime thread pools.
+///
+/// Zenoh runs its tasks on several thread pools, each one dedicated to a specific kind of work.
+/// The threads of each pool are named after it (e.g. `net-0`, `rx-1`).
+#[cfg(feature = "unstable")]
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Default)]
+pub struct zc_runtime_config_t {
+    /// Pool running application-facing tasks, such as blocking operations issued from the API.
+    pub application: zc_runtime_pool_config_t,
+    /// Pool accepting incoming connections.
+    pub acceptor: zc_runtime_pool_config_t,
+    /// Pool transmitting messages.
+    pub tx: zc_runtime_pool_config_t,
+    /// Pool receiving messages.
+    pub rx: zc_runtime_pool_config_t,
+    /// Pool running network related tasks (e.g. scouting, establishing connections).
+    pub net: zc_runtime_pool_config_t,
+}
+
+#[cfg(feature = "unstable")]
+impl zc_runtime_config_t {
+    fn pools(&self) -> [(&'static str, zc_runtime_pool_config_t); 5] {
+        [
+            ("app", self.application),
+            ("acc", self.acceptor),
+            ("tx", self.tx),
+            ("rx", self.rx),
+            ("net", self.net),
+        ]
+    }
+
+    fn to_ron(self) -> String {
+        let mut ron = String::from("(");
+        for (name, pool) in self.pools() {
+            let mut params = Vec::new();
+            if pool.worker_threads != 0 {
+                params.push(format!("worker_threads: {}", pool.worker_threads));
+            }
+            if pool.max_blocking_threads != 0 {
+                params.push(format!(
+                    "max_blocking_threads: {}",
+                    pool.max_blocking_threads
+                ));
+            }
+            if !params.is_empty() {
+                let _ = write!(ron, "{}: ({}), ", name, params.join(", "));
+            }
+        }
+        ron.push(')');
+        ron
+    }
+}
+
+/// Applies the runtime configuration, see `zc_init_runtime()`.
+#[cfg(feature = "unstable")]
+pub(crate) fn init_runtime(config: &zc_runtime_config_t) -> result::z_result_t {
+    let mut state = RUNTIME_STATE.lock().unwrap();
+    if state.started {
+        crate::report_error!(
+            zc_error_kind_t::UNAVAILABLE,
+            "Zenoh runtime is already started, it can no longer be configured"
+        );
+        return result::Z_EUNAVAILABLE;
+    }
+    if !state.env_set && std::env::var_os(ZENOH_RUNTIME_ENV).is_some() {
+        crate::report_error!(
+            zc_error_kind_t::UNAVAILABLE,
+            "The {} environment variable is set, it takes precedence over the runtime configuration",
+            ZENOH_RUNTIME_ENV
+        );
+        return result::Z_EUNAVAILABLE;
+    }
+    // Zenoh only reads the configuration of its thread pools from the environment.
+    std::env::set_var(ZENOH_RUNTIME_ENV, config.to_ron());
+    state.env_set = true;
+    result::Z_OK
+}
+
+/// @warning This API has been marked as unstable: it works as advertised, but it may be changed in a future release.
+/// @brief Constructs the default runtime configuration, leaving every pool with zenoh default parameters.
+#[cfg(feature = "unstable")]
+#[no_mangle]
+pub extern "C" fn zc_runtime_config_default(this_: &mut MaybeUninit<zc_runtime_config_t>) {
+    this_.write(zc_runtime_config_t::default());
+}
+
+/// @warning This API has been marked as unstable: it works as advertised, but it may be changed in a future release.
+/// @brief Configures zenoh runtime thread pools.
+///
+/// Zenoh thread pools are created on first use, so this function should be called before any session is opened
+/// or any scouting is started. The configuration can also be passed to `z_open()` with `z_open_options_t::runtime`.
+///
+/// Zenoh reads this configuration from the `ZENOH_RUNTIME` environment variable, which this function sets.
+/// Like `setenv()`, it must not be called while other threads may read or modify the environment.
+/// If `ZENOH_RUNTIME` was set by the user, it takes precedence and this function fails.
+///
+/// Only the number of worker and blocking threads of each pool can be configured.
+///
+/// @return 0 in case of success, `Z_EUNAVAILABLE` if the runtime was already started or if `ZENOH_RUNTIME` is set by the user.
+#[cfg(feature = "unstable")]
+#[no_mangle]
+pub extern "C" fn zc_init_runtime(config: &zc_runtime_config_t) -> result::z_result_t {
+    init_runtime(config)
+}
@@ -0,0 +1,944 @@
+//
+// Copyright (c) 2017, 2024 ZettaScale Technology.
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ZettaScale Zenoh team, <zenoh@zettascale.tech>
+//
+
+use std::{ffi::c_void, fmt, mem::MaybeUninit, sync::Arc};
+
+use libc::c_char;
+use zenoh::bytes::ZBytes;
+use zenoh_ext::{Deserialize, Serialize, VarInt, ZDeserializeError, ZDeserializer, ZSerializer};
+
+pub use crate::opaque_types::{ze_loaned_schema_t, ze_moved_schema_t, ze_owned_schema_t};
+use crate::{
+    result::{self, z_result_t},
+    serialization::SerializedSize,
+    strlen_or_zero,
+    transmute::{Gravestone, LoanedCTypeRef, RustTypeRef, RustTypeRefUninit, TakeRustType},
+    z_loaned_bytes_t, z_owned_bytes_t, z_owned_string_t, CStringOwned, CStringView,
+};
+
+/// @warning This API has been marked as unstable: it works as advertised, but it may be changed in a future release.
+/// @brief Type of a schema field.
+#[repr(C)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ze_schema_type_t {
+    /// A `uint8_t`.
+    UINT8,
+    /// A `uint16_t`.
+    UINT16,
+    /// A `uint32_t`.
+    UINT32,
+    /// A `uint64_t`.
+    UINT64,
+    /// An `int8_t`.
+    INT8,
+    /// An `int16_t`.
+    INT16,
+    /// An `int32_t`.
+    INT32,
+    /// An `int64_t`.
+    INT64,
+    /// A `float`.
+    FLOAT,
+    /// A `double`.
+    DOUBLE,
+    /// A `bool`.
+    BOOL,
+    /// A `z_owned_string_t`.
+    STRING,
+    /// A nested structure, described by its own schema.
+    STRUCT,
+    /// A `ze_schema_sequence_t` of elements of the same type.
+    SEQUENCE,
+    /// A `ze_schema_sequence_t` of key-value entries, described by a schema of two fields.
+    MAP,
+}
+
+/// @warning This API has been marked as unstable: it works as advertised, but it may be changed in a future release.
+/// @brief A C array field, as described by `ZE_SCHEMA_TYPE_SEQUENCE` and `ZE_SCHEMA_TYPE_MAP` schema fields.
+///
+/// Arrays allocated by `ze_schema_deserialize()` should be freed with `ze_schema_value_drop()`.
+#[repr(C)]
+#[derive(Clone, Copy, Debug)]
+pub struct ze_schema_sequence_t {
+    /// A pointer to `len` contiguous elements, may be `NULL` if `len` is 0.
+    pub data: *mut c_void,
+    /// The number of elements.
+    pub len: usize,
+}
+
+/// The type of a schema field, as laid out in memory and on the wire.
+#[derive(Clone, Debug)]
+pub(crate) enum SchemaType {
+    Primitive(ze_schema_type_t),
+    String,
+    Struct(Arc<Schema>),
+    Sequence(Box<SchemaType>),
+    /// A sequence of entries whose two fields are the key and the value.
+    Map(Arc<Schema>),
+}
+
+#[derive(Clone, Debug)]
+pub(crate) struct Field {
+    pub(crate) name: String,
+    offset: usize,
+    pub(crate) ty: SchemaType,
+}
+
+/// The layout of a C structure, serialized field by field in declaration order (i.e. as a tuple).
+#[derive(Clone, Debug, Default)]
+pub struct Schema {
+    size: usize,
+    pub(crate) fields: Vec<Field>,
+}
+
+decl_c_type!(
+    owned(ze_owned_schema_t, option Schema),
+    loaned(ze_loaned_schema_t),
+);
+
+/// An error located at a field of a value described by a schema.
+#[derive(Debug)]
+pub(crate) struct SchemaError {
+    /// Field names and element indices, innermost first.
+    path: Vec<PathSegment>,
+    message: String,
+}
+
+#[derive(Debug)]
+enum PathSegment {
+    Field(String),
+    Index(usize),
+}
+
+impl SchemaError {
+    pub(crate) fn new(message: impl fmt::Display) -> Self {
+        SchemaError {
+            path: Vec::new(),
+            message: message.to_string(),
+        }
+    }
+
+    pub(crate) fn in_field(mut self, name: &str) -> Self {
+        self.path.push(PathSegment::Field(name.to_string()));
+        self
+    }
+
+    pub(crate) fn in_element(mut self, index: usize) -> Self {
+        self.path.push(PathSegment::Index(index));
+        self
+    }
+}
+
+impl From<ZDeserializeError> for SchemaError {
+    fn from(e: ZDeserializeError) -> Self {
+        SchemaError::new(e)
+    }
+}
+
+impl fmt::Display for SchemaError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.path.is_empty() {
+            return f.write_str(&self.message);
+        }
+        for (i, segment) in self.path.iter().rev().enumerate() {
+            match segment {
+                PathSegment::Field(name) if i == 0 => f.write_str(name)?,
+                PathSegment::Field(name) => write!(f, ".{}", name)?,
+                PathSegment::Index(index) => write!(f, "[{}]", index)?,
+            }
+        }
+        write!(f, ": {}", self.message)
+    }
+}
+
+impl SchemaType {
+    /// Size of the C representation of a value of this type.
+    fn c_size(&self) -> usize {
+        match self {
+            SchemaType::Primitive(kind) => match kind {
+                ze_schema_type_t::UINT8 | ze_schema_type_t::INT8 | ze_schema_type_t::BOOL => 1,
+                ze_schema_type_t::UINT16 | ze_schema_type_t::INT16 => 2,
+                ze_schema_type_t::UINT32 | ze_schema_type_t::INT32 | ze_schema_type_t::FLOAT => 4,
+                _ => 8,
+            },
+            SchemaType::String => std::mem::size_of::<z_owned_string_t>(),
+            SchemaType::Struct(schema) => schema.size,
+            SchemaType::Sequence(_) | SchemaType::Map(_) => {
+                std::mem::size_of::<ze_schema_sequence_t>()
+            }
+        }
+    }
+
+    /// Alignment required by the C representation of a value of this type.
+    ///
+    /// Primitives and sequences are accessed with unaligned reads and writes, while strings are accessed by reference.
+    fn c_align(&self) -> usize {
+        match self {
+            SchemaType::Primitive(_) | SchemaType::Sequence(_) | SchemaType::Map(_) => 1,
+            SchemaType::String => std::mem::align_of::<z_owned_string_t>(),
+            SchemaType::Struct(schema) => schema.align(),
+        }
+    }
+
+    /// The type of the elements of a sequence or map.
+    fn element_type(&self) -> Option<SchemaType> {
+        match self {
+            SchemaType::Sequence(element) => Some((**element).clone()),
+            SchemaType::Map(entry) => Some(SchemaType::Struct(entry.clone())),
+            _ => None,
+        }
+    }
+}
+
+unsafe fn serialize_primitive<T: Serialize + Copy>(serializer: &mut ZSerializer, ptr: *const u8) {
+    serializer.serialize(ptr.cast::<T>().read_unaligned());
+}
+
+unsafe fn deserialize_primitive<T: Deserialize + SerializedSize>(
+    deserializer: &mut SchemaDeserializer,
+    ptr: *mut u8,
+) -> Result<(), ZDeserializeError> {
+    ptr.cast::<T>()
+        .write_unaligned(deserializer.deserialize::<T>()?);
+    Ok(())
+}
+
+/// A deserializer keeping track of the remaining payload size, so that sequence lengths can be checked against it.
+pub(crate) struct SchemaDeserializer<'a> {
+    inner: ZDeserializer<'a>,
+    remaining: usize,
+}
+
+impl<'a> SchemaDeserializer<'a> {
+    pub(crate) fn new(payload: &'a ZBytes) -> Self {
+        SchemaDeserializer {
+            inner: ZDeserializer::new(payload),
+            remaining: payload.len(),
+        }
+    }
+
+    pub(crate) fn deserialize<T: Deserialize + SerializedSize>(
+        &mut self,
+    ) -> Result<T, ZDeserializeError> {
+        let v = self.inner.deserialize::<T>()?;
+        self.remaining = self.remaining.saturating_sub(v.serialized_size());
+        Ok(v)
+    }
+
+    /// Reads the length of a sequence, rejecting lengths greater than the remaining payload size.
+    ///
+    /// Every element takes at least one byte on the wire, except for structures without fields: sequences of them
+    /// are limited to the remaining payload size as well, so that a malformed length can not make the caller loop
+    /// or allocate without bound.
+    pub(crate) fn deserialize_len(&mut self) -> Result<usize, SchemaError> {
+        let len = self.deserialize::<VarInt<usize>>()?.0;
+        if len > self.remaining {
+            return Err(SchemaError::new(format!(
+                "sequence length {} exceeds the {} remaining bytes of the payload",
+                len, self.remaining
+            )));
+        }
+        Ok(len)
+    }
+
+    pub(crate) fn done(&self) -> bool {
+        self.inner.done()
+    }
+}
+
+/// Serializes the value of type `ty` located at `ptr`.
+unsafe fn serialize_value(
+    serializer: &mut ZSerializer,
+    ty: &SchemaType,
+    ptr: *const u8,
+) -> Result<(), SchemaError> {
+    match ty {
+        SchemaType::Primitive(kind) => match kind {
+            ze_schema_type_t::UINT8 => serialize_primitive::<u8>(serializer, ptr),
+            ze_schema_type_t::UINT16 => serialize_primitive::<u16>(serializer, ptr),
+            ze_schema_type_t::UINT32 => serialize_primitive::<u32>(serializer, ptr),
+            ze_schema_type_t::UINT64 => serialize_primitive::<u64>(serializer, ptr),
+            ze_schema_type_t::INT8 => serialize_primitive::<i8>(serializer, ptr),
+            ze_schema_type_t::INT16 => serialize_primitive::<i16>(serializer, ptr),
+            ze_schema_type_t::INT32 => serialize_primitive::<i32>(serializer, ptr),
+            ze_schema_type_t::INT64 => serialize_primitive::<i64>(serializer, ptr),
+            ze_schema_type_t::FLOAT => serialize_primitive::<f32>(serializer, ptr),
+            ze_schema_type_t::DOUBLE => serialize_primitive::<f64>(serializer, ptr),
+            // Reading the byte rather than a `bool`, since C code may store any non-zero value in it.
+            ze_schema_type_t::BOOL => serializer.serialize(ptr.read() != 0),
+            _ => unreachable!("non-primitive schema types are not stored as primitives"),
+        },
+        SchemaType::String => {
+            let s: &CStringOwned = (*ptr.cast::<z_owned_string_t>()).as_rust_type_ref();
+            match <&str>::try_from(&**s) {
+                Ok(s) => serializer.serialize(s),
+                Err(e) => return Err(SchemaError::new(format!("invalid utf-8 string: {}", e))),
+            }
+        }
+        SchemaType::Struct(schema) => {
+            for field in &schema.fields {
+                serialize_value(serializer, &field.ty, ptr.add(field.offset))
+                    .map_err(|e| e.in_field(&field.name))?;
+            }
+        }
+        SchemaType::Sequence(_) | SchemaType::Map(_) => {
+            let element = ty.element_type().unwrap();
+            let sequence = ptr.cast::<ze_schema_sequence_t>().read_unaligned();
+            if sequence.data.is_null() && sequence.len > 0 {
+                return Err(SchemaError::new(format!(
+                    "NULL data for a sequence of {} elements",
+                    sequence.len
+                )));
+            }
+            serializer.serialize(VarInt::<usize>(sequence.len));
+            let element_size = element.c_size();
+            for i in 0..sequence.len {
+                let element_ptr = sequence.data.cast::<u8>().add(i * element_size);
+                serialize_value(serializer, &element, element_ptr).map_err(|e| e.in_element(i))?;
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Deserializes a value of type `ty` into the uninitialized memory at `ptr`.
+///
+/// On failure, whatever was deserialized is freed and the memory is left uninitialized.
+unsafe fn deserialize_value(
+    deserializer: &mut SchemaDeserializer,
+    ty: &SchemaType,
+    ptr: *mut u8,
+) -> Result<(), SchemaError> {
+    match ty {
+        SchemaType::Primitive(kind) => match kind {
+            ze_schema_type_t::UINT8 => deserialize_primitive::<u8>(deserializer, ptr)?,
+            ze_schema_type_t::UINT16 => deserialize_primitive::<u16>(deserializer, ptr)?,
+            ze_schema_type_t::UINT32 => deserialize_primitive::<u32>(deserializer, ptr)?,
+            ze_schema_type_t::UINT64 => deserialize_primitive::<u64>(deserializer, ptr)?,
+            ze_schema_type_t::INT8 => deserialize_primitive::<i8>(deserializer, ptr)?,
+            ze_schema_type_t::INT16 => deserialize_primitive::<i16>(deserializer, ptr)?,
+            ze_schema_type_t::INT32 => deserialize_primitive::<i32>(deserializer, ptr)?,
+            ze_schema_type_t::INT64 => deserialize_primitive::<i64>(deserializer, ptr)?,
+            ze_schema_type_t::FLOAT => deserialize_primitive::<f32>(deserializer, ptr)?,
+            ze_schema_type_t::DOUBLE => deserialize_primitive::<f64>(deserializer, ptr)?,
+            ze_schema_type_t::BOOL => deserialize_primitive::<bool>(deserializer, ptr)?,
+            _ => unreachable!("non-primitive schema types are not stored as primitives"),
+        },
+        SchemaType::String => {
+            let s = deserializer.deserialize::<String>()?;
+            (*ptr.cast::<MaybeUninit<z_owned_string_t>>())
+                .as_rust_type_mut_uninit()
+                .write(CStringOwned::from(s));
+        }
+        SchemaType::Struct(schema) => deserialize_fields(deserializer, &schema.fields, ptr)?,
+        SchemaType::Sequence(_) | SchemaType::Map(_) => {
+            let element = ty.element_type().unwrap();
+            let len = deserializer.deserialize_len()?;
+            let element_size = element.c_size();
+            let size = match len.checked_mul(element_size) {
+                Some(size) => size,
+                None => {
+                    return Err(SchemaError::new(format!(
+                        "sequence length {} is too large",
+                        len
+                    )))
+                }
+            };
+            let data = if size == 0 {
+                std::ptr::null_mut()
+            } else {
+                let data = libc::malloc(size);
+                if data.is_null() {
+                    return Err(SchemaError::new(format!(
+                        "failed to allocate {} bytes for a sequence",
+                        size
+                    )));
+                }
+                data
+            };
+            for i in 0..len {
+                let element_ptr = data.cast::<u8>().add(i * element_size);
+                if let Err(e) = deserialize_value(deserializer, &element, element_ptr) {
+                    for j in 0..i {
+                        drop_value(&element, data.cast::<u8>().add(j * element_size));
+                    }
+                    libc::free(data);
+                    return Err(e.in_element(i));
+                }
+            }
+            ptr.cast::<ze_schema_sequence_t>()
+                .write_unaligned(ze_schema_sequence_t { data, len });
+        }
+    }
+    Ok(())
+}
+
+/// Deserializes the fields of a structure into the uninitialized memory at `ptr`.
+///
+/// On failure, whatever was deserialized is freed and the memory is left uninitialized.
+unsafe fn deserialize_fields(
+    deserializer: &mut SchemaDeserializer,
+    fields: &[Field],
+    ptr: *mut u8,
+) -> Result<(), SchemaError> {
+    for (i, field) in fields.iter().enumerate() {
+        if let Err(e) = deserialize_value(deserializer, &field.ty, ptr.add(field.offset)) {
+            drop_fields(&fields[..i], ptr);
+            return Err(e.in_field(&field.name));
+        }
+    }
+    Ok(())
+}
+
+/// Checks that the next value of the payload is of type `ty`.
+pub(crate) fn validate_value(
+    deserializer: &mut SchemaDeserializer,
+    ty: &SchemaType,
+) -> Result<(), SchemaError> {
+    match ty {
+        SchemaType::Primitive(kind) => match kind {
+            ze_schema_type_t::UINT8 => deserializer.deserialize::<u8>().map(drop)?,
+            ze_schema_type_t::UINT16 => deserializer.deserialize::<u16>().map(drop)?,
+            ze_schema_type_t::UINT32 => deserializer.deserialize::<u32>().map(drop)?,
+            ze_schema_type_t::UINT64 => deserializer.deserialize::<u64>().map(drop)?,
+            ze_schema_type_t::INT8 => deserializer.deserialize::<i8>().map(drop)?,
+            ze_schema_type_t::INT16 => deserializer.deserialize::<i16>().map(drop)?,
+            ze_schema_type_t::INT32 => deserializer.deserialize::<i32>().map(drop)?,
+            ze_schema_type_t::INT64 => deserializer.deserialize::<i64>().map(drop)?,
+            ze_schema_type_t::FLOAT => deserializer.deserialize::<f32>().map(drop)?,
+            ze_schema_type_t::DOUBLE => deserializer.deserialize::<f64>().map(drop)?,
+            ze_schema_type_t::BOOL => deserializer.deserialize::<bool>().map(drop)?,
+            _ => unreachable!("non-primitive schema types are not stored as primitives"),
+        },
+        SchemaType::String => deserializer.deserialize::<String>().map(drop)?,
+        SchemaType::Struct(schema) => {
+            for field in &schema.fields {
+                validate_value(deserializer, &field.ty).map_err(|e| e.in_field(&field.name))?;
+            }
+        }
+        SchemaType::Sequence(_) | SchemaType::Map(_) => {
+            let element = ty.element_type().unwrap();
+            let len = deserializer.deserialize_len()?;
+            for i in 0..len {
+                validate_value(deserializer, &element).map_err(|e| e.in_element(i))?;
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Frees strings and sequences of the value of type `ty` at `ptr`, resetting them to their empty state.
+unsafe fn drop_value(ty: &SchemaType, ptr: *mut u8) {
+    match ty {
+        SchemaType::Primitive(_) => {}
+        SchemaType::String => {
+            let s: &mut CStringOwned = (*ptr.cast::<z_owned_string_t>()).as_rust_type_mut();
+            drop(std::mem::replace(s, CStringOwned::gravestone()));
+        }
+        SchemaType::Struct(schema) => drop_fields(&schema.fields, ptr),
+        SchemaType::Sequence(_) | SchemaType::Map(_) => {
+            let element = ty.element_type().unwrap();
+            let sequence = ptr.cast::<ze_schema_sequence_t>().read_unaligned();
+            if sequence.data.is_null() {
+                return;
+            }
+            let element_size = element.c_size();
+            for i in 0..sequence.len {
+                drop_value(&element, sequence.data.cast::<u8>().add(i * element_size));
+            }
+            libc::free(sequence.data);
+            ptr.cast::<ze_schema_sequence_t>()
+                .write_unaligned(ze_schema_sequence_t {
+                    data: std::ptr::null_mut(),
+                    len: 0,
+                });
+        }
+    }
+}
+
+unsafe fn drop_fields(fields: &[Field], ptr: *mut u8) {
+    for field in fields {
+        drop_value(&field.ty, ptr.add(field.offset));
+    }
+}
+
+impl Schema {
+    /// Alignment required by the fields of the structure.
+    fn align(&self) -> usize {
+        self.fields
+            .iter()
+            .map(|f| f.ty.c_align())
+            .max()
+            .unwrap_or(1)
+    }
+
+    fn add_field(&mut self, name: &str, offset: usize, ty: SchemaType) -> z_result_t {
+        if self.fields.iter().any(|f| f.name == name) {
+            crate::report_error!(
+                zc_error_kind_t::INVALID_ARGUMENT,
+                "Schema already has a field named '{}'",
+                name
+            );
+            return result::Z_EINVAL;
+        }
+        match offset.checked_add(ty.c_size()) {
+            Some(end) if end <= self.size => {}
+            _ => {
+                crate::report_error!(
+                    zc_error_kind_t::INVALID_ARGUMENT,
+                    "Field '{}' at offset {} does not fit in a structure of {} bytes",
+                    name,
+                    offset,
+                    self.size
+                );
+                return result::Z_EINVAL;
+            }
+        }
+        let align = ty.c_align();
+        if offset % align != 0 {
+            crate::report_error!(
+                zc_error_kind_t::INVALID_ARGUMENT,
+                "Field '{}' at offset {} is not aligned on {} bytes",
+                name,
+                offset,
+                align
+            );
+            return result::Z_EINVAL;
+        }
+        // Elements are laid out contiguously, so that each of them is only aligned if their size is a multiple
+        // of their alignment.
+        if let Some(element) = ty.element_type() {
+            let align = element.c_align();
+            if element.c_size() % align != 0 {
+                crate::report_error!(
+                    zc_error_kind_t::INVALID_ARGUMENT,
+                    "Elements of field '{}' are {} bytes large, which is not a multiple of their alignment of {} bytes",
+                    name,
+                    element.c_size(),
+                    align
+                );
+                return result::Z_EINVAL;
+            }
+        }
+        self.fields.push(Field {
+            name: name.to_string(),
+            offset,
+            ty,
+        });
+        result::Z_OK
+    }
+
+    /// Serializes the structure at `data`.
+    pub(crate) unsafe fn serialize(&self, data: *const u8) -> Result<ZBytes, SchemaError> {
+        let mut serializer = ZSerializer::new();
+        for field in &self.fields {
+            serialize_value(&mut serializer, &field.ty, data.add(field.offset))
+                .map_err(|e| e.in_field(&field.name))?;
+        }
+        Ok(serializer.finish())
+    }
+
+    /// Checks that `payload` holds a structure described by this schema.
+    pub(crate) fn validate(&self, payload: &ZBytes) -> Result<(), SchemaError> {
+        let mut deserializer = SchemaDeserializer::new(payload);
+        for field in &self.fields {
+            validate_value(&mut deserializer, &field.ty).map_err(|e| e.in_field(&field.name))?;
+        }
+        if !deserializer.done() {
+            return Err(SchemaError::new("unexpected trailing data"));
+        }
+        Ok(())
+    }
+
+    /// Deserializes `payload` into the uninitialized structure at `data`.
+    unsafe fn deserialize(&self, payload: &ZBytes, data: *mut u8) -> Result<(), SchemaError> {
+        let mut deserializer = SchemaDeserializer::new(payload);
+        deserialize_fields(&mut deserializer, &self.fields, data)?;
+        if !deserializer.done() {
+            drop_fields(&self.fields, data);
+            return Err(SchemaError::new("unexpected trailing data"));
+        }
+        Ok(())
+    }
+}
+
+unsafe fn field_name<'a>(name: *const c_char) -> Result<&'a str, z_result_t> {
+    let s = CStringView::new_borrowed(name, strlen_or_zero(name))?;
+    match <&str>::try_from(&s) {
+        Ok("") => {
+            crate::report_error!(
+                zc_error_kind_t::INVALID_ARGUMENT,
+                "Field name should not be empty"
+            );
+            Err(result::Z_EINVAL)
+        }
+        // SAFETY: the string is borrowed from `name` pointer, not from the view.
+        Ok(s) => Ok(std::mem::transmute::<&str, &'a str>(s)),
+        Err(e) => {
+            crate::report_error!(
+                zc_error_kind_t::INVALID_ARGUMENT,
+                "Field name should be a valid utf-8 string: {}",
+                e
+            );
+            Err(result::Z_EINVAL)
+        }
+    }
+}
+
+fn scalar_type(type_: ze_schema_type_t) -> Option<SchemaType> {
+    match type_ {
+        ze_schema_type_t::STRING => Some(SchemaType::String),
+        ze_schema_type_t::STRUCT | ze_schema_type_t::SEQUENCE | ze_schema_type_t::MAP => None,
+        _ => Some(SchemaType::Primitive(type_)),
+    }
+}
+
+/// @warning This API has been marked as unstable: it works as advertised, but it may be changed in a future release.
+/// @brief Constructs an empty schema describing a C structure.
+///
+/// Fields are then added with `ze_schema_add_field()` and its variants, and are serialized in the order they were added.
+///
+/// @param this_: An uninitialized memory location where the schema will be constructed.
+/// @param size: The size of the structure, i.e. `sizeof(struct ...)`.
+#[no_mangle]
+pub extern "C" fn ze_schema_new(this_: &mut MaybeUninit<ze_owned_schema_t>, size: usize) {
+    this_.as_rust_type_mut_uninit().write(Some(Schema {
+        size,
+        fields: Vec::new(),
+    }));
+}
+
+/// @warning This API has been marked as unstable: it works as advertised, but it may be changed in a future release.
+/// @brief Constructs a schema in its gravestone state.
+#[no_mangle]
+pub extern "C" fn ze_internal_schema_null(this_: &mut MaybeUninit<ze_owned_schema_t>) {
+    this_.as_rust_type_mut_uninit().write(None);
+}
+
+/// @warning This API has been marked as unstable: it works as advertised, but it may be changed in a future release.
+/// @brief Returns ``true`` if the schema is valid, ``false`` if it is in gravestone state.
+#[no_mangle]
+pub extern "C" fn ze_internal_schema_check(this_: &ze_owned_schema_t) -> bool {
+    this_.as_rust_type_ref().is_some()
+}
+
+/// @warning This API has been marked as unstable: it works as advertised, but it may be changed in a future release.
+/// @brief Borrows schema.
+#[no_mangle]
+#[allow(clippy::missing_safety_doc)]
+pub unsafe extern "C" fn ze_schema_loan(this_: &ze_owned_schema_t) -> &ze_loaned_schema_t {
+    this_
+        .as_rust_type_ref()
+        .as_ref()
+        .unwrap_unchecked()
+        .as_loaned_c_type_ref()
+}
+
+/// @warning This API has been marked as unstable: it works as advertised, but it may be changed in a future release.
+/// @brief Mutably borrows schema.
+#[no_mangle]
+#[allow(clippy::missing_safety_doc)]
+pub unsafe extern "C" fn ze_schema_loan_mut(
+    this_: &mut ze_owned_schema_t,
+) -> &mut ze_loaned_schema_t {
+    this_
+        .as_rust_type_mut()
+        .as_mut()
+        .unwrap_unchecked()
+        .as_loaned_c_type_mut()
+}
+
+/// @warning This API has been marked as unstable: it works as advertised, but it may be changed in a future release.
+/// @brief Constructs a copy of the schema.
+#[no_mangle]
+pub extern "C" fn ze_schema_clone(
+    dst: &mut MaybeUninit<ze_owned_schema_t>,
+    this_: &ze_loaned_schema_t,
+) {
+    dst.as_rust_type_mut_uninit()
+        .write(Some(this_.as_rust_type_ref().clone()));
+}
+
+/// @warning This API has been marked as unstable: it works as advertised, but it may be changed in a future release.
+/// @brief Frees schema, resetting it to its gravestone state.
+#[no_mangle]
+pub extern "C" fn ze_schema_drop(this_: &mut ze_moved_schema_t) {
+    let _ = this_.take_rust_type();
+}
+
+/// @warning This API has been marked as unstable: it works as advertised, but it may be changed in a future release.
+/// @brief Adds a numeric, boolean or string field to the schema.
+///
+/// @param this_: The schema.
+/// @param name: A null-terminated field name, unique within the schema.
+/// @param offset: The offset of the field in the structure, i.e. `offsetof(struct ..., field)`.
+/// `ZE_SCHEMA_TYPE_STRING` fields, and structure fields holding strings, should be aligned, as they are in C structures.
+/// @param type_: The type of the field, `ZE_SCHEMA_TYPE_STRING` fields being `z_owned_string_t`.
+/// @return 0 in case of success, negative error code otherwise.
+#[allow(clippy::missing_safety_doc)]
+#[no_mangle]
+pub unsafe extern "C" fn ze_schema_add_field(
+    this_: &mut ze_loaned_schema_t,
+    name: *const c_char,
+    offset: usize,
+    type_: ze_schema_type_t,
+) -> z_result_t {
+    let name = match field_name(name) {
+        Ok(name) => name,
+        Err(r) => return r,
+    };
+    let Some(ty) = scalar_type(type_) else {
+        crate::report_error!(
+            zc_error_kind_t::INVALID_ARGUMENT,
+            "Field '{}' should be added with the function dedicated to its type ({:?})",
+            name,
+            type_
+        );
+        return result::Z_EINVAL;
+    };
+    this_.as_rust_type_mut().add_field(name, offset, ty)
+}
+
+/// @warning This API has been marked as unstable: it works as advertised, but it may be changed in a future release.
+/// @brief Adds a nested structure field to the schema.
+///
+/// @param this_: The schema.
+/// @param name: A null-terminated field name, unique within the schema.
+/// @param offset: The offset of the field in the structure.
+/// @param schema: The schema of the nested structure, which is copied: later changes to it do not affect `this_`.
+/// @return 0 in case of success, negative error code otherwise.
+#[allow(clippy::missing_safety_doc)]
+#[no_mangle]
+pub unsafe extern "C" fn ze_schema_add_struct_field(
+    this_: &mut ze_loaned_schema_t,
+    name: *const c_char,
+    offset: usize,
+    schema: &ze_loaned_schema_t,
+) -> z_result_t {
+    let name = match field_name(name) {
+        Ok(name) => name,
+        Err(r) => return r,
+    };
+    let nested = Arc::new(schema.as_rust_type_ref().clone());
+    this_
+        .as_rust_type_mut()
+        .add_field(name, offset, SchemaType::Struct(nested))
+}
+
+/// @warning This API has been marked as unstable: it works as advertised, but it may be changed in a future release.
+/// @brief Adds a sequence field to the schema, stored as a `ze_schema_sequence_t`.
+///
+/// @param this_: The schema.
+/// @param name: A null-terminated field name, unique within the schema.
+/// @param offset: The offset of the field in the structure.
+/// @param element_type: The type of the sequence elements, `ZE_SCHEMA_TYPE_SEQUENCE` and `ZE_SCHEMA_TYPE_MAP` are not
+/// supported, nested sequences should be wrapped in a structure.
+/// @param element_schema: The schema of the elements if `element_type` is `ZE_SCHEMA_TYPE_STRUCT`, `NULL` otherwise.
+/// It is copied: later changes to it do not affect `this_`.
+/// @return 0 in case of success, negative error code otherwise.
+#[allow(clippy::missing_safety_doc)]
+#[no_mangle]
+pub unsafe extern "C" fn ze_schema_add_sequence_field(
+    this_: &mut ze_loaned_schema_t,
+    name: *const c_char,
+    offset: usize,
+    element_type: ze_schema_type_t,
+    element_schema: Option<&ze_loaned_schema_t>,
+) -> z_result_t {
+    let name = match field_name(name) {
+        Ok(name) => name,
+        Err(r) => return r,
+    };
+    let element = match (element_type, element_schema) {
+        (ze_schema_type_t::STRUCT, Some(schema)) => {
+            SchemaType::Struct(Arc::new(schema.as_rust_type_ref().clone()))
+        }
+        (ze_schema_type_t::STRUCT, None) => {
+            crate::report_error!(
+                zc_error_kind_t::INVALID_ARGUMENT,
+                "Sequence field '{}' of structures requires an element schema",
+                name
+            );
+            return result::Z_EINVAL;
+        }
+        (_, Some(_)) => {
+            crate::report_error!(
+                zc_error_kind_t::INVALID_ARGUMENT,
+                "Element schema given for sequence field '{}' of {:?} elements",
+                name,
+                element_type
+            );
+            return result::Z_EINVAL;
+        }
+        (_, None) => match scalar_type(element_type) {
+            Some(ty) => ty,
+            None => {
+                crate::report_error!(
+                    zc_error_kind_t::INVALID_ARGUMENT,
+                    "Unsupported element type {:?} for sequence field '{}'",
+                    element_type,
+                    name
+                );
+                return result::Z_EINVAL;
+            }
+        },
+    };
+    this_
+        .as_rust_type_mut()
+        .add_field(name, offset, SchemaType::Sequence(Box::new(element)))
+}
+
+/// @warning This API has been marked as unstable: it works as advertised, but it may be changed in a future release.
+/// @brief Adds a map field to the schema, stored as a `ze_schema_sequence_t` of entries.
+///
+/// A map is serialized in the same way as a sequence of key-value tuples, which is compatible with maps serialized by
+/// `ze_serializer_serialize_map_begin()`.
+///
+/// @param this_: The schema.
+/// @param name: A null-terminated field name, unique within the schema.
+/// @param offset: The offset of the field in the structure.
+/// @param entry_schema: The schema of the entries, which should have exactly two fields: the key and the value.
+/// It is copied: later changes to it do not affect `this_`.
+/// @return 0 in case of success, negative error code otherwise.
+#[allow(clippy::missing_safety_doc)]
+#[no_mangle]
+pub unsafe extern "C" fn ze_schema_add_map_field(
+    this_: &mut ze_loaned_schema_t,
+    name: *const c_char,
+    offset: usize,
+    entry_schema: &ze_loaned_schema_t,
+) -> z_result_t {
+    let name = match field_name(name) {
+        Ok(name) => name,
+        Err(r) => return r,
+    };
+    let entry = entry_schema.as_rust_type_ref();
+    if entry.fields.len() != 2 {
+        crate::report_error!(
+            zc_error_kind_t::INVALID_ARGUMENT,
+            "Entry schema of map field '{}' should have 2 fields, but it has {}",
+            name,
+            entry.fields.len()
+        );
+        return result::Z_EINVAL;
+    }
+    this_
+        .as_rust_type_mut()
+        .add_field(name, offset, SchemaType::Map(Arc::new(entry.clone())))
+}
+
+/// @warning This API has been marked as unstable: it works as advertised, but it may be changed in a future release.
+/// @brief Serializes a structure described by the schema.
+///
+/// @param this_: The schema.
+/// @param data: A pointer to the structure.
+/// @param bytes: An uninitialized memory location where the serialized payload will be constructed.
+/// @return 0 in case of success, negative error code otherwise (in this case `bytes` will be in its gravestone state).
+#[allow(clippy::missing_safety_doc)]
+#[no_mangle]
+pub unsafe extern "C" fn ze_schema_serialize(
+    this_: &ze_loaned_schema_t,
+    data: *const c_void,
+    bytes: &mut MaybeUninit<z_owned_bytes_t>,
+) -> z_result_t {
+    let bytes = bytes.as_rust_type_mut_uninit();
+    match this_.as_rust_type_ref().serialize(data.cast()) {
+        Ok(payload) => {
+            bytes.write(payload);
+            result::Z_OK
+        }
+        Err(e) => {
+            crate::report_error!(
+                zc_error_kind_t::INVALID_ARGUMENT,
+                "Failed to serialize structure: {}",
+                e
+            );
+            bytes.write(ZBytes::new());
+            result::Z_EINVAL
+        }
+    }
+}
+
+/// @warning This API has been marked as unstable: it works as advertised, but it may be changed in a future release.
+/// @brief Deserializes a structure described by the schema.
+///
+/// Strings and sequences of the structure are allocated, and should be freed with `ze_schema_value_drop()`.
+///
+/// @param this_: The schema.
+/// @param bytes: The serialized payload.
+/// @param data: A pointer to the structure to fill.
+/// @return 0 in case of success, `Z_EDESERIALIZE` if the payload does not match the schema (in this case nothing is
+/// allocated and the content of `data` is unspecified).
+#[allow(clippy::missing_safety_doc)]
+#[no_mangle]
+pub unsafe extern "C" fn ze_schema_deserialize(
+    this_: &ze_loaned_schema_t,
+    bytes: &z_loaned_bytes_t,
+    data: *mut c_void,
+) -> z_result_t {
+    match this_
+        .as_rust_type_ref()
+        .deserialize(bytes.as_rust_type_ref(), data.cast())
+    {
+        Ok(()) => result::Z_OK,
+        Err(e) => {
+            crate::report_error!(
+                zc_error_kind_t::DESERIALIZE,
+                "Payload does not match the schema: {}",
+                e
+            );
+            result::Z_EDESERIALIZE
+        }
+    }
+}
+
+/// @warning This API has been marked as unstable: it works as advertised, but it may be changed in a future release.
+/// @brief Checks that a payload holds a structure described by the schema, without deserializing it.
+///
+/// In case of mismatch, the reported error names the offending field, e.g. `points[3].x: ...`.
+///
+/// @param this_: The schema.
+/// @param bytes: The serialized payload.
+/// @return 0 if the payload matches the schema, `Z_EDESERIALIZE` otherwise.
+#[no_mangle]
+pub extern "C" fn ze_schema_validate(
+    this_: &ze_loaned_schema_t,
+    bytes: &z_loaned_bytes_t,
+) -> z_result_t {
+    match this_.as_rust_type_ref().validate(bytes.as_rust_type_ref()) {
+        Ok(()) => result::Z_OK,
+        Err(e) => {
+            crate::report_error!(
+                zc_error_kind_t::DESERIALIZE,
+                "Payload does not match the schema: {}",
+                e
+            );
+            result::Z_EDESERIALIZE
+        }
+    }
+}
+
+/// @warning This API has been marked as unstable: it works as advertised, but it may be changed in a future release.
+/// @brief Frees the strings and sequences of a structure described by the schema, such as one filled by `ze_schema_deserialize()`.
+///
+/// Strings are reset to their gravestone state and sequences to an empty one, so calling it twice is safe.
+/// Sequences should have been allocated with `malloc()`.
+///
+/// @param this_: The schema.
+/// @param data: A pointer to the structure.
+#[allow(clippy::missing_safety_doc)]
+#[no_mangle]
+pub unsafe extern "C" fn ze_schema_value_drop(this_: &ze_loaned_schema_t, data: *mut c_void) {
+    drop_fields(&this_.as_rust_type_ref().fields, data.cast());
+}
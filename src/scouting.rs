@@ -192,17 +192,22 @@ pub extern "C" fn z_scout(
     let options = options.cloned().unwrap_or_default();
 
     let Ok(what) = WhatAmIMatcher::try_from(options.what as u8) else {
-        crate::report_error!("Invalid WhatAmIMatcher value: {:?}", options.what);
+        crate::report_error!(
+            zc_error_kind_t::INVALID_ARGUMENT,
+            "Invalid WhatAmIMatcher value: {:?}",
+            options.what
+        );
         return result::Z_EINVAL;
     };
 
     #[allow(clippy::unnecessary_cast)] // Required for multi-target
     let timeout = options.timeout_ms;
     let Some(config) = config.take_rust_type() else {
-        crate::report_error!("Config not provided");
+        crate::report_error!(zc_error_kind_t::INVALID_ARGUMENT, "Config not provided");
         return result::Z_EINVAL;
     };
 
+    crate::runtime::mark_runtime_started();
     ZRuntime::Application.block_in_place(async move {
         let res = zenoh::scout(what, config)
             .callback(move |h| {
@@ -219,7 +224,7 @@ pub extern "C" fn z_scout(
                 result::Z_OK
             }
             Err(e) => {
-                crate::report_error!("{}", e);
+                crate::report_error!(zc_error_kind_t::NETWORK, "{}", e);
                 result::Z_EGENERIC
             }
         }
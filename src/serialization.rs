@@ -13,11 +13,12 @@
 //
 
 use core::str;
-use std::{mem::MaybeUninit, slice::from_raw_parts};
+use std::{borrow::Cow, mem::MaybeUninit, slice::from_raw_parts};
 
 use zenoh::bytes::ZBytes;
 use zenoh_ext::{
-    z_deserialize, z_serialize, Deserialize, Serialize, VarInt, ZDeserializer, ZSerializer,
+    z_deserialize, z_serialize, Deserialize, Serialize, VarInt, ZDeserializeError, ZDeserializer,
+    ZSerializer,
 };
 
 pub use crate::opaque_types::{
@@ -28,11 +29,186 @@ use crate::{
     strlen_or_zero,
     transmute::{Gravestone, LoanedCTypeRef, RustTypeRef, RustTypeRefUninit, TakeRustType},
     z_loaned_bytes_t, z_loaned_slice_t, z_loaned_string_t, z_owned_bytes_t, z_owned_slice_t,
-    z_owned_string_t, CSliceOwned, CStringOwned,
+    z_owned_string_t, z_view_slice_t, z_view_string_t, CSliceOwned, CSliceView, CStringOwned,
+    CStringView,
 };
 
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum ContainerKind {
+    Sequence,
+    Map,
+    Tuple,
+    Option,
+    /// A key-value pair of a map, started with `ze_serializer_serialize_map_pair()`.
+    Pair,
+}
+
+impl ContainerKind {
+    /// Returns ``true`` if the container is closed once it holds its elements, instead of by an `_end` function.
+    fn closes_when_full(self) -> bool {
+        matches!(self, ContainerKind::Option | ContainerKind::Pair)
+    }
+}
+
+impl std::fmt::Display for ContainerKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            ContainerKind::Sequence => "sequence",
+            ContainerKind::Map => "map",
+            ContainerKind::Tuple => "tuple",
+            ContainerKind::Option => "option",
+            ContainerKind::Pair => "map pair",
+        })
+    }
+}
+
+/// A sequence, map, tuple or option being serialized.
+struct Container {
+    kind: ContainerKind,
+    /// The number of elements the container should hold, if known (for maps, keys and values are counted separately).
+    expected: Option<usize>,
+    written: usize,
+}
+
+/// A serializer keeping track of the containers being serialized, to check that they are given the expected number of elements.
+pub struct Serializer {
+    inner: ZSerializer,
+    containers: Vec<Container>,
+}
+
+impl Serializer {
+    fn new() -> Self {
+        Serializer {
+            inner: ZSerializer::new(),
+            containers: Vec::new(),
+        }
+    }
+
+    fn check_element(&self) -> z_result_t {
+        match self.containers.last() {
+            Some(Container {
+                kind,
+                expected: Some(expected),
+                written,
+            }) if written >= expected => {
+                crate::report_error!(
+                    zc_error_kind_t::INVALID_ARGUMENT,
+                    "The {} already holds its {} elements",
+                    kind,
+                    expected
+                );
+                result::Z_EINVAL
+            }
+            _ => result::Z_OK,
+        }
+    }
+
+    fn element_done(&mut self) {
+        // A closed pair counts as the key and the value of its map.
+        let mut count = 1;
+        while let Some(container) = self.containers.last_mut() {
+            container.written += count;
+            if !container.kind.closes_when_full() || Some(container.written) != container.expected {
+                break;
+            }
+            count = if container.kind == ContainerKind::Pair {
+                2
+            } else {
+                1
+            };
+            self.containers.pop();
+        }
+    }
+
+    /// Serializes a value, as an element of the current container if any.
+    fn serialize<T: Serialize>(&mut self, t: T) -> z_result_t {
+        let r = self.check_element();
+        if r != result::Z_OK {
+            return r;
+        }
+        self.inner.serialize(t);
+        self.element_done();
+        result::Z_OK
+    }
+
+    /// Starts serialization of a container, as an element of the current container if any.
+    fn begin(&mut self, kind: ContainerKind, expected: Option<usize>) -> z_result_t {
+        let r = self.check_element();
+        if r != result::Z_OK {
+            return r;
+        }
+        match kind {
+            ContainerKind::Sequence | ContainerKind::Map => self
+                .inner
+                .serialize(VarInt::<usize>(expected.unwrap_or_default())),
+            ContainerKind::Tuple | ContainerKind::Pair => {}
+            ContainerKind::Option => self.inner.serialize(true),
+        }
+        let expected = match kind {
+            ContainerKind::Map => expected.map(|n| n * 2),
+            _ => expected,
+        };
+        self.containers.push(Container {
+            kind,
+            expected,
+            written: 0,
+        });
+        result::Z_OK
+    }
+
+    /// Ends serialization of the current container, which should be of the given kind.
+    fn end(&mut self, kind: ContainerKind) -> z_result_t {
+        match self.containers.last() {
+            Some(container) if container.kind == kind => {
+                if let Some(expected) = container.expected.filter(|e| *e != container.written) {
+                    crate::report_error!(
+                        zc_error_kind_t::INVALID_ARGUMENT,
+                        "The {} should hold {} elements, got {}",
+                        kind,
+                        expected,
+                        container.written
+                    );
+                    return result::Z_EINVAL;
+                }
+            }
+            Some(container) => {
+                crate::report_error!(
+                    zc_error_kind_t::INVALID_ARGUMENT,
+                    "Can not end a {} while serializing a {}",
+                    kind,
+                    container.kind
+                );
+                return result::Z_EINVAL;
+            }
+            None => {
+                crate::report_error!(
+                    zc_error_kind_t::INVALID_ARGUMENT,
+                    "No {} is being serialized",
+                    kind
+                );
+                return result::Z_EINVAL;
+            }
+        }
+        self.containers.pop();
+        self.element_done();
+        result::Z_OK
+    }
+
+    fn finish(self) -> Result<ZBytes, z_result_t> {
+        if let Some(container) = self.containers.last() {
+            crate::report_error!(
+                zc_error_kind_t::INVALID_ARGUMENT,
+                "Serializer is finished while a {} is still being serialized",
+                container.kind
+            );
+            return Err(result::Z_EINVAL);
+        }
+        Ok(self.inner.finish())
+    }
+}
+
 decl_c_type! {
-    owned(ze_owned_serializer_t, option ZSerializer),
+    owned(ze_owned_serializer_t, option Serializer),
     loaned(ze_loaned_serializer_t),
 }
 
@@ -42,7 +218,7 @@ decl_c_type! {
 #[no_mangle]
 extern "C" fn ze_serializer_empty(this: &mut MaybeUninit<ze_owned_serializer_t>) -> z_result_t {
     this.as_rust_type_mut_uninit()
-        .write(Some(ZSerializer::new()));
+        .write(Some(Serializer::new()));
     result::Z_OK
 }
 
@@ -91,18 +267,152 @@ pub extern "C" fn ze_internal_serializer_null(this_: &mut MaybeUninit<ze_owned_s
 /// @brief Drop serializer and extract underlying `bytes` object it was writing to.
 /// @param this_: A serializer instance.
 /// @param bytes: An uninitialized memory location where `bytes` object` will be written to.
+/// @return 0 in case of success, `Z_EINVAL` if a container started with one of the `_begin` functions (or an option,
+/// or a map pair) is not complete (in this case `bytes` will be empty).
 #[allow(clippy::missing_safety_doc)]
 #[no_mangle]
 pub unsafe extern "C" fn ze_serializer_finish(
     this: &mut ze_moved_serializer_t,
     bytes: &mut MaybeUninit<z_owned_bytes_t>,
-) {
-    bytes
-        .as_rust_type_mut_uninit()
-        .write(this.take_rust_type().unwrap_unchecked().finish());
+) -> z_result_t {
+    let bytes = bytes.as_rust_type_mut_uninit();
+    match this.take_rust_type().unwrap_unchecked().finish() {
+        Ok(b) => {
+            bytes.write(b);
+            result::Z_OK
+        }
+        Err(r) => {
+            bytes.write(ZBytes::new());
+            r
+        }
+    }
 }
 
-decl_c_type! {loaned(ze_deserializer_t, ZDeserializer<'static>)}
+/// Size of the serialized form of a value, as produced by zenoh-ext.
+pub(crate) trait SerializedSize {
+    fn serialized_size(&self) -> usize;
+}
+
+macro_rules! impl_fixed_serialized_size {
+    ($($t:ty),*) => {
+        $(
+            impl SerializedSize for $t {
+                fn serialized_size(&self) -> usize {
+                    std::mem::size_of::<$t>()
+                }
+            }
+        )*
+    };
+}
+impl_fixed_serialized_size!(u8, u16, u32, u64, i8, i16, i32, i64, f32, f64, bool);
+
+impl SerializedSize for VarInt<usize> {
+    fn serialized_size(&self) -> usize {
+        // Lengths are serialized as LEB128, using 7 bits per byte.
+        let bits = (usize::BITS - self.0.leading_zeros()) as usize;
+        bits.div_ceil(7).max(1)
+    }
+}
+
+impl SerializedSize for Vec<u8> {
+    fn serialized_size(&self) -> usize {
+        VarInt(self.len()).serialized_size() + self.len()
+    }
+}
+
+impl SerializedSize for String {
+    fn serialized_size(&self) -> usize {
+        VarInt(self.len()).serialized_size() + self.len()
+    }
+}
+
+/// A deserializer keeping track of its position in the data, so that it can provide views into it.
+pub struct Deserializer {
+    inner: ZDeserializer<'static>,
+    bytes: &'static ZBytes,
+    /// Number of bytes deserialized so far.
+    position: usize,
+}
+
+impl Deserializer {
+    fn new(bytes: &'static ZBytes) -> Self {
+        Deserializer {
+            inner: ZDeserializer::new(bytes),
+            bytes,
+            position: 0,
+        }
+    }
+
+    fn deserialize<T: Deserialize + SerializedSize>(&mut self) -> Result<T, ZDeserializeError> {
+        let v = self.inner.deserialize::<T>()?;
+        self.position += v.serialized_size();
+        Ok(v)
+    }
+
+    /// Deserializes `out.len()` consecutive values into `out` at once,
+    /// which is a plain copy of the data for numeric types on little-endian targets.
+    fn deserialize_n<T: Deserialize + SerializedSize>(
+        &mut self,
+        out: &mut [T],
+    ) -> Result<(), ZDeserializeError> {
+        T::deserialize_n(out, &mut self.inner)?;
+        self.position += out
+            .iter()
+            .map(SerializedSize::serialized_size)
+            .sum::<usize>();
+        Ok(())
+    }
+
+    /// Deserializes a length-prefixed sequence of bytes, returning a view into the data instead of copying it.
+    /// The data should be contiguous, the deserializer is not advanced otherwise.
+    ///
+    /// The view avoids allocating and copying the bytes for the caller, but advancing the inner deserializer past
+    /// them still reads them through a scratch buffer, in time linear in their length.
+    fn deserialize_view(&mut self) -> Result<&'static [u8], z_result_t> {
+        let data = match self.bytes.to_bytes() {
+            Cow::Borrowed(data) => data,
+            Cow::Owned(_) => {
+                crate::report_error!(
+                    zc_error_kind_t::INVALID_ARGUMENT,
+                    "Data is not contiguous, it can only be deserialized by copy"
+                );
+                return Err(result::Z_EINVAL);
+            }
+        };
+        let len = match self.deserialize::<VarInt<usize>>() {
+            Ok(len) => len.0,
+            Err(e) => {
+                crate::report_error!(
+                    zc_error_kind_t::DESERIALIZE,
+                    "Failed to read the length: {}",
+                    e
+                );
+                return Err(result::Z_EDESERIALIZE);
+            }
+        };
+        let Some(view) = data.get(self.position..self.position.saturating_add(len)) else {
+            crate::report_error!(
+                zc_error_kind_t::DESERIALIZE,
+                "Failed to deserialize the payload: {} bytes are missing",
+                len
+            );
+            return Err(result::Z_EDESERIALIZE);
+        };
+        // Skipping the viewed bytes in bulk, which can not fail since they are available:
+        // zenoh-ext deserializers can not seek nor be rebuilt over a part of the borrowed bytes,
+        // so that the bytes are read into a scratch buffer.
+        let mut scratch = [0u8; 4096];
+        let mut remaining = len;
+        while remaining > 0 {
+            let n = remaining.min(scratch.len());
+            let _ = self.deserialize_n(&mut scratch[..n]);
+            remaining -= n;
+        }
+        Ok(view)
+    }
+}
+
+decl_c_type! {loaned(ze_deserializer_t, Deserializer)}
 
 fn ze_serialize_arithmetic<T>(this: &mut MaybeUninit<z_owned_bytes_t>, val: &T)
 where
@@ -121,7 +431,11 @@ where
             result::Z_OK
         }
         Err(e) => {
-            crate::report_error!("Failed to deserialize the payload: {:?}", e);
+            crate::report_error!(
+                zc_error_kind_t::DESERIALIZE,
+                "Failed to deserialize the payload: {:?}",
+                e
+            );
             result::Z_EPARSE
         }
     }
@@ -356,7 +670,11 @@ pub extern "C" fn ze_deserialize_slice(
             result::Z_OK
         }
         Err(e) => {
-            crate::report_error!("Failed to deserialize the payload: {}", e);
+            crate::report_error!(
+                zc_error_kind_t::DESERIALIZE,
+                "Failed to deserialize the payload: {}",
+                e
+            );
             slice
                 .as_rust_type_mut_uninit()
                 .write(CSliceOwned::gravestone());
@@ -381,7 +699,7 @@ pub extern "C" fn ze_serialize_string(
             result::Z_OK
         }
         Err(e) => {
-            crate::report_error!("{}", e);
+            crate::report_error!(zc_error_kind_t::INVALID_ARGUMENT, "{}", e);
             this.as_rust_type_mut_uninit().write(ZBytes::new());
             result::Z_EUTF8
         }
@@ -407,7 +725,7 @@ pub unsafe extern "C" fn ze_serialize_substr(
             result::Z_OK
         }
         Err(e) => {
-            crate::report_error!("{}", e);
+            crate::report_error!(zc_error_kind_t::INVALID_ARGUMENT, "{}", e);
             this.as_rust_type_mut_uninit().write(ZBytes::new());
             result::Z_EUTF8
         }
@@ -441,7 +759,11 @@ pub unsafe extern "C" fn ze_deserialize_string(
             result::Z_OK
         }
         Err(e) => {
-            crate::report_error!("Failed to deserialize the payload: {}", e);
+            crate::report_error!(
+                zc_error_kind_t::DESERIALIZE,
+                "Failed to deserialize the payload: {}",
+                e
+            );
             str.as_rust_type_mut_uninit()
                 .write(CStringOwned::gravestone());
             result::Z_EDESERIALIZE
@@ -452,7 +774,7 @@ pub unsafe extern "C" fn ze_deserialize_string(
 /// @brief Gets deserializer for`this_`.
 #[no_mangle]
 extern "C" fn ze_deserializer_from_bytes(this: &'static z_loaned_bytes_t) -> ze_deserializer_t {
-    *ZDeserializer::new(this.as_rust_type_ref()).as_loaned_c_type_ref()
+    *Deserializer::new(this.as_rust_type_ref()).as_loaned_c_type_ref()
 }
 
 /// @brief Checks if deserializer parsed all of its data.
@@ -461,14 +783,14 @@ extern "C" fn ze_deserializer_from_bytes(this: &'static z_loaned_bytes_t) -> ze_
 #[allow(clippy::missing_safety_doc)]
 pub unsafe extern "C" fn ze_deserializer_is_done(this_: &ze_deserializer_t) -> bool {
     let deserializer = this_.as_rust_type_ref();
-    deserializer.done()
+    deserializer.inner.done()
 }
 
-fn ze_serializer_serialize_arithmetic<T>(this: &mut ze_loaned_serializer_t, val: &T)
+fn ze_serializer_serialize_arithmetic<T>(this: &mut ze_loaned_serializer_t, val: &T) -> z_result_t
 where
     T: Serialize,
 {
-    this.as_rust_type_mut().serialize(val);
+    this.as_rust_type_mut().serialize(val)
 }
 
 fn ze_deserializer_deserialize_arithmetic<'a, T>(
@@ -476,7 +798,7 @@ fn ze_deserializer_deserialize_arithmetic<'a, T>(
     val: &'a mut T,
 ) -> z_result_t
 where
-    T: Deserialize,
+    T: Deserialize + SerializedSize,
 {
     match this.as_rust_type_mut().deserialize::<T>() {
         Ok(v) => {
@@ -484,7 +806,11 @@ where
             result::Z_OK
         }
         Err(e) => {
-            crate::report_error!("Failed to deserialize the payload: {:?}", e);
+            crate::report_error!(
+                zc_error_kind_t::DESERIALIZE,
+                "Failed to deserialize the payload: {:?}",
+                e
+            );
             result::Z_EDESERIALIZE
         }
     }
@@ -496,8 +822,7 @@ pub extern "C" fn ze_serializer_serialize_uint8(
     this_: &mut ze_loaned_serializer_t,
     val: u8,
 ) -> z_result_t {
-    ze_serializer_serialize_arithmetic::<u8>(this_, &val);
-    result::Z_OK
+    ze_serializer_serialize_arithmetic::<u8>(this_, &val)
 }
 
 /// @brief Serializes an unsigned integer.
@@ -506,8 +831,7 @@ pub extern "C" fn ze_serializer_serialize_uint16(
     this_: &mut ze_loaned_serializer_t,
     val: u16,
 ) -> z_result_t {
-    ze_serializer_serialize_arithmetic::<u16>(this_, &val);
-    result::Z_OK
+    ze_serializer_serialize_arithmetic::<u16>(this_, &val)
 }
 
 /// @brief Serializes an unsigned integer.
@@ -516,8 +840,7 @@ pub extern "C" fn ze_serializer_serialize_uint32(
     this_: &mut ze_loaned_serializer_t,
     val: u32,
 ) -> z_result_t {
-    ze_serializer_serialize_arithmetic::<u32>(this_, &val);
-    result::Z_OK
+    ze_serializer_serialize_arithmetic::<u32>(this_, &val)
 }
 
 /// @brief Serializes an unsigned integer.
@@ -526,8 +849,7 @@ pub extern "C" fn ze_serializer_serialize_uint64(
     this_: &mut ze_loaned_serializer_t,
     val: u64,
 ) -> z_result_t {
-    ze_serializer_serialize_arithmetic::<u64>(this_, &val);
-    result::Z_OK
+    ze_serializer_serialize_arithmetic::<u64>(this_, &val)
 }
 
 /// @brief Serializes a signed integer.
@@ -536,8 +858,7 @@ pub extern "C" fn ze_serializer_serialize_int8(
     this_: &mut ze_loaned_serializer_t,
     val: i8,
 ) -> z_result_t {
-    ze_serializer_serialize_arithmetic::<i8>(this_, &val);
-    result::Z_OK
+    ze_serializer_serialize_arithmetic::<i8>(this_, &val)
 }
 
 /// @brief Serializes a signed integer.
@@ -546,8 +867,7 @@ pub extern "C" fn ze_serializer_serialize_int16(
     this_: &mut ze_loaned_serializer_t,
     val: i16,
 ) -> z_result_t {
-    ze_serializer_serialize_arithmetic::<i16>(this_, &val);
-    result::Z_OK
+    ze_serializer_serialize_arithmetic::<i16>(this_, &val)
 }
 
 /// @brief Serializes a signed integer.
@@ -556,8 +876,7 @@ pub extern "C" fn ze_serializer_serialize_int32(
     this_: &mut ze_loaned_serializer_t,
     val: i32,
 ) -> z_result_t {
-    ze_serializer_serialize_arithmetic::<i32>(this_, &val);
-    result::Z_OK
+    ze_serializer_serialize_arithmetic::<i32>(this_, &val)
 }
 
 /// @brief Serializes a signed integer.
@@ -566,8 +885,7 @@ pub extern "C" fn ze_serializer_serialize_int64(
     this_: &mut ze_loaned_serializer_t,
     val: i64,
 ) -> z_result_t {
-    ze_serializer_serialize_arithmetic::<i64>(this_, &val);
-    result::Z_OK
+    ze_serializer_serialize_arithmetic::<i64>(this_, &val)
 }
 
 /// @brief Serializes a float.
@@ -576,8 +894,7 @@ pub extern "C" fn ze_serializer_serialize_float(
     this_: &mut ze_loaned_serializer_t,
     val: f32,
 ) -> z_result_t {
-    ze_serializer_serialize_arithmetic::<f32>(this_, &val);
-    result::Z_OK
+    ze_serializer_serialize_arithmetic::<f32>(this_, &val)
 }
 
 /// @brief Serializes a double.
@@ -586,8 +903,7 @@ pub extern "C" fn ze_serializer_serialize_double(
     this_: &mut ze_loaned_serializer_t,
     val: f64,
 ) -> z_result_t {
-    ze_serializer_serialize_arithmetic::<f64>(this_, &val);
-    result::Z_OK
+    ze_serializer_serialize_arithmetic::<f64>(this_, &val)
 }
 
 /// @brief Serializes a bool.
@@ -596,8 +912,7 @@ pub extern "C" fn ze_serializer_serialize_bool(
     this_: &mut ze_loaned_serializer_t,
     val: bool,
 ) -> z_result_t {
-    ze_serializer_serialize_arithmetic::<bool>(this_, &val);
-    result::Z_OK
+    ze_serializer_serialize_arithmetic::<bool>(this_, &val)
 }
 
 /// @brief Deserializes into an unsigned integer.
@@ -717,8 +1032,7 @@ pub extern "C" fn ze_serializer_serialize_slice(
     slice: &z_loaned_slice_t,
 ) -> z_result_t {
     let cslice = slice.as_rust_type_ref().slice();
-    this.as_rust_type_mut().serialize(cslice);
-    result::Z_OK
+    this.as_rust_type_mut().serialize(cslice)
 }
 
 /// @brief Serializes a data from buffer.
@@ -732,8 +1046,7 @@ pub extern "C" fn ze_serializer_serialize_buf(
     len: usize,
 ) -> z_result_t {
     let slice = unsafe { from_raw_parts(data, len) };
-    this.as_rust_type_mut().serialize(slice);
-    result::Z_OK
+    this.as_rust_type_mut().serialize(slice)
 }
 
 /// @brief Deserializes into a slice.
@@ -748,7 +1061,11 @@ pub extern "C" fn ze_deserializer_deserialize_slice(
             result::Z_OK
         }
         Err(e) => {
-            crate::report_error!("Failed to deserialize the payload: {}", e);
+            crate::report_error!(
+                zc_error_kind_t::DESERIALIZE,
+                "Failed to deserialize the payload: {}",
+                e
+            );
             slice
                 .as_rust_type_mut_uninit()
                 .write(CSliceOwned::gravestone());
@@ -766,12 +1083,9 @@ pub extern "C" fn ze_serializer_serialize_string(
     str: &z_loaned_string_t,
 ) -> z_result_t {
     match str::from_utf8(str.as_rust_type_ref().slice()) {
-        Ok(s) => {
-            this.as_rust_type_mut().serialize(s);
-            result::Z_OK
-        }
+        Ok(s) => this.as_rust_type_mut().serialize(s),
         Err(e) => {
-            crate::report_error!("{}", e);
+            crate::report_error!(zc_error_kind_t::INVALID_ARGUMENT, "{}", e);
             result::Z_EUTF8
         }
     }
@@ -788,12 +1102,9 @@ pub extern "C" fn ze_serializer_serialize_substr(
 ) -> z_result_t {
     let slice = unsafe { from_raw_parts(start as *const u8, len) };
     match str::from_utf8(slice) {
-        Ok(s) => {
-            this.as_rust_type_mut().serialize(s);
-            result::Z_OK
-        }
+        Ok(s) => this.as_rust_type_mut().serialize(s),
         Err(e) => {
-            crate::report_error!("{}", e);
+            crate::report_error!(zc_error_kind_t::INVALID_ARGUMENT, "{}", e);
             result::Z_EUTF8
         }
     }
@@ -822,7 +1133,11 @@ pub extern "C" fn ze_deserializer_deserialize_string(
             result::Z_OK
         }
         Err(e) => {
-            crate::report_error!("Failed to deserialize the payload: {}", e);
+            crate::report_error!(
+                zc_error_kind_t::DESERIALIZE,
+                "Failed to deserialize the payload: {}",
+                e
+            );
             str.as_rust_type_mut_uninit()
                 .write(CStringOwned::gravestone());
             result::Z_EDESERIALIZE
@@ -833,12 +1148,26 @@ pub extern "C" fn ze_deserializer_deserialize_string(
 /// @brief Initiates serialization of a sequence of multiple elements.
 /// @param this_: A serializer instance.
 /// @param len: Length of the sequence. Could be read during deserialization using `ze_deserializer_deserialize_sequence_length`.
+///
+/// The elements of the sequence are not counted, so that this function can not be used inside of a container
+/// started with one of the `_begin` functions (or of an option, or of a map pair):
+/// `ze_serializer_serialize_sequence_begin()` should be used instead.
+/// @return 0 in case of success, `Z_EINVAL` if a container is being serialized.
 #[no_mangle]
 pub extern "C" fn ze_serializer_serialize_sequence_length(
     this: &mut ze_loaned_serializer_t,
     len: usize,
 ) -> z_result_t {
-    this.as_rust_type_mut().serialize(VarInt::<usize>(len));
+    let this = this.as_rust_type_mut();
+    if let Some(container) = this.containers.last() {
+        crate::report_error!(
+            zc_error_kind_t::INVALID_ARGUMENT,
+            "Can not serialize a sequence length inside of a {}, use ze_serializer_serialize_sequence_begin()",
+            container.kind
+        );
+        return result::Z_EINVAL;
+    }
+    this.inner.serialize(VarInt::<usize>(len));
     result::Z_OK
 }
 
@@ -857,9 +1186,649 @@ pub extern "C" fn ze_deserializer_deserialize_sequence_length(
             result::Z_OK
         }
         Err(e) => {
-            crate::report_error!("Failed to read the sequence length: {}", e);
+            crate::report_error!(
+                zc_error_kind_t::DESERIALIZE,
+                "Failed to read the sequence length: {}",
+                e
+            );
             *len = 0;
             result::Z_EDESERIALIZE
         }
     }
 }
+
+/// @brief Starts serialization of a sequence, compatible with the serialization of Rust `Vec` or C++ `std::vector`.
+///
+/// The `len` elements of the sequence should then be serialized (a nested container counting as a single element),
+/// before calling `ze_serializer_serialize_sequence_end()`.
+/// @param this_: A serializer instance.
+/// @param len: Number of elements of the sequence. Could be read during deserialization using `ze_deserializer_deserialize_sequence_length()`.
+/// @return 0 in case of success, negative error code otherwise.
+#[no_mangle]
+pub extern "C" fn ze_serializer_serialize_sequence_begin(
+    this: &mut ze_loaned_serializer_t,
+    len: usize,
+) -> z_result_t {
+    this.as_rust_type_mut()
+        .begin(ContainerKind::Sequence, Some(len))
+}
+
+/// @brief Ends serialization of a sequence started with `ze_serializer_serialize_sequence_begin()`.
+/// @return 0 in case of success, negative error code if the current container is not a sequence or if it did not get the expected number of elements.
+#[no_mangle]
+pub extern "C" fn ze_serializer_serialize_sequence_end(
+    this: &mut ze_loaned_serializer_t,
+) -> z_result_t {
+    this.as_rust_type_mut().end(ContainerKind::Sequence)
+}
+
+/// @brief Starts serialization of a map, compatible with the serialization of Rust `HashMap` or C++ `std::unordered_map`.
+///
+/// The key and the value of each of the `len` pairs should then be serialized one after the other,
+/// before calling `ze_serializer_serialize_map_end()`.
+/// @param this_: A serializer instance.
+/// @param len: Number of key-value pairs of the map. Could be read during deserialization using `ze_deserializer_deserialize_map_length()`.
+/// @return 0 in case of success, negative error code otherwise.
+#[no_mangle]
+pub extern "C" fn ze_serializer_serialize_map_begin(
+    this: &mut ze_loaned_serializer_t,
+    len: usize,
+) -> z_result_t {
+    match len.checked_mul(2) {
+        Some(_) => this.as_rust_type_mut().begin(ContainerKind::Map, Some(len)),
+        None => {
+            crate::report_error!(
+                zc_error_kind_t::INVALID_ARGUMENT,
+                "Map length {} is too large",
+                len
+            );
+            result::Z_EINVAL
+        }
+    }
+}
+
+/// @brief Starts serialization of the next key-value pair of the map started with `ze_serializer_serialize_map_begin()`.
+///
+/// The key and the value should then be serialized, the pair being complete once both are (a nested container counting
+/// as a single element). Using this function is optional, it only checks that keys and values are not mismatched:
+/// it fails if the previous pair is not complete.
+/// @param this_: A serializer instance.
+/// @return 0 in case of success, negative error code if the current container is not a map, or if it already holds its pairs.
+#[no_mangle]
+pub extern "C" fn ze_serializer_serialize_map_pair(
+    this: &mut ze_loaned_serializer_t,
+) -> z_result_t {
+    let this = this.as_rust_type_mut();
+    match this.containers.last() {
+        Some(container) if container.kind == ContainerKind::Map && container.written % 2 == 0 => {}
+        Some(container) => {
+            crate::report_error!(
+                zc_error_kind_t::INVALID_ARGUMENT,
+                "Can not start a map pair while serializing a {}",
+                if container.kind == ContainerKind::Map {
+                    "map value"
+                } else {
+                    "non-map container"
+                }
+            );
+            return result::Z_EINVAL;
+        }
+        None => {
+            crate::report_error!(
+                zc_error_kind_t::INVALID_ARGUMENT,
+                "No map is being serialized"
+            );
+            return result::Z_EINVAL;
+        }
+    }
+    this.begin(ContainerKind::Pair, Some(2))
+}
+
+/// @brief Ends serialization of a map started with `ze_serializer_serialize_map_begin()`.
+/// @return 0 in case of success, negative error code if the current container is not a map or if it did not get the expected number of pairs.
+#[no_mangle]
+pub extern "C" fn ze_serializer_serialize_map_end(this: &mut ze_loaned_serializer_t) -> z_result_t {
+    this.as_rust_type_mut().end(ContainerKind::Map)
+}
+
+/// @brief Starts serialization of a tuple (or of a structure), compatible with the serialization of Rust tuples or C++ `std::tuple`.
+///
+/// The elements of the tuple should then be serialized in order, before calling `ze_serializer_serialize_tuple_end()`.
+/// Tuples have no header: their serialization is the concatenation of the ones of their elements.
+/// @return 0 in case of success, negative error code otherwise.
+#[no_mangle]
+pub extern "C" fn ze_serializer_serialize_tuple_begin(
+    this: &mut ze_loaned_serializer_t,
+) -> z_result_t {
+    this.as_rust_type_mut().begin(ContainerKind::Tuple, None)
+}
+
+/// @brief Ends serialization of a tuple started with `ze_serializer_serialize_tuple_begin()`.
+/// @return 0 in case of success, negative error code if the current container is not a tuple.
+#[no_mangle]
+pub extern "C" fn ze_serializer_serialize_tuple_end(
+    this: &mut ze_loaned_serializer_t,
+) -> z_result_t {
+    this.as_rust_type_mut().end(ContainerKind::Tuple)
+}
+
+/// @brief Serializes the presence flag of an optional value, compatible with the serialization of Rust `Option` or C++ `std::optional`.
+///
+/// If `is_present` is ``true``, the value should be serialized right after this call.
+/// @param this_: A serializer instance.
+/// @param is_present: ``true`` if the value is present, ``false`` otherwise.
+/// @return 0 in case of success, negative error code otherwise.
+#[no_mangle]
+pub extern "C" fn ze_serializer_serialize_option(
+    this: &mut ze_loaned_serializer_t,
+    is_present: bool,
+) -> z_result_t {
+    let this = this.as_rust_type_mut();
+    if is_present {
+        this.begin(ContainerKind::Option, Some(1))
+    } else {
+        this.serialize(false)
+    }
+}
+
+/// @brief Initiates deserialization of a map serialized with `ze_serializer_serialize_map_begin()`.
+///
+/// The key and the value of each pair should then be deserialized one after the other.
+/// @param this_: A deserializer instance.
+/// @param len: Pointer where the number of key-value pairs of the map will be written.
+/// @return 0 in case of success, negative error code otherwise.
+#[no_mangle]
+pub extern "C" fn ze_deserializer_deserialize_map_length(
+    this: &mut ze_deserializer_t,
+    len: &mut usize,
+) -> z_result_t {
+    match this.as_rust_type_mut().deserialize::<VarInt<usize>>() {
+        Ok(l) => {
+            *len = l.0;
+            result::Z_OK
+        }
+        Err(e) => {
+            crate::report_error!(
+                zc_error_kind_t::DESERIALIZE,
+                "Failed to read the map length: {}",
+                e
+            );
+            *len = 0;
+            result::Z_EDESERIALIZE
+        }
+    }
+}
+
+/// @brief Deserializes the presence flag of an optional value serialized with `ze_serializer_serialize_option()`.
+///
+/// If the value is present, it should be deserialized right after this call.
+/// @param this_: A deserializer instance.
+/// @param is_present: Pointer where ``true`` will be written if the value is present, ``false`` otherwise.
+/// @return 0 in case of success, negative error code otherwise.
+#[no_mangle]
+pub extern "C" fn ze_deserializer_deserialize_option(
+    this: &mut ze_deserializer_t,
+    is_present: &mut bool,
+) -> z_result_t {
+    ze_deserializer_deserialize_arithmetic::<bool>(this, is_present)
+}
+
+fn ze_serializer_serialize_array<T>(
+    this: &mut ze_loaned_serializer_t,
+    data: *const T,
+    len: usize,
+) -> z_result_t
+where
+    T: Serialize,
+{
+    if data.is_null() && len > 0 {
+        crate::report_error!(
+            zc_error_kind_t::INVALID_ARGUMENT,
+            "Array data should not be NULL"
+        );
+        return result::Z_EINVAL;
+    }
+    let slice: &[T] = if len == 0 {
+        &[]
+    } else {
+        unsafe { from_raw_parts(data, len) }
+    };
+    this.as_rust_type_mut().serialize(slice)
+}
+
+fn ze_deserializer_deserialize_array<T>(
+    this: &mut ze_deserializer_t,
+    dst: *mut T,
+    capacity: usize,
+    len: &mut usize,
+) -> z_result_t
+where
+    T: Deserialize + SerializedSize,
+{
+    // Deserializing from a copy, so that `this` is left untouched in case of failure.
+    let mut deserializer = *this;
+    let d = deserializer.as_rust_type_mut();
+    let n = match d.deserialize::<VarInt<usize>>() {
+        Ok(n) => n.0,
+        Err(e) => {
+            crate::report_error!(
+                zc_error_kind_t::DESERIALIZE,
+                "Failed to read the array length: {}",
+                e
+            );
+            *len = 0;
+            return result::Z_EDESERIALIZE;
+        }
+    };
+    *len = n;
+    if n > capacity {
+        crate::report_error!(
+            zc_error_kind_t::INVALID_ARGUMENT,
+            "Array of {} elements does not fit in a buffer of {} elements",
+            n,
+            capacity
+        );
+        return result::Z_EINVAL;
+    }
+    if n == 0 {
+        *this = deserializer;
+        return result::Z_OK;
+    }
+    if dst.is_null() {
+        crate::report_error!(
+            zc_error_kind_t::INVALID_ARGUMENT,
+            "Array buffer should not be NULL"
+        );
+        return result::Z_EINVAL;
+    }
+    let out = unsafe { std::slice::from_raw_parts_mut(dst, n) };
+    if let Err(e) = d.deserialize_n(out) {
+        crate::report_error!(
+            zc_error_kind_t::DESERIALIZE,
+            "Failed to deserialize the array: {}",
+            e
+        );
+        return result::Z_EDESERIALIZE;
+    }
+    *this = deserializer;
+    result::Z_OK
+}
+
+/// @brief Serializes an array of unsigned integers, compatible with the serialization of Rust `Vec<u8>` or C++ `std::vector<uint8_t>`.
+/// @param this_: A serializer instance.
+/// @param data: A pointer to the first element of the array.
+/// @param len: Number of elements of the array.
+/// @return 0 in case of success, negative error code otherwise.
+#[no_mangle]
+pub extern "C" fn ze_serializer_serialize_uint8_array(
+    this: &mut ze_loaned_serializer_t,
+    data: *const u8,
+    len: usize,
+) -> z_result_t {
+    ze_serializer_serialize_array::<u8>(this, data, len)
+}
+
+/// @brief Serializes an array of unsigned integers, compatible with the serialization of Rust `Vec<u16>` or C++ `std::vector<uint16_t>`.
+/// @param this_: A serializer instance.
+/// @param data: A pointer to the first element of the array.
+/// @param len: Number of elements of the array.
+/// @return 0 in case of success, negative error code otherwise.
+#[no_mangle]
+pub extern "C" fn ze_serializer_serialize_uint16_array(
+    this: &mut ze_loaned_serializer_t,
+    data: *const u16,
+    len: usize,
+) -> z_result_t {
+    ze_serializer_serialize_array::<u16>(this, data, len)
+}
+
+/// @brief Serializes an array of unsigned integers, compatible with the serialization of Rust `Vec<u32>` or C++ `std::vector<uint32_t>`.
+/// @param this_: A serializer instance.
+/// @param data: A pointer to the first element of the array.
+/// @param len: Number of elements of the array.
+/// @return 0 in case of success, negative error code otherwise.
+#[no_mangle]
+pub extern "C" fn ze_serializer_serialize_uint32_array(
+    this: &mut ze_loaned_serializer_t,
+    data: *const u32,
+    len: usize,
+) -> z_result_t {
+    ze_serializer_serialize_array::<u32>(this, data, len)
+}
+
+/// @brief Serializes an array of unsigned integers, compatible with the serialization of Rust `Vec<u64>` or C++ `std::vector<uint64_t>`.
+/// @param this_: A serializer instance.
+/// @param data: A pointer to the first element of the array.
+/// @param len: Number of elements of the array.
+/// @return 0 in case of success, negative error code otherwise.
+#[no_mangle]
+pub extern "C" fn ze_serializer_serialize_uint64_array(
+    this: &mut ze_loaned_serializer_t,
+    data: *const u64,
+    len: usize,
+) -> z_result_t {
+    ze_serializer_serialize_array::<u64>(this, data, len)
+}
+
+/// @brief Serializes an array of signed integers, compatible with the serialization of Rust `Vec<i8>` or C++ `std::vector<int8_t>`.
+/// @param this_: A serializer instance.
+/// @param data: A pointer to the first element of the array.
+/// @param len: Number of elements of the array.
+/// @return 0 in case of success, negative error code otherwise.
+#[no_mangle]
+pub extern "C" fn ze_serializer_serialize_int8_array(
+    this: &mut ze_loaned_serializer_t,
+    data: *const i8,
+    len: usize,
+) -> z_result_t {
+    ze_serializer_serialize_array::<i8>(this, data, len)
+}
+
+/// @brief Serializes an array of signed integers, compatible with the serialization of Rust `Vec<i16>` or C++ `std::vector<int16_t>`.
+/// @param this_: A serializer instance.
+/// @param data: A pointer to the first element of the array.
+/// @param len: Number of elements of the array.
+/// @return 0 in case of success, negative error code otherwise.
+#[no_mangle]
+pub extern "C" fn ze_serializer_serialize_int16_array(
+    this: &mut ze_loaned_serializer_t,
+    data: *const i16,
+    len: usize,
+) -> z_result_t {
+    ze_serializer_serialize_array::<i16>(this, data, len)
+}
+
+/// @brief Serializes an array of signed integers, compatible with the serialization of Rust `Vec<i32>` or C++ `std::vector<int32_t>`.
+/// @param this_: A serializer instance.
+/// @param data: A pointer to the first element of the array.
+/// @param len: Number of elements of the array.
+/// @return 0 in case of success, negative error code otherwise.
+#[no_mangle]
+pub extern "C" fn ze_serializer_serialize_int32_array(
+    this: &mut ze_loaned_serializer_t,
+    data: *const i32,
+    len: usize,
+) -> z_result_t {
+    ze_serializer_serialize_array::<i32>(this, data, len)
+}
+
+/// @brief Serializes an array of signed integers, compatible with the serialization of Rust `Vec<i64>` or C++ `std::vector<int64_t>`.
+/// @param this_: A serializer instance.
+/// @param data: A pointer to the first element of the array.
+/// @param len: Number of elements of the array.
+/// @return 0 in case of success, negative error code otherwise.
+#[no_mangle]
+pub extern "C" fn ze_serializer_serialize_int64_array(
+    this: &mut ze_loaned_serializer_t,
+    data: *const i64,
+    len: usize,
+) -> z_result_t {
+    ze_serializer_serialize_array::<i64>(this, data, len)
+}
+
+/// @brief Serializes an array of floats, compatible with the serialization of Rust `Vec<f32>` or C++ `std::vector<float>`.
+/// @param this_: A serializer instance.
+/// @param data: A pointer to the first element of the array.
+/// @param len: Number of elements of the array.
+/// @return 0 in case of success, negative error code otherwise.
+#[no_mangle]
+pub extern "C" fn ze_serializer_serialize_float_array(
+    this: &mut ze_loaned_serializer_t,
+    data: *const f32,
+    len: usize,
+) -> z_result_t {
+    ze_serializer_serialize_array::<f32>(this, data, len)
+}
+
+/// @brief Serializes an array of doubles, compatible with the serialization of Rust `Vec<f64>` or C++ `std::vector<double>`.
+/// @param this_: A serializer instance.
+/// @param data: A pointer to the first element of the array.
+/// @param len: Number of elements of the array.
+/// @return 0 in case of success, negative error code otherwise.
+#[no_mangle]
+pub extern "C" fn ze_serializer_serialize_double_array(
+    this: &mut ze_loaned_serializer_t,
+    data: *const f64,
+    len: usize,
+) -> z_result_t {
+    ze_serializer_serialize_array::<f64>(this, data, len)
+}
+
+/// @brief Deserializes an array of unsigned integers into a buffer.
+/// @param this_: A deserializer instance.
+/// @param dst: A pointer to a buffer of `capacity` elements where the array will be written.
+/// @param capacity: The number of elements of the buffer.
+/// @param len: Pointer where the number of elements of the array will be written, even if they do not fit in the buffer.
+/// @return 0 in case of success, negative error code otherwise. If the array does not fit in the buffer,
+/// `Z_EINVAL` is returned and the deserializer is not advanced, so that deserialization can be retried with a larger buffer.
+#[no_mangle]
+pub extern "C" fn ze_deserializer_deserialize_uint8_array(
+    this: &mut ze_deserializer_t,
+    dst: *mut u8,
+    capacity: usize,
+    len: &mut usize,
+) -> z_result_t {
+    ze_deserializer_deserialize_array::<u8>(this, dst, capacity, len)
+}
+
+/// @brief Deserializes an array of unsigned integers into a buffer.
+/// @param this_: A deserializer instance.
+/// @param dst: A pointer to a buffer of `capacity` elements where the array will be written.
+/// @param capacity: The number of elements of the buffer.
+/// @param len: Pointer where the number of elements of the array will be written, even if they do not fit in the buffer.
+/// @return 0 in case of success, negative error code otherwise. If the array does not fit in the buffer,
+/// `Z_EINVAL` is returned and the deserializer is not advanced, so that deserialization can be retried with a larger buffer.
+#[no_mangle]
+pub extern "C" fn ze_deserializer_deserialize_uint16_array(
+    this: &mut ze_deserializer_t,
+    dst: *mut u16,
+    capacity: usize,
+    len: &mut usize,
+) -> z_result_t {
+    ze_deserializer_deserialize_array::<u16>(this, dst, capacity, len)
+}
+
+/// @brief Deserializes an array of unsigned integers into a buffer.
+/// @param this_: A deserializer instance.
+/// @param dst: A pointer to a buffer of `capacity` elements where the array will be written.
+/// @param capacity: The number of elements of the buffer.
+/// @param len: Pointer where the number of elements of the array will be written, even if they do not fit in the buffer.
+/// @return 0 in case of success, negative error code otherwise. If the array does not fit in the buffer,
+/// `Z_EINVAL` is returned and the deserializer is not advanced, so that deserialization can be retried with a larger buffer.
+#[no_mangle]
+pub extern "C" fn ze_deserializer_deserialize_uint32_array(
+    this: &mut ze_deserializer_t,
+    dst: *mut u32,
+    capacity: usize,
+    len: &mut usize,
+) -> z_result_t {
+    ze_deserializer_deserialize_array::<u32>(this, dst, capacity, len)
+}
+
+/// @brief Deserializes an array of unsigned integers into a buffer.
+/// @param this_: A deserializer instance.
+/// @param dst: A pointer to a buffer of `capacity` elements where the array will be written.
+/// @param capacity: The number of elements of the buffer.
+/// @param len: Pointer where the number of elements of the array will be written, even if they do not fit in the buffer.
+/// @return 0 in case of success, negative error code otherwise. If the array does not fit in the buffer,
+/// `Z_EINVAL` is returned and the deserializer is not advanced, so that deserialization can be retried with a larger buffer.
+#[no_mangle]
+pub extern "C" fn ze_deserializer_deserialize_uint64_array(
+    this: &mut ze_deserializer_t,
+    dst: *mut u64,
+    capacity: usize,
+    len: &mut usize,
+) -> z_result_t {
+    ze_deserializer_deserialize_array::<u64>(this, dst, capacity, len)
+}
+
+/// @brief Deserializes an array of signed integers into a buffer.
+/// @param this_: A deserializer instance.
+/// @param dst: A pointer to a buffer of `capacity` elements where the array will be written.
+/// @param capacity: The number of elements of the buffer.
+/// @param len: Pointer where the number of elements of the array will be written, even if they do not fit in the buffer.
+/// @return 0 in case of success, negative error code otherwise. If the array does not fit in the buffer,
+/// `Z_EINVAL` is returned and the deserializer is not advanced, so that deserialization can be retried with a larger buffer.
+#[no_mangle]
+pub extern "C" fn ze_deserializer_deserialize_int8_array(
+    this: &mut ze_deserializer_t,
+    dst: *mut i8,
+    capacity: usize,
+    len: &mut usize,
+) -> z_result_t {
+    ze_deserializer_deserialize_array::<i8>(this, dst, capacity, len)
+}
+
+/// @brief Deserializes an array of signed integers into a buffer.
+/// @param this_: A deserializer instance.
+/// @param dst: A pointer to a buffer of `capacity` elements where the array will be written.
+/// @param capacity: The number of elements of the buffer.
+/// @param len: Pointer where the number of elements of the array will be written, even if they do not fit in the buffer.
+/// @return 0 in case of success, negative error code otherwise. If the array does not fit in the buffer,
+/// `Z_EINVAL` is returned and the deserializer is not advanced, so that deserialization can be retried with a larger buffer.
+#[no_mangle]
+pub extern "C" fn ze_deserializer_deserialize_int16_array(
+    this: &mut ze_deserializer_t,
+    dst: *mut i16,
+    capacity: usize,
+    len: &mut usize,
+) -> z_result_t {
+    ze_deserializer_deserialize_array::<i16>(this, dst, capacity, len)
+}
+
+/// @brief Deserializes an array of signed integers into a buffer.
+/// @param this_: A deserializer instance.
+/// @param dst: A pointer to a buffer of `capacity` elements where the array will be written.
+/// @param capacity: The number of elements of the buffer.
+/// @param len: Pointer where the number of elements of the array will be written, even if they do not fit in the buffer.
+/// @return 0 in case of success, negative error code otherwise. If the array does not fit in the buffer,
+/// `Z_EINVAL` is returned and the deserializer is not advanced, so that deserialization can be retried with a larger buffer.
+#[no_mangle]
+pub extern "C" fn ze_deserializer_deserialize_int32_array(
+    this: &mut ze_deserializer_t,
+    dst: *mut i32,
+    capacity: usize,
+    len: &mut usize,
+) -> z_result_t {
+    ze_deserializer_deserialize_array::<i32>(this, dst, capacity, len)
+}
+
+/// @brief Deserializes an array of signed integers into a buffer.
+/// @param this_: A deserializer instance.
+/// @param dst: A pointer to a buffer of `capacity` elements where the array will be written.
+/// @param capacity: The number of elements of the buffer.
+/// @param len: Pointer where the number of elements of the array will be written, even if they do not fit in the buffer.
+/// @return 0 in case of success, negative error code otherwise. If the array does not fit in the buffer,
+/// `Z_EINVAL` is returned and the deserializer is not advanced, so that deserialization can be retried with a larger buffer.
+#[no_mangle]
+pub extern "C" fn ze_deserializer_deserialize_int64_array(
+    this: &mut ze_deserializer_t,
+    dst: *mut i64,
+    capacity: usize,
+    len: &mut usize,
+) -> z_result_t {
+    ze_deserializer_deserialize_array::<i64>(this, dst, capacity, len)
+}
+
+/// @brief Deserializes an array of floats into a buffer.
+/// @param this_: A deserializer instance.
+/// @param dst: A pointer to a buffer of `capacity` elements where the array will be written.
+/// @param capacity: The number of elements of the buffer.
+/// @param len: Pointer where the number of elements of the array will be written, even if they do not fit in the buffer.
+/// @return 0 in case of success, negative error code otherwise. If the array does not fit in the buffer,
+/// `Z_EINVAL` is returned and the deserializer is not advanced, so that deserialization can be retried with a larger buffer.
+#[no_mangle]
+pub extern "C" fn ze_deserializer_deserialize_float_array(
+    this: &mut ze_deserializer_t,
+    dst: *mut f32,
+    capacity: usize,
+    len: &mut usize,
+) -> z_result_t {
+    ze_deserializer_deserialize_array::<f32>(this, dst, capacity, len)
+}
+
+/// @brief Deserializes an array of doubles into a buffer.
+/// @param this_: A deserializer instance.
+/// @param dst: A pointer to a buffer of `capacity` elements where the array will be written.
+/// @param capacity: The number of elements of the buffer.
+/// @param len: Pointer where the number of elements of the array will be written, even if they do not fit in the buffer.
+/// @return 0 in case of success, negative error code otherwise. If the array does not fit in the buffer,
+/// `Z_EINVAL` is returned and the deserializer is not advanced, so that deserialization can be retried with a larger buffer.
+#[no_mangle]
+pub extern "C" fn ze_deserializer_deserialize_double_array(
+    this: &mut ze_deserializer_t,
+    dst: *mut f64,
+    capacity: usize,
+    len: &mut usize,
+) -> z_result_t {
+    ze_deserializer_deserialize_array::<f64>(this, dst, capacity, len)
+}
+
+/// @brief Deserializes a slice without copying it, constructing a view into the deserialized data.
+///
+/// The view remains valid as long as the bytes the deserializer was constructed from.
+/// No memory is allocated for the view, but the deserializer still reads through the viewed bytes to advance past them.
+/// @param this_: A deserializer instance.
+/// @param view: An uninitialized memory location where the view will be constructed.
+/// @return 0 in case of success, negative error code otherwise. If the data is not contiguous
+/// (see `z_bytes_get_contiguous_view()`), `Z_EINVAL` is returned and the deserializer is not advanced,
+/// so that the slice can still be deserialized by copy with `ze_deserializer_deserialize_slice()`.
+#[no_mangle]
+pub extern "C" fn ze_deserializer_deserialize_slice_view(
+    this: &mut ze_deserializer_t,
+    view: &mut MaybeUninit<z_view_slice_t>,
+) -> z_result_t {
+    let mut deserializer = *this;
+    match deserializer.as_rust_type_mut().deserialize_view() {
+        Ok(s) => {
+            view.as_rust_type_mut_uninit()
+                .write(CSliceView::new_borrowed_from_slice(s));
+            *this = deserializer;
+            result::Z_OK
+        }
+        Err(r) => {
+            view.as_rust_type_mut_uninit()
+                .write(CSliceView::gravestone());
+            r
+        }
+    }
+}
+
+/// @brief Deserializes a string without copying it, constructing a view into the deserialized data.
+///
+/// The view remains valid as long as the bytes the deserializer was constructed from.
+/// No memory is allocated for the view, but the deserializer still reads through the viewed bytes to advance past them.
+/// @param this_: A deserializer instance.
+/// @param view: An uninitialized memory location where the view will be constructed.
+/// @return 0 in case of success, negative error code otherwise. If the data is not contiguous
+/// (see `z_bytes_get_contiguous_view()`), `Z_EINVAL` is returned and the deserializer is not advanced,
+/// so that the string can still be deserialized by copy with `ze_deserializer_deserialize_string()`.
+#[no_mangle]
+pub extern "C" fn ze_deserializer_deserialize_string_view(
+    this: &mut ze_deserializer_t,
+    view: &mut MaybeUninit<z_view_string_t>,
+) -> z_result_t {
+    let mut deserializer = *this;
+    let r = match deserializer.as_rust_type_mut().deserialize_view() {
+        Ok(s) => match str::from_utf8(s) {
+            Ok(_) => {
+                view.as_rust_type_mut_uninit()
+                    .write(CStringView::new_borrowed_from_slice(s));
+                *this = deserializer;
+                return result::Z_OK;
+            }
+            Err(e) => {
+                crate::report_error!(
+                    zc_error_kind_t::DESERIALIZE,
+                    "Failed to deserialize the payload: {}",
+                    e
+                );
+                result::Z_EUTF8
+            }
+        },
+        Err(r) => r,
+    };
+    view.as_rust_type_mut_uninit()
+        .write(CStringView::gravestone());
+    r
+}
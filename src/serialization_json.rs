@@ -0,0 +1,725 @@
+//
+// Copyright (c) 2017, 2024 ZettaScale Technology.
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ZettaScale Zenoh team, <zenoh@zettascale.tech>
+//
+
+use std::{collections::HashSet, fmt, mem::MaybeUninit};
+
+use libc::c_char;
+use serde::{
+    de::{self, MapAccess, SeqAccess, Visitor},
+    Deserialize, Deserializer,
+};
+use serde_json::{Number, Value};
+use zenoh::bytes::ZBytes;
+use zenoh_ext::{VarInt, ZSerializer};
+
+use crate::{
+    result::{self, z_result_t},
+    schema::{Schema, SchemaDeserializer, SchemaError, SchemaType},
+    str_arg, strlen_or_zero,
+    transmute::{Gravestone, RustTypeRef, RustTypeRefUninit},
+    z_loaned_bytes_t, z_owned_bytes_t, z_owned_string_t, ze_loaned_schema_t, ze_schema_type_t,
+    CStringOwned,
+};
+
+/// The type of a zenoh-ext serialized value, as described by a type hint or a schema.
+#[derive(Debug)]
+enum JsonType {
+    Primitive(ze_schema_type_t),
+    String,
+    Sequence(Box<JsonType>),
+    /// Serialized as a sequence of key-value tuples.
+    Map(Box<JsonType>, Box<JsonType>),
+    Tuple(Vec<JsonType>),
+    /// A tuple whose elements are named, converted to a JSON object.
+    Struct(Vec<(String, JsonType)>),
+    /// Serialized as a presence flag followed by the value, if any.
+    Option(Box<JsonType>),
+}
+
+impl From<&SchemaType> for JsonType {
+    fn from(ty: &SchemaType) -> Self {
+        match ty {
+            SchemaType::Primitive(kind) => JsonType::Primitive(*kind),
+            SchemaType::String => JsonType::String,
+            SchemaType::Struct(schema) => schema.as_ref().into(),
+            SchemaType::Sequence(element) => JsonType::Sequence(Box::new(element.as_ref().into())),
+            SchemaType::Map(entry) => JsonType::Map(
+                Box::new((&entry.fields[0].ty).into()),
+                Box::new((&entry.fields[1].ty).into()),
+            ),
+        }
+    }
+}
+
+impl From<&Schema> for JsonType {
+    fn from(schema: &Schema) -> Self {
+        JsonType::Struct(
+            schema
+                .fields
+                .iter()
+                .map(|f| (f.name.clone(), (&f.ty).into()))
+                .collect(),
+        )
+    }
+}
+
+/// A JSON value keeping the members of objects in order, unlike `serde_json::Value` which sorts them,
+/// so that maps are converted to and from JSON without reordering their entries.
+#[derive(Debug)]
+enum Json {
+    Null,
+    Bool(bool),
+    Number(Number),
+    String(String),
+    Array(Vec<Json>),
+    Object(Vec<(String, Json)>),
+}
+
+impl fmt::Display for Json {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Json::Null => f.write_str("null"),
+            Json::Bool(b) => write!(f, "{}", b),
+            Json::Number(n) => write!(f, "{}", n),
+            Json::String(s) => write!(f, "{}", Value::from(s.as_str())),
+            Json::Array(values) => {
+                f.write_str("[")?;
+                for (i, v) in values.iter().enumerate() {
+                    if i > 0 {
+                        f.write_str(",")?;
+                    }
+                    write!(f, "{}", v)?;
+                }
+                f.write_str("]")
+            }
+            Json::Object(members) => {
+                f.write_str("{")?;
+                for (i, (k, v)) in members.iter().enumerate() {
+                    if i > 0 {
+                        f.write_str(",")?;
+                    }
+                    write!(f, "{}:{}", Value::from(k.as_str()), v)?;
+                }
+                f.write_str("}")
+            }
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for Json {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserializer.deserialize_any(JsonVisitor)
+    }
+}
+
+struct JsonVisitor;
+
+impl<'de> Visitor<'de> for JsonVisitor {
+    type Value = Json;
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("a JSON value")
+    }
+
+    fn visit_unit<E: de::Error>(self) -> Result<Json, E> {
+        Ok(Json::Null)
+    }
+
+    fn visit_bool<E: de::Error>(self, v: bool) -> Result<Json, E> {
+        Ok(Json::Bool(v))
+    }
+
+    fn visit_i64<E: de::Error>(self, v: i64) -> Result<Json, E> {
+        Ok(Json::Number(v.into()))
+    }
+
+    fn visit_u64<E: de::Error>(self, v: u64) -> Result<Json, E> {
+        Ok(Json::Number(v.into()))
+    }
+
+    fn visit_f64<E: de::Error>(self, v: f64) -> Result<Json, E> {
+        Number::from_f64(v)
+            .map(Json::Number)
+            .ok_or_else(|| E::custom(format!("{} cannot be represented in JSON", v)))
+    }
+
+    fn visit_str<E: de::Error>(self, v: &str) -> Result<Json, E> {
+        Ok(Json::String(v.to_string()))
+    }
+
+    fn visit_string<E: de::Error>(self, v: String) -> Result<Json, E> {
+        Ok(Json::String(v))
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Json, A::Error> {
+        let mut values = Vec::new();
+        while let Some(v) = seq.next_element()? {
+            values.push(v);
+        }
+        Ok(Json::Array(values))
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Json, A::Error> {
+        let mut members = Vec::new();
+        while let Some(member) = map.next_entry()? {
+            members.push(member);
+        }
+        Ok(Json::Object(members))
+    }
+}
+
+/// Maximal nesting depth of type hints, as for JSON values.
+const MAX_TYPE_HINT_DEPTH: usize = 128;
+
+/// Parser of type hints such as `(u32, string, [f64])`.
+///
+/// Supported types are `u8`, `u16`, `u32`, `u64`, `i8`, `i16`, `i32`, `i64`, `f32`, `f64`, `bool`, `string`,
+/// sequences `[T]`, maps `{K: V}`, tuples `(T1, T2, ...)` and options `option<T>`.
+struct TypeHintParser<'a> {
+    hint: &'a str,
+    position: usize,
+    /// Number of types being parsed, bounded by [`MAX_TYPE_HINT_DEPTH`] so that deeply nested hints do not overflow the stack.
+    depth: usize,
+}
+
+impl<'a> TypeHintParser<'a> {
+    fn parse(hint: &'a str) -> Result<JsonType, String> {
+        let mut parser = TypeHintParser {
+            hint,
+            position: 0,
+            depth: 0,
+        };
+        let ty = parser.parse_type()?;
+        parser.skip_whitespace();
+        if parser.position != hint.len() {
+            return Err(parser.error("unexpected trailing characters"));
+        }
+        Ok(ty)
+    }
+
+    fn error(&self, message: &str) -> String {
+        format!("{} at position {}", message, self.position)
+    }
+
+    fn skip_whitespace(&mut self) {
+        let rest = &self.hint[self.position..];
+        self.position += rest.len() - rest.trim_start().len();
+    }
+
+    /// Consumes `c` if it is the next non-whitespace character.
+    fn eat(&mut self, c: char) -> bool {
+        self.skip_whitespace();
+        if self.hint[self.position..].starts_with(c) {
+            self.position += c.len_utf8();
+            true
+        } else {
+            false
+        }
+    }
+
+    fn expect(&mut self, c: char) -> Result<(), String> {
+        if self.eat(c) {
+            Ok(())
+        } else {
+            Err(self.error(&format!("expected '{}'", c)))
+        }
+    }
+
+    fn parse_type(&mut self) -> Result<JsonType, String> {
+        if self.depth == MAX_TYPE_HINT_DEPTH {
+            return Err(self.error("type nested too deeply"));
+        }
+        self.depth += 1;
+        let ty = self.parse_nested_type();
+        self.depth -= 1;
+        ty
+    }
+
+    fn parse_nested_type(&mut self) -> Result<JsonType, String> {
+        if self.eat('[') {
+            let element = self.parse_type()?;
+            self.expect(']')?;
+            return Ok(JsonType::Sequence(Box::new(element)));
+        }
+        if self.eat('{') {
+            let key = self.parse_type()?;
+            self.expect(':')?;
+            let value = self.parse_type()?;
+            self.expect('}')?;
+            return Ok(JsonType::Map(Box::new(key), Box::new(value)));
+        }
+        if self.eat('(') {
+            let mut elements = Vec::new();
+            while !self.eat(')') {
+                if !elements.is_empty() {
+                    self.expect(',')?;
+                    // Allowing a trailing comma.
+                    if self.eat(')') {
+                        break;
+                    }
+                }
+                elements.push(self.parse_type()?);
+            }
+            return Ok(JsonType::Tuple(elements));
+        }
+        let rest = &self.hint[self.position..];
+        let len = rest
+            .find(|c: char| !(c.is_ascii_alphanumeric() || c == '_'))
+            .unwrap_or(rest.len());
+        let ty = match &rest[..len] {
+            "u8" => JsonType::Primitive(ze_schema_type_t::UINT8),
+            "u16" => JsonType::Primitive(ze_schema_type_t::UINT16),
+            "u32" => JsonType::Primitive(ze_schema_type_t::UINT32),
+            "u64" => JsonType::Primitive(ze_schema_type_t::UINT64),
+            "i8" => JsonType::Primitive(ze_schema_type_t::INT8),
+            "i16" => JsonType::Primitive(ze_schema_type_t::INT16),
+            "i32" => JsonType::Primitive(ze_schema_type_t::INT32),
+            "i64" => JsonType::Primitive(ze_schema_type_t::INT64),
+            "f32" => JsonType::Primitive(ze_schema_type_t::FLOAT),
+            "f64" => JsonType::Primitive(ze_schema_type_t::DOUBLE),
+            "bool" => JsonType::Primitive(ze_schema_type_t::BOOL),
+            "string" => JsonType::String,
+            "option" => {
+                self.position += len;
+                self.expect('<')?;
+                let value = self.parse_type()?;
+                self.expect('>')?;
+                return Ok(JsonType::Option(Box::new(value)));
+            }
+            "" => return Err(self.error("expected a type")),
+            name => return Err(self.error(&format!("unknown type '{}'", name))),
+        };
+        self.position += len;
+        Ok(ty)
+    }
+}
+
+fn float_to_json(v: f64, display: String) -> Result<Json, SchemaError> {
+    // Going through the shortest representation of `f32` values, so that `0.1f32` is not shown as `0.10000000149011612`.
+    match display.parse::<f64>().ok().and_then(Number::from_f64) {
+        Some(n) => Ok(Json::Number(n)),
+        None => Err(SchemaError::new(format!(
+            "{} cannot be represented in JSON",
+            v
+        ))),
+    }
+}
+
+/// Decodes the next value of the payload into JSON.
+fn decode(deserializer: &mut SchemaDeserializer, ty: &JsonType) -> Result<Json, SchemaError> {
+    Ok(match ty {
+        JsonType::Primitive(kind) => match kind {
+            ze_schema_type_t::UINT8 => Json::Number(deserializer.deserialize::<u8>()?.into()),
+            ze_schema_type_t::UINT16 => Json::Number(deserializer.deserialize::<u16>()?.into()),
+            ze_schema_type_t::UINT32 => Json::Number(deserializer.deserialize::<u32>()?.into()),
+            ze_schema_type_t::UINT64 => Json::Number(deserializer.deserialize::<u64>()?.into()),
+            ze_schema_type_t::INT8 => Json::Number(deserializer.deserialize::<i8>()?.into()),
+            ze_schema_type_t::INT16 => Json::Number(deserializer.deserialize::<i16>()?.into()),
+            ze_schema_type_t::INT32 => Json::Number(deserializer.deserialize::<i32>()?.into()),
+            ze_schema_type_t::INT64 => Json::Number(deserializer.deserialize::<i64>()?.into()),
+            ze_schema_type_t::FLOAT => {
+                let v = deserializer.deserialize::<f32>()?;
+                float_to_json(v as f64, v.to_string())?
+            }
+            ze_schema_type_t::DOUBLE => {
+                let v = deserializer.deserialize::<f64>()?;
+                float_to_json(v, v.to_string())?
+            }
+            ze_schema_type_t::BOOL => Json::Bool(deserializer.deserialize::<bool>()?),
+            _ => unreachable!("non-primitive schema types are not stored as primitives"),
+        },
+        JsonType::String => Json::String(deserializer.deserialize::<String>()?),
+        JsonType::Sequence(element) => {
+            let len = deserializer.deserialize_len()?;
+            let mut values = Vec::new();
+            for i in 0..len {
+                values.push(decode(deserializer, element).map_err(|e| e.in_element(i))?);
+            }
+            Json::Array(values)
+        }
+        JsonType::Map(key, value) => {
+            let len = deserializer.deserialize_len()?;
+            let mut entries = Vec::new();
+            for i in 0..len {
+                let k = decode(deserializer, key).map_err(|e| e.in_element(i))?;
+                let v = decode(deserializer, value).map_err(|e| e.in_element(i))?;
+                entries.push((k, v));
+            }
+            // Converting the map into an object if its keys are unique strings, so that no entry is lost,
+            // or into an array of pairs otherwise.
+            let unique_string_keys = {
+                let mut keys = HashSet::new();
+                entries
+                    .iter()
+                    .all(|(k, _)| matches!(k, Json::String(k) if keys.insert(k)))
+            };
+            if unique_string_keys {
+                Json::Object(
+                    entries
+                        .into_iter()
+                        .map(|(k, v)| match k {
+                            Json::String(k) => (k, v),
+                            _ => unreachable!("keys are checked to be strings"),
+                        })
+                        .collect(),
+                )
+            } else {
+                Json::Array(
+                    entries
+                        .into_iter()
+                        .map(|(k, v)| Json::Array(vec![k, v]))
+                        .collect(),
+                )
+            }
+        }
+        JsonType::Tuple(elements) => {
+            let mut values = Vec::new();
+            for (i, element) in elements.iter().enumerate() {
+                values.push(decode(deserializer, element).map_err(|e| e.in_element(i))?);
+            }
+            Json::Array(values)
+        }
+        JsonType::Struct(fields) => {
+            let mut members = Vec::new();
+            for (name, field) in fields {
+                let v = decode(deserializer, field).map_err(|e| e.in_field(name))?;
+                members.push((name.clone(), v));
+            }
+            Json::Object(members)
+        }
+        JsonType::Option(value) => {
+            if deserializer.deserialize::<bool>()? {
+                decode(deserializer, value)?
+            } else {
+                Json::Null
+            }
+        }
+    })
+}
+
+fn decode_payload(payload: &ZBytes, ty: &JsonType) -> Result<String, SchemaError> {
+    let mut deserializer = SchemaDeserializer::new(payload);
+    let value = decode(&mut deserializer, ty)?;
+    if !deserializer.done() {
+        return Err(SchemaError::new("unexpected trailing data"));
+    }
+    Ok(value.to_string())
+}
+
+fn mismatch(name: &str, value: &Json) -> SchemaError {
+    SchemaError::new(format!("expected {}, found {}", name, value))
+}
+
+fn integer<T: TryFrom<i128>>(value: &Json, name: &str) -> Result<T, SchemaError> {
+    match value {
+        Json::Number(n) => n
+            .as_u64()
+            .map(i128::from)
+            .or_else(|| n.as_i64().map(i128::from))
+            .and_then(|v| T::try_from(v).ok()),
+        _ => None,
+    }
+    .ok_or_else(|| mismatch(name, value))
+}
+
+fn float(value: &Json, name: &str) -> Result<f64, SchemaError> {
+    match value {
+        Json::Number(n) => n.as_f64(),
+        _ => None,
+    }
+    .ok_or_else(|| mismatch(name, value))
+}
+
+fn array<'a>(value: &'a Json, name: &str) -> Result<&'a Vec<Json>, SchemaError> {
+    match value {
+        Json::Array(values) => Ok(values),
+        _ => Err(mismatch(name, value)),
+    }
+}
+
+/// Encodes a JSON value as a value of type `ty`.
+fn encode(serializer: &mut ZSerializer, ty: &JsonType, value: &Json) -> Result<(), SchemaError> {
+    match ty {
+        JsonType::Primitive(kind) => match kind {
+            ze_schema_type_t::UINT8 => serializer.serialize(integer::<u8>(value, "a u8")?),
+            ze_schema_type_t::UINT16 => serializer.serialize(integer::<u16>(value, "a u16")?),
+            ze_schema_type_t::UINT32 => serializer.serialize(integer::<u32>(value, "a u32")?),
+            ze_schema_type_t::UINT64 => serializer.serialize(integer::<u64>(value, "a u64")?),
+            ze_schema_type_t::INT8 => serializer.serialize(integer::<i8>(value, "an i8")?),
+            ze_schema_type_t::INT16 => serializer.serialize(integer::<i16>(value, "an i16")?),
+            ze_schema_type_t::INT32 => serializer.serialize(integer::<i32>(value, "an i32")?),
+            ze_schema_type_t::INT64 => serializer.serialize(integer::<i64>(value, "an i64")?),
+            ze_schema_type_t::FLOAT => serializer.serialize(float(value, "an f32")? as f32),
+            ze_schema_type_t::DOUBLE => serializer.serialize(float(value, "an f64")?),
+            ze_schema_type_t::BOOL => match value {
+                Json::Bool(b) => serializer.serialize(*b),
+                _ => return Err(mismatch("a boolean", value)),
+            },
+            _ => unreachable!("non-primitive schema types are not stored as primitives"),
+        },
+        JsonType::String => match value {
+            Json::String(s) => serializer.serialize(s.as_str()),
+            _ => return Err(mismatch("a string", value)),
+        },
+        JsonType::Sequence(element) => {
+            let values = array(value, "an array")?;
+            serializer.serialize(VarInt::<usize>(values.len()));
+            for (i, v) in values.iter().enumerate() {
+                encode(serializer, element, v).map_err(|e| e.in_element(i))?;
+            }
+        }
+        JsonType::Map(key, element) => {
+            if let (JsonType::String, Json::Object(entries)) = (key.as_ref(), value) {
+                serializer.serialize(VarInt::<usize>(entries.len()));
+                for (k, v) in entries {
+                    serializer.serialize(k.as_str());
+                    encode(serializer, element, v).map_err(|e| e.in_field(k))?;
+                }
+            } else {
+                let entries = array(value, "an object or an array of key-value pairs")?;
+                serializer.serialize(VarInt::<usize>(entries.len()));
+                for (i, entry) in entries.iter().enumerate() {
+                    let (k, v) = match entry {
+                        Json::Array(pair) if pair.len() == 2 => (&pair[0], &pair[1]),
+                        _ => {
+                            return Err(SchemaError::new(format!(
+                                "expected a key-value pair, found {}",
+                                entry
+                            ))
+                            .in_element(i))
+                        }
+                    };
+                    encode(serializer, key, k).map_err(|e| e.in_element(i))?;
+                    encode(serializer, element, v).map_err(|e| e.in_element(i))?;
+                }
+            }
+        }
+        JsonType::Tuple(elements) => {
+            let values = array(value, "an array")?;
+            if values.len() != elements.len() {
+                return Err(SchemaError::new(format!(
+                    "expected an array of {} elements, found {}",
+                    elements.len(),
+                    values.len()
+                )));
+            }
+            for (i, (element, v)) in elements.iter().zip(values).enumerate() {
+                encode(serializer, element, v).map_err(|e| e.in_element(i))?;
+            }
+        }
+        JsonType::Struct(fields) => {
+            let Json::Object(members) = value else {
+                return Err(mismatch("an object", value));
+            };
+            let mut names = HashSet::new();
+            for (k, _) in members {
+                if !fields.iter().any(|(name, _)| name == k) {
+                    return Err(SchemaError::new(format!("unknown field '{}'", k)));
+                }
+                if !names.insert(k) {
+                    return Err(SchemaError::new(format!("duplicate field '{}'", k)));
+                }
+            }
+            for (name, field) in fields {
+                match members.iter().find(|(k, _)| k == name) {
+                    Some((_, v)) => encode(serializer, field, v).map_err(|e| e.in_field(name))?,
+                    None => return Err(SchemaError::new(format!("missing field '{}'", name))),
+                }
+            }
+        }
+        JsonType::Option(element) => {
+            if matches!(value, Json::Null) {
+                serializer.serialize(false);
+            } else {
+                serializer.serialize(true);
+                encode(serializer, element, value)?;
+            }
+        }
+    }
+    Ok(())
+}
+
+unsafe fn type_hint_arg(type_hint: *const c_char) -> Result<JsonType, z_result_t> {
+    let hint = str_arg(type_hint, strlen_or_zero(type_hint), "Type hint")?;
+    TypeHintParser::parse(hint).map_err(|e| {
+        crate::report_error!(
+            zc_error_kind_t::PARSE,
+            "Invalid type hint '{}': {}",
+            hint,
+            e
+        );
+        result::Z_EPARSE
+    })
+}
+
+fn to_json(
+    payload: &ZBytes,
+    ty: &JsonType,
+    json: &mut MaybeUninit<z_owned_string_t>,
+) -> z_result_t {
+    let json = json.as_rust_type_mut_uninit();
+    match decode_payload(payload, ty) {
+        Ok(s) => {
+            json.write(CStringOwned::from(s));
+            result::Z_OK
+        }
+        Err(e) => {
+            crate::report_error!(
+                zc_error_kind_t::DESERIALIZE,
+                "Payload does not match the expected type: {}",
+                e
+            );
+            json.write(CStringOwned::gravestone());
+            result::Z_EDESERIALIZE
+        }
+    }
+}
+
+unsafe fn from_json(
+    json: *const c_char,
+    ty: &JsonType,
+    bytes: &mut MaybeUninit<z_owned_bytes_t>,
+) -> z_result_t {
+    let bytes = bytes.as_rust_type_mut_uninit();
+    let json = match str_arg(json, strlen_or_zero(json), "JSON") {
+        Ok(json) => json,
+        Err(r) => {
+            bytes.write(ZBytes::new());
+            return r;
+        }
+    };
+    let value = match serde_json::from_str::<Json>(json) {
+        Ok(value) => value,
+        Err(e) => {
+            crate::report_error!(zc_error_kind_t::PARSE, "Invalid JSON: {}", e);
+            bytes.write(ZBytes::new());
+            return result::Z_EPARSE;
+        }
+    };
+    let mut serializer = ZSerializer::new();
+    match encode(&mut serializer, ty, &value) {
+        Ok(()) => {
+            bytes.write(serializer.finish());
+            result::Z_OK
+        }
+        Err(e) => {
+            crate::report_error!(
+                zc_error_kind_t::INVALID_ARGUMENT,
+                "JSON does not match the expected type: {}",
+                e
+            );
+            bytes.write(ZBytes::new());
+            result::Z_EINVAL
+        }
+    }
+}
+
+/// @warning This API has been marked as unstable: it works as advertised, but it may be changed in a future release.
+/// @brief Converts a zenoh-ext serialized payload into JSON, given a hint of its type.
+///
+/// A type hint is a Rust-like type description, such as `(u32, string, [f64])`. Supported types are
+/// `u8`, `u16`, `u32`, `u64`, `i8`, `i16`, `i32`, `i64`, `f32`, `f64`, `bool`, `string`, sequences `[T]`, maps `{K: V}`,
+/// tuples `(T1, T2, ...)` and options `option<T>`, nested up to 128 levels deep.
+/// Sequences and tuples are converted into JSON arrays, options into `null` or their value, and maps into JSON objects
+/// if their keys are unique strings, or into arrays of `[key, value]` pairs otherwise. Object keys are kept in order,
+/// so that converting the JSON back gives the same payload.
+///
+/// @param this_: The serialized payload.
+/// @param type_hint: A null-terminated type hint.
+/// @param json: An uninitialized memory location where the JSON string will be constructed.
+/// @return 0 in case of success, `Z_EPARSE` if the type hint is invalid, `Z_EDESERIALIZE` if the payload does not
+/// match it (in this case `json` will be empty).
+#[allow(clippy::missing_safety_doc)]
+#[no_mangle]
+pub unsafe extern "C" fn ze_deserialize_json(
+    this: &z_loaned_bytes_t,
+    type_hint: *const c_char,
+    json: &mut MaybeUninit<z_owned_string_t>,
+) -> z_result_t {
+    match type_hint_arg(type_hint) {
+        Ok(ty) => to_json(this.as_rust_type_ref(), &ty, json),
+        Err(r) => {
+            json.as_rust_type_mut_uninit()
+                .write(CStringOwned::gravestone());
+            r
+        }
+    }
+}
+
+/// @warning This API has been marked as unstable: it works as advertised, but it may be changed in a future release.
+/// @brief Serializes a JSON value, given a hint of its type.
+///
+/// This is the reverse of `ze_deserialize_json()`, using the same type hints and JSON representation. Maps with
+/// string keys may be given either as JSON objects or as arrays of `[key, value]` pairs.
+///
+/// @param this_: An uninitialized memory location where the serialized payload will be constructed.
+/// @param json: A null-terminated JSON string.
+/// @param type_hint: A null-terminated type hint.
+/// @return 0 in case of success, `Z_EPARSE` if the type hint or the JSON are invalid, `Z_EINVAL` if the JSON value does
+/// not match the type hint (in this case `this_` will be empty).
+#[allow(clippy::missing_safety_doc)]
+#[no_mangle]
+pub unsafe extern "C" fn ze_serialize_json(
+    this: &mut MaybeUninit<z_owned_bytes_t>,
+    json: *const c_char,
+    type_hint: *const c_char,
+) -> z_result_t {
+    match type_hint_arg(type_hint) {
+        Ok(ty) => from_json(json, &ty, this),
+        Err(r) => {
+            this.as_rust_type_mut_uninit().write(ZBytes::new());
+            r
+        }
+    }
+}
+
+/// @warning This API has been marked as unstable: it works as advertised, but it may be changed in a future release.
+/// @brief Converts a payload holding a structure described by the schema into a JSON object.
+///
+/// Fields are converted into members of the object named after them, with the same conversions as `ze_deserialize_json()`.
+///
+/// @param this_: The schema.
+/// @param bytes: The serialized payload.
+/// @param json: An uninitialized memory location where the JSON string will be constructed.
+/// @return 0 in case of success, `Z_EDESERIALIZE` if the payload does not match the schema (in this case `json` will be empty).
+#[no_mangle]
+pub extern "C" fn ze_schema_deserialize_json(
+    this_: &ze_loaned_schema_t,
+    bytes: &z_loaned_bytes_t,
+    json: &mut MaybeUninit<z_owned_string_t>,
+) -> z_result_t {
+    let ty = JsonType::from(this_.as_rust_type_ref());
+    to_json(bytes.as_rust_type_ref(), &ty, json)
+}
+
+/// @warning This API has been marked as unstable: it works as advertised, but it may be changed in a future release.
+/// @brief Serializes a JSON object as a structure described by the schema.
+///
+/// The object should have a member for each field of the schema, and no other.
+///
+/// @param this_: The schema.
+/// @param json: A null-terminated JSON string.
+/// @param bytes: An uninitialized memory location where the serialized payload will be constructed.
+/// @return 0 in case of success, `Z_EPARSE` if the JSON is invalid, `Z_EINVAL` if it does not match the schema
+/// (in this case `bytes` will be empty).
+#[allow(clippy::missing_safety_doc)]
+#[no_mangle]
+pub unsafe extern "C" fn ze_schema_serialize_json(
+    this_: &ze_loaned_schema_t,
+    json: *const c_char,
+    bytes: &mut MaybeUninit<z_owned_bytes_t>,
+) -> z_result_t {
+    let ty = JsonType::from(this_.as_rust_type_ref());
+    from_json(json, &ty, bytes)
+}
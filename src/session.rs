@@ -13,6 +13,8 @@
 //
 
 use std::mem::MaybeUninit;
+#[cfg(feature = "unstable")]
+use std::time::Duration;
 
 #[cfg(all(feature = "shared-memory", feature = "unstable"))]
 use zenoh::shm::ShmProviderState;
@@ -31,7 +33,10 @@ use crate::{
     z_loaned_shm_client_storage_t, z_owned_shared_shm_provider_t,
 };
 #[cfg(feature = "unstable")]
-use crate::{z_entity_global_id_t, zc_owned_concurrent_close_handle_t};
+use crate::{
+    z_entity_global_id_t, z_loaned_keyexpr_t, zc_moved_closure_work_item_t,
+    zc_owned_concurrent_close_handle_t, zc_runtime_config_t,
+};
 
 decl_c_type!(
     owned(z_owned_session_t, option Session),
@@ -72,13 +77,50 @@ pub extern "C" fn z_internal_session_null(this_: &mut MaybeUninit<z_owned_sessio
 /// Options passed to the `z_open()` function.
 #[repr(C)]
 pub struct z_open_options_t {
+    #[cfg(feature = "unstable")]
+    /// @warning This API has been marked as unstable: it works as advertised, but it may be changed in a future release.
+    ///
+    /// An optional executor closure, NULL by default. If set, it is consumed by `z_open()`, and the callbacks of the entities
+    /// declared on the session (subscribers, queryables, get operations, listeners...) are not run on zenoh threads,
+    /// but are handed to this closure as work items. The callbacks of a given entity are always run in order and never concurrently.
+    ///
+    /// As without executor, no callback of an entity is run once it is undeclared: its pending callbacks are discarded,
+    /// and undeclaring it waits for the callback being run, if any (unless it is undeclared from this callback).
+    /// The pending replies of a get operation are still run after the last reply is received.
+    /// The closure stops receiving work items for new entities once the session is closed, and is dropped once
+    /// the session is closed and the work items of all its entities are dropped.
+    pub executor: *mut zc_moved_closure_work_item_t,
+    #[cfg(feature = "unstable")]
+    /// @warning This API has been marked as unstable: it works as advertised, but it may be changed in a future release.
+    ///
+    /// An optional key expression namespace (without wildcards), such as a tenant prefix. If set, it is transparently
+    /// prepended to the key expressions of all outgoing messages of the session (publications, subscriptions, queries,
+    /// replies, liveliness tokens...), and stripped from the key expressions of all incoming ones.
+    /// Overrides the `namespace` field of the config.
+    pub key_namespace: Option<&'static z_loaned_keyexpr_t>,
+    #[cfg(feature = "unstable")]
+    /// @warning This API has been marked as unstable: it works as advertised, but it may be changed in a future release.
+    ///
+    /// An optional configuration of zenoh runtime thread pools, NULL by default. If set, it is applied as with `zc_init_runtime()`
+    /// before opening the session, and `z_open()` fails if it can not be applied.
+    pub runtime: Option<&'static zc_runtime_config_t>,
+    #[cfg(not(feature = "unstable"))]
     _dummy: u8,
 }
 
 /// Constructs the default value for `z_open_options_t`.
 #[no_mangle]
 pub extern "C" fn z_open_options_default(this_: &mut MaybeUninit<z_open_options_t>) {
-    this_.write(z_open_options_t { _dummy: 0 });
+    this_.write(z_open_options_t {
+        #[cfg(feature = "unstable")]
+        executor: std::ptr::null_mut(),
+        #[cfg(feature = "unstable")]
+        key_namespace: None,
+        #[cfg(feature = "unstable")]
+        runtime: None,
+        #[cfg(not(feature = "unstable"))]
+        _dummy: 0,
+    });
 }
 
 /// Constructs and opens a new Zenoh session.
@@ -89,21 +131,59 @@ pub extern "C" fn z_open_options_default(this_: &mut MaybeUninit<z_open_options_
 pub extern "C" fn z_open(
     this: &mut MaybeUninit<z_owned_session_t>,
     config: &mut z_moved_config_t,
-    _options: Option<&z_open_options_t>,
+    #[allow(unused)] options: Option<&z_open_options_t>,
 ) -> result::z_result_t {
     let this = this.as_rust_type_mut_uninit();
+    // The executor is consumed on every path, including errors.
+    #[cfg(feature = "unstable")]
+    let executor = options
+        .and_then(|o| unsafe { o.executor.as_mut() })
+        .map(|e| e.take_rust_type())
+        .filter(|e| !e.is_empty());
     let Some(config) = config.take_rust_type() else {
-        crate::report_error!("Config not provided");
+        crate::report_error!(zc_error_kind_t::INVALID_ARGUMENT, "Config not provided");
         this.write(None);
         return result::Z_EINVAL;
     };
+    #[cfg(feature = "unstable")]
+    let config = {
+        let mut config = config;
+        if let Some(key_namespace) = options.and_then(|o| o.key_namespace) {
+            let key_namespace = key_namespace.as_rust_type_ref();
+            let value = serde_json::Value::String(key_namespace.to_string()).to_string();
+            if let Err(e) = config.insert_json5("namespace", &value) {
+                crate::report_error!(
+                    zc_error_kind_t::INVALID_ARGUMENT,
+                    "Invalid namespace '{}': {}",
+                    key_namespace,
+                    e
+                );
+                this.write(None);
+                return result::Z_EINVAL;
+            }
+        }
+        config
+    };
+    #[cfg(feature = "unstable")]
+    if let Some(runtime) = options.and_then(|o| o.runtime) {
+        let res = crate::runtime::init_runtime(runtime);
+        if res != result::Z_OK {
+            this.write(None);
+            return res;
+        }
+    }
+    crate::runtime::mark_runtime_started();
     match zenoh::open(config).wait() {
         Ok(s) => {
+            #[cfg(feature = "unstable")]
+            if let Some(executor) = executor {
+                crate::executor::register_executor(&s, executor);
+            }
             this.write(Some(s));
             result::Z_OK
         }
         Err(e) => {
-            crate::report_error!("Error opening session: {}", e);
+            crate::report_error!(zc_error_kind_t::NETWORK, "Error opening session: {}", e);
             this.write(None);
             result::Z_ENETWORK
         }
@@ -205,10 +285,11 @@ pub extern "C" fn z_open_with_custom_shm_clients(
 ) -> result::z_result_t {
     let this = this.as_rust_type_mut_uninit();
     let Some(config) = config.take_rust_type() else {
-        crate::report_error!("Config not provided");
+        crate::report_error!(zc_error_kind_t::INVALID_ARGUMENT, "Config not provided");
         this.write(None);
         return result::Z_EINVAL;
     };
+    crate::runtime::mark_runtime_started();
     match zenoh::open(config)
         .with_shm_clients(shm_clients.as_rust_type_ref().clone())
         .wait()
@@ -218,7 +299,7 @@ pub extern "C" fn z_open_with_custom_shm_clients(
             result::Z_OK
         }
         Err(e) => {
-            crate::report_error!("Error opening session: {}", e);
+            crate::report_error!(zc_error_kind_t::NETWORK, "Error opening session: {}", e);
             this.write(None);
             result::Z_ENETWORK
         }
@@ -274,6 +355,8 @@ pub extern "C" fn z_close(
     session: &mut z_loaned_session_t,
     #[allow(unused)] options: Option<&mut z_close_options_t>,
 ) -> result::z_result_t {
+    #[cfg(feature = "unstable")]
+    crate::executor::unregister_executor(session.as_rust_type_ref());
     #[allow(unused_mut)]
     let mut close_builder = session.as_rust_type_mut().close().wait_callbacks();
 
@@ -294,7 +377,7 @@ pub extern "C" fn z_close(
 
     match close_builder.wait() {
         Err(e) => {
-            crate::report_error!("Error closing session: {}", e);
+            crate::report_error!(zc_error_kind_t::NETWORK, "Error closing session: {}", e);
             result::Z_EGENERIC
         }
         Ok(_) => result::Z_OK,
@@ -317,6 +400,8 @@ pub extern "C" fn z_session_drop(this_: &mut z_moved_session_t) {
         // Session in zenoh-c is non-clonnable,
         // so it it safe to close it on drop
         let _ = s.close().wait_callbacks().wait();
+        #[cfg(feature = "unstable")]
+        crate::executor::unregister_executor(&s);
     }
 }
 
@@ -328,3 +413,82 @@ pub extern "C" fn z_session_id(session: &z_loaned_session_t) -> z_entity_global_
     use crate::transmute::IntoCType;
     session.as_rust_type_ref().id().into_c_type()
 }
+
+/// Returns the current default timeout of the queries of the session, if it is set in the session config.
+#[cfg(feature = "unstable")]
+pub(crate) fn queries_default_timeout(session: &Session) -> Option<Duration> {
+    session
+        .config()
+        .lock()
+        .queries_default_timeout()
+        .as_ref()
+        .map(|t| Duration::from_millis(*t))
+}
+
+#[cfg(feature = "unstable")]
+fn session_config_snapshot(session: &Session) -> Result<zenoh::Config, String> {
+    let config = json5::to_string(&*session.config().lock()).map_err(|e| e.to_string())?;
+    zenoh::Config::from_json5(&config).map_err(|e| e.to_string())
+}
+
+#[cfg(feature = "unstable")]
+/// @warning This API has been marked as unstable: it works as advertised, but it may be changed in a future release.
+/// @brief Constructs a copy of the current configuration of the session.
+///
+/// @return 0 in case of success, negative error code otherwise (in this case the config will be in its gravestone state).
+#[no_mangle]
+pub extern "C" fn z_session_config_get(
+    session: &z_loaned_session_t,
+    config: &mut MaybeUninit<crate::z_owned_config_t>,
+) -> result::z_result_t {
+    let config = config.as_rust_type_mut_uninit();
+    match session_config_snapshot(session.as_rust_type_ref()) {
+        Ok(c) => {
+            config.write(Some(c));
+            result::Z_OK
+        }
+        Err(e) => {
+            crate::report_error!(
+                zc_error_kind_t::OTHER,
+                "Failed to retrieve session config: {}",
+                e
+            );
+            config.write(None);
+            result::Z_EGENERIC
+        }
+    }
+}
+
+#[cfg(feature = "unstable")]
+/// @warning This API has been marked as unstable: it works as advertised, but it may be changed in a future release.
+/// @brief Changes the default timeout of the queries of an open session.
+///
+/// The new timeout applies to the queries issued and to the queriers declared after the update, unless their options
+/// set a timeout, and is reflected by `z_session_config_get()`. The other config values are only read when the session
+/// is opened.
+///
+/// @param session: The session.
+/// @param timeout_ms: The default query timeout in milliseconds.
+/// @return 0 in case of success, negative error code otherwise.
+#[no_mangle]
+pub extern "C" fn z_session_set_queries_default_timeout(
+    session: &z_loaned_session_t,
+    timeout_ms: u64,
+) -> result::z_result_t {
+    match session
+        .as_rust_type_ref()
+        .config()
+        .insert_json5("queries_default_timeout", &timeout_ms.to_string())
+    {
+        Ok(_) => result::Z_OK,
+        Err(e) => {
+            crate::report_error!(
+                zc_error_kind_t::INVALID_ARGUMENT,
+                "Invalid query timeout {}: {}",
+                timeout_ms,
+                e
+            );
+            result::Z_EINVAL
+        }
+    }
+}
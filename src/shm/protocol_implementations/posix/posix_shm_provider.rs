@@ -48,7 +48,7 @@ pub extern "C" fn z_posix_shm_provider_new(
         }
         Err(e) => {
             this.write(None);
-            crate::report_error!("{}", e);
+            crate::report_error!(zc_error_kind_t::INVALID_ARGUMENT, "{}", e);
             Z_EINVAL
         }
     }
@@ -70,7 +70,7 @@ pub extern "C" fn z_posix_shm_provider_with_layout_new(
         }
         Err(e) => {
             this.write(None);
-            crate::report_error!("{}", e);
+            crate::report_error!(zc_error_kind_t::INVALID_ARGUMENT, "{}", e);
             Z_EINVAL
         }
     }
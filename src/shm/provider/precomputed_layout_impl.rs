@@ -44,7 +44,7 @@ pub(crate) fn alloc_layout(
     let mem_layout = match MemoryLayout::new(size, alignment.into_rust_type()) {
         Ok(mem_layout) => mem_layout,
         Err(e) => {
-            crate::report_error!("{:?}", e);
+            crate::report_error!(zc_error_kind_t::INVALID_ARGUMENT, "{:?}", e);
             return Z_EINVAL;
         }
     };
@@ -54,7 +54,7 @@ pub(crate) fn alloc_layout(
             match provider.alloc_layout(mem_layout) {
                 Ok(layout) => CSHMLayout::Posix(layout),
                 Err(e) => {
-                    crate::report_error!("{:?}", e);
+                    crate::report_error!(zc_error_kind_t::INVALID_ARGUMENT, "{:?}", e);
                     return Z_EINVAL;
                 }
             }
@@ -63,7 +63,7 @@ pub(crate) fn alloc_layout(
             match provider.alloc_layout(mem_layout) {
                 Ok(layout) => CSHMLayout::Dynamic(layout),
                 Err(e) => {
-                    crate::report_error!("{:?}", e);
+                    crate::report_error!(zc_error_kind_t::INVALID_ARGUMENT, "{:?}", e);
                     return Z_EINVAL;
                 }
             }
@@ -72,7 +72,7 @@ pub(crate) fn alloc_layout(
             match provider.alloc_layout(mem_layout) {
                 Ok(layout) => CSHMLayout::DynamicThreadsafe(layout),
                 Err(e) => {
-                    crate::report_error!("{:?}", e);
+                    crate::report_error!(zc_error_kind_t::INVALID_ARGUMENT, "{:?}", e);
                     return Z_EINVAL;
                 }
             }
@@ -81,7 +81,7 @@ pub(crate) fn alloc_layout(
             match provider.alloc_layout(mem_layout) {
                 Ok(layout) => CSHMLayout::Posix(layout),
                 Err(e) => {
-                    crate::report_error!("{:?}", e);
+                    crate::report_error!(zc_error_kind_t::INVALID_ARGUMENT, "{:?}", e);
                     return Z_EINVAL;
                 }
             }
@@ -74,7 +74,7 @@ pub extern "C" fn z_shm_provider_default_new(
             Z_OK
         }
         Err(e) => {
-            crate::report_error!("{:?}", e);
+            crate::report_error!(zc_error_kind_t::INVALID_ARGUMENT, "{:?}", e);
             Z_EINVAL
         }
     }
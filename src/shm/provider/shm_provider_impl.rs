@@ -228,7 +228,7 @@ pub(crate) fn map(
             Z_OK
         }
         Err(e) => {
-            crate::report_error!("{:?}", e);
+            crate::report_error!(zc_error_kind_t::INVALID_ARGUMENT, "{:?}", e);
             Z_EINVAL
         }
     }
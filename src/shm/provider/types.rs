@@ -135,7 +135,7 @@ pub extern "C" fn z_memory_layout_new(
             Z_OK
         }
         Err(e) => {
-            crate::report_error!("{:?}", e);
+            crate::report_error!(zc_error_kind_t::INVALID_ARGUMENT, "{:?}", e);
             Z_EINVAL
         }
     }
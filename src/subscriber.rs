@@ -23,6 +23,7 @@ use zenoh::{
 
 pub use crate::opaque_types::{z_loaned_subscriber_t, z_moved_subscriber_t, z_owned_subscriber_t};
 use crate::{
+    executor::dispatch_callback,
     keyexpr::*,
     result,
     transmute::{LoanedCTypeRef, RustTypeRef, RustTypeRefUninit, TakeRustType},
@@ -89,7 +90,7 @@ pub(crate) fn _declare_subscriber_inner<'a, 'b>(
     let callback = callback.take_rust_type();
     let mut subscriber = session
         .declare_subscriber(key_expr)
-        .callback(move |sample| {
+        .callback(dispatch_callback(session, move |sample: Sample| {
             let mut owned_sample = Some(sample);
             z_closure_sample_call(z_closure_sample_loan(&callback), unsafe {
                 owned_sample
@@ -97,7 +98,7 @@ pub(crate) fn _declare_subscriber_inner<'a, 'b>(
                     .unwrap_unchecked()
                     .as_loaned_c_type_mut()
             })
-        });
+        }));
     if let Some(options) = options {
         subscriber = subscriber.allowed_origin(options.allowed_origin.into());
     }
@@ -129,7 +130,7 @@ pub extern "C" fn z_declare_subscriber(
             result::Z_OK
         }
         Err(e) => {
-            crate::report_error!("{}", e);
+            crate::report_error!(zc_error_kind_t::NETWORK, "{}", e);
             this.write(None);
             result::Z_EGENERIC
         }
@@ -156,7 +157,7 @@ pub extern "C" fn z_declare_background_subscriber(
     match subscriber.background().wait() {
         Ok(_) => result::Z_OK,
         Err(e) => {
-            crate::report_error!("{}", e);
+            crate::report_error!(zc_error_kind_t::NETWORK, "{}", e);
             result::Z_EGENERIC
         }
     }
@@ -192,7 +193,7 @@ pub extern "C" fn z_internal_subscriber_check(this_: &z_owned_subscriber_t) -> b
 pub extern "C" fn z_undeclare_subscriber(this_: &mut z_moved_subscriber_t) -> result::z_result_t {
     if let Some(s) = this_.take_rust_type() {
         if let Err(e) = s.undeclare().wait_callbacks().wait() {
-            crate::report_error!("{}", e);
+            crate::report_error!(zc_error_kind_t::NETWORK, "{}", e);
             return result::Z_EGENERIC;
         }
     }
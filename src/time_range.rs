@@ -0,0 +1,305 @@
+//
+// Copyright (c) 2017, 2024 ZettaScale Technology.
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ZettaScale Zenoh team, <zenoh@zettascale.tech>
+//
+
+use std::{
+    mem::MaybeUninit,
+    str::FromStr,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use libc::c_char;
+use zenoh::{
+    query::{Parameters, TimeBound, TimeExpr, TimeRange},
+    time::Timestamp,
+};
+
+use crate::{
+    result::{self, z_result_t},
+    strlen_or_zero,
+    transmute::{RustTypeRef, RustTypeRefUninit},
+    z_loaned_query_t, z_owned_string_t, z_timestamp_t, CStringInner, CStringView,
+};
+
+/// Name of the selector parameter holding the time range of a query.
+pub(crate) const TIME_RANGE_PARAMETER: &str = "_time";
+
+/// @warning This API has been marked as unstable: it works as advertised, but it may be changed in a future release.
+/// @brief Kind of a time range bound.
+#[repr(C)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum z_time_bound_kind_t {
+    /// The range is not bounded on this side.
+    UNBOUNDED,
+    /// The range includes its bound.
+    INCLUSIVE,
+    /// The range excludes its bound.
+    EXCLUSIVE,
+}
+
+/// @warning This API has been marked as unstable: it works as advertised, but it may be changed in a future release.
+/// @brief A bound of a time range.
+#[repr(C)]
+#[derive(Clone, Copy, Debug)]
+pub struct z_time_bound_t {
+    /// The kind of the bound, `time_ns` and `is_relative` are ignored for unbounded ones.
+    pub kind: z_time_bound_kind_t,
+    /// If ``true``, `time_ns` is an offset from the time the range is evaluated at (e.g. `now(-1h)`),
+    /// otherwise it is a number of nanoseconds since the UNIX epoch.
+    pub is_relative: bool,
+    /// The time of the bound in nanoseconds.
+    pub time_ns: i64,
+}
+
+/// @warning This API has been marked as unstable: it works as advertised, but it may be changed in a future release.
+/// @brief A time range, as used by the `_time` selector parameter of queries (e.g. `_time=[now(-1h)..]`).
+#[repr(C)]
+#[derive(Clone, Copy, Debug)]
+pub struct z_time_range_t {
+    /// The start of the range.
+    pub start: z_time_bound_t,
+    /// The end of the range.
+    pub end: z_time_bound_t,
+}
+
+fn system_time_to_ns(t: SystemTime) -> i64 {
+    match t.duration_since(UNIX_EPOCH) {
+        Ok(d) => d.as_nanos().min(i64::MAX as u128) as i64,
+        Err(e) => -(e.duration().as_nanos().min(i64::MAX as u128) as i64),
+    }
+}
+
+fn ns_to_system_time(ns: i64) -> SystemTime {
+    if ns >= 0 {
+        UNIX_EPOCH + Duration::from_nanos(ns as u64)
+    } else {
+        UNIX_EPOCH - Duration::from_nanos(ns.unsigned_abs())
+    }
+}
+
+impl From<TimeBound<TimeExpr>> for z_time_bound_t {
+    fn from(bound: TimeBound<TimeExpr>) -> Self {
+        let (kind, expr) = match bound {
+            TimeBound::Inclusive(e) => (z_time_bound_kind_t::INCLUSIVE, Some(e)),
+            TimeBound::Exclusive(e) => (z_time_bound_kind_t::EXCLUSIVE, Some(e)),
+            TimeBound::Unbounded => (z_time_bound_kind_t::UNBOUNDED, None),
+        };
+        let (is_relative, time_ns) = match expr {
+            Some(TimeExpr::Fixed(t)) => (false, system_time_to_ns(t)),
+            Some(TimeExpr::Now { offset_secs }) => (true, (offset_secs * 1e9).round() as i64),
+            None => (false, 0),
+        };
+        z_time_bound_t {
+            kind,
+            is_relative,
+            time_ns,
+        }
+    }
+}
+
+impl From<&z_time_bound_t> for TimeBound<TimeExpr> {
+    fn from(bound: &z_time_bound_t) -> Self {
+        let expr = if bound.is_relative {
+            TimeExpr::Now {
+                offset_secs: bound.time_ns as f64 / 1e9,
+            }
+        } else {
+            TimeExpr::Fixed(ns_to_system_time(bound.time_ns))
+        };
+        match bound.kind {
+            z_time_bound_kind_t::UNBOUNDED => TimeBound::Unbounded,
+            z_time_bound_kind_t::INCLUSIVE => TimeBound::Inclusive(expr),
+            z_time_bound_kind_t::EXCLUSIVE => TimeBound::Exclusive(expr),
+        }
+    }
+}
+
+impl From<TimeRange<TimeExpr>> for z_time_range_t {
+    fn from(range: TimeRange<TimeExpr>) -> Self {
+        z_time_range_t {
+            start: range.start.into(),
+            end: range.end.into(),
+        }
+    }
+}
+
+impl From<&z_time_range_t> for TimeRange<TimeExpr> {
+    fn from(range: &z_time_range_t) -> Self {
+        TimeRange {
+            start: (&range.start).into(),
+            end: (&range.end).into(),
+        }
+    }
+}
+
+impl z_time_range_t {
+    /// Returns ``true`` if the time `t` (in nanoseconds since the UNIX epoch) is in the range, relative bounds being
+    /// evaluated at time `now`.
+    pub(crate) fn contains_ns(&self, t: i64, now: i64) -> bool {
+        let resolve = |b: &z_time_bound_t| {
+            if b.is_relative {
+                now as i128 + b.time_ns as i128
+            } else {
+                b.time_ns as i128
+            }
+        };
+        let t = t as i128;
+        let after_start = match self.start.kind {
+            z_time_bound_kind_t::UNBOUNDED => true,
+            z_time_bound_kind_t::INCLUSIVE => t >= resolve(&self.start),
+            z_time_bound_kind_t::EXCLUSIVE => t > resolve(&self.start),
+        };
+        let before_end = match self.end.kind {
+            z_time_bound_kind_t::UNBOUNDED => true,
+            z_time_bound_kind_t::INCLUSIVE => t <= resolve(&self.end),
+            z_time_bound_kind_t::EXCLUSIVE => t < resolve(&self.end),
+        };
+        after_start && before_end
+    }
+
+    /// Returns ``true`` if the timestamp is in the range, relative bounds being evaluated at the current time.
+    pub(crate) fn contains(&self, timestamp: &Timestamp) -> bool {
+        let t = system_time_to_ns(timestamp.get_time().to_system_time());
+        self.contains_ns(t, system_time_to_ns(SystemTime::now()))
+    }
+}
+
+/// Parses the time range of selector parameters, if any.
+pub(crate) fn parameters_time_range(
+    parameters: &Parameters,
+) -> Option<Result<z_time_range_t, String>> {
+    parameters.get(TIME_RANGE_PARAMETER).map(|s| {
+        TimeRange::<TimeExpr>::from_str(s)
+            .map(Into::into)
+            .map_err(|e| e.to_string())
+    })
+}
+
+/// Returns `parameters` with their time range replaced by `time_range`, if set.
+pub(crate) fn with_time_range<'a>(
+    parameters: &'a str,
+    time_range: Option<&z_time_range_t>,
+) -> Parameters<'a> {
+    let mut parameters = Parameters::from(parameters);
+    if let Some(time_range) = time_range {
+        parameters.insert(
+            TIME_RANGE_PARAMETER,
+            TimeRange::<TimeExpr>::from(time_range).to_string(),
+        );
+    }
+    parameters
+}
+
+/// @warning This API has been marked as unstable: it works as advertised, but it may be changed in a future release.
+/// @brief Constructs a time range unbounded on both sides, containing any time.
+#[no_mangle]
+pub extern "C" fn z_time_range_unbounded(this_: &mut MaybeUninit<z_time_range_t>) {
+    let unbounded = z_time_bound_t {
+        kind: z_time_bound_kind_t::UNBOUNDED,
+        is_relative: false,
+        time_ns: 0,
+    };
+    this_.write(z_time_range_t {
+        start: unbounded,
+        end: unbounded,
+    });
+}
+
+/// @warning This API has been marked as unstable: it works as advertised, but it may be changed in a future release.
+/// @brief Parses a time range from a null-terminated string.
+///
+/// Supported formats are `[start..end]` (where each bracket may be reversed to exclude the bound, and each bound may be omitted)
+/// and `[start;duration]`. Times are either RFC3339 dates or offsets from the evaluation time such as `now(-1h)`.
+///
+/// @param this_: An uninitialized memory location where the time range will be constructed.
+/// @param s: A null-terminated string, such as `[now(-1h)..]`.
+/// @return 0 in case of success, negative error code otherwise.
+#[allow(clippy::missing_safety_doc)]
+#[no_mangle]
+pub unsafe extern "C" fn z_time_range_from_str(
+    this_: &mut MaybeUninit<z_time_range_t>,
+    s: *const c_char,
+) -> z_result_t {
+    let s = match CStringView::new_borrowed(s, strlen_or_zero(s)) {
+        Ok(s) => s,
+        Err(r) => return r,
+    };
+    let s: &str = match (&s).try_into() {
+        Ok(s) => s,
+        Err(e) => {
+            crate::report_error!(
+                zc_error_kind_t::INVALID_ARGUMENT,
+                "Time range is not a valid utf-8 string: {}",
+                e
+            );
+            return result::Z_EINVAL;
+        }
+    };
+    match TimeRange::<TimeExpr>::from_str(s) {
+        Ok(range) => {
+            this_.write(range.into());
+            result::Z_OK
+        }
+        Err(e) => {
+            crate::report_error!(zc_error_kind_t::PARSE, "Invalid time range '{}': {}", s, e);
+            result::Z_EPARSE
+        }
+    }
+}
+
+/// @warning This API has been marked as unstable: it works as advertised, but it may be changed in a future release.
+/// @brief Constructs the string representation of a time range, as expected by the `_time` selector parameter.
+#[no_mangle]
+pub extern "C" fn z_time_range_to_string(
+    this_: &z_time_range_t,
+    out_string: &mut MaybeUninit<z_owned_string_t>,
+) {
+    out_string
+        .as_rust_type_mut_uninit()
+        .write(CStringInner::from(
+            TimeRange::<TimeExpr>::from(this_).to_string(),
+        ));
+}
+
+/// @warning This API has been marked as unstable: it works as advertised, but it may be changed in a future release.
+/// @brief Returns ``true`` if the timestamp is in the time range, ``false`` otherwise.
+///
+/// Relative bounds are evaluated at the current time.
+#[no_mangle]
+pub extern "C" fn z_time_range_contains(this_: &z_time_range_t, timestamp: &z_timestamp_t) -> bool {
+    this_.contains(timestamp.as_rust_type_ref())
+}
+
+/// @warning This API has been marked as unstable: it works as advertised, but it may be changed in a future release.
+/// @brief Extracts the time range of a query from its `_time` selector parameter.
+///
+/// @param this_: The query.
+/// @param time_range: An uninitialized memory location where the time range will be constructed.
+/// @return 0 in case of success, `Z_EUNAVAILABLE` if the query has no time range, other negative error code if it is invalid.
+#[no_mangle]
+pub extern "C" fn z_query_time_range(
+    this_: &z_loaned_query_t,
+    time_range: &mut MaybeUninit<z_time_range_t>,
+) -> z_result_t {
+    match parameters_time_range(this_.as_rust_type_ref().parameters()) {
+        Some(Ok(range)) => {
+            time_range.write(range);
+            result::Z_OK
+        }
+        Some(Err(e)) => {
+            crate::report_error!(zc_error_kind_t::PARSE, "Invalid query time range: {}", e);
+            result::Z_EPARSE
+        }
+        None => result::Z_EUNAVAILABLE,
+    }
+}
@@ -127,7 +127,11 @@ pub unsafe extern "C" fn z_bytes_to_string(
             result::Z_OK
         }
         Err(e) => {
-            crate::report_error!("Failed to convert the payload: {}", e);
+            crate::report_error!(
+                zc_error_kind_t::INVALID_ARGUMENT,
+                "Failed to convert the payload: {}",
+                e
+            );
             dst.as_rust_type_mut_uninit()
                 .write(CStringOwned::gravestone());
             result::Z_EINVAL
@@ -170,7 +174,10 @@ pub unsafe extern "C" fn z_bytes_to_owned_shm(
             result::Z_OK
         }
         None => {
-            crate::report_error!("Failed to convert the payload");
+            crate::report_error!(
+                zc_error_kind_t::INVALID_ARGUMENT,
+                "Failed to convert the payload"
+            );
             dst.as_rust_type_mut_uninit().write(None);
             result::Z_EINVAL
         }
@@ -196,7 +203,10 @@ pub unsafe extern "C" fn z_bytes_as_loaned_shm(
             result::Z_OK
         }
         None => {
-            crate::report_error!("Failed to convert the payload");
+            crate::report_error!(
+                zc_error_kind_t::INVALID_ARGUMENT,
+                "Failed to convert the payload"
+            );
             result::Z_EINVAL
         }
     }
@@ -221,7 +231,10 @@ pub unsafe extern "C" fn z_bytes_as_mut_loaned_shm(
             result::Z_OK
         }
         None => {
-            crate::report_error!("Failed to convert the payload");
+            crate::report_error!(
+                zc_error_kind_t::INVALID_ARGUMENT,
+                "Failed to convert the payload"
+            );
             result::Z_EINVAL
         }
     }
@@ -0,0 +1,308 @@
+//
+// Copyright (c) 2024 ZettaScale Technology
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
+
+#include <stddef.h>
+#include <stdint.h>
+#include <stdio.h>
+#include <string.h>
+
+#include "zenoh.h"
+
+#if defined(Z_FEATURE_UNSTABLE_API) && defined(__unix__)
+#include <poll.h>
+#endif
+
+#undef NDEBUG
+#include <assert.h>
+
+const char* keyexpr = "zenoh/test/channels";
+
+void put_str(const z_loaned_session_t* s, const char* key, const char* value) {
+    z_view_keyexpr_t ke;
+    z_view_keyexpr_from_str(&ke, key);
+    z_owned_bytes_t payload;
+    z_bytes_copy_from_str(&payload, value);
+    assert(z_put(s, z_loan(ke), z_move(payload), NULL) == Z_OK);
+}
+
+#if defined(Z_FEATURE_UNSTABLE_API) && defined(__unix__)
+bool is_readable(int fd, int timeout_ms) {
+    struct pollfd pfd = {.fd = fd, .events = POLLIN, .revents = 0};
+    return poll(&pfd, 1, timeout_ms) == 1 && (pfd.revents & POLLIN) != 0;
+}
+
+void test_fifo_handler_fd() {
+    z_owned_config_t config;
+    z_config_default(&config);
+    z_owned_session_t s;
+    assert(z_open(&s, z_move(config), NULL) == Z_OK);
+
+    z_owned_closure_sample_t closure;
+    z_owned_fifo_handler_sample_t handler;
+    z_fifo_channel_sample_new(&closure, &handler, 16);
+    int fd = -1;
+    assert(zc_fifo_handler_sample_get_fd(z_loan(handler), &fd) == Z_OK);
+    assert(fd >= 0);
+
+    z_view_keyexpr_t ke;
+    z_view_keyexpr_from_str(&ke, keyexpr);
+    z_owned_subscriber_t sub;
+    assert(z_declare_subscriber(z_loan(s), &sub, z_loan(ke), z_move(closure), NULL) == Z_OK);
+
+    // Drain the initial signal
+    z_owned_sample_t sample;
+    while (z_try_recv(z_loan(handler), &sample) == Z_OK) {
+        z_drop(z_move(sample));
+    }
+    assert(!is_readable(fd, 0));
+
+    put_str(z_loan(s), keyexpr, "a");
+    put_str(z_loan(s), keyexpr, "b");
+    assert(is_readable(fd, 1000));
+    assert(z_try_recv(z_loan(handler), &sample) == Z_OK);
+    z_drop(z_move(sample));
+    assert(z_try_recv(z_loan(handler), &sample) == Z_OK);
+    z_drop(z_move(sample));
+    assert(z_try_recv(z_loan(handler), &sample) == Z_CHANNEL_NODATA);
+    assert(!is_readable(fd, 0));
+
+    z_drop(z_move(sub));
+    assert(is_readable(fd, 1000));
+    assert(z_try_recv(z_loan(handler), &sample) == Z_CHANNEL_DISCONNECTED);
+
+    z_drop(z_move(handler));
+    z_drop(z_move(s));
+}
+#endif
+
+#if defined(Z_FEATURE_UNSTABLE_API)
+void test_recv_timeout() {
+    z_owned_config_t config;
+    z_config_default(&config);
+    z_owned_session_t s;
+    assert(z_open(&s, z_move(config), NULL) == Z_OK);
+
+    z_owned_closure_sample_t closure1, closure2;
+    z_owned_fifo_handler_sample_t handler1;
+    z_owned_ring_handler_sample_t handler2;
+    z_fifo_channel_sample_new(&closure1, &handler1, 16);
+    z_ring_channel_sample_new(&closure2, &handler2, 16);
+
+    z_view_keyexpr_t ke;
+    z_view_keyexpr_from_str(&ke, keyexpr);
+    z_owned_subscriber_t sub1, sub2;
+    assert(z_declare_subscriber(z_loan(s), &sub1, z_loan(ke), z_move(closure1), NULL) == Z_OK);
+    assert(z_declare_subscriber(z_loan(s), &sub2, z_loan(ke), z_move(closure2), NULL) == Z_OK);
+
+    z_owned_sample_t sample;
+    z_clock_t start = z_clock_now();
+    assert(z_recv_timeout(z_loan(handler1), &sample, 100) == Z_CHANNEL_NODATA);
+    assert(z_clock_elapsed_ms(&start) >= 100);
+    assert(!z_internal_check(sample));
+    assert(z_recv_timeout(z_loan(handler2), &sample, 0) == Z_CHANNEL_NODATA);
+    assert(!z_internal_check(sample));
+
+    put_str(z_loan(s), keyexpr, "a");
+    assert(z_recv_timeout(z_loan(handler1), &sample, 1000) == Z_OK);
+    z_drop(z_move(sample));
+    assert(z_recv_timeout(z_loan(handler2), &sample, 1000) == Z_OK);
+    z_drop(z_move(sample));
+
+    // A timeout overflowing the deadline waits without timeout.
+    put_str(z_loan(s), keyexpr, "b");
+    assert(z_recv_timeout(z_loan(handler1), &sample, UINT64_MAX) == Z_OK);
+    z_drop(z_move(sample));
+    assert(z_recv_timeout(z_loan(handler2), &sample, UINT64_MAX) == Z_OK);
+    z_drop(z_move(sample));
+
+    z_drop(z_move(sub1));
+    z_drop(z_move(sub2));
+    assert(z_recv_timeout(z_loan(handler1), &sample, 1000) == Z_CHANNEL_DISCONNECTED);
+    assert(z_recv_timeout(z_loan(handler2), &sample, 1000) == Z_CHANNEL_DISCONNECTED);
+
+    z_drop(z_move(handler1));
+    z_drop(z_move(handler2));
+    z_drop(z_move(s));
+}
+
+void test_recv_batch() {
+    z_owned_config_t config;
+    z_config_default(&config);
+    z_owned_session_t s;
+    assert(z_open(&s, z_move(config), NULL) == Z_OK);
+
+    z_owned_closure_sample_t closure;
+    z_owned_fifo_handler_sample_t handler;
+    z_fifo_channel_sample_new(&closure, &handler, 16);
+
+    z_view_keyexpr_t ke;
+    z_view_keyexpr_from_str(&ke, keyexpr);
+    z_owned_subscriber_t sub;
+    assert(z_declare_subscriber(z_loan(s), &sub, z_loan(ke), z_move(closure), NULL) == Z_OK);
+
+    z_owned_sample_t samples[4];
+    size_t count = 42;
+    assert(z_fifo_handler_sample_recv_batch(z_loan(handler), samples, 4, &count) == Z_CHANNEL_NODATA);
+    assert(count == 0);
+
+    const char* values[] = {"a", "b", "c", "d", "e", "f"};
+    for (size_t i = 0; i < 6; i++) {
+        put_str(z_loan(s), keyexpr, values[i]);
+    }
+    assert(z_fifo_handler_sample_recv_batch(z_loan(handler), samples, 4, &count) == Z_OK);
+    assert(count == 4);
+    for (size_t i = 0; i < count; i++) {
+        z_owned_string_t value;
+        z_bytes_to_string(z_sample_payload(z_loan(samples[i])), &value);
+        assert(strncmp(z_string_data(z_loan(value)), values[i], z_string_len(z_loan(value))) == 0);
+        z_drop(z_move(value));
+        z_drop(z_move(samples[i]));
+    }
+    assert(z_fifo_handler_sample_recv_batch(z_loan(handler), samples, 4, &count) == Z_OK);
+    assert(count == 2);
+    z_drop(z_move(samples[0]));
+    z_drop(z_move(samples[1]));
+
+    z_drop(z_move(sub));
+    assert(z_fifo_handler_sample_recv_batch(z_loan(handler), samples, 4, &count) == Z_CHANNEL_DISCONNECTED);
+    assert(count == 0);
+
+    z_drop(z_move(handler));
+    z_drop(z_move(s));
+}
+
+void test_matching_status_channel() {
+    z_owned_config_t config;
+    z_config_default(&config);
+    z_owned_session_t s;
+    assert(z_open(&s, z_move(config), NULL) == Z_OK);
+
+    z_view_keyexpr_t ke;
+    z_view_keyexpr_from_str(&ke, "zenoh/test/channels/matching");
+    z_owned_publisher_t pub;
+    assert(z_declare_publisher(z_loan(s), &pub, z_loan(ke), NULL) == Z_OK);
+
+    z_owned_closure_matching_status_t closure;
+    z_owned_fifo_handler_matching_status_t handler;
+    z_fifo_channel_matching_status_new(&closure, &handler, 16);
+    z_owned_matching_listener_t listener;
+    assert(z_publisher_declare_matching_listener(z_loan(pub), &listener, z_move(closure)) == Z_OK);
+
+    z_owned_closure_sample_t sample_closure;
+    z_owned_fifo_handler_sample_t sample_handler;
+    z_fifo_channel_sample_new(&sample_closure, &sample_handler, 16);
+    z_owned_subscriber_t sub;
+    assert(z_declare_subscriber(z_loan(s), &sub, z_loan(ke), z_move(sample_closure), NULL) == Z_OK);
+
+    z_matching_status_t status = {.matching = false};
+    while (!status.matching) {
+        assert(z_recv_timeout(z_loan(handler), &status, 1000) == Z_OK);
+    }
+
+    z_drop(z_move(sub));
+    while (status.matching) {
+        assert(z_recv_timeout(z_loan(handler), &status, 1000) == Z_OK);
+    }
+
+    z_drop(z_move(listener));
+    z_drop(z_move(pub));
+    assert(z_recv_timeout(z_loan(handler), &status, 1000) == Z_CHANNEL_DISCONNECTED);
+
+    z_drop(z_move(handler));
+    z_drop(z_move(sample_handler));
+    z_drop(z_move(s));
+}
+
+void test_handler_set() {
+    const char* keyexpr2 = "zenoh/test/channels/2";
+    z_owned_config_t config;
+    z_config_default(&config);
+    z_owned_session_t s;
+    assert(z_open(&s, z_move(config), NULL) == Z_OK);
+
+    z_owned_closure_sample_t closure1, closure2;
+    z_owned_fifo_handler_sample_t handler1;
+    z_owned_ring_handler_sample_t handler2;
+    z_fifo_channel_sample_new(&closure1, &handler1, 16);
+    z_ring_channel_sample_new(&closure2, &handler2, 16);
+
+    z_view_keyexpr_t ke1, ke2;
+    z_view_keyexpr_from_str(&ke1, keyexpr);
+    z_view_keyexpr_from_str(&ke2, keyexpr2);
+    z_owned_subscriber_t sub1, sub2;
+    assert(z_declare_subscriber(z_loan(s), &sub1, z_loan(ke1), z_move(closure1), NULL) == Z_OK);
+    assert(z_declare_subscriber(z_loan(s), &sub2, z_loan(ke2), z_move(closure2), NULL) == Z_OK);
+
+    z_owned_handler_set_t set;
+    z_handler_set_new(&set);
+    size_t empty_idx;
+    assert(z_handler_set_wait(z_loan(set), &empty_idx) == Z_EINVAL);
+    assert(z_handler_set_wait_timeout(z_loan(set), 0, &empty_idx) == Z_EINVAL);
+    size_t idx1 = z_handler_set_add_fifo_sample(z_loan_mut(set), z_loan(handler1));
+    size_t idx2 = z_handler_set_add_ring_sample(z_loan_mut(set), z_loan(handler2));
+    assert(idx1 != idx2);
+    size_t idx = (size_t)-1;
+    assert(z_handler_set_wait_timeout(z_loan(set), 0, &idx) == Z_CHANNEL_NODATA);
+
+    z_owned_sample_t sample;
+    put_str(z_loan(s), keyexpr2, "a");
+    assert(z_handler_set_wait_timeout(z_loan(set), 1000, &idx) == Z_OK);
+    assert(idx == idx2);
+    assert(z_try_recv(z_loan(handler2), &sample) == Z_OK);
+    z_drop(z_move(sample));
+    assert(z_try_recv(z_loan(handler2), &sample) == Z_CHANNEL_NODATA);
+    assert(z_handler_set_wait_timeout(z_loan(set), 0, &idx) == Z_CHANNEL_NODATA);
+
+    put_str(z_loan(s), keyexpr, "b");
+    assert(z_handler_set_wait(z_loan(set), &idx) == Z_OK);
+    assert(idx == idx1);
+    assert(z_try_recv(z_loan(handler1), &sample) == Z_OK);
+    z_drop(z_move(sample));
+    assert(z_try_recv(z_loan(handler1), &sample) == Z_CHANNEL_NODATA);
+
+    // A timeout overflowing the deadline waits without timeout.
+    put_str(z_loan(s), keyexpr2, "c");
+    assert(z_handler_set_wait_timeout(z_loan(set), UINT64_MAX, &idx) == Z_OK);
+    assert(idx == idx2);
+    assert(z_try_recv(z_loan(handler2), &sample) == Z_OK);
+    z_drop(z_move(sample));
+
+    z_drop(z_move(sub1));
+    assert(z_handler_set_wait_timeout(z_loan(set), 1000, &idx) == Z_OK);
+    assert(idx == idx1);
+    assert(z_try_recv(z_loan(handler1), &sample) == Z_CHANNEL_DISCONNECTED);
+    assert(z_handler_set_remove(z_loan_mut(set), idx1) == Z_OK);
+    assert(z_handler_set_remove(z_loan_mut(set), idx1) == Z_EINVAL);
+    assert(z_handler_set_wait_timeout(z_loan(set), 0, &idx) == Z_CHANNEL_NODATA);
+
+    z_drop(z_move(set));
+    z_drop(z_move(sub2));
+    z_drop(z_move(handler1));
+    z_drop(z_move(handler2));
+    z_drop(z_move(s));
+}
+#endif
+
+int main(int argc, char** argv) {
+    zc_try_init_log_from_env();
+#if defined(Z_FEATURE_UNSTABLE_API) && defined(__unix__)
+    test_fifo_handler_fd();
+#endif
+#if defined(Z_FEATURE_UNSTABLE_API)
+    test_recv_timeout();
+    test_recv_batch();
+    test_matching_status_channel();
+    test_handler_set();
+#endif
+    return 0;
+}
@@ -11,6 +11,7 @@
 // Contributors:
 //   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
 #include <stdio.h>
+#include <stdlib.h>
 #include <string.h>
 
 #include "zenoh.h"
@@ -36,7 +37,276 @@ void insert_get() {
     z_drop(z_move(config));
 }
 
+#if defined(Z_FEATURE_UNSTABLE_API)
+void last_error() {
+    z_owned_config_t config;
+    assert(zc_config_from_str(&config, "{mode:") == Z_EPARSE);
+    assert(zc_get_last_error_kind() == ZC_ERROR_KIND_PARSE);
+    z_view_string_t msg;
+    zc_get_last_error(&msg);
+    assert(z_string_len(z_loan(msg)) > 0);
+
+    z_config_default(&config);
+    z_owned_string_t value;
+    assert(zc_config_get_from_str(z_loan(config), "unknown/key", &value) == Z_EUNAVAILABLE);
+    assert(zc_get_last_error_kind() == ZC_ERROR_KIND_UNAVAILABLE);
+    z_drop(z_move(config));
+}
+
+void assert_config_value(const z_loaned_config_t *config, const char *key, const char *expected) {
+    z_owned_string_t value;
+    assert(zc_config_get_from_str(config, key, &value) == Z_OK);
+    assert(z_string_len(z_loan(value)) == strlen(expected));
+    assert(strncmp(z_string_data(z_loan(value)), expected, strlen(expected)) == 0);
+    z_drop(z_move(value));
+}
+
+void typed_setters() {
+    z_owned_config_t config;
+    z_config_default(&config);
+
+    assert(zc_config_set_mode(z_loan_mut(config), Z_WHATAMI_CLIENT) == Z_OK);
+    assert_config_value(z_loan(config), "mode", "\"client\"");
+
+    const char *endpoints[] = {"tcp/127.0.0.1:7447", "udp/192.168.0.1:7447"};
+    assert(zc_config_set_connect_endpoints(z_loan_mut(config), endpoints, 2) == Z_OK);
+    assert_config_value(z_loan(config), "connect/endpoints", "[\"tcp/127.0.0.1:7447\",\"udp/192.168.0.1:7447\"]");
+    const char *invalid_endpoints[] = {"tcp/127.0.0.1:7447", "not an endpoint"};
+    assert(zc_config_set_connect_endpoints(z_loan_mut(config), invalid_endpoints, 2) == Z_EINVAL);
+    assert(zc_get_last_error_kind() == ZC_ERROR_KIND_INVALID_ARGUMENT);
+    // The configuration is left unchanged on error.
+    assert_config_value(z_loan(config), "connect/endpoints", "[\"tcp/127.0.0.1:7447\",\"udp/192.168.0.1:7447\"]");
+    assert(zc_config_set_listen_endpoints(z_loan_mut(config), endpoints, 1) == Z_OK);
+    assert_config_value(z_loan(config), "listen/endpoints", "[\"tcp/127.0.0.1:7447\"]");
+
+    assert(zc_config_set_scouting_multicast_enabled(z_loan_mut(config), false) == Z_OK);
+    assert_config_value(z_loan(config), "scouting/multicast/enabled", "false");
+    assert(zc_config_set_scouting_multicast_address(z_loan_mut(config), "224.0.0.225:7446") == Z_OK);
+    assert(zc_config_set_scouting_multicast_address(z_loan_mut(config), "127.0.0.1:7446") == Z_EINVAL);
+    assert(zc_config_set_scouting_multicast_address(z_loan_mut(config), NULL) == Z_EINVAL);
+    assert(zc_config_set_scouting_multicast_interface(z_loan_mut(config), "") == Z_EINVAL);
+    assert(zc_config_set_scouting_gossip_enabled(z_loan_mut(config), true) == Z_OK);
+    assert(zc_config_set_scouting_timeout_ms(z_loan_mut(config), 0) == Z_EINVAL);
+    assert(zc_config_set_scouting_timeout_ms(z_loan_mut(config), 1000) == Z_OK);
+    assert_config_value(z_loan(config), "scouting/timeout", "1000");
+
+    assert(zc_config_set_timestamping_enabled(z_loan_mut(config), true) == Z_OK);
+    assert(zc_config_set_timestamping_drop_future_timestamp(z_loan_mut(config), true) == Z_OK);
+    assert(zc_config_set_qos_enabled(z_loan_mut(config), false) == Z_OK);
+    assert_config_value(z_loan(config), "transport/unicast/qos/enabled", "false");
+    assert(zc_config_set_shared_memory_enabled(z_loan_mut(config), false) == Z_OK);
+
+    assert(zc_config_set_transport_max_sessions(z_loan_mut(config), 0) == Z_EINVAL);
+    assert(zc_config_set_transport_max_sessions(z_loan_mut(config), 10) == Z_OK);
+    assert_config_value(z_loan(config), "transport/unicast/max_sessions", "10");
+    assert(zc_config_set_transport_max_links(z_loan_mut(config), 2) == Z_OK);
+    assert(zc_config_set_transport_lease_ms(z_loan_mut(config), 5000) == Z_OK);
+    assert(zc_config_set_transport_keep_alive(z_loan_mut(config), 4) == Z_OK);
+    assert(zc_config_set_transport_batch_size(z_loan_mut(config), 8192) == Z_OK);
+    assert(zc_config_set_transport_max_message_size(z_loan_mut(config), 1 << 20) == Z_OK);
+
+    assert(zc_config_set_access_control_enabled(z_loan_mut(config), true) == Z_OK);
+    assert(zc_config_set_access_control_default_permission(z_loan_mut(config), ZC_ACCESS_CONTROL_PERMISSION_DENY) ==
+           Z_OK);
+    zc_access_control_message_t messages[] = {ZC_ACCESS_CONTROL_MESSAGE_PUT, ZC_ACCESS_CONTROL_MESSAGE_DECLARE_SUBSCRIBER};
+    const char *key_exprs[] = {"demo/**"};
+    zc_access_control_rule_t rule = {.id = "allow_demo",
+                                     .permission = ZC_ACCESS_CONTROL_PERMISSION_ALLOW,
+                                     .flows = ZC_ACCESS_CONTROL_FLOWS_INGRESS_AND_EGRESS,
+                                     .messages = messages,
+                                     .messages_len = 2,
+                                     .key_exprs = key_exprs,
+                                     .key_exprs_len = 1};
+    assert(zc_config_add_access_control_rule(z_loan_mut(config), &rule) == Z_OK);
+    // Duplicated id.
+    assert(zc_config_add_access_control_rule(z_loan_mut(config), &rule) == Z_EINVAL);
+    const char *invalid_key_exprs[] = {"demo//a"};
+    rule.id = "invalid";
+    rule.key_exprs = invalid_key_exprs;
+    assert(zc_config_add_access_control_rule(z_loan_mut(config), &rule) == Z_EINVAL);
+
+    const char *interfaces[] = {"lo"};
+    zc_access_control_subject_t subject = {.id = "local", .interfaces = interfaces, .interfaces_len = 1};
+    assert(zc_config_add_access_control_subject(z_loan_mut(config), &subject) == Z_OK);
+
+    const char *rules[] = {"allow_demo"};
+    const char *subjects[] = {"local"};
+    const char *unknown_subjects[] = {"remote"};
+    zc_access_control_policy_t policy = {
+        .id = "policy", .rules = rules, .rules_len = 1, .subjects = unknown_subjects, .subjects_len = 1};
+    assert(zc_config_add_access_control_policy(z_loan_mut(config), &policy) == Z_EINVAL);
+    policy.subjects = subjects;
+    assert(zc_config_add_access_control_policy(z_loan_mut(config), &policy) == Z_OK);
+    assert_config_value(z_loan(config), "access_control/default_permission", "\"deny\"");
+
+    z_drop(z_move(config));
+}
+
+bool has_diagnostic(const zc_loaned_config_diagnostics_t *diagnostics, zc_config_diagnostic_severity_t severity,
+                    const char *key) {
+    for (size_t i = 0; i < zc_config_diagnostics_len(diagnostics); i++) {
+        const zc_loaned_config_diagnostic_t *d = zc_config_diagnostics_get(diagnostics, i);
+        const z_loaned_string_t *k = zc_config_diagnostic_key(d);
+        assert(z_string_len(zc_config_diagnostic_message(d)) > 0);
+        if (zc_config_diagnostic_severity(d) == severity && z_string_len(k) == strlen(key) &&
+            strncmp(z_string_data(k), key, strlen(key)) == 0) {
+            return true;
+        }
+    }
+    return false;
+}
+
+void validate() {
+    z_owned_config_t config;
+    zc_owned_config_diagnostics_t diagnostics;
+    z_config_default(&config);
+    assert(zc_config_validate(z_loan(config), &diagnostics) == Z_OK);
+    assert(zc_internal_config_diagnostics_check(&diagnostics));
+    assert(zc_config_diagnostics_get(zc_config_diagnostics_loan(&diagnostics), 100) == NULL);
+    zc_config_diagnostics_drop(zc_config_diagnostics_move(&diagnostics));
+    z_drop(z_move(config));
+
+    assert(zc_config_from_str(&config,
+                              "{mode: 'client', scouting: {multicast: {enabled: false}},"
+                              "access_control: {rules: [{id: 'r', permission: 'allow', flows: ['ingress'],"
+                              "messages: ['put'], key_exprs: ['a/b']}],"
+                              "policies: [{id: 'p', rules: ['r', 'unknown'], subjects: []}]}}") == Z_OK);
+    assert(zc_config_validate(z_loan(config), &diagnostics) == Z_EINVAL);
+    const zc_loaned_config_diagnostics_t *d = zc_config_diagnostics_loan(&diagnostics);
+    assert(has_diagnostic(d, ZC_CONFIG_DIAGNOSTIC_SEVERITY_ERROR, "connect/endpoints"));
+    assert(has_diagnostic(d, ZC_CONFIG_DIAGNOSTIC_SEVERITY_ERROR, "access_control/policies"));
+    assert(has_diagnostic(d, ZC_CONFIG_DIAGNOSTIC_SEVERITY_WARNING, "access_control/enabled"));
+    zc_config_diagnostics_drop(zc_config_diagnostics_move(&diagnostics));
+    z_drop(z_move(config));
+
+    assert(zc_config_validate_str("{unknown_key: 1}", &diagnostics) == Z_EINVAL);
+    assert(has_diagnostic(zc_config_diagnostics_loan(&diagnostics), ZC_CONFIG_DIAGNOSTIC_SEVERITY_ERROR, ""));
+    zc_config_diagnostics_drop(zc_config_diagnostics_move(&diagnostics));
+    assert(zc_config_validate_str("{mode: 'peer'}", &diagnostics) == Z_OK);
+    zc_config_diagnostics_drop(zc_config_diagnostics_move(&diagnostics));
+}
+
+void diff() {
+    z_owned_config_t a, b;
+    z_config_default(&a);
+    z_config_default(&b);
+    z_owned_string_array_t keys;
+    assert(zc_config_diff(z_loan(a), z_loan(b), &keys) == Z_OK);
+    assert(z_string_array_len(z_loan(keys)) == 0);
+    z_drop(z_move(keys));
+
+    zc_config_insert_json5(z_loan_mut(b), "mode", "'client'");
+    zc_config_insert_json5(z_loan_mut(b), "scouting/multicast/enabled", "false");
+    assert(zc_config_diff(z_loan(a), z_loan(b), &keys) == Z_OK);
+    assert(z_string_array_len(z_loan(keys)) == 2);
+    const z_loaned_string_t *k = z_string_array_get(z_loan(keys), 0);
+    assert(strncmp(z_string_data(k), "mode", z_string_len(k)) == 0);
+    k = z_string_array_get(z_loan(keys), 1);
+    assert(strncmp(z_string_data(k), "scouting/multicast/enabled", z_string_len(k)) == 0);
+    z_drop(z_move(keys));
+    z_drop(z_move(a));
+    z_drop(z_move(b));
+}
+
+void set_env(const char *name, const char *value) {
+#if defined(_WIN32)
+    _putenv_s(name, value);
+#else
+    setenv(name, value, 1);
+#endif
+}
+
+void write_file(const char *path, const char *content) {
+    FILE *f = fopen(path, "w");
+    assert(f != NULL);
+    fputs(content, f);
+    fclose(f);
+}
+
+void from_str_with_env() {
+    set_env("ZC_TEST_CONFIG_MODE", "client");
+    set_env("ZC_TEST_CONFIG_EMPTY", "");
+    z_owned_config_t config;
+    assert(zc_config_from_str_with_env(&config,
+                                       "{mode: '${ZC_TEST_CONFIG_MODE}', "
+                                       "connect: {endpoints: ['tcp/${ZC_TEST_CONFIG_HOST:-127.0.0.1}:7447']}, "
+                                       "scouting: {timeout: ${ZC_TEST_CONFIG_EMPTY:-1234}}, "
+                                       "metadata: {name: '$${ZC_TEST_CONFIG_MODE}'}}") == Z_OK);
+    assert_config_value(z_loan(config), "mode", "\"client\"");
+    assert_config_value(z_loan(config), "connect/endpoints", "[\"tcp/127.0.0.1:7447\"]");
+    assert_config_value(z_loan(config), "scouting/timeout", "1234");
+    assert_config_value(z_loan(config), "metadata/name", "\"${ZC_TEST_CONFIG_MODE}\"");
+    z_drop(z_move(config));
+
+    // Substituted values can not change the structure of the config.
+    set_env("ZC_TEST_CONFIG_INJECT", "x', mode: 'router");
+    assert(zc_config_from_str_with_env(&config,
+                                       "{mode: 'client', metadata: {name: '${ZC_TEST_CONFIG_INJECT}', "
+                                       "location: \"${ZC_TEST_CONFIG_INJECT}\"}}") == Z_OK);
+    assert_config_value(z_loan(config), "mode", "\"client\"");
+    assert_config_value(z_loan(config), "metadata/name", "\"x', mode: 'router\"");
+    assert_config_value(z_loan(config), "metadata/location", "\"x', mode: 'router\"");
+    z_drop(z_move(config));
+    assert(zc_config_from_str_with_env(&config, "{scouting: {timeout: ${ZC_TEST_CONFIG_INJECT}}}") != Z_OK);
+    assert(!z_internal_check(config));
+    // Variables are not substituted in comments.
+    assert(zc_config_from_str_with_env(&config, "{mode: 'client' // ${ZC_TEST_CONFIG_UNSET}\n}") == Z_OK);
+    z_drop(z_move(config));
+
+    // Variables without default value must be set.
+    assert(zc_config_from_str_with_env(&config, "{mode: '${ZC_TEST_CONFIG_UNSET}'}") == Z_EINVAL);
+    assert(zc_get_last_error_kind() == ZC_ERROR_KIND_INVALID_ARGUMENT);
+    assert(!z_internal_check(config));
+    assert(zc_config_from_str_with_env(&config, "{mode: '${ZC_TEST_CONFIG_MODE'}") == Z_EPARSE);
+    assert(zc_get_last_error_kind() == ZC_ERROR_KIND_PARSE);
+    assert(!z_internal_check(config));
+
+    // Default values may contain balanced braces.
+    assert(zc_config_from_str_with_env(&config, "{metadata: {name: '${ZC_TEST_CONFIG_UNSET:-{a: {b: 1}}}'}}") ==
+           Z_OK);
+    assert_config_value(z_loan(config), "metadata/name", "\"{a: {b: 1}}\"");
+    z_drop(z_move(config));
+}
+
+void from_files() {
+    write_file("zc_test_config_base.json5",
+               "{mode: 'client', connect: {endpoints: ['tcp/127.0.0.1:7447']}, scouting: {timeout: 1000}}");
+    write_file("zc_test_config_site.json5",
+               "{include: 'zc_test_config_base.json5', connect: {endpoints: ['tcp/${ZC_TEST_CONFIG_HOST:-10.0.0.1}:7447']}}");
+    write_file("zc_test_config_local.json5", "{scouting: {timeout: 2000}}");
+    write_file("zc_test_config_cycle.json5", "{include: ['zc_test_config_cycle.json5']}");
+
+    const char *paths[] = {"zc_test_config_site.json5", "zc_test_config_local.json5"};
+    z_owned_config_t config;
+    assert(zc_config_from_files(&config, paths, 2) == Z_OK);
+    assert_config_value(z_loan(config), "mode", "\"client\"");
+    assert_config_value(z_loan(config), "connect/endpoints", "[\"tcp/10.0.0.1:7447\"]");
+    assert_config_value(z_loan(config), "scouting/timeout", "2000");
+    z_drop(z_move(config));
+
+    const char *cycle[] = {"zc_test_config_cycle.json5"};
+    assert(zc_config_from_files(&config, cycle, 1) != Z_OK);
+    assert(!z_internal_check(config));
+    const char *missing[] = {"zc_test_config_missing.json5"};
+    assert(zc_config_from_files(&config, missing, 1) != Z_OK);
+    assert(!z_internal_check(config));
+
+    remove("zc_test_config_base.json5");
+    remove("zc_test_config_site.json5");
+    remove("zc_test_config_local.json5");
+    remove("zc_test_config_cycle.json5");
+}
+#endif
+
 int main(int argc, char **argv) {
     zc_try_init_log_from_env();
     insert_get();
+#if defined(Z_FEATURE_UNSTABLE_API)
+    last_error();
+    typed_setters();
+    validate();
+    diff();
+    from_str_with_env();
+    from_files();
+#endif
 }
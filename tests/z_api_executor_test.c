@@ -0,0 +1,198 @@
+//
+// Copyright (c) 2025 ZettaScale Technology
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
+#include <stdio.h>
+#include <stdlib.h>
+#include <string.h>
+
+#include "zenoh.h"
+
+#undef NDEBUG
+#include <assert.h>
+
+#if defined(Z_FEATURE_UNSTABLE_API)
+
+#define N 50
+#define QUEUE_CAPACITY 1024
+
+typedef struct executor_t {
+    z_owned_mutex_t mutex;
+    zc_owned_work_item_t items[QUEUE_CAPACITY];
+    size_t head;
+    size_t tail;
+    bool dropped;
+} executor_t;
+
+void on_work_item(zc_loaned_work_item_t* work_item, void* context) {
+    executor_t* executor = (executor_t*)context;
+    z_mutex_lock(z_mutex_loan_mut(&executor->mutex));
+    assert(executor->tail - executor->head < QUEUE_CAPACITY);
+    zc_work_item_take_from_loaned(&executor->items[executor->tail % QUEUE_CAPACITY], work_item);
+    executor->tail++;
+    z_mutex_unlock(z_mutex_loan_mut(&executor->mutex));
+}
+
+void on_executor_drop(void* context) {
+    executor_t* executor = (executor_t*)context;
+    executor->dropped = true;
+}
+
+// Runs the queued work items on the calling thread, returns the number of items run.
+size_t executor_run_pending(executor_t* executor) {
+    size_t count = 0;
+    while (true) {
+        zc_owned_work_item_t item;
+        z_mutex_lock(z_mutex_loan_mut(&executor->mutex));
+        if (executor->head == executor->tail) {
+            z_mutex_unlock(z_mutex_loan_mut(&executor->mutex));
+            return count;
+        }
+        item = executor->items[executor->head % QUEUE_CAPACITY];
+        executor->head++;
+        z_mutex_unlock(z_mutex_loan_mut(&executor->mutex));
+        zc_work_item_run(zc_work_item_move(&item));
+        count++;
+    }
+}
+
+typedef struct received_t {
+    int values[N];
+    size_t count;
+    bool dropped;
+} received_t;
+
+void on_sample(z_loaned_sample_t* sample, void* context) {
+    received_t* received = (received_t*)context;
+    z_owned_string_t s;
+    z_bytes_to_string(z_sample_payload(sample), &s);
+    char buf[16] = {0};
+    size_t len = z_string_len(z_string_loan(&s));
+    assert(len < sizeof(buf));
+    memcpy(buf, z_string_data(z_string_loan(&s)), len);
+    z_string_drop(z_string_move(&s));
+    assert(received->count < N);
+    received->values[received->count++] = atoi(buf);
+}
+
+void on_sample_drop(void* context) {
+    received_t* received = (received_t*)context;
+    received->dropped = true;
+}
+
+void test_executor_dispatch(void) {
+    printf("test_executor_dispatch\n");
+    executor_t executor;
+    memset(&executor, 0, sizeof(executor));
+    z_mutex_init(&executor.mutex);
+
+    zc_owned_closure_work_item_t executor_closure;
+    zc_closure_work_item(&executor_closure, on_work_item, on_executor_drop, &executor);
+
+    z_owned_config_t c1, c2;
+    z_config_default(&c1);
+    z_config_default(&c2);
+    z_open_options_t opts;
+    z_open_options_default(&opts);
+    opts.executor = zc_closure_work_item_move(&executor_closure);
+    z_owned_session_t s1, s2;
+    assert(z_open(&s1, z_config_move(&c1), &opts) == Z_OK);
+    assert(z_open(&s2, z_config_move(&c2), NULL) == Z_OK);
+
+    z_view_keyexpr_t ke;
+    z_view_keyexpr_from_str(&ke, "zenoh-c/test/executor");
+
+    received_t received;
+    memset(&received, 0, sizeof(received));
+    z_owned_closure_sample_t callback;
+    z_closure_sample(&callback, on_sample, on_sample_drop, &received);
+    z_owned_subscriber_t sub;
+    assert(z_declare_subscriber(z_session_loan(&s1), &sub, z_view_keyexpr_loan(&ke), z_closure_sample_move(&callback),
+                                NULL) == Z_OK);
+    z_sleep_s(1);
+
+    for (int i = 0; i < N; i++) {
+        char buf[16];
+        snprintf(buf, sizeof(buf), "%d", i);
+        z_owned_bytes_t payload;
+        z_bytes_copy_from_str(&payload, buf);
+        z_put(z_session_loan(&s2), z_view_keyexpr_loan(&ke), z_bytes_move(&payload), NULL);
+    }
+    z_sleep_s(1);
+
+    // Callbacks are only run by the executor.
+    assert(received.count == 0);
+    size_t runs = 0;
+    while (received.count < N && runs < 100) {
+        executor_run_pending(&executor);
+        z_sleep_ms(10);
+        runs++;
+    }
+    assert(received.count == N);
+    for (int i = 0; i < N; i++) {
+        assert(received.values[i] == i);
+    }
+
+    // Pending callbacks are discarded when the subscriber is undeclared.
+    for (int i = 0; i < N; i++) {
+        z_owned_bytes_t payload;
+        z_bytes_copy_from_str(&payload, "0");
+        z_put(z_session_loan(&s2), z_view_keyexpr_loan(&ke), z_bytes_move(&payload), NULL);
+    }
+    z_sleep_s(1);
+    z_subscriber_drop(z_subscriber_move(&sub));
+    assert(received.dropped);
+    executor_run_pending(&executor);
+    assert(received.count == N);
+
+    // The executor is unregistered when the session is closed, and dropped once its work items are.
+    z_close(z_session_loan_mut(&s1), NULL);
+    executor_run_pending(&executor);
+    assert(executor.dropped);
+
+    z_session_drop(z_session_move(&s2));
+    z_session_drop(z_session_move(&s1));
+    z_mutex_drop(z_mutex_move(&executor.mutex));
+}
+
+void test_executor_empty(void) {
+    printf("test_executor_empty\n");
+    zc_owned_closure_work_item_t executor_closure;
+    zc_internal_closure_work_item_null(&executor_closure);
+    assert(!zc_internal_closure_work_item_check(&executor_closure));
+
+    zc_owned_work_item_t item;
+    zc_internal_work_item_null(&item);
+    assert(!zc_internal_work_item_check(&item));
+    // Running or dropping an empty work item is a no-op.
+    zc_work_item_run(zc_work_item_move(&item));
+    zc_work_item_drop(zc_work_item_move(&item));
+
+    // An empty executor closure is ignored, callbacks are run on zenoh threads.
+    z_owned_config_t c;
+    z_config_default(&c);
+    z_open_options_t opts;
+    z_open_options_default(&opts);
+    opts.executor = zc_closure_work_item_move(&executor_closure);
+    z_owned_session_t s;
+    assert(z_open(&s, z_config_move(&c), &opts) == Z_OK);
+    z_session_drop(z_session_move(&s));
+}
+
+int main(void) {
+    test_executor_dispatch();
+    test_executor_empty();
+    return 0;
+}
+
+#else
+int main(void) { return 0; }
+#endif
@@ -0,0 +1,211 @@
+//
+// Copyright (c) 2025 ZettaScale Technology
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
+#include <stdio.h>
+#include <string.h>
+
+#include "zenoh.h"
+
+#undef NDEBUG
+#include <assert.h>
+
+#if defined(Z_FEATURE_UNSTABLE_API)
+
+#define STORAGE_PATH "z_api_file_storage_test.db"
+#define STORAGE_KEYEXPR "zenoh-c/test/file_storage/**"
+
+typedef struct replies_t {
+    int count;
+    char last[32];
+    char last_attachment[32];
+} replies_t;
+
+void copy_bytes(const z_loaned_bytes_t* bytes, char* out, size_t size) {
+    z_owned_string_t s;
+    z_bytes_to_string(bytes, &s);
+    size_t len = z_string_len(z_loan(s));
+    assert(len < size);
+    memcpy(out, z_string_data(z_loan(s)), len);
+    out[len] = '\0';
+    z_drop(z_move(s));
+}
+
+void on_reply(z_loaned_reply_t* reply, void* context) {
+    replies_t* replies = (replies_t*)context;
+    assert(z_reply_is_ok(reply));
+    const z_loaned_sample_t* sample = z_reply_ok(reply);
+    copy_bytes(z_sample_payload(sample), replies->last, sizeof(replies->last));
+    replies->last_attachment[0] = '\0';
+    if (z_sample_attachment(sample) != NULL) {
+        copy_bytes(z_sample_attachment(sample), replies->last_attachment, sizeof(replies->last_attachment));
+    }
+    replies->count++;
+}
+
+replies_t get(const z_loaned_session_t* s, const char* key, const char* parameters) {
+    replies_t replies = {0};
+    z_view_keyexpr_t ke;
+    z_view_keyexpr_from_str(&ke, key);
+    z_owned_closure_reply_t cb;
+    z_closure(&cb, on_reply, NULL, &replies);
+    assert(z_get(s, z_loan(ke), parameters, z_move(cb), NULL) == Z_OK);
+    z_sleep_ms(500);
+    return replies;
+}
+
+void put(const z_loaned_session_t* s, const char* key, const char* value, const char* attachment) {
+    z_view_keyexpr_t ke;
+    z_view_keyexpr_from_str(&ke, key);
+    z_owned_bytes_t payload;
+    z_bytes_copy_from_str(&payload, value);
+    z_put_options_t opts;
+    z_put_options_default(&opts);
+    z_owned_bytes_t a;
+    if (attachment != NULL) {
+        z_bytes_copy_from_str(&a, attachment);
+        opts.attachment = z_move(a);
+    }
+    assert(z_put(s, z_loan(ke), z_move(payload), &opts) == Z_OK);
+    z_sleep_ms(10);
+}
+
+void declare(const z_loaned_session_t* s, ze_owned_file_storage_t* storage) {
+    z_view_keyexpr_t ke;
+    z_view_keyexpr_from_str(&ke, STORAGE_KEYEXPR);
+    ze_file_storage_options_t opts;
+    ze_file_storage_options_default(&opts);
+    opts.history = 2;
+    assert(ze_declare_file_storage(s, storage, z_loan(ke), STORAGE_PATH, &opts) == Z_OK);
+    z_sleep_s(1);
+}
+
+long file_size(const char* path) {
+    FILE* f = fopen(path, "rb");
+    assert(f != NULL);
+    fseek(f, 0, SEEK_END);
+    long size = ftell(f);
+    fclose(f);
+    return size;
+}
+
+void append_bytes(const char* path, const unsigned char* bytes, size_t len) {
+    FILE* f = fopen(path, "ab");
+    assert(f != NULL);
+    assert(fwrite(bytes, 1, len, f) == len);
+    fclose(f);
+}
+
+unsigned char patch_byte(const char* path, long offset, unsigned char value) {
+    FILE* f = fopen(path, "r+b");
+    assert(f != NULL);
+    fseek(f, offset, SEEK_SET);
+    unsigned char old = (unsigned char)fgetc(f);
+    fseek(f, offset, SEEK_SET);
+    fputc(value, f);
+    fclose(f);
+    return old;
+}
+
+void test_file_storage(void) {
+    printf("test_file_storage\n");
+    remove(STORAGE_PATH);
+    z_owned_config_t c;
+    z_config_default(&c);
+    z_owned_session_t s;
+    assert(z_open(&s, z_move(c), NULL) == Z_OK);
+
+    ze_owned_file_storage_t storage;
+    declare(z_loan(s), &storage);
+    put(z_loan(s), "zenoh-c/test/file_storage/a", "1", NULL);
+    put(z_loan(s), "zenoh-c/test/file_storage/a", "2", NULL);
+    put(z_loan(s), "zenoh-c/test/file_storage/a", "3", "attachment");
+    put(z_loan(s), "zenoh-c/test/file_storage/b", "4", NULL);
+    z_view_keyexpr_t b;
+    z_view_keyexpr_from_str(&b, "zenoh-c/test/file_storage/b");
+    assert(z_delete(z_loan(s), z_loan(b), NULL) == Z_OK);
+    z_sleep_ms(100);
+    replies_t replies = get(z_loan(s), STORAGE_KEYEXPR, "");
+    assert(replies.count == 1);
+    assert(strcmp(replies.last, "3") == 0);
+    assert(ze_undeclare_file_storage(z_move(storage)) == Z_OK);
+    replies = get(z_loan(s), STORAGE_KEYEXPR, "");
+    assert(replies.count == 0);
+
+    // The samples are restored from the file.
+    declare(z_loan(s), &storage);
+    replies = get(z_loan(s), "zenoh-c/test/file_storage/a", "");
+    assert(replies.count == 1);
+    assert(strcmp(replies.last, "3") == 0);
+    assert(strcmp(replies.last_attachment, "attachment") == 0);
+    replies = get(z_loan(s), "zenoh-c/test/file_storage/a", "_time=[..]");
+    assert(replies.count == 2);
+    replies = get(z_loan(s), "zenoh-c/test/file_storage/b", "_time=[..]");
+    assert(replies.count == 0);
+
+    // Compaction keeps the stored samples.
+    assert(ze_file_storage_compact(z_loan(storage)) == Z_OK);
+    z_drop(z_move(storage));
+    declare(z_loan(s), &storage);
+    replies = get(z_loan(s), "zenoh-c/test/file_storage/a", "_time=[..]");
+    assert(replies.count == 2);
+    assert(strcmp(replies.last, "3") == 0);
+    replies = get(z_loan(s), "zenoh-c/test/file_storage/b", "");
+    assert(replies.count == 0);
+    z_drop(z_move(storage));
+
+    // A record torn by an interrupted append is discarded.
+    long size = file_size(STORAGE_PATH);
+    const unsigned char torn[] = {100, 0, 0, 0, 0x9b, 0xff, 0xff, 0xff, 0, 1, 2};
+    append_bytes(STORAGE_PATH, torn, sizeof(torn));
+    declare(z_loan(s), &storage);
+    assert(file_size(STORAGE_PATH) == size);
+    replies = get(z_loan(s), "zenoh-c/test/file_storage/a", "_time=[..]");
+    assert(replies.count == 2);
+    z_drop(z_move(storage));
+
+    // A corrupted length in the middle of the file is reported, leaving the file untouched.
+    z_view_keyexpr_t storage_ke;
+    z_view_keyexpr_from_str(&storage_ke, STORAGE_KEYEXPR);
+    unsigned char old = patch_byte(STORAGE_PATH, 8, 0xff);
+    assert(ze_declare_file_storage(z_loan(s), &storage, z_loan(storage_ke), STORAGE_PATH, NULL) == Z_EIO);
+    assert(!z_internal_check(storage));
+    assert(file_size(STORAGE_PATH) == size);
+    assert(patch_byte(STORAGE_PATH, 8, old) == 0xff);
+
+    // Any other invalid record is reported, leaving the file untouched.
+    const unsigned char invalid[] = {1, 0, 0, 0, 0xfe, 0xff, 0xff, 0xff, 0, 0, 0, 0, 9};
+    append_bytes(STORAGE_PATH, invalid, sizeof(invalid));
+    assert(ze_declare_file_storage(z_loan(s), &storage, z_loan(storage_ke), STORAGE_PATH, NULL) == Z_EIO);
+    assert(!z_internal_check(storage));
+    assert(file_size(STORAGE_PATH) == size + (long)sizeof(invalid));
+
+    // A file that is not a storage file is rejected.
+    FILE* f = fopen(STORAGE_PATH, "wb");
+    fputs("not a storage", f);
+    fclose(f);
+    z_view_keyexpr_t ke;
+    z_view_keyexpr_from_str(&ke, STORAGE_KEYEXPR);
+    assert(ze_declare_file_storage(z_loan(s), &storage, z_loan(ke), STORAGE_PATH, NULL) != Z_OK);
+    assert(!z_internal_check(storage));
+
+    remove(STORAGE_PATH);
+    z_drop(z_move(s));
+}
+
+int main(void) {
+    test_file_storage();
+    return 0;
+}
+
+#else
+int main(void) { return 0; }
+#endif
@@ -0,0 +1,159 @@
+//
+// Copyright (c) 2025 ZettaScale Technology
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
+#include <stddef.h>
+#include <stdint.h>
+#include <stdio.h>
+#include <string.h>
+
+#include "zenoh.h"
+
+#undef NDEBUG
+#include <assert.h>
+
+#if defined(Z_FEATURE_UNSTABLE_API)
+
+bool string_equals(const z_loaned_string_t* s, const char* expected) {
+    return z_string_len(s) == strlen(expected) && strncmp(z_string_data(s), expected, strlen(expected)) == 0;
+}
+
+// Checks that `json` is converted to a payload of type `hint` and back to `json`.
+void check_roundtrip(const char* hint, const char* json) {
+    z_owned_bytes_t payload;
+    assert(ze_serialize_json(&payload, json, hint) == Z_OK);
+    z_owned_string_t out;
+    assert(ze_deserialize_json(z_loan(payload), hint, &out) == Z_OK);
+    assert(string_equals(z_loan(out), json));
+    z_drop(z_move(out));
+    z_drop(z_move(payload));
+}
+
+void test_deserialize_json(void) {
+    printf("test_deserialize_json\n");
+    ze_owned_serializer_t serializer;
+    ze_serializer_empty(&serializer);
+    ze_loaned_serializer_t* s = z_loan_mut(serializer);
+    ze_serializer_serialize_uint32(s, 1);
+    ze_serializer_serialize_str(s, "abc");
+    ze_serializer_serialize_sequence_length(s, 2);
+    ze_serializer_serialize_double(s, 0.5);
+    ze_serializer_serialize_double(s, -1.5);
+    z_owned_bytes_t payload;
+    ze_serializer_finish(z_move(serializer), &payload);
+
+    z_owned_string_t json;
+    assert(ze_deserialize_json(z_loan(payload), "(u32, string, [f64])", &json) == Z_OK);
+    assert(string_equals(z_loan(json), "[1,\"abc\",[0.5,-1.5]]"));
+    z_drop(z_move(json));
+
+    // The payload does not match the type.
+    assert(ze_deserialize_json(z_loan(payload), "(u32, string, [f64], bool)", &json) == Z_EDESERIALIZE);
+    assert(ze_deserialize_json(z_loan(payload), "(u32, string)", &json) == Z_EDESERIALIZE);
+    z_drop(z_move(json));
+    z_drop(z_move(payload));
+
+    // Sequence lengths greater than the payload size are rejected, even for elements taking no space.
+    ze_serializer_empty(&serializer);
+    ze_serializer_serialize_sequence_length(z_loan_mut(serializer), SIZE_MAX);
+    ze_serializer_finish(z_move(serializer), &payload);
+    assert(ze_deserialize_json(z_loan(payload), "[()]", &json) == Z_EDESERIALIZE);
+    z_drop(z_move(json));
+    z_drop(z_move(payload));
+}
+
+void test_json_roundtrip(void) {
+    printf("test_json_roundtrip\n");
+    check_roundtrip("(u32, string, [f64])", "[1,\"abc\",[0.5,-1.5]]");
+    check_roundtrip("( i8 , f32 , bool , )", "[-8,0.1,true]");
+    check_roundtrip("{string: i32}", "{\"a\":1,\"b\":-2}");
+    // Keys are kept in order, and repeated keys are not merged.
+    check_roundtrip("{string: i32}", "{\"b\":1,\"a\":-2}");
+    check_roundtrip("{string: i32}", "[[\"a\",1],[\"a\",2]]");
+    check_roundtrip("{u8: [bool]}", "[[1,[true,false]],[2,[]]]");
+    check_roundtrip("[option<u64>]", "[null,18446744073709551615]");
+    check_roundtrip("[(u16, {string: string})]", "[[7,{\"key\":\"value\"}]]");
+}
+
+void test_invalid_json(void) {
+    printf("test_invalid_json\n");
+    z_owned_bytes_t payload;
+    // Invalid type hints.
+    assert(ze_serialize_json(&payload, "1", "u128") == Z_EPARSE);
+    assert(ze_serialize_json(&payload, "[1]", "[u8") == Z_EPARSE);
+    assert(ze_serialize_json(&payload, "1", "u8 u8") == Z_EPARSE);
+    // Type hints nested too deeply.
+    static char hint[2 * 100000 + 3];
+    memset(hint, '[', 100000);
+    strcpy(hint + 100000, "u8");
+    memset(hint + 100002, ']', 100000);
+    hint[2 * 100000 + 2] = '\0';
+    assert(ze_serialize_json(&payload, "[]", hint) == Z_EPARSE);
+    // The maximal nesting is accepted.
+    strcpy(hint + 127, "u8");
+    memset(hint + 129, ']', 127);
+    hint[256] = '\0';
+    assert(ze_serialize_json(&payload, "[]", hint) == Z_OK);
+    z_drop(z_move(payload));
+    // Invalid JSON.
+    assert(ze_serialize_json(&payload, "[1,", "[u8]") == Z_EPARSE);
+    // JSON not matching the type hint.
+    assert(ze_serialize_json(&payload, "300", "u8") == Z_EINVAL);
+    assert(ze_serialize_json(&payload, "-1", "u32") == Z_EINVAL);
+    assert(ze_serialize_json(&payload, "[1,2]", "(u8, u8, u8)") == Z_EINVAL);
+    assert(ze_serialize_json(&payload, "[[1]]", "{u8: u8}") == Z_EINVAL);
+    assert(ze_serialize_json(&payload, "\"abc\"", "[string]") == Z_EINVAL);
+    z_drop(z_move(payload));
+}
+
+typedef struct {
+    float x;
+    float y;
+} point_t;
+
+void test_schema_json(void) {
+    printf("test_schema_json\n");
+    ze_owned_schema_t schema;
+    ze_schema_new(&schema, sizeof(point_t));
+    ze_schema_add_field(z_loan_mut(schema), "x", offsetof(point_t, x), ZE_SCHEMA_TYPE_FLOAT);
+    ze_schema_add_field(z_loan_mut(schema), "y", offsetof(point_t, y), ZE_SCHEMA_TYPE_FLOAT);
+
+    z_owned_bytes_t payload;
+    assert(ze_schema_serialize_json(z_loan(schema), "{\"x\": 0.1, \"y\": 2}", &payload) == Z_OK);
+    point_t point;
+    assert(ze_schema_deserialize(z_loan(schema), z_loan(payload), &point) == Z_OK);
+    assert(point.x == 0.1f && point.y == 2.0f);
+
+    z_owned_string_t json;
+    assert(ze_schema_deserialize_json(z_loan(schema), z_loan(payload), &json) == Z_OK);
+    assert(string_equals(z_loan(json), "{\"x\":0.1,\"y\":2.0}"));
+    z_drop(z_move(json));
+    z_drop(z_move(payload));
+
+    // Missing and unknown fields.
+    assert(ze_schema_serialize_json(z_loan(schema), "{\"x\": 1}", &payload) == Z_EINVAL);
+    assert(ze_schema_serialize_json(z_loan(schema), "{\"x\": 1, \"y\": 2, \"z\": 3}", &payload) == Z_EINVAL);
+    z_drop(z_move(payload));
+
+    z_drop(z_move(schema));
+}
+
+int main(void) {
+    test_deserialize_json();
+    test_json_roundtrip();
+    test_invalid_json();
+    test_schema_json();
+    return 0;
+}
+
+#else
+int main(void) { return 0; }
+#endif
@@ -109,6 +109,183 @@ void relation_to() {
     assert(z_keyexpr_relation_to(z_loan(foostar), z_loan(foostar)) == Z_KEYEXPR_INTERSECTION_LEVEL_EQUALS);
     assert(z_keyexpr_relation_to(z_loan(barstar), z_loan(foobar)) == Z_KEYEXPR_INTERSECTION_LEVEL_DISJOINT);
 }
+
+static int tree_dropped = 0;
+void tree_value_drop(void *value) { tree_dropped += *(int *)value; }
+
+// Returns the sum of the values of the iterated entries, checking that their keys match `pattern`.
+int tree_iterator_sum(z_owned_keyexpr_tree_iterator_t *it, const z_loaned_keyexpr_t *pattern, bool included) {
+    int sum = 0;
+    z_view_keyexpr_t key;
+    void *value;
+    while (z_keyexpr_tree_iterator_next(z_keyexpr_tree_iterator_loan_mut(it), &key, &value)) {
+        if (included) {
+            assert(z_keyexpr_includes(pattern, z_loan(key)));
+        } else {
+            assert(z_keyexpr_intersects(pattern, z_loan(key)));
+        }
+        sum += *(int *)value;
+    }
+    z_keyexpr_tree_iterator_drop(z_keyexpr_tree_iterator_move(it));
+    return sum;
+}
+
+void keyexpr_tree() {
+    static int values[] = {1, 2, 4, 8, 16};
+    const char *keys[] = {"a/b", "a/c", "a/b/c", "a/*", "d/**"};
+    z_owned_keyexpr_tree_t tree;
+    z_keyexpr_tree_new(&tree, tree_value_drop);
+    assert(z_internal_keyexpr_tree_check(&tree));
+    for (int i = 0; i < 5; i++) {
+        z_view_keyexpr_t ke;
+        z_view_keyexpr_from_str(&ke, keys[i]);
+        assert(!z_keyexpr_tree_insert(z_keyexpr_tree_loan_mut(&tree), z_loan(ke), &values[i], NULL));
+    }
+
+    z_view_keyexpr_t ab, astar, ae, dx, all;
+    z_view_keyexpr_from_str(&ab, "a/b");
+    z_view_keyexpr_from_str(&astar, "a/*");
+    z_view_keyexpr_from_str(&ae, "a/e");
+    z_view_keyexpr_from_str(&dx, "d/x/y");
+    z_view_keyexpr_from_str(&all, "**");
+
+    void *value = NULL;
+    assert(z_keyexpr_tree_get(z_keyexpr_tree_loan(&tree), z_loan(ab), &value));
+    assert(*(int *)value == 1);
+    assert(!z_keyexpr_tree_get(z_keyexpr_tree_loan(&tree), z_loan(ae), &value));
+
+    z_owned_keyexpr_tree_iterator_t it;
+    z_keyexpr_tree_intersecting(z_keyexpr_tree_loan(&tree), z_loan(astar), &it);
+    assert(tree_iterator_sum(&it, z_loan(astar), false) == 1 + 2 + 8);
+    z_keyexpr_tree_included(z_keyexpr_tree_loan(&tree), z_loan(astar), &it);
+    assert(tree_iterator_sum(&it, z_loan(astar), true) == 1 + 2 + 8);
+    z_keyexpr_tree_intersecting(z_keyexpr_tree_loan(&tree), z_loan(ae), &it);
+    assert(tree_iterator_sum(&it, z_loan(ae), false) == 8);
+    z_keyexpr_tree_intersecting(z_keyexpr_tree_loan(&tree), z_loan(dx), &it);
+    assert(tree_iterator_sum(&it, z_loan(dx), false) == 16);
+    z_keyexpr_tree_included(z_keyexpr_tree_loan(&tree), z_loan(all), &it);
+    assert(tree_iterator_sum(&it, z_loan(all), true) == 31);
+
+    // Replaced and removed values are returned to the caller, or dropped by the tree.
+    static int replacement = 32;
+    void *previous = NULL;
+    assert(z_keyexpr_tree_insert(z_keyexpr_tree_loan_mut(&tree), z_loan(ab), &replacement, &previous));
+    assert(previous == &values[0]);
+    assert(tree_dropped == 0);
+    assert(z_keyexpr_tree_remove(z_keyexpr_tree_loan_mut(&tree), z_loan(astar), NULL));
+    assert(tree_dropped == 8);
+    assert(!z_keyexpr_tree_remove(z_keyexpr_tree_loan_mut(&tree), z_loan(astar), NULL));
+    z_keyexpr_tree_intersecting(z_keyexpr_tree_loan(&tree), z_loan(ae), &it);
+    assert(tree_iterator_sum(&it, z_loan(ae), false) == 0);
+
+    z_keyexpr_tree_drop(z_keyexpr_tree_move(&tree));
+    assert(!z_internal_keyexpr_tree_check(&tree));
+    assert(tree_dropped == 8 + 32 + 2 + 4 + 16);
+}
+
+bool keyexpr_equals_str(const z_loaned_keyexpr_t *ke, const char *expected) {
+    z_view_string_t s;
+    z_keyexpr_as_view_string(ke, &s);
+    return z_string_len(z_loan(s)) == strlen(expected) && strncmp(z_string_data(z_loan(s)), expected, strlen(expected)) == 0;
+}
+
+void chunks() {
+    z_view_keyexpr_t ke;
+    z_view_keyexpr_from_str(&ke, "a/b/**/c");
+    assert(z_keyexpr_chunk_count(z_loan(ke)) == 4);
+    assert(z_keyexpr_is_wild(z_loan(ke)));
+
+    const char *expected[] = {"a", "b", "**", "c"};
+    z_keyexpr_chunk_iterator_t it = z_keyexpr_get_chunk_iterator(z_loan(ke));
+    z_view_keyexpr_t chunk;
+    size_t n = 0;
+    while (z_keyexpr_chunk_iterator_next(&it, &chunk)) {
+        assert(n < 4);
+        assert(keyexpr_equals_str(z_loan(chunk), expected[n]));
+        n++;
+    }
+    assert(n == 4);
+
+    z_view_keyexpr_t part;
+    assert(z_keyexpr_prefix(z_loan(ke), 2, &part) == Z_OK);
+    assert(keyexpr_equals_str(z_loan(part), "a/b"));
+    assert(!z_keyexpr_is_wild(z_loan(part)));
+    assert(z_keyexpr_prefix(z_loan(ke), 4, &part) == Z_OK);
+    assert(keyexpr_equals_str(z_loan(part), "a/b/**/c"));
+    assert(z_keyexpr_prefix(z_loan(ke), 0, &part) != Z_OK);
+    assert(z_keyexpr_prefix(z_loan(ke), 5, &part) != Z_OK);
+    assert(z_keyexpr_suffix(z_loan(ke), 2, &part) == Z_OK);
+    assert(keyexpr_equals_str(z_loan(part), "**/c"));
+    assert(z_keyexpr_suffix(z_loan(ke), 1, &part) == Z_OK);
+    assert(keyexpr_equals_str(z_loan(part), "c"));
+    assert(z_keyexpr_suffix(z_loan(ke), 5, &part) != Z_OK);
+
+    z_view_keyexpr_t prefix;
+    z_view_keyexpr_from_str(&prefix, "a/b");
+    assert(z_keyexpr_strip_prefix(z_loan(ke), z_loan(prefix), &part) == Z_OK);
+    assert(keyexpr_equals_str(z_loan(part), "**/c"));
+    z_view_keyexpr_from_str(&prefix, "a/b/**/c");
+    assert(z_keyexpr_strip_prefix(z_loan(ke), z_loan(prefix), &part) != Z_OK);
+    z_view_keyexpr_from_str(&prefix, "a/bb");
+    z_view_keyexpr_t abb;
+    z_view_keyexpr_from_str(&abb, "a/bbb/c");
+    assert(z_keyexpr_strip_prefix(z_loan(abb), z_loan(prefix), &part) != Z_OK);
+}
+
+bool view_string_equals(const z_view_string_t *s, const char *expected) {
+    return z_string_len(z_loan(*s)) == strlen(expected) &&
+           strncmp(z_string_data(z_loan(*s)), expected, strlen(expected)) == 0;
+}
+
+void keyexpr_format() {
+    z_owned_keyexpr_format_t format;
+    assert(z_keyexpr_format_from_str(&format, "robot/${id:*}/sensor/${kind:*#temperature}/${rest:**}") == Z_OK);
+    assert(z_internal_keyexpr_format_check(&format));
+
+    const char *names[] = {"id", "kind", "rest"};
+    const char *values[] = {"r2", "lidar", "a/b"};
+    z_owned_keyexpr_t ke;
+    assert(z_keyexpr_format_build(z_keyexpr_format_loan(&format), names, values, 3, &ke) == Z_OK);
+    z_view_string_t s;
+    z_keyexpr_as_view_string(z_loan(ke), &s);
+    assert(view_string_equals(&s, "robot/r2/sensor/lidar/a/b"));
+
+    z_view_string_t parsed[3];
+    assert(z_keyexpr_format_parse(z_keyexpr_format_loan(&format), z_loan(ke), names, 3, parsed) == Z_OK);
+    assert(view_string_equals(&parsed[0], "r2"));
+    assert(view_string_equals(&parsed[1], "lidar"));
+    assert(view_string_equals(&parsed[2], "a/b"));
+    z_drop(z_move(ke));
+
+    // Default values are used for missing chunks, and `**` may match nothing.
+    assert(z_keyexpr_format_build(z_keyexpr_format_loan(&format), names, values, 1, &ke) == Z_OK);
+    z_keyexpr_as_view_string(z_loan(ke), &s);
+    assert(view_string_equals(&s, "robot/r2/sensor/temperature"));
+    assert(z_keyexpr_format_parse(z_keyexpr_format_loan(&format), z_loan(ke), names, 3, parsed) == Z_OK);
+    assert(view_string_equals(&parsed[1], "temperature"));
+    assert(z_string_is_empty(z_loan(parsed[2])));
+    z_drop(z_move(ke));
+
+    // Values must match the pattern of their chunk, and keys must match the format.
+    const char *invalid[] = {"r2/r3"};
+    assert(z_keyexpr_format_build(z_keyexpr_format_loan(&format), names, invalid, 1, &ke) != Z_OK);
+    assert(!z_internal_check(ke));
+    const char *unknown[] = {"unknown"};
+    assert(z_keyexpr_format_build(z_keyexpr_format_loan(&format), unknown, values, 1, &ke) != Z_OK);
+    z_view_keyexpr_t other;
+    z_view_keyexpr_from_str(&other, "robot/r2/actuator/arm");
+    assert(z_keyexpr_format_parse(z_keyexpr_format_loan(&format), z_loan(other), names, 3, parsed) != Z_OK);
+    // Arrays should not be NULL unless empty.
+    assert(z_keyexpr_format_build(z_keyexpr_format_loan(&format), NULL, values, 1, &ke) == Z_EINVAL);
+    assert(!z_internal_check(ke));
+    assert(z_keyexpr_format_build(z_keyexpr_format_loan(&format), names, NULL, 1, &ke) == Z_EINVAL);
+    assert(z_keyexpr_format_parse(z_keyexpr_format_loan(&format), z_loan(other), NULL, 1, parsed) == Z_EINVAL);
+
+    z_keyexpr_format_drop(z_keyexpr_format_move(&format));
+    assert(!z_internal_keyexpr_format_check(&format));
+    assert(z_keyexpr_format_from_str(&format, "robot/${id:*") != Z_OK);
+    assert(!z_internal_keyexpr_format_check(&format));
+}
 #endif
 
 int main(int argc, char **argv) {
@@ -118,5 +295,8 @@ int main(int argc, char **argv) {
     undeclare();
 #if defined(Z_FEATURE_UNSTABLE_API)
     relation_to();
+    keyexpr_tree();
+    keyexpr_format();
+    chunks();
 #endif
 }
@@ -0,0 +1,221 @@
+//
+// Copyright (c) 2025 ZettaScale Technology
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
+#include <stdio.h>
+#include <string.h>
+
+#include "zenoh.h"
+
+#undef NDEBUG
+#include <assert.h>
+
+#if defined(Z_FEATURE_UNSTABLE_API)
+
+typedef struct replies_t {
+    int count;
+    int errors;
+    char last[32];
+} replies_t;
+
+void on_reply(z_loaned_reply_t* reply, void* context) {
+    replies_t* replies = (replies_t*)context;
+    if (!z_reply_is_ok(reply)) {
+        replies->errors++;
+        return;
+    }
+    const z_loaned_sample_t* sample = z_reply_ok(reply);
+    assert(z_sample_timestamp(sample) != NULL);
+    z_owned_string_t s;
+    z_bytes_to_string(z_sample_payload(sample), &s);
+    size_t len = z_string_len(z_loan(s));
+    assert(len < sizeof(replies->last));
+    memcpy(replies->last, z_string_data(z_loan(s)), len);
+    replies->last[len] = '\0';
+    z_drop(z_move(s));
+    replies->count++;
+}
+
+replies_t get(const z_loaned_session_t* s, const char* key, const char* parameters) {
+    replies_t replies = {0};
+    z_view_keyexpr_t ke;
+    z_view_keyexpr_from_str(&ke, key);
+    z_owned_closure_reply_t cb;
+    z_closure(&cb, on_reply, NULL, &replies);
+    assert(z_get(s, z_loan(ke), parameters, z_move(cb), NULL) == Z_OK);
+    z_sleep_ms(500);
+    return replies;
+}
+
+void put(const z_loaned_session_t* s, const char* key, const char* value) {
+    z_view_keyexpr_t ke;
+    z_view_keyexpr_from_str(&ke, key);
+    z_owned_bytes_t payload;
+    z_bytes_copy_from_str(&payload, value);
+    assert(z_put(s, z_loan(ke), z_move(payload), NULL) == Z_OK);
+    z_sleep_ms(10);
+}
+
+void test_memory_storage(void) {
+    printf("test_memory_storage\n");
+    z_owned_config_t c;
+    z_config_default(&c);
+    z_owned_session_t s;
+    assert(z_open(&s, z_move(c), NULL) == Z_OK);
+
+    z_view_keyexpr_t ke;
+    z_view_keyexpr_from_str(&ke, "zenoh-c/test/storage/**");
+    ze_memory_storage_options_t opts;
+    ze_memory_storage_options_default(&opts);
+    opts.history = 0;
+    ze_owned_memory_storage_t storage;
+    assert(ze_declare_memory_storage(z_loan(s), &storage, z_loan(ke), &opts) != Z_OK);
+    assert(!z_internal_check(storage));
+
+    opts.history = 2;
+    assert(ze_declare_memory_storage(z_loan(s), &storage, z_loan(ke), &opts) == Z_OK);
+    assert(z_keyexpr_equals(ze_memory_storage_keyexpr(z_loan(storage)), z_loan(ke)));
+    z_sleep_s(1);
+
+    put(z_loan(s), "zenoh-c/test/storage/a", "1");
+    put(z_loan(s), "zenoh-c/test/storage/a", "2");
+    put(z_loan(s), "zenoh-c/test/storage/a", "3");
+    put(z_loan(s), "zenoh-c/test/storage/b", "4");
+    z_sleep_ms(100);
+
+    // Only the latest sample of each key is returned by default.
+    replies_t replies = get(z_loan(s), "zenoh-c/test/storage/a", "");
+    assert(replies.count == 1);
+    assert(strcmp(replies.last, "3") == 0);
+    replies = get(z_loan(s), "zenoh-c/test/storage/**", "");
+    assert(replies.count == 2);
+
+    // All stored samples in the time range are returned when it is set.
+    replies = get(z_loan(s), "zenoh-c/test/storage/a", "_time=[..]");
+    assert(replies.count == 2);
+    assert(strcmp(replies.last, "3") == 0);
+    replies = get(z_loan(s), "zenoh-c/test/storage/a", "_time=[now(1h)..]");
+    assert(replies.count == 0);
+    replies = get(z_loan(s), "zenoh-c/test/storage/a", "_time=[yesterday..]");
+    assert(replies.count == 0);
+    assert(replies.errors == 1);
+
+    z_view_keyexpr_t b;
+    z_view_keyexpr_from_str(&b, "zenoh-c/test/storage/b");
+    assert(z_delete(z_loan(s), z_loan(b), NULL) == Z_OK);
+    z_sleep_ms(100);
+    replies = get(z_loan(s), "zenoh-c/test/storage/b", "_time=[..]");
+    assert(replies.count == 0);
+    put(z_loan(s), "zenoh-c/test/storage/b", "5");
+    z_sleep_ms(100);
+    replies = get(z_loan(s), "zenoh-c/test/storage/b", "");
+    assert(replies.count == 1);
+    assert(strcmp(replies.last, "5") == 0);
+
+    assert(ze_undeclare_memory_storage(z_move(storage)) == Z_OK);
+    assert(!z_internal_check(storage));
+    replies = get(z_loan(s), "zenoh-c/test/storage/a", "");
+    assert(replies.count == 0);
+
+    z_drop(z_move(s));
+}
+
+void put_with_timestamp(const z_loaned_session_t* s, const char* key, const char* value, z_timestamp_t* ts) {
+    z_view_keyexpr_t ke;
+    z_view_keyexpr_from_str(&ke, key);
+    z_owned_bytes_t payload;
+    z_bytes_copy_from_str(&payload, value);
+    z_put_options_t opts;
+    z_put_options_default(&opts);
+    opts.timestamp = ts;
+    assert(z_put(s, z_loan(ke), z_move(payload), &opts) == Z_OK);
+    z_sleep_ms(100);
+}
+
+void test_tombstone_ttl(void) {
+    printf("test_tombstone_ttl\n");
+    z_owned_config_t c;
+    z_config_default(&c);
+    z_owned_session_t s;
+    assert(z_open(&s, z_move(c), NULL) == Z_OK);
+
+    z_view_keyexpr_t ke;
+    z_view_keyexpr_from_str(&ke, "zenoh-c/test/storage_ttl/**");
+    ze_memory_storage_options_t opts;
+    ze_memory_storage_options_default(&opts);
+    assert(opts.tombstone_ttl_ms == 0);
+    opts.tombstone_ttl_ms = 500;
+    ze_owned_memory_storage_t storage;
+    assert(ze_declare_memory_storage(z_loan(s), &storage, z_loan(ke), &opts) == Z_OK);
+    z_sleep_s(1);
+
+    z_timestamp_t old;
+    assert(z_timestamp_new(&old, z_loan(s)) == Z_OK);
+    z_view_keyexpr_t a;
+    z_view_keyexpr_from_str(&a, "zenoh-c/test/storage_ttl/a");
+    assert(z_delete(z_loan(s), z_loan(a), NULL) == Z_OK);
+    z_sleep_ms(100);
+
+    // Samples older than the deletion are ignored as long as its tombstone is kept...
+    put_with_timestamp(z_loan(s), "zenoh-c/test/storage_ttl/a", "1", &old);
+    assert(get(z_loan(s), "zenoh-c/test/storage_ttl/a", "").count == 0);
+
+    // ...and stored again once it is purged.
+    z_sleep_ms(1100);
+    put_with_timestamp(z_loan(s), "zenoh-c/test/storage_ttl/a", "2", &old);
+    replies_t replies = get(z_loan(s), "zenoh-c/test/storage_ttl/a", "");
+    assert(replies.count == 1);
+    assert(strcmp(replies.last, "2") == 0);
+
+    ze_memory_storage_drop(z_move(storage));
+    z_drop(z_move(s));
+}
+
+void test_back_to_back(void) {
+    printf("test_back_to_back\n");
+    z_owned_config_t c;
+    z_config_default(&c);
+    z_owned_session_t s;
+    assert(z_open(&s, z_move(c), NULL) == Z_OK);
+
+    z_view_keyexpr_t ke;
+    z_view_keyexpr_from_str(&ke, "zenoh-c/test/storage_burst/a");
+    ze_memory_storage_options_t opts;
+    ze_memory_storage_options_default(&opts);
+    opts.history = 100;
+    ze_owned_memory_storage_t storage;
+    assert(ze_declare_memory_storage(z_loan(s), &storage, z_loan(ke), &opts) == Z_OK);
+    z_sleep_s(1);
+
+    // Samples received without timestamp are stamped by the session clock, so that none of them is dropped
+    // as a duplicate, however close they are received.
+    for (int i = 0; i < 50; i++) {
+        z_owned_bytes_t payload;
+        z_bytes_copy_from_str(&payload, "x");
+        assert(z_put(z_loan(s), z_loan(ke), z_move(payload), NULL) == Z_OK);
+    }
+    z_sleep_ms(100);
+    assert(get(z_loan(s), "zenoh-c/test/storage_burst/a", "_time=[..]").count == 50);
+
+    ze_memory_storage_drop(z_move(storage));
+    z_drop(z_move(s));
+}
+
+int main(void) {
+    test_memory_storage();
+    test_tombstone_ttl();
+    test_back_to_back();
+    return 0;
+}
+
+#else
+int main(void) { return 0; }
+#endif
@@ -0,0 +1,159 @@
+//
+// Copyright (c) 2025 ZettaScale Technology
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
+#include <stdio.h>
+#include <string.h>
+
+#include "zenoh.h"
+
+#undef NDEBUG
+#include <assert.h>
+
+#if defined(Z_FEATURE_UNSTABLE_API)
+
+typedef struct received_t {
+    char keyexpr[64];
+    int count;
+} received_t;
+
+void on_sample(z_loaned_sample_t* sample, void* context) {
+    received_t* received = (received_t*)context;
+    z_view_string_t ke;
+    z_keyexpr_as_view_string(z_sample_keyexpr(sample), &ke);
+    size_t len = z_string_len(z_loan(ke));
+    assert(len < sizeof(received->keyexpr));
+    memcpy(received->keyexpr, z_string_data(z_loan(ke)), len);
+    received->keyexpr[len] = '\0';
+    received->count++;
+}
+
+void on_query(z_loaned_query_t* query, void* context) {
+    z_view_string_t ke;
+    z_keyexpr_as_view_string(z_query_keyexpr(query), &ke);
+    // The namespace is stripped from incoming queries.
+    assert(strncmp(z_string_data(z_loan(ke)), "service", z_string_len(z_loan(ke))) == 0);
+    z_owned_bytes_t payload;
+    z_bytes_copy_from_str(&payload, "reply");
+    z_query_reply(query, z_query_keyexpr(query), z_move(payload), NULL);
+}
+
+void on_reply(z_loaned_reply_t* reply, void* context) {
+    assert(z_reply_is_ok(reply));
+    on_sample((z_loaned_sample_t*)z_reply_ok(reply), context);
+}
+
+void open_session(z_owned_session_t* s, const char* key_namespace) {
+    z_owned_config_t c;
+    z_config_default(&c);
+    z_open_options_t opts;
+    z_open_options_default(&opts);
+    z_view_keyexpr_t ns;
+    if (key_namespace != NULL) {
+        z_view_keyexpr_from_str(&ns, key_namespace);
+        opts.key_namespace = z_loan(ns);
+    }
+    assert(z_open(s, z_move(c), &opts) == Z_OK);
+}
+
+void test_namespace_pub_sub(void) {
+    printf("test_namespace_pub_sub\n");
+    z_owned_session_t tenant, global;
+    open_session(&tenant, "tenant1");
+    open_session(&global, NULL);
+
+    z_view_keyexpr_t prefixed, local;
+    z_view_keyexpr_from_str(&prefixed, "tenant1/zenoh-c/test/namespace");
+    z_view_keyexpr_from_str(&local, "zenoh-c/test/namespace");
+
+    received_t tenant_received = {0}, global_received = {0};
+    z_owned_closure_sample_t cb1, cb2;
+    z_closure(&cb1, on_sample, NULL, &tenant_received);
+    z_closure(&cb2, on_sample, NULL, &global_received);
+    z_owned_subscriber_t tenant_sub, global_sub;
+    assert(z_declare_subscriber(z_loan(tenant), &tenant_sub, z_loan(local), z_move(cb1), NULL) == Z_OK);
+    assert(z_declare_subscriber(z_loan(global), &global_sub, z_loan(prefixed), z_move(cb2), NULL) == Z_OK);
+    z_sleep_s(1);
+
+    // Outgoing key expressions are prefixed with the namespace.
+    z_owned_bytes_t payload;
+    z_bytes_copy_from_str(&payload, "from tenant");
+    z_put(z_loan(tenant), z_loan(local), z_move(payload), NULL);
+    z_sleep_s(1);
+    assert(global_received.count == 1);
+    assert(strcmp(global_received.keyexpr, "tenant1/zenoh-c/test/namespace") == 0);
+
+    // Incoming key expressions are stripped from the namespace.
+    z_bytes_copy_from_str(&payload, "from global");
+    z_put(z_loan(global), z_loan(prefixed), z_move(payload), NULL);
+    z_sleep_s(1);
+    assert(tenant_received.count >= 1);
+    assert(strcmp(tenant_received.keyexpr, "zenoh-c/test/namespace") == 0);
+
+    z_drop(z_move(tenant_sub));
+    z_drop(z_move(global_sub));
+    z_drop(z_move(tenant));
+    z_drop(z_move(global));
+}
+
+void test_namespace_query(void) {
+    printf("test_namespace_query\n");
+    z_owned_session_t tenant, global;
+    open_session(&tenant, "tenant1");
+    open_session(&global, NULL);
+
+    z_view_keyexpr_t service, prefixed;
+    z_view_keyexpr_from_str(&service, "service");
+    z_view_keyexpr_from_str(&prefixed, "tenant1/service");
+
+    z_owned_closure_query_t query_cb;
+    z_closure(&query_cb, on_query, NULL, NULL);
+    z_owned_queryable_t queryable;
+    assert(z_declare_queryable(z_loan(tenant), &queryable, z_loan(service), z_move(query_cb), NULL) == Z_OK);
+    z_sleep_s(1);
+
+    received_t received = {0};
+    z_owned_closure_reply_t reply_cb;
+    z_closure(&reply_cb, on_reply, NULL, &received);
+    assert(z_get(z_loan(global), z_loan(prefixed), "", z_move(reply_cb), NULL) == Z_OK);
+    z_sleep_s(1);
+    assert(received.count == 1);
+    assert(strcmp(received.keyexpr, "tenant1/service") == 0);
+
+    z_drop(z_move(queryable));
+    z_drop(z_move(tenant));
+    z_drop(z_move(global));
+}
+
+void test_invalid_namespace(void) {
+    printf("test_invalid_namespace\n");
+    z_owned_session_t s;
+    z_owned_config_t c;
+    z_config_default(&c);
+    z_open_options_t opts;
+    z_open_options_default(&opts);
+    z_view_keyexpr_t ns;
+    z_view_keyexpr_from_str(&ns, "tenant/*");
+    opts.key_namespace = z_loan(ns);
+    assert(z_open(&s, z_move(c), &opts) != Z_OK);
+    assert(!z_internal_check(s));
+}
+
+int main(void) {
+    test_namespace_pub_sub();
+    test_namespace_query();
+    test_invalid_namespace();
+    return 0;
+}
+
+#else
+int main(void) { return 0; }
+#endif
@@ -0,0 +1,147 @@
+//
+// Copyright (c) 2025 ZettaScale Technology
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
+#include <stdio.h>
+#include <string.h>
+
+#include "zenoh.h"
+
+#undef NDEBUG
+#include <assert.h>
+
+#if defined(Z_FEATURE_UNSTABLE_API)
+
+bool view_string_equals(const z_view_string_t *s, const char *expected) {
+    return z_string_len(z_loan(*s)) == strlen(expected) &&
+           strncmp(z_string_data(z_loan(*s)), expected, strlen(expected)) == 0;
+}
+
+// Checks that `s` holds `expected`, and drops it.
+bool string_equals_drop(z_owned_string_t *s, const char *expected) {
+    bool equals = z_string_len(z_loan(*s)) == strlen(expected) &&
+                  strncmp(z_string_data(z_loan(*s)), expected, strlen(expected)) == 0;
+    z_drop(z_move(*s));
+    return equals;
+}
+
+void test_parse_get(void) {
+    printf("test_parse_get\n");
+    z_owned_parameters_t params;
+    assert(z_parameters_from_str(&params, "a=1;b=2|3;;flag;a=4") == Z_OK);
+    assert(!z_parameters_is_empty(z_parameters_loan(&params)));
+
+    z_owned_string_t value;
+    assert(z_parameters_get(z_parameters_loan(&params), "a", &value));
+    assert(string_equals_drop(&value, "1"));
+    assert(z_parameters_get(z_parameters_loan(&params), "b", &value));
+    assert(string_equals_drop(&value, "2|3"));
+    assert(z_parameters_get(z_parameters_loan(&params), "flag", &value));
+    assert(string_equals_drop(&value, ""));
+    assert(!z_parameters_get(z_parameters_loan(&params), "c", &value));
+    assert(!z_internal_check(value));
+
+    const char *keys[] = {"a", "b", "flag", "a"};
+    const char *values[] = {"1", "2|3", "", "4"};
+    z_parameters_iterator_t it = z_parameters_get_iterator(z_parameters_loan(&params));
+    z_owned_string_t k, v;
+    size_t n = 0;
+    while (z_parameters_iterator_next(&it, &k, &v)) {
+        assert(n < 4);
+        assert(string_equals_drop(&k, keys[n]));
+        assert(string_equals_drop(&v, values[n]));
+        n++;
+    }
+    assert(n == 4);
+    z_parameters_drop(z_parameters_move(&params));
+    assert(!z_internal_parameters_check(&params));
+}
+
+void test_insert_remove(void) {
+    printf("test_insert_remove\n");
+    z_owned_parameters_t params;
+    z_parameters_empty(&params);
+    assert(z_parameters_is_empty(z_parameters_loan(&params)));
+
+    assert(z_parameters_insert(z_parameters_loan_mut(&params), "a", "1") == Z_OK);
+    assert(z_parameters_insert(z_parameters_loan_mut(&params), "b", "x|y") == Z_OK);
+    assert(z_parameters_insert(z_parameters_loan_mut(&params), "a", "2") == Z_OK);
+    assert(z_parameters_insert(z_parameters_loan_mut(&params), "", "1") != Z_OK);
+
+    z_owned_string_t value;
+    assert(z_parameters_get(z_parameters_loan(&params), "a", &value));
+    assert(string_equals_drop(&value, "2"));
+
+    z_owned_parameters_t copy;
+    z_parameters_clone(&copy, z_parameters_loan(&params));
+    assert(z_parameters_remove(z_parameters_loan_mut(&params), "a"));
+    assert(!z_parameters_remove(z_parameters_loan_mut(&params), "a"));
+    assert(!z_parameters_get(z_parameters_loan(&params), "a", &value));
+    z_view_string_t s;
+    z_parameters_as_view_string(z_parameters_loan(&params), &s);
+    assert(view_string_equals(&s, "b=x%7Cy"));
+
+    // The copy is not affected, and its string representation can be parsed back.
+    z_parameters_as_view_string(z_parameters_loan(&copy), &s);
+    z_owned_parameters_t parsed;
+    assert(z_parameters_from_substr(&parsed, z_string_data(z_loan(s)), z_string_len(z_loan(s))) == Z_OK);
+    assert(z_parameters_get(z_parameters_loan(&parsed), "a", &value));
+    assert(string_equals_drop(&value, "2"));
+    assert(z_parameters_get(z_parameters_loan(&parsed), "b", &value));
+    assert(string_equals_drop(&value, "x|y"));
+
+    z_parameters_drop(z_parameters_move(&parsed));
+    z_parameters_drop(z_parameters_move(&copy));
+    z_parameters_drop(z_parameters_move(&params));
+}
+
+void test_escaping(void) {
+    printf("test_escaping\n");
+    z_owned_parameters_t params;
+    z_parameters_empty(&params);
+    assert(z_parameters_insert(z_parameters_loan_mut(&params), "c=d", "1;e=2|3%") == Z_OK);
+    assert(z_parameters_insert(z_parameters_loan_mut(&params), "f", "g") == Z_OK);
+
+    z_view_string_t s;
+    z_parameters_as_view_string(z_parameters_loan(&params), &s);
+    assert(view_string_equals(&s, "c%3Dd=1%3Be%3D2%7C3%25;f=g"));
+
+    z_owned_string_t value;
+    assert(z_parameters_get(z_parameters_loan(&params), "c=d", &value));
+    assert(string_equals_drop(&value, "1;e=2|3%"));
+    assert(!z_parameters_get(z_parameters_loan(&params), "c", &value));
+    z_parameters_iterator_t it = z_parameters_get_iterator(z_parameters_loan(&params));
+    z_owned_string_t k;
+    assert(z_parameters_iterator_next(&it, &k, &value));
+    assert(string_equals_drop(&k, "c=d"));
+    assert(string_equals_drop(&value, "1;e=2|3%"));
+    assert(z_parameters_remove(z_parameters_loan_mut(&params), "c=d"));
+    z_parameters_drop(z_parameters_move(&params));
+
+    // Invalid escape sequences are kept as is.
+    assert(z_parameters_from_str(&params, "a=50%;b=%zz%4") == Z_OK);
+    assert(z_parameters_get(z_parameters_loan(&params), "a", &value));
+    assert(string_equals_drop(&value, "50%"));
+    assert(z_parameters_get(z_parameters_loan(&params), "b", &value));
+    assert(string_equals_drop(&value, "%zz%4"));
+    z_parameters_drop(z_parameters_move(&params));
+}
+
+int main(void) {
+    test_parse_get();
+    test_insert_remove();
+    test_escaping();
+    return 0;
+}
+
+#else
+int main(void) { return 0; }
+#endif
@@ -315,6 +315,236 @@ void test_serialize_sequence(void) {
     z_drop(z_move(b));
 }
 
+void test_serialize_structured(void) {
+    // Equivalent of the Rust value (HashMap::from([("a", (1u8, Some(-1i32))), ("b", (2u8, None))]), vec![vec![1u16, 2], vec![3]]).
+    z_owned_bytes_t b;
+    ze_owned_serializer_t serializer;
+    ze_serializer_empty(&serializer);
+    assert(ze_serializer_serialize_tuple_begin(z_loan_mut(serializer)) == 0);
+    assert(ze_serializer_serialize_map_begin(z_loan_mut(serializer), 2) == 0);
+    // Sequence lengths are not counted, so they are rejected inside containers.
+    assert(ze_serializer_serialize_sequence_length(z_loan_mut(serializer), 1) == Z_EINVAL);
+    assert(ze_serializer_serialize_map_pair(z_loan_mut(serializer)) == 0);
+    assert(ze_serializer_serialize_str(z_loan_mut(serializer), "a") == 0);
+    // The pair is missing its value.
+    assert(ze_serializer_serialize_map_pair(z_loan_mut(serializer)) == Z_EINVAL);
+    assert(ze_serializer_serialize_tuple_begin(z_loan_mut(serializer)) == 0);
+    assert(ze_serializer_serialize_uint8(z_loan_mut(serializer), 1) == 0);
+    assert(ze_serializer_serialize_option(z_loan_mut(serializer), true) == 0);
+    assert(ze_serializer_serialize_int32(z_loan_mut(serializer), -1) == 0);
+    assert(ze_serializer_serialize_tuple_end(z_loan_mut(serializer)) == 0);
+    assert(ze_serializer_serialize_map_pair(z_loan_mut(serializer)) == 0);
+    assert(ze_serializer_serialize_str(z_loan_mut(serializer), "b") == 0);
+    assert(ze_serializer_serialize_tuple_begin(z_loan_mut(serializer)) == 0);
+    assert(ze_serializer_serialize_uint8(z_loan_mut(serializer), 2) == 0);
+    assert(ze_serializer_serialize_option(z_loan_mut(serializer), false) == 0);
+    assert(ze_serializer_serialize_tuple_end(z_loan_mut(serializer)) == 0);
+    // The map already holds its 2 pairs.
+    assert(ze_serializer_serialize_str(z_loan_mut(serializer), "c") != 0);
+    assert(ze_serializer_serialize_map_pair(z_loan_mut(serializer)) != 0);
+    // The tuple can not be ended before the map.
+    assert(ze_serializer_serialize_tuple_end(z_loan_mut(serializer)) != 0);
+    assert(ze_serializer_serialize_map_end(z_loan_mut(serializer)) == 0);
+    assert(ze_serializer_serialize_sequence_begin(z_loan_mut(serializer), 2) == 0);
+    assert(ze_serializer_serialize_sequence_begin(z_loan_mut(serializer), 2) == 0);
+    assert(ze_serializer_serialize_uint16(z_loan_mut(serializer), 1) == 0);
+    // The nested sequence is missing an element.
+    assert(ze_serializer_serialize_sequence_end(z_loan_mut(serializer)) != 0);
+    assert(ze_serializer_serialize_uint16(z_loan_mut(serializer), 2) == 0);
+    assert(ze_serializer_serialize_sequence_end(z_loan_mut(serializer)) == 0);
+    assert(ze_serializer_serialize_sequence_begin(z_loan_mut(serializer), 1) == 0);
+    assert(ze_serializer_serialize_uint16(z_loan_mut(serializer), 3) == 0);
+    assert(ze_serializer_serialize_sequence_end(z_loan_mut(serializer)) == 0);
+    assert(ze_serializer_serialize_sequence_end(z_loan_mut(serializer)) == 0);
+    assert(ze_serializer_serialize_tuple_end(z_loan_mut(serializer)) == 0);
+    assert(ze_serializer_serialize_tuple_end(z_loan_mut(serializer)) != 0);
+    assert(ze_serializer_serialize_map_pair(z_loan_mut(serializer)) != 0);
+    assert(ze_serializer_finish(z_move(serializer), &b) == Z_OK);
+
+    // Finishing a serializer with an incomplete container fails.
+    z_owned_bytes_t incomplete;
+    ze_serializer_empty(&serializer);
+    assert(ze_serializer_serialize_sequence_begin(z_loan_mut(serializer), 1) == 0);
+    assert(ze_serializer_finish(z_move(serializer), &incomplete) == Z_EINVAL);
+    assert(z_bytes_len(z_loan(incomplete)) == 0);
+    z_drop(z_move(incomplete));
+
+    // The containers are serialized as their elements, preceded by the sequence length or the option flag.
+    z_owned_bytes_t expected;
+    ze_serializer_empty(&serializer);
+    ze_serializer_serialize_sequence_length(z_loan_mut(serializer), 2);
+    ze_serializer_serialize_str(z_loan_mut(serializer), "a");
+    ze_serializer_serialize_uint8(z_loan_mut(serializer), 1);
+    ze_serializer_serialize_bool(z_loan_mut(serializer), true);
+    ze_serializer_serialize_int32(z_loan_mut(serializer), -1);
+    ze_serializer_serialize_str(z_loan_mut(serializer), "b");
+    ze_serializer_serialize_uint8(z_loan_mut(serializer), 2);
+    ze_serializer_serialize_bool(z_loan_mut(serializer), false);
+    ze_serializer_serialize_sequence_length(z_loan_mut(serializer), 2);
+    ze_serializer_serialize_sequence_length(z_loan_mut(serializer), 2);
+    ze_serializer_serialize_uint16(z_loan_mut(serializer), 1);
+    ze_serializer_serialize_uint16(z_loan_mut(serializer), 2);
+    ze_serializer_serialize_sequence_length(z_loan_mut(serializer), 1);
+    ze_serializer_serialize_uint16(z_loan_mut(serializer), 3);
+    ze_serializer_finish(z_move(serializer), &expected);
+    z_owned_slice_t s1, s2;
+    z_bytes_to_slice(z_loan(b), &s1);
+    z_bytes_to_slice(z_loan(expected), &s2);
+    assert(z_slice_len(z_loan(s1)) == z_slice_len(z_loan(s2)));
+    assert(memcmp(z_slice_data(z_loan(s1)), z_slice_data(z_loan(s2)), z_slice_len(z_loan(s1))) == 0);
+    z_drop(z_move(s1));
+    z_drop(z_move(s2));
+    z_drop(z_move(expected));
+
+    ze_deserializer_t deserializer = ze_deserializer_from_bytes(z_loan(b));
+    size_t len = 0;
+    assert(ze_deserializer_deserialize_map_length(&deserializer, &len) == 0);
+    assert(len == 2);
+    for (size_t i = 0; i < len; i++) {
+        z_owned_string_t key;
+        uint8_t u = 0;
+        bool is_present = false;
+        assert(ze_deserializer_deserialize_string(&deserializer, &key) == 0);
+        assert(ze_deserializer_deserialize_uint8(&deserializer, &u) == 0);
+        assert(ze_deserializer_deserialize_option(&deserializer, &is_present) == 0);
+        assert(u == i + 1);
+        assert(is_present == (i == 0));
+        if (is_present) {
+            int32_t v = 0;
+            assert(ze_deserializer_deserialize_int32(&deserializer, &v) == 0);
+            assert(v == -1);
+        }
+        z_drop(z_move(key));
+    }
+    assert(ze_deserializer_deserialize_sequence_length(&deserializer, &len) == 0);
+    assert(len == 2);
+    uint16_t next = 1;
+    for (size_t i = 0; i < 2; i++) {
+        size_t inner_len = 0;
+        assert(ze_deserializer_deserialize_sequence_length(&deserializer, &inner_len) == 0);
+        assert(inner_len == 2 - i);
+        for (size_t j = 0; j < inner_len; j++) {
+            uint16_t v = 0;
+            assert(ze_deserializer_deserialize_uint16(&deserializer, &v) == 0);
+            assert(v == next++);
+        }
+    }
+    assert(ze_deserializer_is_done(&deserializer));
+    z_drop(z_move(b));
+}
+
+void test_serialize_arrays(void) {
+    float input[5] = {0.5f, -1.0f, 2.25f, 1e10f, -3e-5f};
+    int16_t ints[3] = {-1, 0, 32767};
+    z_owned_bytes_t b;
+    ze_owned_serializer_t serializer;
+    ze_serializer_empty(&serializer);
+    assert(ze_serializer_serialize_float_array(z_loan_mut(serializer), input, 5) == 0);
+    assert(ze_serializer_serialize_int16_array(z_loan_mut(serializer), ints, 3) == 0);
+    assert(ze_serializer_serialize_uint64_array(z_loan_mut(serializer), NULL, 0) == 0);
+    ze_serializer_finish(z_move(serializer), &b);
+
+    // Arrays are serialized as sequences.
+    z_owned_bytes_t expected;
+    ze_serializer_empty(&serializer);
+    ze_serializer_serialize_sequence_length(z_loan_mut(serializer), 5);
+    for (size_t i = 0; i < 5; i++) {
+        ze_serializer_serialize_float(z_loan_mut(serializer), input[i]);
+    }
+    ze_serializer_serialize_sequence_length(z_loan_mut(serializer), 3);
+    for (size_t i = 0; i < 3; i++) {
+        ze_serializer_serialize_int16(z_loan_mut(serializer), ints[i]);
+    }
+    ze_serializer_serialize_sequence_length(z_loan_mut(serializer), 0);
+    ze_serializer_finish(z_move(serializer), &expected);
+    z_owned_slice_t s1, s2;
+    z_bytes_to_slice(z_loan(b), &s1);
+    z_bytes_to_slice(z_loan(expected), &s2);
+    assert(z_slice_len(z_loan(s1)) == z_slice_len(z_loan(s2)));
+    assert(memcmp(z_slice_data(z_loan(s1)), z_slice_data(z_loan(s2)), z_slice_len(z_loan(s1))) == 0);
+    z_drop(z_move(s1));
+    z_drop(z_move(s2));
+    z_drop(z_move(expected));
+
+    ze_deserializer_t deserializer = ze_deserializer_from_bytes(z_loan(b));
+    float output[5] = {0};
+    size_t len = 0;
+    // The deserializer is not advanced if the buffer is too small.
+    assert(ze_deserializer_deserialize_float_array(&deserializer, output, 4, &len) == Z_EINVAL);
+    assert(len == 5);
+    assert(ze_deserializer_deserialize_float_array(&deserializer, NULL, 5, &len) == Z_EINVAL);
+    assert(ze_deserializer_deserialize_float_array(&deserializer, output, 5, &len) == 0);
+    assert(len == 5);
+    assert(memcmp(input, output, sizeof(input)) == 0);
+    int16_t ints_out[8] = {0};
+    assert(ze_deserializer_deserialize_int16_array(&deserializer, ints_out, 8, &len) == 0);
+    assert(len == 3);
+    assert(memcmp(ints, ints_out, sizeof(ints)) == 0);
+    assert(ze_deserializer_deserialize_uint64_array(&deserializer, NULL, 0, &len) == 0);
+    assert(len == 0);
+    assert(ze_deserializer_is_done(&deserializer));
+    z_drop(z_move(b));
+}
+
+void test_deserialize_views(void) {
+    uint8_t data[] = {1, 2, 3, 4};
+    z_owned_bytes_t b;
+    ze_owned_serializer_t serializer;
+    ze_serializer_empty(&serializer);
+    ze_serializer_serialize_str(z_loan_mut(serializer), "abc");
+    ze_serializer_serialize_buf(z_loan_mut(serializer), data, 4);
+    ze_serializer_serialize_uint32(z_loan_mut(serializer), 42);
+    ze_serializer_finish(z_move(serializer), &b);
+
+    z_view_slice_t contiguous;
+    assert(z_bytes_get_contiguous_view(z_loan(b), &contiguous) == 0);
+    const uint8_t* start = z_slice_data(z_loan(contiguous));
+    const uint8_t* end = start + z_slice_len(z_loan(contiguous));
+
+    ze_deserializer_t deserializer = ze_deserializer_from_bytes(z_loan(b));
+    z_view_string_t s;
+    z_view_slice_t slice;
+    uint32_t u = 0;
+    // A string can also be viewed as a slice.
+    assert(ze_deserializer_deserialize_slice_view(&deserializer, &slice) == 0);
+    deserializer = ze_deserializer_from_bytes(z_loan(b));
+    assert(ze_deserializer_deserialize_string_view(&deserializer, &s) == 0);
+    assert(z_string_len(z_loan(s)) == 3);
+    assert(strncmp(z_string_data(z_loan(s)), "abc", 3) == 0);
+    // The views point into the serialized data.
+    const uint8_t* p = (const uint8_t*)z_string_data(z_loan(s));
+    assert(p >= start && p + 3 <= end);
+    assert(ze_deserializer_deserialize_slice_view(&deserializer, &slice) == 0);
+    assert(z_slice_len(z_loan(slice)) == 4);
+    assert(memcmp(z_slice_data(z_loan(slice)), data, 4) == 0);
+    assert(z_slice_data(z_loan(slice)) >= start && z_slice_data(z_loan(slice)) + 4 <= end);
+    assert(ze_deserializer_deserialize_uint32(&deserializer, &u) == 0);
+    assert(u == 42);
+    assert(ze_deserializer_is_done(&deserializer));
+    assert(ze_deserializer_deserialize_slice_view(&deserializer, &slice) != 0);
+    z_drop(z_move(b));
+
+    // Fragmented data can only be deserialized by copy.
+    z_owned_bytes_writer_t writer;
+    z_bytes_writer_empty(&writer);
+    ze_serializer_empty(&serializer);
+    ze_serializer_serialize_sequence_length(z_loan_mut(serializer), 4);
+    ze_serializer_finish(z_move(serializer), &b);
+    z_bytes_writer_append(z_loan_mut(writer), z_move(b));
+    z_bytes_copy_from_buf(&b, data, 4);
+    z_bytes_writer_append(z_loan_mut(writer), z_move(b));
+    z_bytes_writer_finish(z_move(writer), &b);
+    deserializer = ze_deserializer_from_bytes(z_loan(b));
+    assert(ze_deserializer_deserialize_slice_view(&deserializer, &slice) == Z_EINVAL);
+    z_owned_slice_t owned;
+    assert(ze_deserializer_deserialize_slice(&deserializer, &owned) == 0);
+    assert(z_slice_len(z_loan(owned)) == 4);
+    assert(memcmp(z_slice_data(z_loan(owned)), data, 4) == 0);
+    assert(ze_deserializer_is_done(&deserializer));
+    z_drop(z_move(owned));
+    z_drop(z_move(b));
+}
+
 int main(void) {
     test_reader_seek();
     test_reader_read();
@@ -325,4 +555,7 @@ int main(void) {
     test_slices();
     test_serialize_simple();
     test_serialize_sequence();
+    test_serialize_structured();
+    test_serialize_arrays();
+    test_deserialize_views();
 }
@@ -0,0 +1,75 @@
+//
+// Copyright (c) 2025 ZettaScale Technology
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
+#include <stdio.h>
+#include <stdlib.h>
+#include <string.h>
+
+#include "zenoh.h"
+
+#undef NDEBUG
+#include <assert.h>
+
+#if defined(Z_FEATURE_UNSTABLE_API)
+
+void test_runtime_config(void) {
+    printf("test_runtime_config\n");
+    zc_runtime_config_t config;
+    zc_runtime_config_default(&config);
+    assert(config.application.worker_threads == 0);
+    assert(config.net.max_blocking_threads == 0);
+
+    // A user-provided ZENOH_RUNTIME takes precedence.
+    setenv("ZENOH_RUNTIME", "(app: (worker_threads: 1))", 1);
+    assert(zc_init_runtime(&config) == Z_EUNAVAILABLE);
+    assert(strcmp(getenv("ZENOH_RUNTIME"), "(app: (worker_threads: 1))") == 0);
+    unsetenv("ZENOH_RUNTIME");
+
+    config.application.worker_threads = 2;
+    config.rx.worker_threads = 1;
+    config.rx.max_blocking_threads = 4;
+    assert(zc_init_runtime(&config) == Z_OK);
+    const char* env = getenv("ZENOH_RUNTIME");
+    assert(env != NULL);
+    assert(strstr(env, "app: (worker_threads: 2)") != NULL);
+    assert(strstr(env, "rx: (worker_threads: 1, max_blocking_threads: 4)") != NULL);
+    assert(strstr(env, "tx:") == NULL);
+    // Reconfiguring is allowed as long as the runtime is not started.
+    assert(zc_init_runtime(&config) == Z_OK);
+
+    z_owned_config_t c;
+    z_config_default(&c);
+    z_open_options_t opts;
+    z_open_options_default(&opts);
+    opts.runtime = &config;
+    z_owned_session_t s;
+    assert(z_open(&s, z_config_move(&c), &opts) == Z_OK);
+
+    assert(zc_init_runtime(&config) == Z_EUNAVAILABLE);
+    assert(zc_get_last_error_kind() == ZC_ERROR_KIND_UNAVAILABLE);
+
+    // The runtime config of a session can no longer be applied once the runtime is started.
+    z_config_default(&c);
+    z_owned_session_t s2;
+    assert(z_open(&s2, z_config_move(&c), &opts) == Z_EUNAVAILABLE);
+    assert(!z_internal_session_check(&s2));
+    z_session_drop(z_session_move(&s));
+}
+
+int main(void) {
+    test_runtime_config();
+    return 0;
+}
+
+#else
+int main(void) { return 0; }
+#endif
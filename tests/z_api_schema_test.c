@@ -0,0 +1,287 @@
+//
+// Copyright (c) 2025 ZettaScale Technology
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
+#include <stddef.h>
+#include <stdint.h>
+#include <stdio.h>
+#include <stdlib.h>
+#include <string.h>
+
+#include "zenoh.h"
+
+#undef NDEBUG
+#include <assert.h>
+
+#if defined(Z_FEATURE_UNSTABLE_API)
+
+typedef struct {
+    float x;
+    float y;
+} point_t;
+
+typedef struct {
+    z_owned_string_t key;
+    int32_t value;
+} tag_t;
+
+typedef struct {
+    uint32_t id;
+    z_owned_string_t name;
+    point_t origin;
+    ze_schema_sequence_t points;
+    ze_schema_sequence_t tags;
+    bool visible;
+} shape_t;
+
+void make_schema(ze_owned_schema_t* schema) {
+    ze_owned_schema_t point, tag;
+    ze_schema_new(&point, sizeof(point_t));
+    assert(ze_schema_add_field(z_loan_mut(point), "x", offsetof(point_t, x), ZE_SCHEMA_TYPE_FLOAT) == Z_OK);
+    assert(ze_schema_add_field(z_loan_mut(point), "y", offsetof(point_t, y), ZE_SCHEMA_TYPE_FLOAT) == Z_OK);
+    ze_schema_new(&tag, sizeof(tag_t));
+    assert(ze_schema_add_field(z_loan_mut(tag), "key", offsetof(tag_t, key), ZE_SCHEMA_TYPE_STRING) == Z_OK);
+    assert(ze_schema_add_field(z_loan_mut(tag), "value", offsetof(tag_t, value), ZE_SCHEMA_TYPE_INT32) == Z_OK);
+
+    ze_schema_new(schema, sizeof(shape_t));
+    ze_loaned_schema_t* s = z_loan_mut(*schema);
+    assert(ze_schema_add_field(s, "id", offsetof(shape_t, id), ZE_SCHEMA_TYPE_UINT32) == Z_OK);
+    assert(ze_schema_add_field(s, "name", offsetof(shape_t, name), ZE_SCHEMA_TYPE_STRING) == Z_OK);
+    assert(ze_schema_add_struct_field(s, "origin", offsetof(shape_t, origin), z_loan(point)) == Z_OK);
+    assert(ze_schema_add_sequence_field(s, "points", offsetof(shape_t, points), ZE_SCHEMA_TYPE_STRUCT,
+                                        z_loan(point)) == Z_OK);
+    assert(ze_schema_add_map_field(s, "tags", offsetof(shape_t, tags), z_loan(tag)) == Z_OK);
+    assert(ze_schema_add_field(s, "visible", offsetof(shape_t, visible), ZE_SCHEMA_TYPE_BOOL) == Z_OK);
+
+    z_drop(z_move(point));
+    z_drop(z_move(tag));
+}
+
+void test_invalid_fields(void) {
+    printf("test_invalid_fields\n");
+    ze_owned_schema_t schema, entry;
+    ze_schema_new(&schema, sizeof(shape_t));
+    assert(ze_schema_add_field(z_loan_mut(schema), "id", offsetof(shape_t, id), ZE_SCHEMA_TYPE_UINT32) == Z_OK);
+    // Duplicate name.
+    assert(ze_schema_add_field(z_loan_mut(schema), "id", offsetof(shape_t, id), ZE_SCHEMA_TYPE_UINT32) != Z_OK);
+    // Field out of the structure.
+    assert(ze_schema_add_field(z_loan_mut(schema), "far", sizeof(shape_t), ZE_SCHEMA_TYPE_UINT8) != Z_OK);
+    // Sequences and structures have dedicated functions.
+    assert(ze_schema_add_field(z_loan_mut(schema), "points", offsetof(shape_t, points), ZE_SCHEMA_TYPE_SEQUENCE) !=
+           Z_OK);
+    assert(ze_schema_add_sequence_field(z_loan_mut(schema), "points", offsetof(shape_t, points),
+                                        ZE_SCHEMA_TYPE_STRUCT, NULL) != Z_OK);
+    // Map entries have exactly two fields.
+    ze_schema_new(&entry, sizeof(tag_t));
+    assert(ze_schema_add_field(z_loan_mut(entry), "value", offsetof(tag_t, value), ZE_SCHEMA_TYPE_INT32) == Z_OK);
+    assert(ze_schema_add_map_field(z_loan_mut(schema), "tags", offsetof(shape_t, tags), z_loan(entry)) != Z_OK);
+    z_drop(z_move(entry));
+    // Strings, and structures holding them, should be aligned.
+    assert(ze_schema_add_field(z_loan_mut(schema), "name", offsetof(shape_t, name) + 1, ZE_SCHEMA_TYPE_STRING) !=
+           Z_OK);
+    // So should elements holding them, whose size should then be a multiple of their alignment.
+    ze_schema_new(&entry, sizeof(tag_t) + 1);
+    assert(ze_schema_add_field(z_loan_mut(entry), "key", offsetof(tag_t, key), ZE_SCHEMA_TYPE_STRING) == Z_OK);
+    assert(ze_schema_add_field(z_loan_mut(entry), "value", offsetof(tag_t, value), ZE_SCHEMA_TYPE_INT32) == Z_OK);
+    assert(ze_schema_add_map_field(z_loan_mut(schema), "tags", offsetof(shape_t, tags), z_loan(entry)) != Z_OK);
+    z_drop(z_move(entry));
+    z_drop(z_move(schema));
+}
+
+void test_roundtrip(void) {
+    printf("test_roundtrip\n");
+    ze_owned_schema_t schema;
+    make_schema(&schema);
+
+    point_t points[3] = {{1.0f, 2.0f}, {3.0f, 4.0f}, {5.0f, 6.0f}};
+    tag_t tags[2];
+    z_string_copy_from_str(&tags[0].key, "color");
+    tags[0].value = 7;
+    z_string_copy_from_str(&tags[1].key, "layer");
+    tags[1].value = -2;
+    shape_t in;
+    in.id = 42;
+    z_string_copy_from_str(&in.name, "triangle");
+    in.origin.x = 0.5f;
+    in.origin.y = -0.5f;
+    in.points.data = points;
+    in.points.len = 3;
+    in.tags.data = tags;
+    in.tags.len = 2;
+    in.visible = true;
+
+    z_owned_bytes_t payload;
+    assert(ze_schema_serialize(z_loan(schema), &in, &payload) == Z_OK);
+    assert(ze_schema_validate(z_loan(schema), z_loan(payload)) == Z_OK);
+
+    shape_t out;
+    assert(ze_schema_deserialize(z_loan(schema), z_loan(payload), &out) == Z_OK);
+    assert(out.id == 42);
+    assert(z_string_len(z_loan(out.name)) == strlen("triangle"));
+    assert(strncmp(z_string_data(z_loan(out.name)), "triangle", strlen("triangle")) == 0);
+    assert(out.origin.x == 0.5f && out.origin.y == -0.5f);
+    assert(out.points.len == 3);
+    assert(memcmp(out.points.data, points, sizeof(points)) == 0);
+    assert(out.tags.len == 2);
+    tag_t* out_tags = (tag_t*)out.tags.data;
+    assert(strncmp(z_string_data(z_loan(out_tags[1].key)), "layer", strlen("layer")) == 0);
+    assert(out_tags[1].value == -2);
+    assert(out.visible);
+
+    ze_schema_value_drop(z_loan(schema), &out);
+    assert(out.points.data == NULL && out.points.len == 0);
+    assert(!z_internal_check(out.name));
+    // Dropping twice is harmless.
+    ze_schema_value_drop(z_loan(schema), &out);
+
+    z_drop(z_move(in.name));
+    z_drop(z_move(tags[0].key));
+    z_drop(z_move(tags[1].key));
+    z_drop(z_move(payload));
+    z_drop(z_move(schema));
+}
+
+void test_serializer_compatibility(void) {
+    printf("test_serializer_compatibility\n");
+    ze_owned_schema_t schema;
+    make_schema(&schema);
+
+    ze_owned_serializer_t serializer;
+    ze_serializer_empty(&serializer);
+    ze_loaned_serializer_t* s = z_loan_mut(serializer);
+    ze_serializer_serialize_uint32(s, 1);
+    ze_serializer_serialize_str(s, "square");
+    ze_serializer_serialize_tuple_begin(s);
+    ze_serializer_serialize_float(s, 1.0f);
+    ze_serializer_serialize_float(s, 2.0f);
+    ze_serializer_serialize_tuple_end(s);
+    ze_serializer_serialize_sequence_begin(s, 1);
+    ze_serializer_serialize_tuple_begin(s);
+    ze_serializer_serialize_float(s, 3.0f);
+    ze_serializer_serialize_float(s, 4.0f);
+    ze_serializer_serialize_tuple_end(s);
+    ze_serializer_serialize_sequence_end(s);
+    ze_serializer_serialize_map_begin(s, 1);
+    ze_serializer_serialize_str(s, "layer");
+    ze_serializer_serialize_int32(s, 3);
+    ze_serializer_serialize_map_end(s);
+    ze_serializer_serialize_bool(s, false);
+    z_owned_bytes_t payload;
+    ze_serializer_finish(z_move(serializer), &payload);
+
+    shape_t out;
+    assert(ze_schema_deserialize(z_loan(schema), z_loan(payload), &out) == Z_OK);
+    assert(out.id == 1);
+    assert(out.origin.y == 2.0f);
+    assert(out.points.len == 1 && ((point_t*)out.points.data)[0].x == 3.0f);
+    assert(out.tags.len == 1 && ((tag_t*)out.tags.data)[0].value == 3);
+    assert(!out.visible);
+    ze_schema_value_drop(z_loan(schema), &out);
+
+    z_drop(z_move(payload));
+    z_drop(z_move(schema));
+}
+
+void test_mismatch(void) {
+    printf("test_mismatch\n");
+    ze_owned_schema_t schema;
+    make_schema(&schema);
+
+    // The name is missing.
+    ze_owned_serializer_t serializer;
+    ze_serializer_empty(&serializer);
+    ze_serializer_serialize_uint32(z_loan_mut(serializer), 1);
+    z_owned_bytes_t payload;
+    ze_serializer_finish(z_move(serializer), &payload);
+    assert(ze_schema_validate(z_loan(schema), z_loan(payload)) == Z_EDESERIALIZE);
+    shape_t out;
+    assert(ze_schema_deserialize(z_loan(schema), z_loan(payload), &out) == Z_EDESERIALIZE);
+    z_drop(z_move(payload));
+
+    // A truncated point in the middle of the sequence.
+    ze_serializer_empty(&serializer);
+    ze_loaned_serializer_t* s = z_loan_mut(serializer);
+    ze_serializer_serialize_uint32(s, 1);
+    ze_serializer_serialize_str(s, "line");
+    ze_serializer_serialize_float(s, 0.0f);
+    ze_serializer_serialize_float(s, 0.0f);
+    ze_serializer_serialize_sequence_length(s, 2);
+    ze_serializer_serialize_float(s, 1.0f);
+    ze_serializer_serialize_float(s, 1.0f);
+    ze_serializer_serialize_float(s, 2.0f);
+    ze_serializer_finish(z_move(serializer), &payload);
+    assert(ze_schema_validate(z_loan(schema), z_loan(payload)) == Z_EDESERIALIZE);
+    assert(ze_schema_deserialize(z_loan(schema), z_loan(payload), &out) == Z_EDESERIALIZE);
+    z_drop(z_move(payload));
+
+    z_drop(z_move(schema));
+}
+
+void test_trailing_data(void) {
+    printf("test_trailing_data\n");
+    ze_owned_schema_t schema;
+    ze_schema_new(&schema, sizeof(point_t));
+    ze_schema_add_field(z_loan_mut(schema), "x", offsetof(point_t, x), ZE_SCHEMA_TYPE_FLOAT);
+    ze_schema_add_field(z_loan_mut(schema), "y", offsetof(point_t, y), ZE_SCHEMA_TYPE_FLOAT);
+
+    ze_owned_serializer_t serializer;
+    ze_serializer_empty(&serializer);
+    ze_serializer_serialize_float(z_loan_mut(serializer), 1.0f);
+    ze_serializer_serialize_float(z_loan_mut(serializer), 2.0f);
+    ze_serializer_serialize_float(z_loan_mut(serializer), 3.0f);
+    z_owned_bytes_t payload;
+    ze_serializer_finish(z_move(serializer), &payload);
+
+    assert(ze_schema_validate(z_loan(schema), z_loan(payload)) == Z_EDESERIALIZE);
+    point_t out;
+    assert(ze_schema_deserialize(z_loan(schema), z_loan(payload), &out) == Z_EDESERIALIZE);
+
+    z_drop(z_move(payload));
+    z_drop(z_move(schema));
+}
+
+void test_sequence_length_bound(void) {
+    printf("test_sequence_length_bound\n");
+    // Structures without fields take no space on the wire.
+    ze_owned_schema_t empty, schema;
+    ze_schema_new(&empty, 0);
+    ze_schema_new(&schema, sizeof(ze_schema_sequence_t));
+    assert(ze_schema_add_sequence_field(z_loan_mut(schema), "items", 0, ZE_SCHEMA_TYPE_STRUCT, z_loan(empty)) ==
+           Z_OK);
+
+    ze_owned_serializer_t serializer;
+    ze_serializer_empty(&serializer);
+    ze_serializer_serialize_sequence_length(z_loan_mut(serializer), SIZE_MAX);
+    z_owned_bytes_t payload;
+    ze_serializer_finish(z_move(serializer), &payload);
+
+    assert(ze_schema_validate(z_loan(schema), z_loan(payload)) == Z_EDESERIALIZE);
+    ze_schema_sequence_t out;
+    assert(ze_schema_deserialize(z_loan(schema), z_loan(payload), &out) == Z_EDESERIALIZE);
+
+    z_drop(z_move(payload));
+    z_drop(z_move(schema));
+    z_drop(z_move(empty));
+}
+
+int main(void) {
+    test_invalid_fields();
+    test_roundtrip();
+    test_serializer_compatibility();
+    test_mismatch();
+    test_trailing_data();
+    test_sequence_length_bound();
+    return 0;
+}
+
+#else
+int main(void) { return 0; }
+#endif
@@ -83,9 +83,70 @@ void close_concurrent() {
 #endif
 }
 
+#if defined(Z_FEATURE_UNSTABLE_API)
+z_owned_query_t pending_query;
+
+void hold_query(z_loaned_query_t *query, void *context) { z_query_clone(&pending_query, query); }
+
+// Returns the time in milliseconds for a query to a queryable which never replies to complete.
+uint64_t unanswered_query_duration_ms(const z_loaned_session_t *s, const z_loaned_keyexpr_t *ke) {
+    z_owned_closure_reply_t callback;
+    z_owned_fifo_handler_reply_t handler;
+    z_fifo_channel_reply_new(&callback, &handler, 16);
+    z_clock_t clock = z_clock_now();
+    assert(z_get(s, ke, "", z_move(callback), NULL) == Z_OK);
+    z_owned_reply_t reply;
+    assert(z_recv(z_loan(handler), &reply) == Z_CHANNEL_DISCONNECTED);
+    uint64_t elapsed = z_clock_elapsed_ms(&clock);
+    z_drop(z_move(handler));
+    z_drop(z_move(pending_query));
+    return elapsed;
+}
+#endif
+
+void config_update() {
+#if defined(Z_FEATURE_UNSTABLE_API)
+    z_owned_config_t config;
+    z_config_default(&config);
+
+    z_owned_session_t s;
+    if (z_open(&s, z_move(config), NULL) < 0) {
+        perror("Unable to open session!");
+        exit(-1);
+    }
+
+    assert(z_session_set_queries_default_timeout(z_loan(s), 1234) == Z_OK);
+    z_owned_config_t current;
+    assert(z_session_config_get(z_loan(s), &current) == Z_OK);
+    z_owned_string_t value;
+    assert(zc_config_get_from_str(z_loan(current), "queries_default_timeout", &value) == Z_OK);
+    assert(strncmp(z_string_data(z_loan(value)), "1234", z_string_len(z_loan(value))) == 0);
+    z_drop(z_move(value));
+    z_drop(z_move(current));
+
+    // The updated query timeout applies to the next queries of the live session.
+    z_view_keyexpr_t ke;
+    z_view_keyexpr_from_str(&ke, "test/session/config_update");
+    z_owned_closure_query_t query_callback;
+    z_closure(&query_callback, hold_query, NULL, NULL);
+    z_owned_queryable_t queryable;
+    assert(z_declare_queryable(z_loan(s), &queryable, z_loan(ke), z_move(query_callback), NULL) == Z_OK);
+    assert(z_session_set_queries_default_timeout(z_loan(s), 300) == Z_OK);
+    uint64_t elapsed = unanswered_query_duration_ms(z_loan(s), z_loan(ke));
+    assert(elapsed >= 250 && elapsed < 2000);
+    assert(z_session_set_queries_default_timeout(z_loan(s), 2500) == Z_OK);
+    elapsed = unanswered_query_duration_ms(z_loan(s), z_loan(ke));
+    assert(elapsed >= 2000);
+    z_drop(z_move(queryable));
+
+    z_drop(z_move(s));
+#endif
+}
+
 int main(int argc, char **argv) {
     zc_try_init_log_from_env();
     close_drop();
     close_sync();
     close_concurrent();
+    config_update();
 }
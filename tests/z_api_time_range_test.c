@@ -0,0 +1,150 @@
+//
+// Copyright (c) 2025 ZettaScale Technology
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
+#include <stdio.h>
+#include <string.h>
+
+#include "zenoh.h"
+
+#undef NDEBUG
+#include <assert.h>
+
+#if defined(Z_FEATURE_UNSTABLE_API)
+
+#define HOUR_NS (3600LL * 1000000000LL)
+
+void test_parse_format(void) {
+    printf("test_parse_format\n");
+    z_time_range_t range;
+    assert(z_time_range_from_str(&range, "[now(-1h)..now(1h)[") == Z_OK);
+    assert(range.start.kind == Z_TIME_BOUND_KIND_INCLUSIVE);
+    assert(range.start.is_relative);
+    assert(range.start.time_ns == -HOUR_NS);
+    assert(range.end.kind == Z_TIME_BOUND_KIND_EXCLUSIVE);
+    assert(range.end.is_relative);
+    assert(range.end.time_ns == HOUR_NS);
+
+    // The string representation can be parsed back.
+    z_owned_string_t s;
+    z_time_range_to_string(&range, &s);
+    char buf[64] = {0};
+    assert(z_string_len(z_loan(s)) < sizeof(buf));
+    memcpy(buf, z_string_data(z_loan(s)), z_string_len(z_loan(s)));
+    z_drop(z_move(s));
+    z_time_range_t parsed;
+    assert(z_time_range_from_str(&parsed, buf) == Z_OK);
+    assert(parsed.start.kind == range.start.kind && parsed.start.time_ns == range.start.time_ns);
+    assert(parsed.end.kind == range.end.kind && parsed.end.time_ns == range.end.time_ns);
+
+    assert(z_time_range_from_str(&range, "[..]") == Z_OK);
+    assert(range.start.kind == Z_TIME_BOUND_KIND_UNBOUNDED);
+    assert(range.end.kind == Z_TIME_BOUND_KIND_UNBOUNDED);
+    assert(z_time_range_from_str(&range, "[2025-01-01T00:00:00Z..]") == Z_OK);
+    assert(!range.start.is_relative);
+    assert(range.start.time_ns == 1735689600LL * 1000000000LL);
+
+    assert(z_time_range_from_str(&range, "now(-1h)..") != Z_OK);
+    assert(z_time_range_from_str(&range, "[yesterday..]") != Z_OK);
+}
+
+void test_contains(void) {
+    printf("test_contains\n");
+    z_owned_config_t c;
+    z_config_default(&c);
+    z_owned_session_t s;
+    assert(z_open(&s, z_move(c), NULL) == Z_OK);
+    z_timestamp_t ts;
+    z_timestamp_new(&ts, z_loan(s));
+
+    z_time_range_t range;
+    z_time_range_unbounded(&range);
+    assert(z_time_range_contains(&range, &ts));
+    assert(z_time_range_from_str(&range, "[now(-1h)..]") == Z_OK);
+    assert(z_time_range_contains(&range, &ts));
+    assert(z_time_range_from_str(&range, "[now(1h)..]") == Z_OK);
+    assert(!z_time_range_contains(&range, &ts));
+    assert(z_time_range_from_str(&range, "[..2020-01-01T00:00:00Z]") == Z_OK);
+    assert(!z_time_range_contains(&range, &ts));
+
+    z_drop(z_move(s));
+}
+
+typedef struct query_context_t {
+    z_result_t result;
+    z_time_range_t range;
+    int count;
+} query_context_t;
+
+void on_query(z_loaned_query_t* query, void* context) {
+    query_context_t* ctx = (query_context_t*)context;
+    ctx->result = z_query_time_range(query, &ctx->range);
+    ctx->count++;
+}
+
+void on_reply(z_loaned_reply_t* reply, void* context) {
+    (void)reply;
+    (void)context;
+}
+
+void test_query(void) {
+    printf("test_query\n");
+    z_owned_config_t c;
+    z_config_default(&c);
+    z_owned_session_t s;
+    assert(z_open(&s, z_move(c), NULL) == Z_OK);
+
+    z_view_keyexpr_t ke;
+    z_view_keyexpr_from_str(&ke, "zenoh-c/test/time_range");
+    query_context_t ctx = {0};
+    z_owned_closure_query_t query_cb;
+    z_closure(&query_cb, on_query, NULL, &ctx);
+    z_owned_queryable_t queryable;
+    assert(z_declare_queryable(z_loan(s), &queryable, z_loan(ke), z_move(query_cb), NULL) == Z_OK);
+    z_sleep_s(1);
+
+    // The time range of the options replaces the one of the parameters.
+    z_time_range_t range;
+    assert(z_time_range_from_str(&range, "[now(-2h)..]") == Z_OK);
+    z_get_options_t opts;
+    z_get_options_default(&opts);
+    opts.time_range = &range;
+    z_owned_closure_reply_t reply_cb;
+    z_closure(&reply_cb, on_reply, NULL, NULL);
+    assert(z_get(z_loan(s), z_loan(ke), "a=1;_time=[..]", z_move(reply_cb), &opts) == Z_OK);
+    z_sleep_s(1);
+    assert(ctx.count == 1);
+    assert(ctx.result == Z_OK);
+    assert(ctx.range.start.kind == Z_TIME_BOUND_KIND_INCLUSIVE);
+    assert(ctx.range.start.is_relative);
+    assert(ctx.range.start.time_ns == -2 * HOUR_NS);
+    assert(ctx.range.end.kind == Z_TIME_BOUND_KIND_UNBOUNDED);
+
+    z_closure(&reply_cb, on_reply, NULL, NULL);
+    assert(z_get(z_loan(s), z_loan(ke), "a=1", z_move(reply_cb), NULL) == Z_OK);
+    z_sleep_s(1);
+    assert(ctx.count == 2);
+    assert(ctx.result == Z_EUNAVAILABLE);
+
+    z_drop(z_move(queryable));
+    z_drop(z_move(s));
+}
+
+int main(void) {
+    test_parse_format();
+    test_contains();
+    test_query();
+    return 0;
+}
+
+#else
+int main(void) { return 0; }
+#endif